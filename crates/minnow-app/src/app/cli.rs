@@ -0,0 +1,137 @@
+//! Headless subcommands. They call the capture and OCR services directly and
+//! never start GPUI, so they work while the tray app is running or not.
+
+use super::commands::Region;
+use crate::platform::shell;
+use crate::services::capture::{
+    filename::CaptureOrigin, long_capture_recording, select_capture_target, select_region_target, service::CaptureService,
+};
+use crate::services::geometry::Rect;
+use crate::services::ocr::service::{self, OcrImageInput};
+use std::path::Path;
+use std::time::Duration;
+
+const EXIT_SUCCESS: u8 = 0;
+const EXIT_NOT_FOUND: u8 = 2;
+const EXIT_FAILED: u8 = 3;
+
+pub(super) fn capture(region: Option<Region>, out: Option<&Path>, delay_seconds: u32) -> u8 {
+    if delay_seconds > 0 {
+        eprintln!("Capturing in {delay_seconds} seconds...");
+        std::thread::sleep(Duration::from_secs(u64::from(delay_seconds)));
+    }
+
+    let rect = match region {
+        Some(region) => match select_region_target(Rect::new(region.x, region.y, region.width, region.height)) {
+            Some(rect) => rect,
+            None => return fail("No monitor found for the capture region"),
+        },
+        None => {
+            select_capture_target(shell::cursor_position());
+            Rect::empty()
+        }
+    };
+
+    let Some(image) = CaptureService::capture_region(rect) else {
+        return fail("Capture failed");
+    };
+
+    let saved = match out {
+        Some(path) => shell::save_image_to_path(&image, path),
+        None => shell::save_image_to_user_dir(&image, None, &CaptureOrigin::default()),
+    };
+    match saved {
+        Ok(path) => {
+            println!("{path}");
+            EXIT_SUCCESS
+        }
+        Err(err) => fail(&err),
+    }
+}
+
+pub(super) fn ocr(path: &Path, json: bool) -> u8 {
+    if !service::mobile_models_ready() {
+        return fail("OCR model is not downloaded; download it from Preferences > OCR first");
+    }
+
+    let blocks = match crate::RUNTIME.block_on(service::recognize_image_blocks(OcrImageInput::Path(path.to_path_buf()))) {
+        Ok(blocks) => blocks,
+        Err(err) => return fail(&format!("OCR failed for '{}': {err}", path.display())),
+    };
+
+    if json {
+        match serde_json::to_string_pretty(&blocks) {
+            Ok(output) => println!("{output}"),
+            Err(err) => return fail(&format!("Failed to encode OCR result: {err}")),
+        }
+    } else {
+        for block in &blocks {
+            println!("{}", block.text);
+        }
+    }
+
+    if blocks.is_empty() { EXIT_NOT_FOUND } else { EXIT_SUCCESS }
+}
+
+pub(super) fn qr(path: &Path) -> u8 {
+    let image = match image::open(path) {
+        Ok(decoded) => decoded.to_rgba8(),
+        Err(err) => return fail(&format!("Failed to open '{}': {err}", path.display())),
+    };
+
+    match CaptureService::decode_qrcode(&image) {
+        Some(content) => {
+            println!("{content}");
+            EXIT_SUCCESS
+        }
+        None => {
+            eprintln!("No QR code found");
+            EXIT_NOT_FOUND
+        }
+    }
+}
+
+pub(super) fn replay_long_capture(dir: &Path, out: Option<&Path>, report: Option<&Path>) -> u8 {
+    let outcome = match long_capture_recording::replay(dir) {
+        Ok(outcome) => outcome,
+        Err(err) => return fail(&format!("Failed to replay '{}': {err}", dir.display())),
+    };
+
+    println!("target: {:?}", outcome.manifest.target);
+    for (index, frame) in outcome.frames.iter().enumerate() {
+        let diagnostics = frame.diagnostics;
+        let shift = diagnostics.shift.map_or_else(|| "-".to_string(), |shift| shift.to_string());
+        let score = diagnostics.score.map_or_else(|| "-".to_string(), |score| format!("{score:.3}"));
+        let gap = diagnostics.score_gap.map_or_else(|| "-".to_string(), |gap| format!("{gap:.3}"));
+        let warning = frame.warning.as_deref().unwrap_or_default();
+        println!(
+            "{index:05} {:?} height={} shift={shift} score={score} gap={gap} fixed={}/{} {}us {warning}",
+            frame.status, frame.height, diagnostics.fixed_top, diagnostics.fixed_bottom, diagnostics.elapsed_micros
+        );
+    }
+
+    if let Some(report_path) = report {
+        if let Err(err) = outcome.report.export(report_path) {
+            return fail(&format!("Failed to write report '{}': {err}", report_path.display()));
+        }
+        println!("report: {}", report_path.display());
+    }
+
+    let Some(image) = outcome.image else {
+        eprintln!("No frames were stitched");
+        return EXIT_NOT_FOUND;
+    };
+    let out = out.map_or_else(|| dir.join("stitched.png"), Path::to_path_buf);
+    match shell::save_image_to_path(&image, &out) {
+        Ok(path) => {
+            println!("{path}");
+            EXIT_SUCCESS
+        }
+        Err(err) => fail(&err),
+    }
+}
+
+fn fail(message: &str) -> u8 {
+    eprintln!("{message}");
+    EXIT_FAILED
+}
@@ -1,23 +1,187 @@
+use crate::services::settings::MAX_CAPTURE_DELAY_SECONDS;
+use std::path::PathBuf;
 use std::process::ExitCode;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+const USAGE: &str = "Usage: MinnowSnap [run | shutdown | capture [--region x,y,w,h] [--out FILE] [--delay SECS] | ocr IMAGE [--json] | qr IMAGE | control COMMAND | replay-long-capture DIR [--out FILE] [--report FILE]]\n\
+                     Control commands: capture, quick-capture, delayed-capture, open-preferences, open-history, pin IMAGE";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Command {
     Run,
     Shutdown,
+    /// Captures without opening the overlay and prints the saved path.
+    ///
+    /// `region` is in logical desktop coordinates; without it the monitor
+    /// under the cursor (or the whole desktop, per settings) is captured.
+    /// Without `out` the image goes to the save directory like a normal save.
+    /// `delay_seconds` waits before the screen is grabbed.
+    Capture {
+        region: Option<Region>,
+        out: Option<PathBuf>,
+        delay_seconds: u32,
+    },
+    /// Prints recognized text, one block per line, or the blocks as JSON.
+    Ocr {
+        image: PathBuf,
+        json: bool,
+    },
+    /// Prints the decoded content of the first QR code found.
+    Qr {
+        image: PathBuf,
+    },
+    /// Asks the running instance to act as if its hotkey or tray item was
+    /// used, for desktops where global hotkeys are unavailable.
+    Control(RemoteCommand),
+    /// Stitches a recorded long-capture session again and prints the result
+    /// of every frame. Without `out` the image is saved next to the frames;
+    /// `report` also writes the stitch diagnostics as JSON.
+    ReplayLongCapture {
+        dir: PathBuf,
+        out: Option<PathBuf>,
+        report: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RemoteCommand {
+    Capture,
+    QuickCapture,
+    /// Counts down the delay configured in preferences, then captures.
+    DelayedCapture,
+    OpenPreferences,
+    OpenHistory,
+    Pin(PathBuf),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Region {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Region {
+    fn parse(value: &str) -> Result<Self, String> {
+        let parts = value.split(',').map(|part| part.trim().parse::<i32>()).collect::<Result<Vec<_>, _>>();
+        match parts.as_deref() {
+            Ok(&[x, y, width, height]) if width > 0 && height > 0 => Ok(Self { x, y, width, height }),
+            _ => Err(format!("Invalid region '{value}'. Expected x,y,w,h with a positive width and height")),
+        }
+    }
 }
 
 pub fn parse_command_from(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
     match args.next().as_deref() {
         None | Some("run") => Ok(Command::Run),
         Some("shutdown") => Ok(Command::Shutdown),
-        Some(other) => Err(format!("Unknown command '{other}'. Supported commands: run, shutdown")),
+        Some("capture") => parse_capture(args),
+        Some("ocr") => parse_image_command("ocr", args, true).map(|(image, json)| Command::Ocr { image, json }),
+        Some("qr") => parse_image_command("qr", args, false).map(|(image, _)| Command::Qr { image }),
+        Some("control") => parse_control(args),
+        Some("replay-long-capture") => parse_replay_long_capture(args),
+        Some(other) => Err(format!(
+            "Unknown command '{other}'. Supported commands: run, shutdown, capture, ocr, qr, control, replay-long-capture\n{USAGE}"
+        )),
     }
 }
 
+fn parse_capture(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut region = None;
+    let mut out = None;
+    let mut delay_seconds = 0;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--region" => region = Some(Region::parse(&option_value("--region", &mut args)?)?),
+            "--out" => out = Some(PathBuf::from(option_value("--out", &mut args)?)),
+            "--delay" => delay_seconds = parse_delay(&option_value("--delay", &mut args)?)?,
+            other => return Err(unexpected_argument("capture", other)),
+        }
+    }
+
+    Ok(Command::Capture { region, out, delay_seconds })
+}
+
+fn parse_delay(value: &str) -> Result<u32, String> {
+    value
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|seconds| *seconds <= MAX_CAPTURE_DELAY_SECONDS)
+        .ok_or_else(|| format!("Invalid delay '{value}'. Expected whole seconds from 0 to {MAX_CAPTURE_DELAY_SECONDS}"))
+}
+
+fn parse_control(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let command = match args.next().as_deref() {
+        Some("capture") => RemoteCommand::Capture,
+        Some("quick-capture") => RemoteCommand::QuickCapture,
+        Some("delayed-capture") => RemoteCommand::DelayedCapture,
+        Some("open-preferences") => RemoteCommand::OpenPreferences,
+        Some("open-history") => RemoteCommand::OpenHistory,
+        Some("pin") => {
+            let (image, _) = parse_image_command("control pin", args, false)?;
+            return Ok(Command::Control(RemoteCommand::Pin(image)));
+        }
+        Some(other) => return Err(format!("Unknown control command '{other}'\n{USAGE}")),
+        None => return Err(format!("'control' needs a command\n{USAGE}")),
+    };
+
+    match args.next() {
+        Some(arg) => Err(unexpected_argument("control", &arg)),
+        None => Ok(Command::Control(command)),
+    }
+}
+
+fn parse_replay_long_capture(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut dir = None;
+    let mut out = None;
+    let mut report = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => out = Some(PathBuf::from(option_value("--out", &mut args)?)),
+            "--report" => report = Some(PathBuf::from(option_value("--report", &mut args)?)),
+            other if !other.starts_with("--") && dir.is_none() => dir = Some(PathBuf::from(other)),
+            other => return Err(unexpected_argument("replay-long-capture", other)),
+        }
+    }
+
+    let dir = dir.ok_or_else(|| format!("'replay-long-capture' needs a recording directory\n{USAGE}"))?;
+    Ok(Command::ReplayLongCapture { dir, out, report })
+}
+
+fn parse_image_command(command: &str, args: impl Iterator<Item = String>, accepts_json: bool) -> Result<(PathBuf, bool), String> {
+    let mut image = None;
+    let mut json = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--json" if accepts_json => json = true,
+            other if !other.starts_with("--") && image.is_none() => image = Some(PathBuf::from(other)),
+            other => return Err(unexpected_argument(command, other)),
+        }
+    }
+
+    let image = image.ok_or_else(|| format!("'{command}' needs an image path\n{USAGE}"))?;
+    Ok((image, json))
+}
+
+fn option_value(option: &str, args: &mut impl Iterator<Item = String>) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("Option '{option}' needs a value\n{USAGE}"))
+}
+
+fn unexpected_argument(command: &str, arg: &str) -> String {
+    format!("Unexpected argument '{arg}' for '{command}'\n{USAGE}")
+}
+
 pub fn parse_command() -> Result<Command, String> {
     parse_command_from(std::env::args().skip(1))
 }
 
+/// Exit codes: `0` success, `1` invalid arguments (reported by `main`), `2`
+/// nothing found (no running instance, no text, no QR code, no recorded
+/// frames) and `3` failure.
 pub fn run_command(command: Command) -> ExitCode {
     match command {
         Command::Run => {
@@ -25,14 +189,24 @@ pub fn run_command(command: Command) -> ExitCode {
             ExitCode::SUCCESS
         }
         Command::Shutdown => ExitCode::from(super::runtime::shutdown_running_instance()),
+        Command::Capture { region, out, delay_seconds } => ExitCode::from(super::cli::capture(region, out.as_deref(), delay_seconds)),
+        Command::Ocr { image, json } => ExitCode::from(super::cli::ocr(&image, json)),
+        Command::Qr { image } => ExitCode::from(super::cli::qr(&image)),
+        Command::Control(command) => ExitCode::from(super::runtime::send_remote_command(command)),
+        Command::ReplayLongCapture { dir, out, report } => ExitCode::from(super::cli::replay_long_capture(&dir, out.as_deref(), report.as_deref())),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Command, parse_command_from, run_command};
+    use super::{Command, Region, RemoteCommand, parse_command_from, run_command};
+    use std::path::PathBuf;
     use std::process::ExitCode;
 
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_command_from(args.iter().map(ToString::to_string))
+    }
+
     #[test]
     fn parse_command_defaults_to_run() {
         let cmd = parse_command_from(Vec::<String>::new().into_iter()).expect("command");
@@ -51,6 +225,104 @@ mod tests {
         assert!(err.contains("Unknown command"));
     }
 
+    #[test]
+    fn parse_capture_reads_region_and_output() {
+        assert_eq!(
+            parse(&["capture"]).expect("command"),
+            Command::Capture {
+                region: None,
+                out: None,
+                delay_seconds: 0,
+            }
+        );
+        assert_eq!(
+            parse(&["capture", "--region", "10,-20,300,200", "--out", "shot.png", "--delay", "5"]).expect("command"),
+            Command::Capture {
+                region: Some(Region {
+                    x: 10,
+                    y: -20,
+                    width: 300,
+                    height: 200,
+                }),
+                out: Some(PathBuf::from("shot.png")),
+                delay_seconds: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_capture_rejects_bad_regions_and_missing_values() {
+        assert!(parse(&["capture", "--region", "1,2,3"]).is_err());
+        assert!(parse(&["capture", "--region", "0,0,0,10"]).is_err());
+        assert!(parse(&["capture", "--out"]).expect_err("missing value").contains("--out"));
+        assert!(parse(&["capture", "--json"]).is_err());
+        assert!(parse(&["capture", "--delay", "-1"]).is_err());
+        assert!(parse(&["capture", "--delay", "3600"]).is_err());
+    }
+
+    #[test]
+    fn parse_image_commands_need_exactly_one_image() {
+        assert_eq!(
+            parse(&["ocr", "--json", "scan.png"]).expect("command"),
+            Command::Ocr {
+                image: PathBuf::from("scan.png"),
+                json: true,
+            }
+        );
+        assert_eq!(
+            parse(&["qr", "code.png"]).expect("command"),
+            Command::Qr {
+                image: PathBuf::from("code.png"),
+            }
+        );
+        assert!(parse(&["ocr"]).is_err());
+        assert!(parse(&["qr", "a.png", "b.png"]).is_err());
+        assert!(parse(&["qr", "code.png", "--json"]).is_err());
+    }
+
+    #[test]
+    fn parse_control_reads_remote_commands() {
+        assert_eq!(
+            parse(&["control", "quick-capture"]).expect("command"),
+            Command::Control(RemoteCommand::QuickCapture)
+        );
+        assert_eq!(
+            parse(&["control", "pin", "shot.png"]).expect("command"),
+            Command::Control(RemoteCommand::Pin(PathBuf::from("shot.png")))
+        );
+        assert_eq!(
+            parse(&["control", "delayed-capture"]).expect("command"),
+            Command::Control(RemoteCommand::DelayedCapture)
+        );
+        assert!(parse(&["control"]).is_err());
+        assert!(parse(&["control", "shutdown"]).is_err());
+        assert!(parse(&["control", "capture", "now"]).is_err());
+        assert!(parse(&["control", "pin"]).is_err());
+    }
+
+    #[test]
+    fn parse_replay_long_capture_reads_directory_and_output() {
+        assert_eq!(
+            parse(&["replay-long-capture", "session", "--out", "stitched.png"]).expect("command"),
+            Command::ReplayLongCapture {
+                dir: PathBuf::from("session"),
+                out: Some(PathBuf::from("stitched.png")),
+                report: None,
+            }
+        );
+        assert_eq!(
+            parse(&["replay-long-capture", "--report", "report.json", "session"]).expect("command"),
+            Command::ReplayLongCapture {
+                dir: PathBuf::from("session"),
+                out: None,
+                report: Some(PathBuf::from("report.json")),
+            }
+        );
+        assert!(parse(&["replay-long-capture", "session", "--report"]).is_err());
+        assert!(parse(&["replay-long-capture"]).is_err());
+        assert!(parse(&["replay-long-capture", "a", "b"]).is_err());
+    }
+
     #[test]
     fn run_command_has_stable_exit_code_signature() {
         let _run_command: fn(Command) -> ExitCode = run_command;
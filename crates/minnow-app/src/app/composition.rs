@@ -1,14 +1,23 @@
 use gpui::{App, Application};
+use std::path::PathBuf;
 use tokio::sync::broadcast;
 use tracing::info;
 
 use super::workflows;
 #[cfg(target_os = "windows")]
 use crate::platform::notify::init_windows_notification_app_id;
-use crate::platform::{self, hotkey::HotkeyActionSink, shutdown, system::install_ui_system_actions, tray::TrayActions};
+use crate::platform::{
+    self,
+    control::{self, ControlActions},
+    hotkey::HotkeyActionSink,
+    system::install_ui_system_actions,
+    tray::TrayActions,
+};
+use crate::services::capture::{QuickCaptureMode, action::PinCaptureRequest};
+use crate::services::hotkeys::HotkeyAction;
 use crate::services::{assets::AppAssets, settings};
 use crate::ui::{
-    features::{overlay, pin, preferences},
+    features::{history, overlay, pin, preferences},
     support::{appearance, locale},
 };
 
@@ -41,18 +50,28 @@ pub(super) fn run_application(set_auto_start: fn(bool), _hide_dock_icon: fn()) {
         pin::bind_keys(cx);
         pin::install(cx);
         set_auto_start(settings::auto_start_enabled());
-        platform::hotkey::install_hotkey_service(
-            cx,
-            HotkeyActionSink::new(open_capture_overlay, workflows::run_quick_capture_with_notification),
-        );
+        platform::hotkey::install_hotkey_service(cx, HotkeyActionSink::new(run_hotkey_action));
         let overlay_handle = overlay::OverlayHandle::new(cx);
         cx.set_global(overlay_handle);
+        control::install_control_actions(
+            cx,
+            ControlActions::new(
+                open_capture_overlay,
+                run_quick_capture,
+                start_delayed_capture,
+                open_history_window,
+                open_preferences_window,
+                open_pin_window,
+            ),
+        );
 
         if let Err(err) = platform::tray::SystemTray::install(
             cx,
             TrayActions::new(
                 open_capture_overlay,
-                workflows::run_quick_capture_with_notification,
+                run_quick_capture,
+                start_delayed_capture,
+                open_history_window,
                 open_preferences_window,
             ),
         ) {
@@ -69,11 +88,11 @@ pub(super) fn run_application(set_auto_start: fn(bool), _hide_dock_icon: fn()) {
 }
 
 fn install_shutdown_listener(cx: &mut App) {
-    let Some(mut shutdown_rx) = shutdown::subscribe() else {
+    let Some(mut shutdown_rx) = control::subscribe() else {
         tracing::warn!("Shutdown control plane is not initialized; skip shutdown listener.");
         return;
     };
-    let Some(shutdown_token) = shutdown::cancellation_token() else {
+    let Some(shutdown_token) = control::cancellation_token() else {
         tracing::warn!("Shutdown cancellation token is unavailable; skip shutdown listener.");
         return;
     };
@@ -116,6 +135,52 @@ fn open_capture_overlay(cx: &mut gpui::App) {
     overlay::open_window(cx);
 }
 
+fn start_long_capture(cx: &mut gpui::App) {
+    let overlay_handle = cx.global::<overlay::OverlayHandle>().clone();
+    overlay_handle.prepare_long_capture(cx);
+    overlay::open_window(cx);
+}
+
+fn run_hotkey_action(cx: &mut gpui::App, action: HotkeyAction) {
+    match action {
+        HotkeyAction::Capture => open_capture_overlay(cx),
+        HotkeyAction::QuickCapture => run_quick_capture(cx),
+        HotkeyAction::DelayedCapture => start_delayed_capture(cx),
+        HotkeyAction::WindowCapture => run_quick_capture_mode(cx, QuickCaptureMode::WindowUnderCursor),
+        HotkeyAction::FocusedWindowCapture => run_quick_capture_mode(cx, QuickCaptureMode::FocusedWindow),
+        HotkeyAction::RepeatRegionCapture => run_quick_capture_mode(cx, QuickCaptureMode::LastRegion),
+        HotkeyAction::PinClipboardImage => workflows::pin_clipboard_image(cx, open_capture_pin),
+        HotkeyAction::CloseAllPins => pin::close_all_windows(cx),
+        HotkeyAction::TogglePinVisibility => pin::toggle_visibility(cx),
+        HotkeyAction::LongCapture => start_long_capture(cx),
+        HotkeyAction::OcrLastCapture => workflows::ocr_last_capture(cx),
+    }
+}
+
+fn run_quick_capture(cx: &mut gpui::App) {
+    run_quick_capture_mode(cx, QuickCaptureMode::FullScreen);
+}
+
+fn run_quick_capture_mode(cx: &mut gpui::App, mode: QuickCaptureMode) {
+    workflows::run_quick_capture(cx, mode, open_capture_pin);
+}
+
+fn start_delayed_capture(cx: &mut gpui::App) {
+    workflows::start_delayed_capture(cx, open_capture_overlay, run_quick_capture);
+}
+
+fn open_history_window(cx: &mut gpui::App) {
+    history::open_window(cx);
+}
+
 fn open_preferences_window(cx: &mut gpui::App) {
     preferences::open_window(cx);
 }
+
+fn open_pin_window(cx: &mut gpui::App, path: PathBuf) {
+    pin::open_window(cx, pin::PinRequest::new(path, None, false));
+}
+
+fn open_capture_pin(cx: &mut gpui::App, request: PinCaptureRequest) {
+    pin::open_window(cx, pin::PinRequest::from_capture(request));
+}
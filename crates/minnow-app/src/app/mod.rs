@@ -1,4 +1,5 @@
 mod bootstrap;
+mod cli;
 mod commands;
 mod composition;
 mod runtime;
@@ -1,8 +1,12 @@
 #[cfg(target_os = "macos")]
 use super::bootstrap::hide_dock_icon;
 use super::bootstrap::{ensure_single_instance, get_instance_id, set_auto_start};
+use super::commands::RemoteCommand;
 use super::composition::run_application;
-use crate::platform::{logging, shutdown};
+use crate::platform::{
+    control::{self, ControlRequest},
+    logging,
+};
 use crate::services::settings;
 use tracing::info;
 
@@ -15,12 +19,10 @@ pub fn run() {
         return;
     }
 
-    shutdown::init_control_plane();
+    control::init_control_plane();
     #[cfg(target_os = "windows")]
-    {
-        shutdown::install_ctrl_c_handler();
-        shutdown::start_control_pipe_server();
-    }
+    control::install_ctrl_c_handler();
+    control::start_control_server();
 
     #[cfg(target_os = "macos")]
     run_application(set_auto_start, hide_dock_icon);
@@ -28,26 +30,39 @@ pub fn run() {
     run_application(set_auto_start, noop_hide_dock_icon);
 
     settings::flush();
-    shutdown::clear_control_plane();
+    control::clear_control_plane();
 }
 
 pub fn shutdown_running_instance() -> u8 {
-    #[cfg(target_os = "windows")]
-    {
-        match shutdown::shutdown_running_instance() {
-            Ok(()) => 0,
-            Err(shutdown::ShutdownClientError::NotRunning) => 2,
+    send_control_request(ControlRequest::Shutdown)
+}
+
+pub fn send_remote_command(command: RemoteCommand) -> u8 {
+    let request = match command {
+        RemoteCommand::Capture => ControlRequest::Capture,
+        RemoteCommand::QuickCapture => ControlRequest::QuickCapture,
+        RemoteCommand::DelayedCapture => ControlRequest::DelayedCapture,
+        RemoteCommand::OpenPreferences => ControlRequest::OpenPreferences,
+        RemoteCommand::OpenHistory => ControlRequest::OpenHistory,
+        RemoteCommand::Pin(path) => match std::path::absolute(&path) {
+            Ok(path) => ControlRequest::Pin(path),
             Err(err) => {
-                eprintln!("Failed to request graceful shutdown: {err}");
-                3
+                eprintln!("Failed to resolve '{}': {err}", path.display());
+                return 3;
             }
-        }
-    }
+        },
+    };
+    send_control_request(request)
+}
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        eprintln!("Shutdown command is only supported on Windows.");
-        3
+fn send_control_request(request: ControlRequest) -> u8 {
+    match control::send_control_request(&request) {
+        Ok(()) => 0,
+        Err(control::ControlClientError::NotRunning) => 2,
+        Err(err) => {
+            eprintln!("Failed to send {request:?} to the running instance: {err}");
+            3
+        }
     }
 }
 
@@ -1,29 +1,66 @@
-use crate::platform::shell;
+use crate::platform::{shell, update_app};
 use crate::services::capture::{
-    action::{ActionContext, ActionResult, CaptureAction, CaptureActionPlan, PinCaptureRequest},
+    QuickCaptureMode,
+    action::{ActionContext, ActionResult, CaptureAction, CaptureActionPlan, CaptureInputMode, HistorySource, PinCaptureRequest},
+    filename::CaptureOrigin,
+    last_selection, logical_cursor_position,
+    long_capture::StitchedCapture,
+    screen_recording::Animation,
+    select_capture_target, select_region_target,
     service::CaptureService,
 };
 use crate::services::geometry::Rect;
-use crate::services::i18n;
+use crate::services::history::{self, HistoryLimits, PendingRecord};
+use crate::services::ocr::service::{self as ocr_service, OcrImageInput};
+use crate::services::settings::{DelayedCaptureMode, QuickCaptureDestination, RecordingFormat};
+use crate::services::{i18n, settings};
+use crate::ui::features::overlay::window_catalog::{self, WindowInfo};
+use gpui::App;
+use image::RgbaImage;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use tracing::{error, info};
 
+static DELAYED_CAPTURE_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// What a fresh capture needs to be recorded once its plan succeeds.
+struct PendingHistory {
+    source: HistorySource,
+    rect: Rect,
+    input_mode: CaptureInputMode,
+    origin: CaptureOrigin,
+}
+
 /// Executes the platform effects for a domain capture plan.
 ///
 /// The capture service never reaches into clipboard, storage, or notification
 /// adapters. This workflow is the single owner of that translation.
-pub(crate) fn execute_capture_action(action: CaptureAction, context: ActionContext) -> ActionResult {
+pub(crate) fn execute_capture_action(action: CaptureAction, mut context: ActionContext) -> ActionResult {
+    let history = context.history.take().map(|source| PendingHistory {
+        source,
+        rect: context.rect,
+        input_mode: context.input_mode,
+        origin: context.origin.clone(),
+    });
     let plan = action.plan(context);
     match plan {
         CaptureActionPlan::CopyImage(image) => {
             if shell::copy_image_to_clipboard(&image) {
+                record_history(history, &image);
                 ActionResult::Copied
             } else {
                 ActionResult::Error(i18n::capture::copy_failed())
             }
         }
-        CaptureActionPlan::SaveImage { image, save_path_override } => match shell::save_image_to_user_dir(&image, save_path_override) {
+        CaptureActionPlan::SaveImage {
+            image,
+            save_path_override,
+            origin,
+        } => match shell::save_image_to_user_dir(&image, save_path_override, &origin) {
             Ok(path) => {
                 shell::play_shutter();
+                record_history(history, &image);
                 ActionResult::Saved(path)
             }
             Err(error) => ActionResult::Error(error),
@@ -37,11 +74,13 @@ pub(crate) fn execute_capture_action(action: CaptureAction, context: ActionConte
                 return ActionResult::Error(i18n::capture::pin_failed());
             };
 
+            let history_id = record_history(history, &image);
             ActionResult::PinRequested(PinCaptureRequest {
                 image_path,
                 source_bounds,
                 auto_ocr,
                 ocr_image: image,
+                history_id,
             })
         }
         CaptureActionPlan::Text(content) => ActionResult::OcrResult(content),
@@ -51,30 +90,288 @@ pub(crate) fn execute_capture_action(action: CaptureAction, context: ActionConte
     }
 }
 
-/// Runs the tray/global-hotkey quick capture path and owns its user feedback.
-pub(crate) fn run_quick_capture_with_notification() {
-    info!("Starting quick capture workflow");
-    let copied = CaptureService::capture_region(Rect::empty()).is_some_and(|image| {
-        let copied = shell::copy_image_to_clipboard(&image);
-        if copied {
+/// Saves a long capture that outgrew memory as a PNG streamed from its
+/// strips. It is too large for the history, so none is recorded.
+pub(crate) fn save_long_capture(capture: &StitchedCapture, origin: &CaptureOrigin) -> ActionResult {
+    match shell::save_streamed_file_to_user_dir(capture.dimensions(), origin, "png", |path| capture.write_png(path)) {
+        Ok(path) => {
+            info!("Long capture streamed to {path}");
             shell::play_shutter();
+            ActionResult::Saved(path)
+        }
+        Err(error) => ActionResult::Error(error),
+    }
+}
+
+/// Saves a screen recording as an animation in `format`. Like long
+/// captures, recordings are not kept in the history.
+pub(crate) fn save_recording(animation: &Animation, format: RecordingFormat, origin: &CaptureOrigin) -> ActionResult {
+    let write = |path: &std::path::Path| animation.write(path, format);
+    match shell::save_streamed_file_to_user_dir(animation.dimensions(), origin, format.extension(), write) {
+        Ok(path) => {
+            info!("Recording saved to {path}");
+            ActionResult::Saved(path)
         }
-        copied
+        Err(error) => ActionResult::Error(error),
+    }
+}
+
+/// Queues a successful capture for the history store and returns its entry id.
+fn record_history(pending: Option<PendingHistory>, image: &Arc<RgbaImage>) -> Option<String> {
+    let pending = pending?;
+    let settings = settings::history_settings();
+    if !settings.enabled {
+        return None;
+    }
+
+    let annotations = pending.source.annotations;
+    let base_image = annotations.as_ref().and(pending.source.base_image).and_then(|base| {
+        CaptureService::resolve_rgba_image(base, pending.rect, pending.input_mode).map(|resolved| Arc::unwrap_or_clone(resolved.into_arc()))
     });
+    let created_at_millis = history::now_millis();
+    let id = history::next_entry_id(created_at_millis);
+    history::record_in_background(PendingRecord {
+        id: id.clone(),
+        created_at_millis,
+        image: image.clone(),
+        annotations: base_image.is_some().then_some(annotations).flatten(),
+        base_image,
+        source_bounds: pending.rect.has_area().then_some(pending.rect),
+        origin: pending.origin,
+        limits: HistoryLimits::from(&settings),
+    });
+    Some(id)
+}
+
+/// A quick capture taken without the overlay.
+struct QuickCapture {
+    image: RgbaImage,
+    /// Captured area relative to the capture target, in logical pixels.
+    region: Rect,
+    origin: CaptureOrigin,
+}
+
+/// Runs the tray/hotkey/control quick capture path, delivers the image to the
+/// configured destination and owns the user feedback.
+pub(crate) fn run_quick_capture(cx: &mut App, mode: QuickCaptureMode, open_pin: fn(&mut App, PinCaptureRequest)) {
+    info!("Starting quick capture workflow ({mode:?})");
+    let capture = match take_quick_capture(mode) {
+        Ok(capture) => capture,
+        Err(message) => {
+            error!("Quick capture workflow failed: {message}");
+            notify_action_failed(message);
+            return;
+        }
+    };
+
+    let destination = settings::general_settings().quick_capture_destination;
+    let action = match destination {
+        QuickCaptureDestination::Clipboard => CaptureAction::Copy,
+        QuickCaptureDestination::File => CaptureAction::Save,
+        QuickCaptureDestination::Pin => CaptureAction::Pin,
+    };
+    let context = ActionContext::full_image_data(Arc::new(capture.image))
+        .with_origin(capture.origin)
+        .with_history(HistorySource::default());
+
+    match execute_capture_action(action, context) {
+        ActionResult::Copied => {
+            info!("Quick capture image copied to clipboard");
+            shell::play_shutter();
+            shell::show_notification(
+                i18n::app::capture_name().as_str(),
+                i18n::notify::quick_capture_copied().as_str(),
+                shell::NotificationType::Copy,
+            );
+        }
+        ActionResult::Saved(path) => {
+            info!("Quick capture image saved to {path}");
+            shell::show_notification(
+                i18n::app::capture_name().as_str(),
+                i18n::notify::saved_image(path).as_str(),
+                shell::NotificationType::Save,
+            );
+        }
+        ActionResult::PinRequested(mut request) => {
+            info!("Quick capture image pinned");
+            shell::play_shutter();
+            request.source_bounds = capture.region;
+            open_pin(cx, request);
+        }
+        ActionResult::Error(message) => {
+            error!("Quick capture workflow failed: {message}");
+            notify_action_failed(i18n::notify::quick_capture_failed());
+        }
+        result => error!("Unexpected quick capture result: {result:?}"),
+    }
+}
+
+fn take_quick_capture(mode: QuickCaptureMode) -> Result<QuickCapture, String> {
+    let failed = i18n::notify::quick_capture_failed;
+    let (desktop_region, origin) = match mode {
+        QuickCaptureMode::FullScreen => {
+            let target = select_capture_target(shell::cursor_position()).ok_or_else(failed)?;
+            let bounds = target.logical_bounds();
+            let image = CaptureService::capture_region(Rect::empty()).ok_or_else(failed)?;
+            return Ok(QuickCapture {
+                image,
+                region: Rect::new(0, 0, bounds.width, bounds.height),
+                origin: CaptureOrigin::default(),
+            });
+        }
+        QuickCaptureMode::WindowUnderCursor => {
+            let windows = window_catalog::fetch_desktop_windows();
+            // Where the cursor cannot be queried, such as on Wayland, the
+            // window the user is working in is the closest match.
+            let index = match shell::cursor_position() {
+                Some(cursor) => {
+                    logical_cursor_position(cursor).and_then(|(x, y)| window_catalog::find_window_at(&windows, f64::from(x), f64::from(y)))
+                }
+                None => window_catalog::find_focused_window(&windows),
+            };
+            let window = index.map(|index| &windows[index]).ok_or_else(i18n::notify::quick_capture_no_window)?;
+            (window.rect(), window_origin(window))
+        }
+        QuickCaptureMode::FocusedWindow => {
+            let windows = window_catalog::fetch_desktop_windows();
+            let window = window_catalog::find_focused_window(&windows)
+                .map(|index| &windows[index])
+                .ok_or_else(i18n::notify::quick_capture_no_window)?;
+            (window.rect(), window_origin(window))
+        }
+        QuickCaptureMode::LastRegion => (
+            last_selection().ok_or_else(i18n::notify::quick_capture_no_region)?,
+            CaptureOrigin::default(),
+        ),
+    };
 
-    if copied {
-        info!("Quick capture image copied to clipboard");
+    let region = select_region_target(desktop_region).ok_or_else(failed)?;
+    let image = CaptureService::capture_region(region).ok_or_else(failed)?;
+    Ok(QuickCapture { image, region, origin })
+}
+
+/// Fills the `{app}` and `{window_title}` filename tokens for a window capture.
+fn window_origin(window: &WindowInfo) -> CaptureOrigin {
+    let non_empty = |value: &str| (!value.trim().is_empty()).then(|| value.to_string());
+    CaptureOrigin {
+        app_name: non_empty(&window.app_name),
+        window_title: non_empty(&window.title),
+    }
+}
+
+fn notify_action_failed(message: String) {
+    shell::show_notification(i18n::app::name().as_str(), message.as_str(), shell::NotificationType::Info);
+}
+
+/// Pins the image currently on the clipboard, centered on screen.
+pub(crate) fn pin_clipboard_image(cx: &mut App, open_pin: fn(&mut App, PinCaptureRequest)) {
+    let Some(image) = shell::read_image_from_clipboard() else {
+        notify_action_failed(i18n::notify::clipboard_no_image());
+        return;
+    };
+
+    match execute_capture_action(CaptureAction::Pin, ActionContext::full_image_data(Arc::new(image))) {
+        ActionResult::PinRequested(request) => {
+            info!("Clipboard image pinned");
+            open_pin(cx, request);
+        }
+        ActionResult::Error(message) => {
+            error!("Pinning the clipboard image failed: {message}");
+            notify_action_failed(message);
+        }
+        result => error!("Unexpected clipboard pin result: {result:?}"),
+    }
+}
+
+/// Recognizes text in the newest history capture and copies it. Text that
+/// was already recognized for that capture is reused.
+pub(crate) fn ocr_last_capture(cx: &mut App) {
+    if !ocr_service::is_enabled() || !ocr_service::mobile_models_ready() {
+        notify_action_failed(i18n::notify::ocr_unavailable());
+        return;
+    }
+
+    let store = history::store();
+    let Some((entry, path)) = store
+        .entries()
+        .into_iter()
+        .next()
+        .and_then(|entry| store.capture_path(&entry.id).map(|path| (entry, path)))
+    else {
+        notify_action_failed(i18n::notify::no_capture_history());
+        return;
+    };
+
+    if let Some(text) = entry.ocr_text {
+        copy_recognized_text(text);
+        return;
+    }
+
+    info!("Recognizing text in capture {}", entry.id);
+    cx.spawn(
+        async move |_| match ocr_service::recognize_image_blocks(OcrImageInput::Path(path)).await {
+            Ok(blocks) => {
+                let text = blocks.iter().map(|block| block.text.as_str()).collect::<Vec<_>>().join("\n");
+                if text.trim().is_empty() {
+                    notify_action_failed(i18n::notify::ocr_no_text());
+                    return;
+                }
+                history::set_ocr_text_in_background(entry.id, text.clone());
+                copy_recognized_text(text);
+            }
+            Err(err) => {
+                error!("OCR of the last capture failed: {err}");
+                notify_action_failed(i18n::notify::ocr_failed());
+            }
+        },
+    )
+    .detach();
+}
+
+fn copy_recognized_text(text: String) {
+    if shell::copy_text_to_clipboard(text) {
         shell::show_notification(
             i18n::app::capture_name().as_str(),
-            i18n::notify::quick_capture_copied().as_str(),
+            i18n::notify::copied_text().as_str(),
             shell::NotificationType::Copy,
         );
     } else {
-        error!("Quick capture workflow failed");
-        shell::show_notification(
-            i18n::app::name().as_str(),
-            i18n::notify::quick_capture_failed().as_str(),
-            shell::NotificationType::Info,
-        );
+        notify_action_failed(i18n::capture::copy_failed());
     }
 }
+
+/// Starts the tray/hotkey/control timer capture. The screen is only frozen
+/// once the countdown ends, so menus, tooltips and hover states opened in the
+/// meantime end up in the frame. A second request while counting down is
+/// ignored.
+pub(crate) fn start_delayed_capture(cx: &mut App, open_capture_overlay: fn(&mut App), run_quick_capture: fn(&mut App)) {
+    if DELAYED_CAPTURE_PENDING.swap(true, Ordering::SeqCst) {
+        info!("Delayed capture is already counting down");
+        return;
+    }
+
+    let general = settings::general_settings();
+    let seconds = general.capture_delay_seconds.max(1);
+    let mode = general.delayed_capture_mode;
+    info!("Starting delayed capture in {seconds}s ({})", mode.as_str());
+    shell::show_notification(
+        i18n::app::capture_name().as_str(),
+        i18n::notify::delayed_capture_countdown(seconds).as_str(),
+        shell::NotificationType::Info,
+    );
+
+    cx.spawn(async move |cx| {
+        cx.background_executor().timer(Duration::from_secs(u64::from(seconds))).await;
+        DELAYED_CAPTURE_PENDING.store(false, Ordering::SeqCst);
+
+        match mode {
+            DelayedCaptureMode::Overlay => {
+                update_app(cx, open_capture_overlay);
+            }
+            DelayedCaptureMode::QuickCapture => {
+                update_app(cx, run_quick_capture);
+            }
+        }
+    })
+    .detach();
+}
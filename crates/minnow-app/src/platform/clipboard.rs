@@ -51,3 +51,27 @@ pub fn copy_text_to_clipboard(text: String) -> bool {
         true
     }
 }
+
+pub fn read_image_from_clipboard() -> Option<RgbaImage> {
+    let mut clipboard = match Clipboard::new() {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Failed to initialize clipboard: {}", e);
+            return None;
+        }
+    };
+
+    let data = match clipboard.get_image() {
+        Ok(data) => data,
+        Err(e) => {
+            info!("Clipboard has no image: {}", e);
+            return None;
+        }
+    };
+
+    let image = RgbaImage::from_raw(data.width as u32, data.height as u32, data.bytes.into_owned());
+    if image.is_none() {
+        error!("Clipboard image has an unexpected size ({}x{})", data.width, data.height);
+    }
+    image
+}
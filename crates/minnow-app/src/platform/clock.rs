@@ -0,0 +1,31 @@
+/// Returns the current offset of local time from UTC in seconds, or `0` when
+/// the platform cannot report it.
+#[cfg(target_os = "windows")]
+pub fn local_utc_offset_seconds() -> i32 {
+    use windows::Win32::System::Time::{GetTimeZoneInformation, TIME_ZONE_INFORMATION};
+
+    const TIME_ZONE_ID_INVALID: u32 = u32::MAX;
+    const TIME_ZONE_ID_DAYLIGHT: u32 = 2;
+
+    let mut info = TIME_ZONE_INFORMATION::default();
+    let bias_minutes = match unsafe { GetTimeZoneInformation(&mut info) } {
+        TIME_ZONE_ID_INVALID => {
+            tracing::warn!("Failed to query time zone; using UTC");
+            return 0;
+        }
+        TIME_ZONE_ID_DAYLIGHT => info.Bias + info.DaylightBias,
+        _ => info.Bias + info.StandardBias,
+    };
+    -bias_minutes * 60
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn local_utc_offset_seconds() -> i32 {
+    let now = unsafe { libc::time(std::ptr::null_mut()) };
+    let mut tm = unsafe { std::mem::zeroed::<libc::tm>() };
+    if unsafe { libc::localtime_r(&now, &mut tm) }.is_null() {
+        tracing::warn!("Failed to query local time; using UTC");
+        return 0;
+    }
+    i32::try_from(tm.tm_gmtoff).unwrap_or(0)
+}
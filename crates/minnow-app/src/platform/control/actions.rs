@@ -0,0 +1,99 @@
+use super::{cancellation_token, control_plane, protocol::ControlRequest};
+use crate::platform::{app_ready, update_app};
+use gpui::{App, AsyncApp};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::mpsc::{UnboundedReceiver, unbounded_channel};
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+#[derive(Clone)]
+pub struct ControlActions {
+    open_capture_overlay: Arc<dyn Fn(&mut App) + Send + Sync>,
+    run_quick_capture: Arc<dyn Fn(&mut App) + Send + Sync>,
+    start_delayed_capture: Arc<dyn Fn(&mut App) + Send + Sync>,
+    open_history: Arc<dyn Fn(&mut App) + Send + Sync>,
+    open_preferences: Arc<dyn Fn(&mut App) + Send + Sync>,
+    open_pin: Arc<dyn Fn(&mut App, PathBuf) + Send + Sync>,
+}
+
+impl ControlActions {
+    pub fn new<F1, F2, F3, F4, F5, F6>(
+        open_capture_overlay: F1,
+        run_quick_capture: F2,
+        start_delayed_capture: F3,
+        open_history: F4,
+        open_preferences: F5,
+        open_pin: F6,
+    ) -> Self
+    where
+        F1: Fn(&mut App) + Send + Sync + 'static,
+        F2: Fn(&mut App) + Send + Sync + 'static,
+        F3: Fn(&mut App) + Send + Sync + 'static,
+        F4: Fn(&mut App) + Send + Sync + 'static,
+        F5: Fn(&mut App) + Send + Sync + 'static,
+        F6: Fn(&mut App, PathBuf) + Send + Sync + 'static,
+    {
+        Self {
+            open_capture_overlay: Arc::new(open_capture_overlay),
+            run_quick_capture: Arc::new(run_quick_capture),
+            start_delayed_capture: Arc::new(start_delayed_capture),
+            open_history: Arc::new(open_history),
+            open_preferences: Arc::new(open_preferences),
+            open_pin: Arc::new(open_pin),
+        }
+    }
+
+    fn handle(&self, request: ControlRequest, cx: &mut AsyncApp) -> bool {
+        if !app_ready(cx) {
+            return false;
+        }
+
+        info!("Control command received: {request:?}");
+        match request {
+            ControlRequest::Capture => update_app(cx, |app| (self.open_capture_overlay)(app)),
+            ControlRequest::QuickCapture => update_app(cx, |app| (self.run_quick_capture)(app)),
+            ControlRequest::DelayedCapture => update_app(cx, |app| (self.start_delayed_capture)(app)),
+            ControlRequest::OpenHistory => update_app(cx, |app| (self.open_history)(app)),
+            ControlRequest::OpenPreferences => update_app(cx, |app| (self.open_preferences)(app)),
+            ControlRequest::Pin(path) => update_app(cx, |app| (self.open_pin)(app, path)),
+            // Answered by the control server itself; never forwarded.
+            ControlRequest::Shutdown => true,
+        }
+    }
+}
+
+/// Routes control commands from other processes (`MinnowSnap control ...`)
+/// to the UI until shutdown.
+pub fn install_control_actions(cx: &mut App, actions: ControlActions) {
+    let (request_tx, request_rx) = unbounded_channel();
+    control_plane::set_command_sink(Some(request_tx));
+    let shutdown_token = cancellation_token().unwrap_or_default();
+    cx.spawn(async move |cx| {
+        control_action_loop(request_rx, shutdown_token, actions, cx).await;
+        control_plane::set_command_sink(None);
+    })
+    .detach();
+}
+
+async fn control_action_loop(
+    mut request_rx: UnboundedReceiver<ControlRequest>,
+    shutdown_token: CancellationToken,
+    actions: ControlActions,
+    cx: &mut AsyncApp,
+) {
+    loop {
+        tokio::select! {
+            _ = shutdown_token.cancelled() => return,
+            request = request_rx.recv() => {
+                let Some(request) = request else {
+                    return;
+                };
+
+                if !actions.handle(request, cx) {
+                    return;
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use super::ShutdownTrigger;
+use super::protocol::ControlRequest;
 use std::sync::{LazyLock, Mutex};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc::UnboundedSender};
 use tokio_util::sync::CancellationToken;
 
 #[derive(Clone)]
@@ -10,6 +11,7 @@ struct ShutdownControlPlane {
 }
 
 static SHUTDOWN_CONTROL: LazyLock<Mutex<Option<ShutdownControlPlane>>> = LazyLock::new(|| Mutex::new(None));
+static COMMAND_SINK: LazyLock<Mutex<Option<UnboundedSender<ControlRequest>>>> = LazyLock::new(|| Mutex::new(None));
 
 pub(super) fn init() {
     let (bus, _) = broadcast::channel(8);
@@ -25,6 +27,23 @@ pub(super) fn clear() {
     if let Ok(mut slot) = SHUTDOWN_CONTROL.lock() {
         *slot = None;
     }
+    set_command_sink(None);
+}
+
+pub(super) fn set_command_sink(sink: Option<UnboundedSender<ControlRequest>>) {
+    if let Ok(mut slot) = COMMAND_SINK.lock() {
+        *slot = sink;
+    }
+}
+
+/// Forwards a request to the UI; `false` until the app has installed its
+/// control actions or after it stopped listening.
+pub(super) fn dispatch(request: ControlRequest) -> bool {
+    COMMAND_SINK
+        .lock()
+        .ok()
+        .and_then(|slot| slot.as_ref().map(|sink| sink.send(request).is_ok()))
+        .unwrap_or(false)
 }
 
 pub(super) fn subscribe() -> Option<broadcast::Receiver<ShutdownTrigger>> {
@@ -0,0 +1,110 @@
+mod actions;
+mod control_plane;
+mod protocol;
+
+#[cfg(unix)]
+mod unix;
+#[cfg(target_os = "windows")]
+mod windows;
+
+use protocol::ControlResponse;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ShutdownTrigger {
+    TrayMenu,
+    #[cfg(target_os = "windows")]
+    CtrlC,
+    ControlCommand,
+}
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+pub fn init_control_plane() {
+    SHUTTING_DOWN.store(false, Ordering::SeqCst);
+    control_plane::init();
+}
+
+pub fn clear_control_plane() {
+    control_plane::clear();
+}
+
+pub fn subscribe() -> Option<broadcast::Receiver<ShutdownTrigger>> {
+    control_plane::subscribe()
+}
+
+pub fn cancellation_token() -> Option<CancellationToken> {
+    control_plane::cancellation_token()
+}
+
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+pub fn request_shutdown(trigger: ShutdownTrigger) -> bool {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return false;
+    }
+
+    info!("Shutdown requested: {trigger:?}");
+    control_plane::broadcast(trigger);
+    control_plane::cancel();
+    true
+}
+
+/// Answers a request read by the control pipe or socket server. Everything
+/// but shutdown is forwarded to the UI through the installed control actions.
+fn handle_request(request: ControlRequest) -> ControlResponse {
+    match request {
+        ControlRequest::Shutdown => {
+            request_shutdown(ShutdownTrigger::ControlCommand);
+            ControlResponse::Ok
+        }
+        ControlRequest::Pin(path) if !path.is_file() => ControlResponse::Error(format!("no image file at {}", path.display())),
+        request => {
+            if control_plane::dispatch(request) {
+                ControlResponse::Ok
+            } else {
+                ControlResponse::Error("MinnowSnap is not ready to handle commands".to_string())
+            }
+        }
+    }
+}
+
+pub use actions::{ControlActions, install_control_actions};
+pub use protocol::{ControlClientError, ControlRequest};
+
+#[cfg(unix)]
+pub use unix::{send_control_request, start_control_server};
+#[cfg(target_os = "windows")]
+pub use windows::{install_ctrl_c_handler, send_control_request, start_control_server};
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ControlRequest, ControlResponse, ShutdownTrigger, clear_control_plane, handle_request, init_control_plane, request_shutdown, subscribe,
+    };
+
+    #[test]
+    fn shutdown_request_is_idempotent() {
+        init_control_plane();
+        let mut rx = subscribe().expect("shutdown receiver");
+        assert!(request_shutdown(ShutdownTrigger::TrayMenu));
+        assert!(!request_shutdown(ShutdownTrigger::TrayMenu));
+
+        let received = crate::RUNTIME.block_on(async { rx.recv().await }).expect("shutdown trigger");
+        assert_eq!(received, ShutdownTrigger::TrayMenu);
+
+        clear_control_plane();
+    }
+
+    #[test]
+    fn pin_request_for_missing_file_is_rejected_before_dispatch() {
+        let path = std::env::temp_dir().join("minnow-control-missing-pin.png");
+        let response = handle_request(ControlRequest::Pin(path));
+        assert!(matches!(response, ControlResponse::Error(message) if message.contains("no image file")));
+    }
+}
@@ -0,0 +1,228 @@
+//! Line protocol spoken over the control pipe (Windows) and control socket
+//! (Unix). A client writes one request line and reads one response line:
+//! `ok` or `error <message>`.
+
+use std::path::PathBuf;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+pub(super) const CONTROL_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Longest accepted request or response line, including the newline.
+const MAX_LINE_LEN: u64 = 4096;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ControlRequest {
+    Shutdown,
+    Capture,
+    QuickCapture,
+    DelayedCapture,
+    OpenPreferences,
+    OpenHistory,
+    /// Pins an image file; the path must be absolute because the running
+    /// instance does not share the client's working directory.
+    Pin(PathBuf),
+}
+
+impl ControlRequest {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let (command, argument) = match line.split_once(' ') {
+            Some((command, argument)) => (command, Some(argument)),
+            None => (line, None),
+        };
+
+        let request = match command {
+            "shutdown" => Self::Shutdown,
+            "capture" => Self::Capture,
+            "quick-capture" => Self::QuickCapture,
+            "delayed-capture" => Self::DelayedCapture,
+            "open-preferences" => Self::OpenPreferences,
+            "open-history" => Self::OpenHistory,
+            "pin" => {
+                let path = PathBuf::from(argument.unwrap_or_default());
+                if !path.is_absolute() {
+                    return Err("pin needs an absolute image path".to_string());
+                }
+                return Ok(Self::Pin(path));
+            }
+            _ => return Err(format!("unknown command '{command}'")),
+        };
+
+        match argument {
+            None => Ok(request),
+            Some(_) => Err(format!("'{command}' takes no argument")),
+        }
+    }
+
+    pub fn to_line(&self) -> String {
+        match self {
+            Self::Shutdown => "shutdown\n".to_string(),
+            Self::Capture => "capture\n".to_string(),
+            Self::QuickCapture => "quick-capture\n".to_string(),
+            Self::DelayedCapture => "delayed-capture\n".to_string(),
+            Self::OpenPreferences => "open-preferences\n".to_string(),
+            Self::OpenHistory => "open-history\n".to_string(),
+            Self::Pin(path) => format!("pin {}\n", path.display()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ControlResponse {
+    Ok,
+    Error(String),
+}
+
+impl ControlResponse {
+    fn parse(line: &str) -> Option<Self> {
+        match line.trim_end_matches(['\r', '\n']) {
+            "ok" => Some(Self::Ok),
+            line => line.strip_prefix("error ").map(|message| Self::Error(message.to_string())),
+        }
+    }
+
+    fn to_line(&self) -> String {
+        match self {
+            Self::Ok => "ok\n".to_string(),
+            Self::Error(message) => format!("error {}\n", message.replace(['\r', '\n'], " ")),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ControlClientError {
+    #[error("no running MinnowSnap instance was found")]
+    NotRunning,
+    #[error("{0}")]
+    Transport(String),
+    #[error("{0}")]
+    Protocol(String),
+    #[error("the running instance rejected the request: {0}")]
+    Rejected(String),
+}
+
+/// Answers a single request on an accepted connection.
+pub(super) async fn serve_connection<S>(stream: S, handle: impl FnOnce(ControlRequest) -> ControlResponse) -> std::io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut reader = BufReader::new(stream);
+    let line = match tokio::time::timeout(CONTROL_TIMEOUT, read_line(&mut reader)).await {
+        Ok(line) => line?,
+        Err(_) => return Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out reading control request")),
+    };
+
+    let response = match ControlRequest::parse(&line) {
+        Ok(request) => handle(request),
+        Err(err) => ControlResponse::Error(err),
+    };
+
+    let stream = reader.get_mut();
+    stream.write_all(response.to_line().as_bytes()).await?;
+    stream.flush().await
+}
+
+/// Sends `request` on a connected stream and waits for the response.
+pub(super) async fn send_request<S>(stream: S, request: &ControlRequest) -> Result<(), ControlClientError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut reader = BufReader::new(stream);
+
+    tokio::time::timeout(CONTROL_TIMEOUT, async {
+        let stream = reader.get_mut();
+        stream.write_all(request.to_line().as_bytes()).await?;
+        stream.flush().await
+    })
+    .await
+    .map_err(|_| ControlClientError::Transport("timed out writing control request".to_string()))?
+    .map_err(|err| ControlClientError::Transport(format!("failed to write control request: {err}")))?;
+
+    let line = tokio::time::timeout(CONTROL_TIMEOUT, read_line(&mut reader))
+        .await
+        .map_err(|_| ControlClientError::Transport("timed out reading control response".to_string()))?
+        .map_err(|err| ControlClientError::Transport(format!("failed to read control response: {err}")))?;
+
+    if line.is_empty() {
+        return Err(ControlClientError::Protocol("control response was empty".to_string()));
+    }
+
+    match ControlResponse::parse(&line) {
+        Some(ControlResponse::Ok) => Ok(()),
+        Some(ControlResponse::Error(message)) => Err(ControlClientError::Rejected(message)),
+        None => Err(ControlClientError::Protocol(format!("unexpected control response: {}", line.trim()))),
+    }
+}
+
+async fn read_line<R: AsyncRead + Unpin>(reader: &mut BufReader<R>) -> std::io::Result<String> {
+    let mut line = String::new();
+    reader.take(MAX_LINE_LEN).read_line(&mut line).await?;
+    Ok(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ControlRequest, ControlResponse, send_request, serve_connection};
+    use std::path::PathBuf;
+
+    fn absolute_image_path() -> PathBuf {
+        std::env::temp_dir().join("pinned image.png")
+    }
+
+    #[test]
+    fn requests_round_trip_through_their_line_form() {
+        let requests = [
+            ControlRequest::Shutdown,
+            ControlRequest::Capture,
+            ControlRequest::QuickCapture,
+            ControlRequest::DelayedCapture,
+            ControlRequest::OpenPreferences,
+            ControlRequest::OpenHistory,
+            ControlRequest::Pin(absolute_image_path()),
+        ];
+
+        for request in requests {
+            assert_eq!(ControlRequest::parse(&request.to_line()), Ok(request));
+        }
+    }
+
+    #[test]
+    fn parse_rejects_unknown_commands_and_bad_arguments() {
+        assert!(ControlRequest::parse("reboot\n").is_err());
+        assert!(ControlRequest::parse("capture now\n").is_err());
+        assert!(ControlRequest::parse("pin\n").is_err());
+        assert!(ControlRequest::parse("pin relative.png\n").is_err());
+    }
+
+    #[test]
+    fn responses_keep_error_messages_on_one_line() {
+        let response = ControlResponse::Error("first\nsecond".to_string());
+        assert_eq!(response.to_line(), "error first second\n");
+        assert_eq!(
+            ControlResponse::parse(&response.to_line()),
+            Some(ControlResponse::Error("first second".to_string()))
+        );
+        assert_eq!(ControlResponse::parse("ok\n"), Some(ControlResponse::Ok));
+        assert_eq!(ControlResponse::parse("okay\n"), None);
+    }
+
+    #[test]
+    fn client_and_server_exchange_one_request() {
+        let (client, server) = tokio::io::duplex(256);
+
+        let (sent, served) = crate::RUNTIME.block_on(async {
+            tokio::join!(
+                send_request(client, &ControlRequest::OpenHistory),
+                serve_connection(server, |request| {
+                    assert_eq!(request, ControlRequest::OpenHistory);
+                    ControlResponse::Ok
+                })
+            )
+        });
+
+        assert!(sent.is_ok());
+        assert!(served.is_ok());
+    }
+}
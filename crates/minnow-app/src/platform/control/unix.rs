@@ -0,0 +1,137 @@
+use super::protocol::{self, ControlClientError, ControlRequest};
+use super::{cancellation_token, handle_request};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+use tokio::net::{UnixListener, UnixStream};
+use tracing::warn;
+
+const CONTROL_SOCKET_NAME: &str = "MinnowSnap.Control.v1";
+
+static SOCKET_SERVER_ONCE: Once = Once::new();
+
+/// Socket inside a per-user directory under `$XDG_RUNTIME_DIR`, falling back
+/// to the temp dir.
+fn control_socket_path() -> PathBuf {
+    let dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .unwrap_or_else(std::env::temp_dir);
+    let uid = unsafe { libc::getuid() };
+    dir.join(format!("MinnowSnap.{uid}")).join(format!("{CONTROL_SOCKET_NAME}.sock"))
+}
+
+pub fn start_control_server() {
+    SOCKET_SERVER_ONCE.call_once(|| {
+        crate::RUNTIME.spawn(async {
+            run_control_socket_server().await;
+        });
+    });
+}
+
+async fn run_control_socket_server() {
+    let Some(shutdown_token) = cancellation_token() else {
+        warn!("Shutdown control plane is not initialized; skip control socket server.");
+        return;
+    };
+
+    let path = control_socket_path();
+    let listener = match bind_control_socket(&path) {
+        Ok(listener) => listener,
+        Err(err) => {
+            warn!("Failed to bind control socket {}: {err}", path.display());
+            return;
+        }
+    };
+
+    loop {
+        tokio::select! {
+            _ = shutdown_token.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    tokio::spawn(async move {
+                        if let Err(err) = protocol::serve_connection(stream, handle_request).await {
+                            warn!("Control socket request failed: {err}");
+                        }
+                    });
+                }
+                Err(err) => warn!("Control socket accept failed: {err}"),
+            }
+        }
+    }
+
+    let _ = fs::remove_file(&path);
+}
+
+/// The single-instance lock is already held, so a leftover socket file can
+/// only come from a crashed run and is safe to replace. The socket is bound
+/// inside a private directory, so other users cannot reach it even while the
+/// temp dir stands in for `$XDG_RUNTIME_DIR`.
+fn bind_control_socket(path: &Path) -> io::Result<UnixListener> {
+    if let Some(dir) = path.parent() {
+        ensure_private_dir(dir)?;
+    }
+    if let Err(err) = fs::remove_file(path)
+        && err.kind() != ErrorKind::NotFound
+    {
+        return Err(err);
+    }
+
+    UnixListener::bind(path)
+}
+
+/// Creates `dir` with mode 0700, or checks that an existing one belongs to
+/// this user and tightens its mode.
+fn ensure_private_dir(dir: &Path) -> io::Result<()> {
+    fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+
+    let metadata = fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() || metadata.uid() != unsafe { libc::getuid() } {
+        return Err(io::Error::new(
+            ErrorKind::PermissionDenied,
+            format!("{} is not a directory owned by this user", dir.display()),
+        ));
+    }
+    if metadata.mode() & 0o077 != 0 {
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+pub fn send_control_request(request: &ControlRequest) -> Result<(), ControlClientError> {
+    crate::RUNTIME.block_on(async {
+        let stream = match UnixStream::connect(control_socket_path()).await {
+            Ok(stream) => stream,
+            Err(err) if matches!(err.kind(), ErrorKind::NotFound | ErrorKind::ConnectionRefused) => {
+                return Err(ControlClientError::NotRunning);
+            }
+            Err(err) => return Err(ControlClientError::Transport(format!("failed to connect to control socket: {err}"))),
+        };
+        protocol::send_request(stream, request).await
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ensure_private_dir;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn control_socket_dir_is_private_to_the_user() {
+        let dir = std::env::temp_dir().join(format!("minnow-control-dir-{}", std::process::id()));
+        let mode = |dir: &std::path::Path| fs::metadata(dir).expect("dir metadata").permissions().mode() & 0o777;
+
+        ensure_private_dir(&dir).expect("create private dir");
+        let created = mode(&dir);
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o777)).expect("loosen dir");
+        ensure_private_dir(&dir).expect("tighten private dir");
+        let tightened = mode(&dir);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(created, 0o700);
+        assert_eq!(tightened, 0o700);
+    }
+}
@@ -0,0 +1,79 @@
+use super::protocol::{self, ControlClientError, ControlRequest};
+use super::{ShutdownTrigger, cancellation_token, handle_request, request_shutdown};
+use std::sync::Once;
+use tracing::{error, warn};
+
+pub const CONTROL_PIPE_NAME: &str = r"\\.\pipe\MinnowSnap.Control.v1";
+
+static CTRL_C_HANDLER_ONCE: Once = Once::new();
+static PIPE_SERVER_ONCE: Once = Once::new();
+
+pub fn install_ctrl_c_handler() {
+    CTRL_C_HANDLER_ONCE.call_once(|| {
+        if let Err(err) = ctrlc::set_handler(|| {
+            request_shutdown(ShutdownTrigger::CtrlC);
+        }) {
+            error!("Failed to install Ctrl+C handler: {err}");
+        }
+    });
+}
+
+pub fn start_control_server() {
+    PIPE_SERVER_ONCE.call_once(|| {
+        crate::RUNTIME.spawn(async {
+            run_control_pipe_server().await;
+        });
+    });
+}
+
+async fn run_control_pipe_server() {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let Some(shutdown_token) = cancellation_token() else {
+        warn!("Shutdown control plane is not initialized; skip control pipe server.");
+        return;
+    };
+
+    while !shutdown_token.is_cancelled() {
+        let server = match ServerOptions::new().create(CONTROL_PIPE_NAME) {
+            Ok(server) => server,
+            Err(err) => {
+                warn!("Failed to create control pipe server: {err}");
+                return;
+            }
+        };
+
+        tokio::select! {
+            _ = shutdown_token.cancelled() => return,
+            result = server.connect() => {
+                if let Err(err) = result {
+                    warn!("Control pipe connect failed: {err}");
+                    continue;
+                }
+            }
+        }
+
+        tokio::select! {
+            _ = shutdown_token.cancelled() => return,
+            result = protocol::serve_connection(server, handle_request) => {
+                if let Err(err) = result {
+                    warn!("Control pipe request failed: {err}");
+                }
+            }
+        }
+    }
+}
+
+pub fn send_control_request(request: &ControlRequest) -> Result<(), ControlClientError> {
+    use std::io::ErrorKind;
+    use tokio::net::windows::named_pipe::ClientOptions;
+
+    crate::RUNTIME.block_on(async {
+        let client = match ClientOptions::new().open(CONTROL_PIPE_NAME) {
+            Ok(client) => client,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Err(ControlClientError::NotRunning),
+            Err(err) => return Err(ControlClientError::Transport(format!("failed to open control pipe: {err}"))),
+        };
+        protocol::send_request(client, request).await
+    })
+}
@@ -0,0 +1,165 @@
+/// Returns the cursor position in desktop coordinates, the same space the
+/// capture backend reports monitor geometry in.
+///
+/// Returns `None` where the position cannot be queried, such as a Wayland
+/// session without an X server; capture then falls back to the primary
+/// monitor.
+#[cfg(target_os = "windows")]
+pub fn cursor_position() -> Option<(i32, i32)> {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
+
+    let mut point = POINT::default();
+    match unsafe { GetCursorPos(&mut point) } {
+        Ok(()) => Some((point.x, point.y)),
+        Err(err) => {
+            tracing::warn!("Failed to query cursor position: {err}");
+            None
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[allow(clippy::cast_possible_truncation)]
+pub fn cursor_position() -> Option<(i32, i32)> {
+    use objc2_core_graphics::CGEvent;
+
+    // A new event carries the current cursor location, in the global points
+    // the window server also reports displays in.
+    let event = CGEvent::new(None)?;
+    let location = CGEvent::location(Some(&*event));
+    Some((location.x.round() as i32, location.y.round() as i32))
+}
+
+#[cfg(target_os = "linux")]
+pub fn cursor_position() -> Option<(i32, i32)> {
+    let (connection, root) = x11_root(&[]).inspect_err(|err| tracing::warn!("{err}")).ok()?;
+    let cookie = connection.send_request(&xcb::x::QueryPointer { window: root });
+    match connection.wait_for_reply(cookie) {
+        Ok(reply) => Some((i32::from(reply.root_x()), i32::from(reply.root_y()))),
+        Err(err) => {
+            tracing::warn!("Failed to query cursor position: {err}");
+            None
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub fn cursor_position() -> Option<(i32, i32)> {
+    None
+}
+
+/// Whether [`scroll_wheel_at`] can send input here; on Linux it needs an X11
+/// display, which Wayland-only sessions do not have.
+pub fn can_scroll_wheel() -> bool {
+    if cfg!(target_os = "linux") {
+        return std::env::var_os("DISPLAY").is_some();
+    }
+    cfg!(any(target_os = "windows", target_os = "macos"))
+}
+
+/// Moves the cursor to `point` and sends `notches` wheel steps there, positive
+/// values scrolling the content down, as if the user turned the mouse wheel.
+///
+/// `point` is in the same desktop coordinates as [`cursor_position`].
+#[cfg(target_os = "windows")]
+pub fn scroll_wheel_at(point: (i32, i32), notches: i32) -> Result<(), String> {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{INPUT, INPUT_0, INPUT_MOUSE, MOUSEEVENTF_WHEEL, MOUSEINPUT, SendInput};
+    use windows::Win32::UI::WindowsAndMessaging::{SetCursorPos, WHEEL_DELTA};
+
+    unsafe { SetCursorPos(point.0, point.1) }.map_err(|err| format!("Failed to move cursor: {err}"))?;
+
+    let input = INPUT {
+        r#type: INPUT_MOUSE,
+        Anonymous: INPUT_0 {
+            mi: MOUSEINPUT {
+                mouseData: notches.saturating_mul(-(WHEEL_DELTA as i32)).cast_unsigned(),
+                dwFlags: MOUSEEVENTF_WHEEL,
+                ..MOUSEINPUT::default()
+            },
+        },
+    };
+    let sent = unsafe { SendInput(&[input], std::mem::size_of::<INPUT>() as i32) };
+    if sent == 1 {
+        Ok(())
+    } else {
+        Err(format!("Failed to send scroll input: {}", windows::core::Error::from_thread()))
+    }
+}
+
+/// Needs the accessibility permission; without it the window server drops
+/// the event.
+#[cfg(target_os = "macos")]
+pub fn scroll_wheel_at(point: (i32, i32), notches: i32) -> Result<(), String> {
+    use objc2_core_foundation::CGPoint;
+    use objc2_core_graphics::{CGError, CGEvent, CGEventTapLocation, CGScrollEventUnit, CGWarpMouseCursorPosition};
+
+    let position = CGPoint {
+        x: f64::from(point.0),
+        y: f64::from(point.1),
+    };
+    let moved = CGWarpMouseCursorPosition(position);
+    if moved != CGError::Success {
+        return Err(format!("Failed to move cursor: error {}", moved.0));
+    }
+
+    // Positive wheel values scroll the content up.
+    let event = CGEvent::new_scroll_wheel_event2(None, CGScrollEventUnit::Line, 1, -notches, 0, 0)
+        .ok_or_else(|| "Failed to create scroll input".to_string())?;
+    CGEvent::post(CGEventTapLocation::HIDEventTap, Some(&*event));
+    Ok(())
+}
+
+/// Sends the wheel through the XTest extension, so it reaches X clients
+/// only; Wayland sessions without an X server report an error.
+#[cfg(target_os = "linux")]
+#[allow(clippy::cast_possible_truncation)]
+pub fn scroll_wheel_at(point: (i32, i32), notches: i32) -> Result<(), String> {
+    use xcb::BaseEvent;
+    use xcb::x::{ButtonPressEvent, ButtonReleaseEvent, MotionNotifyEvent};
+
+    let (connection, root) = x11_root(&[xcb::Extension::Test])?;
+    let coordinate = |value: i32| value.clamp(i32::from(i16::MIN), i32::from(i16::MAX)) as i16;
+    let fake = |r#type: u32, detail: u8| xcb::xtest::FakeInput {
+        r#type: r#type as u8,
+        detail,
+        time: xcb::x::CURRENT_TIME,
+        root,
+        root_x: coordinate(point.0),
+        root_y: coordinate(point.1),
+        deviceid: 0,
+    };
+    // Buttons 4 and 5 are the wheel turned up and down.
+    let button = if notches > 0 { 5 } else { 4 };
+    let mut requests = vec![connection.send_request_checked(&fake(MotionNotifyEvent::NUMBER, 0))];
+    for _ in 0..notches.unsigned_abs() {
+        requests.push(connection.send_request_checked(&fake(ButtonPressEvent::NUMBER, button)));
+        requests.push(connection.send_request_checked(&fake(ButtonReleaseEvent::NUMBER, button)));
+    }
+    for request in requests {
+        connection
+            .check_request(request)
+            .map_err(|err| format!("Failed to send scroll input: {err}"))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub fn scroll_wheel_at(_point: (i32, i32), _notches: i32) -> Result<(), String> {
+    Err("Synthetic scrolling is not supported on this platform".to_string())
+}
+
+/// Connects to the X server with the `extensions` it must have and returns
+/// the connection with the root window of its default screen.
+#[cfg(target_os = "linux")]
+fn x11_root(extensions: &[xcb::Extension]) -> Result<(xcb::Connection, xcb::x::Window), String> {
+    let (connection, screen) =
+        xcb::Connection::connect_with_extensions(None, extensions, &[]).map_err(|err| format!("Failed to connect to the X server: {err}"))?;
+    let root = connection
+        .get_setup()
+        .roots()
+        .nth(usize::try_from(screen).unwrap_or_default())
+        .map(xcb::x::Screen::root)
+        .ok_or_else(|| "The X server reported no screen".to_string())?;
+    Ok((connection, root))
+}
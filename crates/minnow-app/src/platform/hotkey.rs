@@ -3,6 +3,7 @@ use crate::services::hotkeys::{HotkeyAction, HotkeyUpdateError, ShortcutBindings
 use crate::services::settings::{self, SettingsAction};
 use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState, hotkey::HotKey};
 use gpui::{App, AsyncApp, Global};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex, MutexGuard};
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
 use tokio_util::sync::CancellationToken;
@@ -10,8 +11,7 @@ use tracing::{error, info};
 
 #[derive(Default)]
 struct HotkeyIds {
-    screen_capture: Option<u32>,
-    quick_capture: Option<u32>,
+    actions: HashMap<u32, HotkeyAction>,
 }
 
 impl HotkeyIds {
@@ -20,39 +20,19 @@ impl HotkeyIds {
             return None;
         }
 
-        if self.screen_capture == Some(event.id) {
-            Some(HotkeyAction::Capture)
-        } else if self.quick_capture == Some(event.id) {
-            Some(HotkeyAction::QuickCapture)
-        } else {
-            None
-        }
+        self.actions.get(&event.id).copied()
     }
 
-    fn set(&mut self, action: HotkeyAction, id: Option<u32>) {
-        match action {
-            HotkeyAction::Capture => self.screen_capture = id,
-            HotkeyAction::QuickCapture => self.quick_capture = id,
-        }
+    fn replace(&mut self, registered: impl IntoIterator<Item = (u32, HotkeyAction)>) {
+        self.actions = registered.into_iter().collect();
     }
 }
 
+#[derive(Default)]
 struct NativeHotkeyRegistry {
     backend: Option<GlobalHotKeyManager>,
     ids: Arc<Mutex<HotkeyIds>>,
-    screen_hotkey: Option<HotKey>,
-    quick_hotkey: Option<HotKey>,
-}
-
-impl Default for NativeHotkeyRegistry {
-    fn default() -> Self {
-        Self {
-            backend: None,
-            ids: Arc::new(Mutex::new(HotkeyIds::default())),
-            screen_hotkey: None,
-            quick_hotkey: None,
-        }
-    }
+    hotkeys: Vec<HotKey>,
 }
 
 pub struct HotkeyService {
@@ -75,30 +55,25 @@ fn hotkey_ids_guard<'a>(ids: &'a Mutex<HotkeyIds>) -> MutexGuard<'a, HotkeyIds>
 
 impl Global for HotkeyService {}
 
+/// Runs a triggered shortcut-table action. The table can point any hotkey at
+/// any action, so the app layer owns the action-to-workflow mapping.
 #[derive(Clone)]
 pub struct HotkeyActionSink {
-    open_capture_overlay: Arc<dyn Fn(&mut App) + Send + Sync>,
-    run_quick_capture: Arc<dyn Fn() + Send + Sync>,
+    run_action: Arc<dyn Fn(&mut App, HotkeyAction) + Send + Sync>,
 }
 
 impl HotkeyActionSink {
-    pub fn new<F1, F2>(open_capture_overlay: F1, run_quick_capture: F2) -> Self
+    pub fn new<F>(run_action: F) -> Self
     where
-        F1: Fn(&mut App) + Send + Sync + 'static,
-        F2: Fn() + Send + Sync + 'static,
+        F: Fn(&mut App, HotkeyAction) + Send + Sync + 'static,
     {
         Self {
-            open_capture_overlay: Arc::new(open_capture_overlay),
-            run_quick_capture: Arc::new(run_quick_capture),
+            run_action: Arc::new(run_action),
         }
     }
 
-    fn open_capture_overlay(&self, app: &mut App) {
-        (self.open_capture_overlay)(app);
-    }
-
-    fn run_quick_capture(&self) {
-        (self.run_quick_capture)();
+    fn run_action(&self, app: &mut App, action: HotkeyAction) {
+        (self.run_action)(app, action);
     }
 }
 
@@ -107,7 +82,7 @@ pub fn install_hotkey_service(cx: &mut App, sink: HotkeyActionSink) {
     let mut service = HotkeyService::new(action_tx, sink);
     service.register_from_settings();
     let sink = service.sink.clone();
-    let shutdown_token = crate::platform::shutdown::cancellation_token().unwrap_or_default();
+    let shutdown_token = crate::platform::control::cancellation_token().unwrap_or_default();
     cx.spawn(async move |cx| {
         hotkey_action_loop(action_rx, shutdown_token, sink, cx).await;
         GlobalHotKeyEvent::set_event_handler::<fn(GlobalHotKeyEvent)>(None);
@@ -129,32 +104,8 @@ impl NativeHotkeyRegistry {
                 return;
             }
         };
-
         self.backend = Some(manager);
-        let screen_hotkey = crate::services::hotkeys::parse_hotkey(&bindings.capture);
-        let quick_hotkey = crate::services::hotkeys::parse_hotkey(&bindings.quick_capture);
-
-        if let Some(ref backend) = self.backend {
-            if let Some(hk) = screen_hotkey {
-                if let Err(e) = backend.register(hk) {
-                    error!("Failed to register screen hotkey: {e}");
-                } else {
-                    hotkey_ids_guard(&self.ids).screen_capture = Some(hk.id());
-                    self.screen_hotkey = Some(hk);
-                    info!("Screen capture hotkey registered: {}", bindings.capture);
-                }
-            }
-
-            if let Some(hk) = quick_hotkey {
-                if let Err(e) = backend.register(hk) {
-                    error!("Failed to register quick hotkey: {e}");
-                } else {
-                    hotkey_ids_guard(&self.ids).quick_capture = Some(hk.id());
-                    self.quick_hotkey = Some(hk);
-                    info!("Quick capture hotkey registered: {}", bindings.quick_capture);
-                }
-            }
-        }
+        self.apply(bindings);
 
         let ids_clone = self.ids.clone();
         GlobalHotKeyEvent::set_event_handler(Some(move |event: GlobalHotKeyEvent| {
@@ -168,47 +119,31 @@ impl NativeHotkeyRegistry {
         info!("Global hotkeys registered");
     }
 
-    fn update_shortcut(&mut self, shortcut: &str, action: HotkeyAction) {
-        let mut shortcut_str = shortcut.to_string();
-        if shortcut_str.is_empty() {
-            let defaults = ShortcutBindings::default();
-            shortcut_str = match action {
-                HotkeyAction::Capture => defaults.capture,
-                HotkeyAction::QuickCapture => defaults.quick_capture,
-            };
-        }
-
+    /// Swaps the registered hotkeys for the bound rows of `bindings`.
+    fn apply(&mut self, bindings: &ShortcutBindings) {
         let Some(backend) = &self.backend else {
             return;
         };
-        let new_hotkey = crate::services::hotkeys::parse_hotkey(&shortcut_str);
-
-        let current_hotkey = match action {
-            HotkeyAction::Capture => &mut self.screen_hotkey,
-            HotkeyAction::QuickCapture => &mut self.quick_hotkey,
-        };
 
-        if let Some(old) = current_hotkey
-            && let Err(e) = backend.unregister(*old)
-        {
-            error!("Failed to unregister hotkey: {e}");
+        if let Err(e) = backend.unregister_all(&self.hotkeys) {
+            error!("Failed to unregister hotkeys: {e}");
         }
+        self.hotkeys.clear();
 
-        let mut next_hotkey = None;
-
-        if let Some(hotkey) = new_hotkey {
+        let mut registered = Vec::new();
+        for binding in bindings.bound() {
+            let Some(hotkey) = crate::services::hotkeys::parse_hotkey(&binding.shortcut) else {
+                continue;
+            };
             if let Err(e) = backend.register(hotkey) {
-                error!("Failed to register hotkey: {e}");
+                error!("Failed to register {} hotkey: {e}", action_label(binding.action).to_lowercase());
             } else {
-                next_hotkey = Some(hotkey);
-                info!("{} hotkey updated to: {shortcut_str}", action_label(action));
+                self.hotkeys.push(hotkey);
+                registered.push((hotkey.id(), binding.action));
+                info!("{} hotkey registered: {}", action_label(binding.action), binding.shortcut);
             }
-        } else {
-            info!("{} hotkey cleared", action_label(action));
         }
-
-        *current_hotkey = next_hotkey;
-        hotkey_ids_guard(&self.ids).set(action, next_hotkey.map(|hotkey| hotkey.id()));
+        hotkey_ids_guard(&self.ids).replace(registered);
     }
 }
 
@@ -216,6 +151,15 @@ fn action_label(action: HotkeyAction) -> &'static str {
     match action {
         HotkeyAction::Capture => "Screen capture",
         HotkeyAction::QuickCapture => "Quick capture",
+        HotkeyAction::DelayedCapture => "Delayed capture",
+        HotkeyAction::WindowCapture => "Window capture",
+        HotkeyAction::FocusedWindowCapture => "Focused window capture",
+        HotkeyAction::RepeatRegionCapture => "Repeat region capture",
+        HotkeyAction::PinClipboardImage => "Pin clipboard image",
+        HotkeyAction::CloseAllPins => "Close all pins",
+        HotkeyAction::TogglePinVisibility => "Toggle pin visibility",
+        HotkeyAction::LongCapture => "Long capture",
+        HotkeyAction::OcrLastCapture => "OCR last capture",
     }
 }
 
@@ -247,16 +191,12 @@ impl HotkeyService {
             return Err(HotkeyUpdateError::Conflict);
         }
 
-        settings::apply(SettingsAction::Shortcuts {
-            capture: bindings.capture.clone(),
-            quick_capture: bindings.quick_capture.clone(),
-        });
+        settings::apply(SettingsAction::Shortcuts(bindings.entries().to_vec()));
 
         if !self.registry.is_initialized() {
             self.register_from_settings();
         } else {
-            self.registry.update_shortcut(&bindings.capture, HotkeyAction::Capture);
-            self.registry.update_shortcut(&bindings.quick_capture, HotkeyAction::QuickCapture);
+            self.registry.apply(&bindings);
         }
 
         Ok(())
@@ -296,37 +236,28 @@ fn handle_hotkey_action(action: HotkeyAction, sink: &HotkeyActionSink, async_app
         return false;
     }
 
-    match action {
-        HotkeyAction::Capture => {
-            if !update_app(async_app, |app| {
-                sink.open_capture_overlay(app);
-            }) {
-                return false;
-            }
-        }
-        HotkeyAction::QuickCapture => {
-            sink.run_quick_capture();
-        }
-    }
-
-    true
+    update_app(async_app, |app| {
+        sink.run_action(app, action);
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::HotkeyIds;
     use crate::services::hotkeys::{
-        DEFAULT_CAPTURE_SHORTCUT, DEFAULT_QUICK_CAPTURE_SHORTCUT, HotkeyAction, ShortcutBindings, format_keystroke, resolve_shortcut,
-        shortcuts_conflict,
+        DEFAULT_CAPTURE_SHORTCUT, DEFAULT_QUICK_CAPTURE_SHORTCUT, HotkeyAction, ShortcutBinding, ShortcutBindings, format_keystroke,
+        resolve_shortcut, shortcuts_conflict,
     };
     use global_hotkey::{GlobalHotKeyEvent, HotKeyState};
 
     #[test]
     fn native_events_map_to_domain_actions_only_on_press() {
-        let ids = HotkeyIds {
-            screen_capture: Some(7),
-            quick_capture: Some(11),
-        };
+        let mut ids = HotkeyIds::default();
+        ids.replace([
+            (7, HotkeyAction::Capture),
+            (11, HotkeyAction::QuickCapture),
+            (13, HotkeyAction::DelayedCapture),
+        ]);
 
         assert_eq!(
             ids.action_for_event(&GlobalHotKeyEvent {
@@ -342,6 +273,13 @@ mod tests {
             }),
             Some(HotkeyAction::QuickCapture)
         );
+        assert_eq!(
+            ids.action_for_event(&GlobalHotKeyEvent {
+                id: 13,
+                state: HotKeyState::Pressed,
+            }),
+            Some(HotkeyAction::DelayedCapture)
+        );
         assert_eq!(
             ids.action_for_event(&GlobalHotKeyEvent {
                 id: 7,
@@ -358,6 +296,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reloading_the_table_drops_previous_ids() {
+        let mut ids = HotkeyIds::default();
+        ids.replace([(7, HotkeyAction::WindowCapture), (8, HotkeyAction::WindowCapture)]);
+        ids.replace([(8, HotkeyAction::CloseAllPins)]);
+
+        let event = |id| GlobalHotKeyEvent {
+            id,
+            state: HotKeyState::Pressed,
+        };
+        assert_eq!(ids.action_for_event(&event(7)), None);
+        assert_eq!(ids.action_for_event(&event(8)), Some(HotkeyAction::CloseAllPins));
+
+        ids.replace([]);
+        assert_eq!(ids.action_for_event(&event(8)), None);
+    }
+
     #[test]
     fn empty_shortcuts_fall_back_to_defaults() {
         assert_eq!(resolve_shortcut("", HotkeyAction::Capture), DEFAULT_CAPTURE_SHORTCUT);
@@ -366,22 +321,78 @@ mod tests {
 
     #[test]
     fn bindings_update_independently() {
-        let bindings = ShortcutBindings::default().with_capture("Ctrl+Shift+A").with_quick_capture("Ctrl+Alt+B");
+        let bindings = ShortcutBindings::default()
+            .with_shortcut(0, " Ctrl+Shift+A ")
+            .with_shortcut(1, "Ctrl+Alt+B")
+            .with_action(1, HotkeyAction::TogglePinVisibility);
+
+        assert_eq!(bindings.entries()[0], ShortcutBinding::new("Ctrl+Shift+A", HotkeyAction::Capture));
+        assert_eq!(
+            bindings.entries()[1],
+            ShortcutBinding::new("Ctrl+Alt+B", HotkeyAction::TogglePinVisibility)
+        );
+        assert_eq!(bindings.entries()[2], ShortcutBindings::default().entries()[2]);
+    }
+
+    #[test]
+    fn table_rows_can_be_added_and_removed() {
+        let defaults = ShortcutBindings::default();
+
+        let added = defaults.with_added(HotkeyAction::OcrLastCapture);
+        assert_eq!(added.entries().len(), defaults.entries().len() + 1);
+        assert_eq!(added.entries().last(), Some(&ShortcutBinding::new("", HotkeyAction::OcrLastCapture)));
+        assert_eq!(added.bound().count(), defaults.entries().len());
 
-        assert_eq!(bindings.capture, "Ctrl+Shift+A");
-        assert_eq!(bindings.quick_capture, "Ctrl+Alt+B");
+        let removed = added.without(0).without(usize::MAX);
+        assert_eq!(removed.entries().len(), defaults.entries().len());
+        assert!(removed.entries().iter().all(|binding| binding.action != HotkeyAction::Capture));
     }
 
     #[test]
     fn repeated_shortcuts_are_rejected() {
         assert!(shortcuts_conflict("ctrl+shift+a", "Ctrl+Shift+A"));
-        assert!(ShortcutBindings::default().with_quick_capture("F1").has_conflict());
+        assert!(shortcuts_conflict("Shift+Ctrl+F2", "Ctrl+Shift+F2"));
+        assert!(
+            ShortcutBindings::new(vec![
+                ShortcutBinding::new("Shift+Ctrl+F3", HotkeyAction::Capture),
+                ShortcutBinding::new("Ctrl + Shift + F3", HotkeyAction::QuickCapture),
+            ])
+            .has_conflict()
+        );
+        assert!(ShortcutBindings::default().with_shortcut(1, "F1").has_conflict());
+        assert!(ShortcutBindings::default().with_shortcut(2, "f2").has_conflict());
+        assert!(!ShortcutBindings::default().with_shortcut(2, "Ctrl+F1").has_conflict());
+        assert!(
+            ShortcutBindings::default()
+                .with_added(HotkeyAction::CloseAllPins)
+                .with_shortcut(6, "shift+f2")
+                .has_conflict()
+        );
+        assert!(!ShortcutBindings::default().has_conflict());
+    }
+
+    #[test]
+    fn unbound_rows_never_conflict() {
+        let bindings = ShortcutBindings::default()
+            .with_added(HotkeyAction::PinClipboardImage)
+            .with_added(HotkeyAction::CloseAllPins);
+
+        assert!(!bindings.has_conflict());
+        assert!(!ShortcutBindings::new(Vec::new()).has_conflict());
+    }
+
+    #[test]
+    fn actions_round_trip_through_their_names() {
+        for action in HotkeyAction::ALL {
+            assert_eq!(HotkeyAction::parse(action.as_str()), Some(action));
+        }
+        assert_eq!(HotkeyAction::parse("reboot"), None);
     }
 
     #[test]
-    fn blank_shortcuts_still_use_default_values_for_conflict_checks() {
-        assert!(shortcuts_conflict("", DEFAULT_CAPTURE_SHORTCUT));
-        assert!(!shortcuts_conflict("", DEFAULT_QUICK_CAPTURE_SHORTCUT));
+    fn blank_shortcuts_never_conflict() {
+        assert!(!shortcuts_conflict("", ""));
+        assert!(!shortcuts_conflict("", DEFAULT_CAPTURE_SHORTCUT));
     }
 
     #[test]
@@ -20,6 +20,8 @@ pub enum Level {
 pub trait WindowLevelExt {
     fn set_level(&mut self, level: Level) -> Result<()>;
     fn set_click_through(&mut self, enabled: bool) -> Result<()>;
+    /// Moves the window by `dx`, `dy` logical pixels.
+    fn move_by(&mut self, dx: i32, dy: i32) -> Result<()>;
 }
 
 impl WindowLevelExt for Window {
@@ -30,6 +32,10 @@ impl WindowLevelExt for Window {
     fn set_click_through(&mut self, enabled: bool) -> Result<()> {
         platform::set_click_through(self, enabled)
     }
+
+    fn move_by(&mut self, dx: i32, dy: i32) -> Result<()> {
+        platform::move_by(self, dx, dy)
+    }
 }
 
 #[cfg_attr(target_os = "windows", allow(dead_code))]
@@ -54,10 +60,10 @@ pub fn with_level<T>(level: Level, build: impl FnOnce(&mut Window, &mut App) ->
 #[cfg(target_os = "windows")]
 mod platform {
     use super::*;
-    use windows::Win32::Foundation::{GetLastError, HWND, SetLastError, WIN32_ERROR};
+    use windows::Win32::Foundation::{GetLastError, HWND, RECT, SetLastError, WIN32_ERROR};
     use windows::Win32::UI::WindowsAndMessaging::{
-        GWL_EXSTYLE, GetWindowLongPtrW, HWND_NOTOPMOST, HWND_TOPMOST, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SWP_SHOWWINDOW, SetWindowLongPtrW,
-        SetWindowPos, WS_EX_LAYERED, WS_EX_TRANSPARENT,
+        GWL_EXSTYLE, GetWindowLongPtrW, GetWindowRect, HWND_NOTOPMOST, HWND_TOPMOST, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER,
+        SWP_SHOWWINDOW, SetWindowLongPtrW, SetWindowPos, WS_EX_LAYERED, WS_EX_TRANSPARENT,
     };
 
     fn hwnd(window: &Window) -> Result<HWND> {
@@ -123,6 +129,30 @@ mod platform {
 
         Ok(())
     }
+
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub(super) fn move_by(window: &Window, dx: i32, dy: i32) -> Result<()> {
+        let hwnd = hwnd(window)?;
+        let scale = window.scale_factor();
+        let device = |delta: i32| (delta as f32 * scale).round() as i32;
+
+        unsafe {
+            let mut rect = RECT::default();
+            GetWindowRect(hwnd, &mut rect).map_err(|e| anyhow!("GetWindowRect failed: {e}"))?;
+            SetWindowPos(
+                hwnd,
+                None,
+                rect.left + device(dx),
+                rect.top + device(dy),
+                0,
+                0,
+                SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE,
+            )
+            .map_err(|e| anyhow!("SetWindowPos failed: {e}"))?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -144,4 +174,8 @@ mod platform {
             unsupported_platform_operation("click-through")
         }
     }
+
+    pub(super) fn move_by(_window: &Window, _dx: i32, _dy: i32) -> Result<()> {
+        unsupported_platform_operation("window moves")
+    }
 }
@@ -1,19 +1,31 @@
 use anyhow::Result;
 use gpui::{App, Window, WindowOptions};
 use image::RgbaImage;
+use std::io;
+use std::path::Path;
 
 pub(crate) use super::hotkey::HotkeyService;
 use super::native_window::{Level, WindowLevelExt};
 pub(crate) use super::notify::NotificationType;
+use super::storage::ImageEncoding;
 pub(crate) use super::system::UiSystemActions;
 pub(crate) use super::window_drag::{PopupDragBehavior, PopupDragRegionExt};
+pub(crate) use super::window_geometry::WindowGeometry;
 pub(crate) use super::windowing::PopupWindowSpec;
 use crate::services::app_meta::APP_ID;
+use crate::services::capture::filename::CaptureOrigin;
+use crate::services::geometry::RectF;
+use crate::services::settings::{OutputFormat, OutputSettings};
 
 pub(crate) fn popup_window_options(spec: PopupWindowSpec) -> WindowOptions {
     super::windowing::popup_window_options(spec, APP_ID)
 }
 
+/// Options for a popup pinned at `rect` in screen points.
+pub(crate) fn fixed_popup_window_options(rect: RectF, focus: bool) -> WindowOptions {
+    popup_window_options(super::windowing::fixed_popup_spec(rect, focus))
+}
+
 pub(crate) fn configure_window(window: &mut Window, cx: &mut App, focus: bool) {
     super::windowing::configure_window(window, cx, focus);
 }
@@ -32,6 +44,26 @@ pub(crate) fn set_click_through(window: &mut Window, enabled: bool) -> Result<()
     window.set_click_through(enabled)
 }
 
+pub(crate) fn move_window_by(window: &mut Window, dx: i32, dy: i32) -> Result<()> {
+    window.move_by(dx, dy)
+}
+
+pub(crate) fn cursor_position() -> Option<(i32, i32)> {
+    super::cursor::cursor_position()
+}
+
+pub(crate) fn scroll_wheel_at(point: (i32, i32), notches: i32) -> Result<(), String> {
+    super::cursor::scroll_wheel_at(point, notches)
+}
+
+pub(crate) fn can_scroll_wheel() -> bool {
+    super::cursor::can_scroll_wheel()
+}
+
+pub(crate) fn window_geometry(id: u32) -> Option<WindowGeometry> {
+    super::window_geometry::window_geometry(id)
+}
+
 pub(crate) fn show_notification(title: &str, message: &str, notification_type: NotificationType) {
     super::notify::show(title, message, notification_type);
 }
@@ -44,16 +76,71 @@ pub(crate) fn copy_image_to_clipboard(image: &RgbaImage) -> bool {
     super::clipboard::copy_image_to_clipboard(image)
 }
 
-pub(crate) fn save_image_to_user_dir(image: &RgbaImage, save_path_override: Option<String>) -> Result<String, String> {
+pub(crate) fn read_image_from_clipboard() -> Option<RgbaImage> {
+    super::clipboard::read_image_from_clipboard()
+}
+
+pub(crate) fn save_image_to_user_dir(image: &RgbaImage, save_path_override: Option<String>, origin: &CaptureOrigin) -> Result<String, String> {
     let settings = crate::services::settings::output_settings();
+    let encoding = output_encoding(&settings);
     let save_path = save_path_override.or(settings.save_path);
-    super::storage::save_image_to_user_dir(image, settings.oxipng_enabled, save_path).ok_or_else(|| "Failed to save image to disk".to_string())
+    super::storage::save_image_to_user_dir(image, encoding, save_path, &settings.filename_template, origin)
+        .ok_or_else(|| "Failed to save image to disk".to_string())
+}
+
+/// Saves a file of `dimensions` that `write` encodes itself, for long
+/// captures too large to hold in memory and for recordings. The configured
+/// image format does not apply.
+pub(crate) fn save_streamed_file_to_user_dir(
+    dimensions: (u32, u32),
+    origin: &CaptureOrigin,
+    extension: &str,
+    write: impl FnOnce(&Path) -> io::Result<()>,
+) -> Result<String, String> {
+    let settings = crate::services::settings::output_settings();
+    let path = super::storage::user_save_file(settings.save_path, &settings.filename_template, origin, dimensions, extension)
+        .ok_or_else(|| "Failed to save image to disk".to_string())?;
+    if let Err(err) = write(&path) {
+        super::storage::release_save_file(&path);
+        return Err(format!("Failed to save image to '{}': {err}", path.display()));
+    }
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Saves to an explicit file; its extension picks the format and the output
+/// settings supply the format's options.
+pub(crate) fn save_image_to_path(image: &RgbaImage, path: &Path) -> Result<String, String> {
+    let extension = path.extension().and_then(|extension| extension.to_str()).map(str::to_ascii_lowercase);
+    let Some(format) = extension.as_deref().and_then(OutputFormat::parse) else {
+        return Err(format!("Unsupported image type for '{}'; use .png, .jpg or .webp", path.display()));
+    };
+    let settings = OutputSettings {
+        format,
+        ..crate::services::settings::output_settings()
+    };
+    super::storage::save_image_to_path(image, output_encoding(&settings), path).ok_or_else(|| format!("Failed to save image to '{}'", path.display()))
+}
+
+fn output_encoding(settings: &OutputSettings) -> ImageEncoding {
+    match settings.format {
+        OutputFormat::Png => ImageEncoding::Png {
+            optimize: settings.oxipng_enabled,
+        },
+        OutputFormat::Jpeg => ImageEncoding::Jpeg {
+            quality: settings.jpeg_quality,
+        },
+        OutputFormat::Webp => ImageEncoding::WebpLossless,
+    }
 }
 
 pub(crate) fn save_temp_image(image: &RgbaImage) -> Option<String> {
     super::storage::save_temp_image(image, false).map(|path| path.replace('\\', "/"))
 }
 
+pub(crate) fn local_utc_offset_seconds() -> i32 {
+    super::clock::local_utc_offset_seconds()
+}
+
 pub(crate) fn play_shutter() {
     super::notify::play_shutter();
 }
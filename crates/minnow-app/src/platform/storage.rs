@@ -1,13 +1,48 @@
+use crate::services::capture::filename::{CaptureOrigin, FileNameFields, unique_file_name};
 use crate::services::paths::ensure_dir;
+use crate::services::settings::OutputFormat;
 use directories::UserDirs;
+use image::codecs::jpeg::JpegEncoder;
 use image::codecs::png::PngEncoder;
-use image::{ExtendedColorType, ImageEncoder, RgbaImage};
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, ExtendedColorType, ImageEncoder, RgbaImage};
 use std::fs;
-use std::io::BufWriter;
-use std::path::PathBuf;
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tracing::{error, info};
 
+/// How an image is written to disk.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ImageEncoding {
+    /// PNG, optionally optimized with oxipng.
+    Png {
+        optimize: bool,
+    },
+    /// Baseline JPEG; alpha is discarded.
+    Jpeg {
+        quality: u8,
+    },
+    WebpLossless,
+}
+
+impl ImageEncoding {
+    /// The output format this encoding writes.
+    #[must_use]
+    pub const fn format(self) -> OutputFormat {
+        match self {
+            Self::Png { .. } => OutputFormat::Png,
+            Self::Jpeg { .. } => OutputFormat::Jpeg,
+            Self::WebpLossless => OutputFormat::Webp,
+        }
+    }
+
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        self.format().extension()
+    }
+}
+
 #[must_use]
 pub fn get_default_save_path() -> String {
     if let Some(path) = minnow_picture_dir() {
@@ -35,7 +70,31 @@ pub fn save_temp_image(image: &RgbaImage, compress: bool) -> Option<String> {
 }
 
 #[must_use]
-pub fn save_image_to_user_dir(image: &RgbaImage, compress: bool, custom_path: Option<String>) -> Option<String> {
+pub fn save_image_to_user_dir(
+    image: &RgbaImage,
+    encoding: ImageEncoding,
+    custom_path: Option<String>,
+    filename_template: &str,
+    origin: &CaptureOrigin,
+) -> Option<String> {
+    let path = user_save_file(custom_path, filename_template, origin, image.dimensions(), encoding.extension())?;
+    let saved = save_encoded(image, encoding, &path);
+    if saved.is_none() {
+        release_save_file(&path);
+    }
+    saved
+}
+
+/// Picks a new file in the save directory, named from the template, and
+/// creates the directory when missing.
+#[must_use]
+pub fn user_save_file(
+    custom_path: Option<String>,
+    filename_template: &str,
+    origin: &CaptureOrigin,
+    (width, height): (u32, u32),
+    extension: &str,
+) -> Option<PathBuf> {
     let mut dir = if let Some(path) = custom_path.filter(|s| !s.is_empty()) {
         PathBuf::from(path)
     } else if let Some(path) = minnow_picture_dir() {
@@ -50,26 +109,106 @@ pub fn save_image_to_user_dir(image: &RgbaImage, compress: bool, custom_path: Op
         return None;
     }
 
-    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
-    dir.push(format!("snap_{timestamp}.png"));
+    let fields = FileNameFields {
+        unix_millis: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis(),
+        utc_offset_seconds: super::clock::local_utc_offset_seconds(),
+        origin,
+        width,
+        height,
+    };
+    let file_name = unique_file_name(filename_template, &fields, extension, |name| claim_file(&dir.join(name)));
+    dir.push(file_name);
+    Some(dir)
+}
 
-    if compress {
-        save_compressed_png(image, &dir)
-    } else {
-        save_uncompressed_png(image, &dir)
+/// Removes a file picked by [`user_save_file`] whose write failed, so the
+/// empty placeholder does not push later saves onto the next name.
+pub fn release_save_file(path: &Path) {
+    if let Err(e) = fs::remove_file(path)
+        && e.kind() != io::ErrorKind::NotFound
+    {
+        error!("Failed to remove unsaved file {:?}: {}", path, e);
     }
 }
 
-fn save_uncompressed_png(image: &RgbaImage, path: &PathBuf) -> Option<String> {
-    let file = match fs::File::create(path) {
-        Ok(f) => f,
+/// Creates `path` only when nothing is there yet, so two saves racing for the
+/// same name never end up writing the same file.
+fn claim_file(path: &Path) -> bool {
+    match fs::OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(_) => true,
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => false,
+        Err(e) => {
+            // Not a name clash, so another name will not help; the write reports it.
+            error!("Failed to create {:?}: {}", path, e);
+            true
+        }
+    }
+}
+
+/// Writes `image` to exactly `path`, creating its directory when missing.
+#[must_use]
+pub fn save_image_to_path(image: &RgbaImage, encoding: ImageEncoding, path: &Path) -> Option<String> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty())
+        && let Err(e) = ensure_dir(dir)
+    {
+        error!("Failed to create directory {:?}: {}", dir, e);
+        return None;
+    }
+
+    save_encoded(image, encoding, path)
+}
+
+fn save_encoded(image: &RgbaImage, encoding: ImageEncoding, path: &Path) -> Option<String> {
+    match encoding {
+        ImageEncoding::Png { optimize: true } => save_compressed_png(image, path),
+        ImageEncoding::Png { optimize: false } => save_uncompressed_png(image, path),
+        ImageEncoding::Jpeg { quality } => save_jpeg(image, quality, path),
+        ImageEncoding::WebpLossless => save_webp_lossless(image, path),
+    }
+}
+
+fn create_buffered_file(path: &Path) -> Option<BufWriter<fs::File>> {
+    match fs::File::create(path) {
+        Ok(file) => Some(BufWriter::with_capacity(64 * 1024, file)),
         Err(e) => {
             error!("Failed to create output file: {e}");
-            return None;
+            None
         }
-    };
+    }
+}
+
+fn save_jpeg(image: &RgbaImage, quality: u8, path: &Path) -> Option<String> {
+    let writer = create_buffered_file(path)?;
+    let encoder = JpegEncoder::new_with_quality(writer, quality.clamp(1, 100));
+    // JPEG has no alpha channel; captures are opaque so dropping it is lossless.
+    let rgb = DynamicImage::ImageRgba8(image.clone()).into_rgb8();
+
+    if let Err(e) = encoder.write_image(rgb.as_raw(), rgb.width(), rgb.height(), ExtendedColorType::Rgb8) {
+        error!("Failed to encode/save JPEG image: {e}");
+        return None;
+    }
+
+    let path_str = path.to_string_lossy().to_string();
+    info!("Image saved successfully (jpeg, quality {quality}): {path_str}");
+    Some(path_str)
+}
+
+fn save_webp_lossless(image: &RgbaImage, path: &Path) -> Option<String> {
+    let writer = create_buffered_file(path)?;
+    let encoder = WebPEncoder::new_lossless(writer);
+
+    if let Err(e) = encoder.write_image(image.as_raw(), image.width(), image.height(), ExtendedColorType::Rgba8) {
+        error!("Failed to encode/save WebP image: {e}");
+        return None;
+    }
 
-    let writer = BufWriter::with_capacity(64 * 1024, file);
+    let path_str = path.to_string_lossy().to_string();
+    info!("Image saved successfully (webp): {path_str}");
+    Some(path_str)
+}
+
+fn save_uncompressed_png(image: &RgbaImage, path: &Path) -> Option<String> {
+    let writer = create_buffered_file(path)?;
     let encoder = PngEncoder::new(writer);
 
     if let Err(e) = encoder.write_image(image.as_raw(), image.width(), image.height(), ExtendedColorType::Rgba8) {
@@ -82,7 +221,7 @@ fn save_uncompressed_png(image: &RgbaImage, path: &PathBuf) -> Option<String> {
     Some(path_str)
 }
 
-fn save_compressed_png(image: &RgbaImage, path: &PathBuf) -> Option<String> {
+fn save_compressed_png(image: &RgbaImage, path: &Path) -> Option<String> {
     let start_time = Instant::now();
 
     let mut options = oxipng::Options::from_preset(0);
@@ -131,3 +270,127 @@ fn minnow_picture_dir() -> Option<PathBuf> {
     path.push("MinnowSnap");
     Some(path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::ImageFormat;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static TEST_FILE_ID: AtomicU64 = AtomicU64::new(0);
+
+    fn test_path(extension: &str) -> PathBuf {
+        let id = TEST_FILE_ID.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!("minnowsnap-storage-{}-{id}.{extension}", std::process::id()))
+    }
+
+    fn test_image() -> RgbaImage {
+        RgbaImage::from_fn(16, 8, |x, y| image::Rgba([(x * 16) as u8, (y * 32) as u8, 128, 255]))
+    }
+
+    fn round_trip(encoding: ImageEncoding) -> (ImageFormat, RgbaImage) {
+        let path = test_path(encoding.extension());
+        let saved = save_encoded(&test_image(), encoding, &path).expect("save encoded image");
+        assert_eq!(saved, path.to_string_lossy());
+
+        let bytes = fs::read(&path).expect("read encoded image");
+        let _ = fs::remove_file(&path);
+        let format = image::guess_format(&bytes).expect("detect encoded format");
+        let decoded = image::load_from_memory(&bytes).expect("decode encoded image").into_rgba8();
+        (format, decoded)
+    }
+
+    #[test]
+    fn png_encodings_are_lossless() {
+        for optimize in [false, true] {
+            let (format, decoded) = round_trip(ImageEncoding::Png { optimize });
+
+            assert_eq!(format, ImageFormat::Png);
+            assert_eq!(decoded, test_image());
+        }
+    }
+
+    #[test]
+    fn jpeg_encoding_keeps_dimensions() {
+        let (format, decoded) = round_trip(ImageEncoding::Jpeg { quality: 80 });
+
+        assert_eq!(format, ImageFormat::Jpeg);
+        assert_eq!(decoded.dimensions(), test_image().dimensions());
+        assert!(decoded.pixels().all(|pixel| pixel.0[3] == 255));
+    }
+
+    #[test]
+    fn webp_lossless_encoding_round_trips_pixels() {
+        let (format, decoded) = round_trip(ImageEncoding::WebpLossless);
+
+        assert_eq!(format, ImageFormat::WebP);
+        assert_eq!(decoded, test_image());
+    }
+
+    #[test]
+    fn save_to_user_dir_renders_the_template_and_never_overwrites() {
+        let dir = test_path("dir");
+        let origin = CaptureOrigin {
+            app_name: Some("Editor".to_string()),
+            window_title: Some("notes: draft?".to_string()),
+        };
+        let save = || {
+            save_image_to_user_dir(
+                &test_image(),
+                ImageEncoding::Png { optimize: false },
+                Some(dir.to_string_lossy().to_string()),
+                "{app} {window_title} {width}x{height}",
+                &origin,
+            )
+            .expect("save templated image")
+        };
+
+        let first = save();
+        let second = save();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(first.ends_with("Editor notes_ draft_ 16x8.png"));
+        assert!(second.ends_with("Editor notes_ draft_ 16x8 (2).png"));
+    }
+
+    #[test]
+    fn user_save_file_claims_the_name_before_returning_it() {
+        let dir = test_path("dir");
+        let pick =
+            || user_save_file(Some(dir.to_string_lossy().to_string()), "shot", &CaptureOrigin::default(), (16, 8), "png").expect("pick a save file");
+
+        let first = pick();
+        let claimed = first.exists();
+        let second = pick();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(claimed);
+        assert!(first.ends_with("shot.png"));
+        assert!(second.ends_with("shot (2).png"));
+    }
+
+    #[test]
+    fn failed_save_to_user_dir_leaves_no_file_behind() {
+        let dir = test_path("dir");
+        // JPEG cannot encode images wider than 65535 pixels.
+        let saved = save_image_to_user_dir(
+            &RgbaImage::new(70_000, 1),
+            ImageEncoding::Jpeg { quality: 80 },
+            Some(dir.to_string_lossy().to_string()),
+            "shot",
+            &CaptureOrigin::default(),
+        );
+        let leftovers = fs::read_dir(&dir).expect("read save dir").count();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(saved, None);
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn encodings_use_matching_extensions() {
+        assert_eq!(ImageEncoding::Png { optimize: true }.extension(), "png");
+        assert_eq!(ImageEncoding::Jpeg { quality: 90 }.extension(), "jpg");
+        assert_eq!(ImageEncoding::WebpLossless.extension(), "webp");
+    }
+}
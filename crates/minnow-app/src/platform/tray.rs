@@ -1,5 +1,5 @@
-use crate::platform::shutdown::{self, ShutdownTrigger};
-use crate::platform::{app_ready, update_app};
+use crate::platform::control::{self, ShutdownTrigger};
+use crate::platform::update_app;
 use crate::services::app_meta::APP_NAME;
 use crate::services::assets::asset_bytes;
 use crate::services::i18n;
@@ -17,6 +17,8 @@ use tray_icon::{
 pub struct TrayMenuIds {
     capture_overlay: MenuId,
     quick_capture: MenuId,
+    delayed_capture: MenuId,
+    history: MenuId,
     preferences: MenuId,
     exit: MenuId,
 }
@@ -26,6 +28,8 @@ impl TrayMenuIds {
         Self {
             capture_overlay: MenuId::new("tray.capture_overlay"),
             quick_capture: MenuId::new("tray.quick_capture"),
+            delayed_capture: MenuId::new("tray.delayed_capture"),
+            history: MenuId::new("tray.history"),
             preferences: MenuId::new("tray.preferences"),
             exit: MenuId::new("tray.exit"),
         }
@@ -40,20 +44,32 @@ pub struct SystemTray {
 #[derive(Clone)]
 pub struct TrayActions {
     open_capture_overlay: Arc<dyn Fn(&mut App) + Send + Sync>,
-    run_quick_capture: Arc<dyn Fn() + Send + Sync>,
+    run_quick_capture: Arc<dyn Fn(&mut App) + Send + Sync>,
+    start_delayed_capture: Arc<dyn Fn(&mut App) + Send + Sync>,
+    open_history: Arc<dyn Fn(&mut App) + Send + Sync>,
     open_preferences: Arc<dyn Fn(&mut App) + Send + Sync>,
 }
 
 impl TrayActions {
-    pub fn new<F1, F2, F3>(open_capture_overlay: F1, run_quick_capture: F2, open_preferences: F3) -> Self
+    pub fn new<F1, F2, F3, F4, F5>(
+        open_capture_overlay: F1,
+        run_quick_capture: F2,
+        start_delayed_capture: F3,
+        open_history: F4,
+        open_preferences: F5,
+    ) -> Self
     where
         F1: Fn(&mut App) + Send + Sync + 'static,
-        F2: Fn() + Send + Sync + 'static,
+        F2: Fn(&mut App) + Send + Sync + 'static,
         F3: Fn(&mut App) + Send + Sync + 'static,
+        F4: Fn(&mut App) + Send + Sync + 'static,
+        F5: Fn(&mut App) + Send + Sync + 'static,
     {
         Self {
             open_capture_overlay: Arc::new(open_capture_overlay),
             run_quick_capture: Arc::new(run_quick_capture),
+            start_delayed_capture: Arc::new(start_delayed_capture),
+            open_history: Arc::new(open_history),
             open_preferences: Arc::new(open_preferences),
         }
     }
@@ -62,8 +78,16 @@ impl TrayActions {
         (self.open_capture_overlay)(app);
     }
 
-    fn run_quick_capture(&self) {
-        (self.run_quick_capture)();
+    fn run_quick_capture(&self, app: &mut App) {
+        (self.run_quick_capture)(app);
+    }
+
+    fn start_delayed_capture(&self, app: &mut App) {
+        (self.start_delayed_capture)(app);
+    }
+
+    fn open_history(&self, app: &mut App) {
+        (self.open_history)(app);
     }
 
     fn open_preferences(&self, app: &mut App) {
@@ -86,7 +110,7 @@ impl SystemTray {
 
         let (event_tx, event_rx) = unbounded_channel();
         Self::install_event_handlers(event_tx);
-        let shutdown_token = shutdown::cancellation_token().unwrap_or_default();
+        let shutdown_token = control::cancellation_token().unwrap_or_default();
 
         cx.spawn(async move |cx| {
             Self::event_loop(menu_ids, actions, event_rx, shutdown_token, cx).await;
@@ -103,13 +127,24 @@ impl SystemTray {
 
         let capture_overlay = MenuItem::with_id(menu_ids.capture_overlay.clone(), i18n::tray::capture_overlay(), true, None);
         let quick_capture = MenuItem::with_id(menu_ids.quick_capture.clone(), i18n::tray::quick_capture(), true, None);
+        let delayed_capture = MenuItem::with_id(menu_ids.delayed_capture.clone(), i18n::tray::delayed_capture(), true, None);
+        let history = MenuItem::with_id(menu_ids.history.clone(), i18n::tray::history(), true, None);
         let preferences = MenuItem::with_id(menu_ids.preferences.clone(), i18n::tray::preferences(), true, None);
         let exit = MenuItem::with_id(menu_ids.exit.clone(), i18n::tray::exit(), true, None);
         let separator = PredefinedMenuItem::separator();
 
         let menu = Menu::new();
-        menu.append_items(&[&capture_overlay, &quick_capture, &separator, &preferences, &separator, &exit])
-            .map_err(|err| format!("failed to build tray menu: {err}"))?;
+        menu.append_items(&[
+            &capture_overlay,
+            &quick_capture,
+            &delayed_capture,
+            &separator,
+            &history,
+            &preferences,
+            &separator,
+            &exit,
+        ])
+        .map_err(|err| format!("failed to build tray menu: {err}"))?;
 
         let icon = load_icon()?;
         let tray_icon = TrayIconBuilder::new()
@@ -175,11 +210,21 @@ impl SystemTray {
         }
 
         if event.id == menu_ids.quick_capture {
-            if !app_ready(cx) {
-                return true;
-            }
-            actions.run_quick_capture();
-            return false;
+            return !update_app(cx, |app| {
+                actions.run_quick_capture(app);
+            });
+        }
+
+        if event.id == menu_ids.delayed_capture {
+            return !update_app(cx, |app| {
+                actions.start_delayed_capture(app);
+            });
+        }
+
+        if event.id == menu_ids.history {
+            return !update_app(cx, |app| {
+                actions.open_history(app);
+            });
         }
 
         if event.id == menu_ids.preferences {
@@ -189,7 +234,7 @@ impl SystemTray {
         }
 
         if event.id == menu_ids.exit {
-            shutdown::request_shutdown(ShutdownTrigger::TrayMenu);
+            control::request_shutdown(ShutdownTrigger::TrayMenu);
             return true;
         }
 
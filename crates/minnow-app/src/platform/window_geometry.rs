@@ -0,0 +1,149 @@
+use crate::services::geometry::Rect;
+
+/// Where a window is, in the desktop coordinates the capture backend reports
+/// windows in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WindowGeometry {
+    /// The whole window, like the capture backend's window bounds.
+    pub bounds: Rect,
+    /// The area the application draws into, without the title bar and the
+    /// borders the system draws around it.
+    pub content: Rect,
+}
+
+/// Looks up the window with `id`, the capture backend's window id, without
+/// enumerating every window. Returns `None` once it is closed or minimized.
+#[cfg(target_os = "windows")]
+#[allow(clippy::cast_possible_truncation)]
+pub fn window_geometry(id: u32) -> Option<WindowGeometry> {
+    use windows::Win32::Foundation::{HWND, POINT, RECT};
+    use windows::Win32::Graphics::Dwm::{DWMWA_EXTENDED_FRAME_BOUNDS, DwmGetWindowAttribute};
+    use windows::Win32::Graphics::Gdi::ClientToScreen;
+    use windows::Win32::UI::WindowsAndMessaging::{GetClientRect, IsIconic, IsWindow};
+
+    let hwnd = HWND(id as usize as *mut _);
+    if !unsafe { IsWindow(Some(hwnd)) }.as_bool() || unsafe { IsIconic(hwnd) }.as_bool() {
+        return None;
+    }
+
+    let mut frame = RECT::default();
+    unsafe {
+        DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_EXTENDED_FRAME_BOUNDS,
+            (&raw mut frame).cast(),
+            std::mem::size_of::<RECT>() as u32,
+        )
+    }
+    .ok()?;
+    let mut client = RECT::default();
+    unsafe { GetClientRect(hwnd, &mut client) }.ok()?;
+    let mut client_origin = POINT::default();
+    if !unsafe { ClientToScreen(hwnd, &mut client_origin) }.as_bool() {
+        return None;
+    }
+
+    Some(WindowGeometry {
+        bounds: Rect::new(frame.left, frame.top, frame.right - frame.left, frame.bottom - frame.top),
+        content: Rect::new(client_origin.x, client_origin.y, client.right, client.bottom),
+    })
+}
+
+#[cfg(target_os = "macos")]
+#[allow(clippy::cast_possible_truncation)]
+pub fn window_geometry(id: u32) -> Option<WindowGeometry> {
+    use objc2_core_foundation::{CFBoolean, CFDictionary, CFString, CGRect};
+    use objc2_core_graphics::{
+        CGRectMakeWithDictionaryRepresentation, CGWindowListCopyWindowInfo, CGWindowListOption, kCGWindowBounds, kCGWindowIsOnscreen,
+    };
+
+    let windows = CGWindowListCopyWindowInfo(CGWindowListOption::OptionIncludingWindow, id)?;
+    if windows.count() == 0 {
+        return None;
+    }
+
+    // SAFETY: the window list holds a dictionary per window, and the keys
+    // read here map to a boolean and a rect dictionary.
+    let frame = unsafe {
+        let info = &*windows.value_at_index(0).cast::<CFDictionary>();
+        let value = |key: &CFString| info.value(std::ptr::from_ref(key).cast());
+        let on_screen = value(kCGWindowIsOnscreen).cast::<CFBoolean>();
+        if on_screen.is_null() || !(*on_screen).value() {
+            return None;
+        }
+        let bounds = value(kCGWindowBounds).cast::<CFDictionary>();
+        let mut frame = CGRect::default();
+        if bounds.is_null() || !CGRectMakeWithDictionaryRepresentation(Some(&*bounds), &mut frame) {
+            return None;
+        }
+        frame
+    };
+
+    let bounds = Rect::new(
+        frame.origin.x.round() as i32,
+        frame.origin.y.round() as i32,
+        frame.size.width.round() as i32,
+        frame.size.height.round() as i32,
+    );
+    let title_bar = title_bar_height().min(bounds.height);
+    Some(WindowGeometry {
+        bounds,
+        content: Rect::new(bounds.x, bounds.y + title_bar, bounds.width, bounds.height - title_bar),
+    })
+}
+
+/// Height of a standard title bar, as the window server lays it out. AppKit
+/// only answers on the main thread; elsewhere the title bar is kept.
+#[cfg(target_os = "macos")]
+#[allow(clippy::cast_possible_truncation)]
+fn title_bar_height() -> i32 {
+    use objc2::MainThreadMarker;
+    use objc2_app_kit::{NSWindow, NSWindowStyleMask};
+    use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+
+    MainThreadMarker::new().map_or(0, |mtm| {
+        let frame = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 1000.0));
+        let content = NSWindow::contentRectForFrameRect_styleMask(frame, NSWindowStyleMask::Titled, mtm);
+        (frame.size.height - content.size.height).round() as i32
+    })
+}
+
+/// The window manager draws its decorations outside the client window the
+/// capture backend reports, so the whole window is content.
+#[cfg(target_os = "linux")]
+pub fn window_geometry(id: u32) -> Option<WindowGeometry> {
+    use xcb::XidNew;
+    use xcb::x::{Drawable, GetGeometry, GetWindowAttributes, MapState, TranslateCoordinates, Window};
+
+    let (connection, _) = xcb::Connection::connect(None).ok()?;
+    let window = Window::new(id);
+    let attributes = connection.send_request(&GetWindowAttributes { window });
+    let geometry = connection.send_request(&GetGeometry {
+        drawable: Drawable::Window(window),
+    });
+    if connection.wait_for_reply(attributes).ok()?.map_state() != MapState::Viewable {
+        return None;
+    }
+    let geometry = connection.wait_for_reply(geometry).ok()?;
+    let origin = connection
+        .wait_for_reply(connection.send_request(&TranslateCoordinates {
+            src_window: window,
+            dst_window: geometry.root(),
+            src_x: 0,
+            src_y: 0,
+        }))
+        .ok()?;
+
+    let bounds = Rect::new(
+        i32::from(origin.dst_x()),
+        i32::from(origin.dst_y()),
+        i32::from(geometry.width()),
+        i32::from(geometry.height()),
+    );
+    Some(WindowGeometry { bounds, content: bounds })
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub fn window_geometry(_id: u32) -> Option<WindowGeometry> {
+    None
+}
@@ -1,4 +1,5 @@
-use gpui::{App, DisplayId, Pixels, Size, Window, WindowBackgroundAppearance, WindowBounds, WindowKind, WindowOptions};
+use crate::services::geometry::RectF;
+use gpui::{App, Bounds, DisplayId, Pixels, Size, Window, WindowBackgroundAppearance, WindowBounds, WindowKind, WindowOptions, point, px, size};
 
 #[derive(Clone, Copy, Debug)]
 pub struct PopupWindowSpec {
@@ -32,6 +33,25 @@ pub fn popup_window_options(spec: PopupWindowSpec, app_id: &'static str) -> Wind
     }
 }
 
+/// A popup pinned at `rect` in screen points, like the frame and toolbar
+/// windows that float over a capture session.
+pub fn fixed_popup_spec(rect: RectF, focus: bool) -> PopupWindowSpec {
+    PopupWindowSpec {
+        window_bounds: Some(WindowBounds::Windowed(Bounds::new(
+            point(px(rect.x as f32), px(rect.y as f32)),
+            size(px(rect.width.max(1.0) as f32), px(rect.height.max(1.0) as f32)),
+        ))),
+        kind: WindowKind::PopUp,
+        focus,
+        show: true,
+        is_movable: false,
+        is_resizable: false,
+        is_minimizable: false,
+        display_id: None,
+        window_min_size: None,
+    }
+}
+
 pub fn configure_window(window: &mut Window, cx: &mut App, focus: bool) {
     if focus {
         let focus_handle = cx.focus_handle();
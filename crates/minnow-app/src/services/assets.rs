@@ -16,22 +16,31 @@ pub mod asset_paths {
         pub const ARROW_DROP_DOWN: &str = "resources/icons/arrow_drop_down.svg";
         pub const ARROW_DROP_UP: &str = "resources/icons/arrow_drop_up.svg";
         pub const ARROW_INSERT: &str = "resources/icons/arrow_insert.svg";
+        pub const ARROW_RANGE: &str = "resources/icons/arrow_range.svg";
         pub const BLUR_ON: &str = "resources/icons/blur_on.svg";
         pub const CIRCLE: &str = "resources/icons/circle.svg";
         pub const CLOSE: &str = "resources/icons/close.svg";
         pub const COUNTER_1: &str = "resources/icons/counter_1.svg";
         pub const CROP_FREE: &str = "resources/icons/crop_free.svg";
+        pub const DRAW: &str = "resources/icons/draw.svg";
         pub const FILE_COPY: &str = "resources/icons/file_copy.svg";
         pub const GRID_ON: &str = "resources/icons/grid_on.svg";
+        pub const HORIZONTAL_RULE: &str = "resources/icons/horizontal_rule.svg";
+        pub const INK_HIGHLIGHTER: &str = "resources/icons/ink_highlighter.svg";
         pub const KEEP: &str = "resources/icons/keep.svg";
         pub const LENS_BLUR: &str = "resources/icons/lens_blur.svg";
+        pub const LINE_CURVE: &str = "resources/icons/line_curve.svg";
+        pub const LINE_STYLE: &str = "resources/icons/line_style.svg";
+        pub const MONITORING: &str = "resources/icons/monitoring.svg";
         pub const REDO: &str = "resources/icons/redo.svg";
         pub const SAVE: &str = "resources/icons/save.svg";
         pub const SCROLL: &str = "resources/icons/scroll.svg";
         pub const SQUARE: &str = "resources/icons/square.svg";
         pub const SQUARE_FILL: &str = "resources/icons/square_fill.svg";
+        pub const STOP: &str = "resources/icons/stop.svg";
         pub const TEXT_FIELDS: &str = "resources/icons/text_fields.svg";
         pub const UNDO: &str = "resources/icons/undo.svg";
+        pub const VIDEOCAM: &str = "resources/icons/videocam.svg";
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::services::capture::filename::CaptureOrigin;
 use crate::services::capture::service::{CaptureService, ResolvedCaptureImage};
 use crate::services::geometry::Rect;
 use crate::services::i18n;
@@ -12,6 +13,7 @@ pub enum CaptureAction {
     Pin,
     Ocr,
     Scroll,
+    Record,
     QrCode,
     PickColor,
     Unknown,
@@ -27,6 +29,7 @@ impl FromStr for CaptureAction {
             "pin" => Ok(CaptureAction::Pin),
             "ocr" => Ok(CaptureAction::Ocr),
             "scroll" => Ok(CaptureAction::Scroll),
+            "record" => Ok(CaptureAction::Record),
             "qrcode" => Ok(CaptureAction::QrCode),
             "pick-color" => Ok(CaptureAction::PickColor),
             _ => Ok(CaptureAction::Unknown),
@@ -53,6 +56,7 @@ pub(crate) enum CaptureActionPlan {
     SaveImage {
         image: Arc<RgbaImage>,
         save_path_override: Option<String>,
+        origin: CaptureOrigin,
     },
     Pin {
         image: Arc<RgbaImage>,
@@ -71,6 +75,8 @@ pub struct PinCaptureRequest {
     pub source_bounds: Rect,
     pub auto_ocr: bool,
     pub ocr_image: Arc<RgbaImage>,
+    /// History entry recorded for this pin, so OCR text can be attached to it.
+    pub history_id: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -79,11 +85,22 @@ pub enum CaptureInputMode {
     FullImage,
 }
 
+/// Marks a fresh capture for the history store. Hosts that replay an earlier
+/// capture (pins, the history window) leave it unset.
+#[derive(Clone, Debug, Default)]
+pub struct HistorySource {
+    /// The un-annotated source, cropped with the same rect as the capture.
+    pub base_image: Option<Arc<RgbaImage>>,
+    pub annotations: Option<String>,
+}
+
 pub struct ActionContext {
     source: ActionImageSource,
     pub rect: Rect,
     pub input_mode: CaptureInputMode,
     pub save_path_override: Option<String>,
+    pub origin: CaptureOrigin,
+    pub history: Option<HistorySource>,
 }
 
 enum ActionImageSource {
@@ -98,6 +115,8 @@ impl ActionContext {
             rect,
             input_mode: CaptureInputMode::CropSelection,
             save_path_override: None,
+            origin: CaptureOrigin::default(),
+            history: None,
         }
     }
 
@@ -107,6 +126,8 @@ impl ActionContext {
             rect: Rect::empty(),
             input_mode: CaptureInputMode::FullImage,
             save_path_override: None,
+            origin: CaptureOrigin::default(),
+            history: None,
         }
     }
 
@@ -116,6 +137,8 @@ impl ActionContext {
             rect: Rect::empty(),
             input_mode: CaptureInputMode::FullImage,
             save_path_override: None,
+            origin: CaptureOrigin::default(),
+            history: None,
         }
     }
 
@@ -123,6 +146,16 @@ impl ActionContext {
         self.save_path_override = Some(save_path_override);
         self
     }
+
+    pub fn with_origin(mut self, origin: CaptureOrigin) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn with_history(mut self, history: HistorySource) -> Self {
+        self.history = Some(history);
+        self
+    }
 }
 
 impl CaptureAction {
@@ -134,7 +167,7 @@ impl CaptureAction {
             CaptureAction::Ocr => Self::plan_pin_ocr(ctx, true),
             CaptureAction::QrCode => Self::plan_qrcode(ctx),
             CaptureAction::PickColor => Self::plan_pick_color(ctx),
-            CaptureAction::Scroll | CaptureAction::Unknown => CaptureActionPlan::NoOp,
+            CaptureAction::Scroll | CaptureAction::Record | CaptureAction::Unknown => CaptureActionPlan::NoOp,
         }
     }
 
@@ -160,6 +193,7 @@ impl CaptureAction {
             CaptureActionPlan::SaveImage {
                 image,
                 save_path_override: ctx.save_path_override,
+                origin: ctx.origin,
             }
         } else {
             CaptureActionPlan::CopyImage(image)
@@ -0,0 +1,127 @@
+//! Pacing for automatic scrolling during a long capture: inject one wheel
+//! step, wait until the stitcher sees the viewport settle, then step again.
+
+use super::stitcher::StitchFrameStatus;
+
+/// Wheel notches per step. Small steps keep enough overlap between frames
+/// for a confident match.
+pub(super) const STEP_NOTCHES: i32 = 2;
+/// Stationary frames in a row that count as the viewport having settled.
+const SETTLE_FRAMES: u32 = 3;
+/// Frames to wait for a settle before stepping anyway, for pages that keep
+/// animating.
+const SETTLE_TIMEOUT_FRAMES: u32 = 60;
+/// Steps in a row that moved nothing before the page counts as ended.
+const END_OF_PAGE_STEPS: u32 = 3;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum AutoScrollAction {
+    Wait,
+    Step,
+    /// The content stopped moving, or the capture is full.
+    Finish,
+}
+
+/// Decides, frame by frame, when the next wheel step is due. A step is
+/// expected to have been injected right before the first frame is fed.
+#[derive(Debug, Default)]
+pub(super) struct AutoScroller {
+    moved: bool,
+    stationary_frames: u32,
+    waited_frames: u32,
+    idle_steps: u32,
+}
+
+impl AutoScroller {
+    pub(super) fn on_frame(&mut self, status: StitchFrameStatus) -> AutoScrollAction {
+        self.waited_frames += 1;
+        match status {
+            StitchFrameStatus::Full => return AutoScrollAction::Finish,
+            StitchFrameStatus::Appended | StitchFrameStatus::Revisited => {
+                self.moved = true;
+                self.stationary_frames = 0;
+            }
+            StitchFrameStatus::LowConfidence | StitchFrameStatus::Reverse => self.stationary_frames = 0,
+            StitchFrameStatus::Stationary => self.stationary_frames += 1,
+        }
+
+        if self.stationary_frames < SETTLE_FRAMES && self.waited_frames < SETTLE_TIMEOUT_FRAMES {
+            return AutoScrollAction::Wait;
+        }
+
+        if self.moved {
+            self.idle_steps = 0;
+        } else {
+            self.idle_steps += 1;
+            if self.idle_steps >= END_OF_PAGE_STEPS {
+                return AutoScrollAction::Finish;
+            }
+        }
+        self.moved = false;
+        self.stationary_frames = 0;
+        self.waited_frames = 0;
+        AutoScrollAction::Step
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(scroller: &mut AutoScroller, statuses: &[StitchFrameStatus]) -> Vec<AutoScrollAction> {
+        statuses.iter().map(|status| scroller.on_frame(*status)).collect()
+    }
+
+    #[test]
+    fn steps_again_once_the_scrolled_viewport_settles() {
+        use StitchFrameStatus::{Appended, LowConfidence, Stationary};
+        let mut scroller = AutoScroller::default();
+
+        let actions = feed(&mut scroller, &[LowConfidence, Appended, Stationary, Stationary, Stationary]);
+
+        assert_eq!(
+            actions,
+            [
+                AutoScrollAction::Wait,
+                AutoScrollAction::Wait,
+                AutoScrollAction::Wait,
+                AutoScrollAction::Wait,
+                AutoScrollAction::Step,
+            ]
+        );
+    }
+
+    #[test]
+    fn finishes_when_steps_stop_moving_the_content() {
+        use StitchFrameStatus::{Appended, Stationary};
+        let mut scroller = AutoScroller::default();
+
+        assert_eq!(
+            feed(&mut scroller, &[Appended, Stationary, Stationary, Stationary])[3],
+            AutoScrollAction::Step
+        );
+        assert_eq!(feed(&mut scroller, &[Stationary; 3])[2], AutoScrollAction::Step);
+        assert_eq!(feed(&mut scroller, &[Stationary; 3])[2], AutoScrollAction::Step);
+        assert_eq!(feed(&mut scroller, &[Stationary; 3])[2], AutoScrollAction::Finish);
+    }
+
+    #[test]
+    fn finishes_as_soon_as_the_capture_is_full() {
+        let mut scroller = AutoScroller::default();
+
+        assert_eq!(
+            feed(&mut scroller, &[StitchFrameStatus::Appended, StitchFrameStatus::Full]),
+            [AutoScrollAction::Wait, AutoScrollAction::Finish]
+        );
+    }
+
+    #[test]
+    fn steps_anyway_when_the_viewport_never_settles() {
+        let mut scroller = AutoScroller::default();
+
+        let actions = feed(&mut scroller, &[StitchFrameStatus::LowConfidence; SETTLE_TIMEOUT_FRAMES as usize]);
+
+        assert!(actions[..actions.len() - 1].iter().all(|action| *action == AutoScrollAction::Wait));
+        assert_eq!(actions.last(), Some(&AutoScrollAction::Step));
+    }
+}
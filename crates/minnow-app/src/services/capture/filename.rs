@@ -0,0 +1,247 @@
+//! Filename templates for saved captures.
+//!
+//! A template is plain text with `{token}` placeholders:
+//!
+//! | token            | value                                        |
+//! |------------------|----------------------------------------------|
+//! | `{date}`         | local date, `YYYY-MM-DD`                     |
+//! | `{time}`         | local time, `HH-MM-SS`                       |
+//! | `{timestamp}`    | Unix time in milliseconds                    |
+//! | `{app}`          | app of the window the selection snapped to   |
+//! | `{window_title}` | title of the window the selection snapped to |
+//! | `{width}`        | image width in pixels                        |
+//! | `{height}`       | image height in pixels                       |
+//! | `{counter}`      | lowest free number, padded to three digits   |
+//!
+//! Unknown tokens are kept verbatim. The rendered name is sanitised for every
+//! platform so a template written on macOS still saves on Windows.
+
+/// Where a capture came from, used to fill the `{app}` and `{window_title}`
+/// tokens. Empty when the selection was drawn by hand.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CaptureOrigin {
+    pub app_name: Option<String>,
+    pub window_title: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct FileNameFields<'a> {
+    pub unix_millis: u128,
+    /// Offset of local time from UTC, used for `{date}` and `{time}`.
+    pub utc_offset_seconds: i32,
+    pub origin: &'a CaptureOrigin,
+    pub width: u32,
+    pub height: u32,
+}
+
+const FALLBACK_STEM: &str = "snap";
+const MAX_STEM_CHARS: usize = 200;
+const COUNTER_TOKEN: &str = "{counter}";
+const MAX_COLLISION_ATTEMPTS: u32 = 10_000;
+
+/// Picks the first file name for `template` that `claim` manages to take.
+///
+/// Templates with `{counter}` take the lowest free number; other templates get
+/// a ` (2)`, ` (3)`, ... suffix instead of overwriting an earlier capture.
+pub(crate) fn unique_file_name(template: &str, fields: &FileNameFields<'_>, extension: &str, mut claim: impl FnMut(&str) -> bool) -> String {
+    if template.contains(COUNTER_TOKEN) {
+        for counter in 1..=MAX_COLLISION_ATTEMPTS {
+            let name = format!("{}.{extension}", render_file_stem(template, fields, counter));
+            if claim(&name) {
+                return name;
+            }
+        }
+    }
+
+    let stem = render_file_stem(template, fields, 1);
+    let name = format!("{stem}.{extension}");
+    if claim(&name) {
+        return name;
+    }
+    for suffix in 2..=MAX_COLLISION_ATTEMPTS {
+        let name = format!("{stem} ({suffix}).{extension}");
+        if claim(&name) {
+            return name;
+        }
+    }
+    format!("{stem} ({}).{extension}", fields.unix_millis)
+}
+
+pub(crate) fn render_file_stem(template: &str, fields: &FileNameFields<'_>, counter: u32) -> String {
+    let (date, time) = local_date_time(fields.unix_millis, fields.utc_offset_seconds);
+    let mut rendered = String::with_capacity(template.len() + 16);
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        rendered.push_str(&rest[..open]);
+        let after_open = &rest[open..];
+        let Some(close) = after_open.find('}') else {
+            rendered.push_str(after_open);
+            rest = "";
+            break;
+        };
+        let token = &after_open[1..close];
+        match token {
+            "date" => rendered.push_str(&date),
+            "time" => rendered.push_str(&time),
+            "timestamp" => rendered.push_str(&fields.unix_millis.to_string()),
+            "app" => rendered.push_str(fields.origin.app_name.as_deref().unwrap_or_default()),
+            "window_title" => rendered.push_str(fields.origin.window_title.as_deref().unwrap_or_default()),
+            "width" => rendered.push_str(&fields.width.to_string()),
+            "height" => rendered.push_str(&fields.height.to_string()),
+            "counter" => rendered.push_str(&format!("{counter:03}")),
+            _ => rendered.push_str(&after_open[..=close]),
+        }
+        rest = &after_open[close + 1..];
+    }
+    rendered.push_str(rest);
+
+    sanitize_file_stem(&rendered)
+}
+
+/// Makes `stem` safe as a file name on Windows, macOS and Linux.
+pub(crate) fn sanitize_file_stem(stem: &str) -> String {
+    let replaced: String = stem
+        .chars()
+        .map(|ch| match ch {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            ch if ch.is_control() => '_',
+            ch => ch,
+        })
+        .take(MAX_STEM_CHARS)
+        .collect();
+    let trimmed = replaced.trim_start().trim_end_matches(['.', ' ']).trim_end();
+
+    if trimmed.is_empty() || trimmed.chars().all(|ch| ch == '_' || ch == '.') {
+        return FALLBACK_STEM.to_string();
+    }
+    if is_reserved_windows_name(trimmed) {
+        return format!("_{trimmed}");
+    }
+    trimmed.to_string()
+}
+
+fn is_reserved_windows_name(stem: &str) -> bool {
+    let base = stem.split('.').next().unwrap_or(stem).trim_end().to_ascii_uppercase();
+    match base.as_str() {
+        "CON" | "PRN" | "AUX" | "NUL" => true,
+        _ => {
+            let bytes = base.as_bytes();
+            bytes.len() == 4 && (base.starts_with("COM") || base.starts_with("LPT")) && (b'1'..=b'9').contains(&bytes[3])
+        }
+    }
+}
+
+/// Formats `unix_millis` shifted by `utc_offset_seconds` as `YYYY-MM-DD` and
+/// `HH-MM-SS`.
+pub(crate) fn local_date_time(unix_millis: u128, utc_offset_seconds: i32) -> (String, String) {
+    let seconds = i64::try_from(unix_millis / 1000).unwrap_or(i64::MAX) + i64::from(utc_offset_seconds);
+    let days = seconds.div_euclid(86_400);
+    let second_of_day = seconds.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+
+    (
+        format!("{year:04}-{month:02}-{day:02}"),
+        format!("{:02}-{:02}-{:02}", second_of_day / 3600, second_of_day % 3600 / 60, second_of_day % 60),
+    )
+}
+
+/// Converts days since 1970-01-01 to a proleptic Gregorian date.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, u32::try_from(month).unwrap_or(1), u32::try_from(day).unwrap_or(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    // 2024-02-29 13:05:09 UTC
+    const LEAP_DAY_MILLIS: u128 = 1_709_211_909_000;
+
+    fn fields(origin: &CaptureOrigin) -> FileNameFields<'_> {
+        FileNameFields {
+            unix_millis: LEAP_DAY_MILLIS,
+            utc_offset_seconds: 0,
+            origin,
+            width: 640,
+            height: 480,
+        }
+    }
+
+    #[test]
+    fn render_fills_every_known_token() {
+        let origin = CaptureOrigin {
+            app_name: Some("Firefox".to_string()),
+            window_title: Some("Issue #42".to_string()),
+        };
+
+        let stem = render_file_stem(
+            "{app}-{window_title}-{date}_{time}-{width}x{height}-{counter}-{timestamp}",
+            &fields(&origin),
+            7,
+        );
+
+        assert_eq!(stem, "Firefox-Issue #42-2024-02-29_13-05-09-640x480-007-1709211909000");
+    }
+
+    #[test]
+    fn render_applies_the_local_offset_and_keeps_unknown_tokens() {
+        let origin = CaptureOrigin::default();
+        let east = FileNameFields {
+            utc_offset_seconds: 11 * 3600,
+            ..fields(&origin)
+        };
+
+        assert_eq!(render_file_stem("{date} {time} {nope}", &east, 1), "2024-03-01 00-05-09 {nope}");
+        assert_eq!(render_file_stem("{date", &east, 1), "{date");
+    }
+
+    #[test]
+    fn sanitize_replaces_illegal_characters_and_reserved_names() {
+        assert_eq!(sanitize_file_stem("a/b\\c:d*e?f\"g<h>i|j"), "a_b_c_d_e_f_g_h_i_j");
+        assert_eq!(sanitize_file_stem("title. . "), "title");
+        assert_eq!(sanitize_file_stem("CON"), "_CON");
+        assert_eq!(sanitize_file_stem("lpt1.log"), "_lpt1.log");
+        assert_eq!(sanitize_file_stem("COM10"), "COM10");
+        assert_eq!(sanitize_file_stem("  "), "snap");
+        assert_eq!(sanitize_file_stem("{app}"), "{app}");
+    }
+
+    #[test]
+    fn unique_name_appends_a_suffix_instead_of_overwriting() {
+        let origin = CaptureOrigin::default();
+        let taken: HashSet<&str> = ["shot.png", "shot (2).png"].into_iter().collect();
+
+        let name = unique_file_name("shot", &fields(&origin), "png", |name| !taken.contains(name));
+
+        assert_eq!(name, "shot (3).png");
+    }
+
+    #[test]
+    fn unique_name_takes_the_lowest_free_counter() {
+        let origin = CaptureOrigin::default();
+        let taken: HashSet<&str> = ["Screenshot 001.jpg", "Screenshot 002.jpg"].into_iter().collect();
+
+        let name = unique_file_name("Screenshot {counter}", &fields(&origin), "jpg", |name| !taken.contains(name));
+
+        assert_eq!(name, "Screenshot 003.jpg");
+    }
+
+    #[test]
+    fn civil_from_days_handles_epoch_and_century_boundaries() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+    }
+}
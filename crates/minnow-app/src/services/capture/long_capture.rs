@@ -1,47 +1,112 @@
-use super::stitcher::{ScrollStitcher, StitchFrameStatus};
-use super::{active_monitor, crop_scaled_region};
+use super::auto_scroll::{AutoScrollAction, AutoScroller, STEP_NOTCHES};
+use super::long_capture_recording::FrameRecorder;
+use super::screen_recording::{Animation, AnimationSampler, MAX_RECORDING_BYTES, RecordingOptions};
+pub use super::stitch_report::{StitchReport, StitchSummary, StitchTuning};
+pub use super::stitched_capture::StitchedCapture;
+pub use super::stitcher::StitchMask;
+use super::stitcher::{ScrollStitcher, StitchConfig, StitchFrameResult, StitchFrameStatus};
+use super::{active_capture_target, crop_scaled_region};
 use crate::services::geometry::{Rect, RectF};
+use crate::services::settings;
 use image::RgbaImage;
+use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, MutexGuard, mpsc};
 use std::time::{Duration, Instant};
-use tracing::error;
+use tracing::{error, info, warn};
 
 const SCALE_EPSILON: f32 = 0.01;
 const CAPTURE_LOOP_INTERVAL: Duration = Duration::from_millis(16);
 const PREVIEW_EVENT_INTERVAL: Duration = Duration::from_millis(33);
-
-#[derive(Clone, Copy, Debug, PartialEq)]
-struct CaptureFrameTarget {
+/// Width of the live preview, which also bounds the frames of a session
+/// animation.
+const PREVIEW_WIDTH: u32 = 500;
+const DIAGNOSTICS_EVENT_INTERVAL: Duration = Duration::from_millis(250);
+/// How often a followed window is looked up again.
+const WINDOW_TRACK_INTERVAL: Duration = Duration::from_millis(200);
+/// Bad frames in a row before the user is warned.
+const LOW_CONFIDENCE_STREAK: usize = 6;
+
+/// Where long-capture frames are cropped from the captured surface.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CaptureFrameTarget {
     rect: Rect,
     viewport_rect: RectF,
     scale_hint: f32,
 }
 
 impl CaptureFrameTarget {
-    const fn new(rect: Rect, viewport_rect: RectF, scale_hint: f32) -> Self {
+    pub(super) const fn new(rect: Rect, viewport_rect: RectF, scale_hint: f32) -> Self {
         Self {
             rect,
             viewport_rect,
             scale_hint,
         }
     }
+
+    /// The same target with its rect moved by `dx`, `dy` selection points.
+    const fn offset(self, dx: i32, dy: i32) -> Self {
+        Self {
+            rect: Rect::new(self.rect.x + dx, self.rect.y + dy, self.rect.width, self.rect.height),
+            ..self
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum LongCaptureEvent {
     Started,
-    Progress { height: i32, preview_image: RgbaImage },
-    Warning { text: String },
+    /// `memory_bytes` counts the part of the capture held in memory; the
+    /// rest is spilled to disk.
+    Progress {
+        height: i32,
+        preview_image: RgbaImage,
+        memory_bytes: u64,
+    },
+    Warning {
+        text: String,
+    },
+    AutoScroll {
+        active: bool,
+    },
+    /// The tracked window moved; the capture rect now sits this far from
+    /// where it started.
+    TargetMoved {
+        dx: i32,
+        dy: i32,
+    },
+    /// How well frames have matched so far; sent a few times a second and
+    /// once more when the capture ends.
+    Diagnostics {
+        summary: StitchSummary,
+    },
     Finished,
 }
 
+/// Sends wheel notches to the captured content, positive values scrolling it
+/// down.
+pub type ScrollDriver = Box<dyn FnMut(i32) -> Result<(), String> + Send>;
+
+/// Looks up where the captured window is now, in the coordinates of the
+/// capture rect, or `None` when it cannot be found.
+pub type WindowTracker = Box<dyn FnMut() -> Option<Rect> + Send>;
+
+enum LongCaptureControl {
+    StartAutoScroll(ScrollDriver),
+    StopAutoScroll,
+    TrackWindow { bounds: Rect, locate: WindowTracker },
+}
+
 #[derive(Clone)]
 pub struct LongCaptureRuntime {
     active: Arc<AtomicBool>,
     events_tx: mpsc::Sender<LongCaptureEvent>,
     events_rx: Arc<Mutex<mpsc::Receiver<LongCaptureEvent>>>,
-    final_image: Arc<Mutex<Option<RgbaImage>>>,
+    controls_tx: mpsc::Sender<LongCaptureControl>,
+    controls_rx: Arc<Mutex<mpsc::Receiver<LongCaptureControl>>>,
+    final_capture: Arc<Mutex<Option<StitchedCapture>>>,
+    final_animation: Arc<Mutex<Option<Animation>>>,
+    report: Arc<Mutex<StitchReport>>,
 }
 
 impl Default for LongCaptureRuntime {
@@ -53,29 +118,60 @@ impl Default for LongCaptureRuntime {
 impl LongCaptureRuntime {
     pub fn new() -> Self {
         let (events_tx, events_rx) = mpsc::channel();
+        let (controls_tx, controls_rx) = mpsc::channel();
         Self {
             active: Arc::new(AtomicBool::new(false)),
             events_tx,
             events_rx: Arc::new(Mutex::new(events_rx)),
-            final_image: Arc::new(Mutex::new(None)),
+            controls_tx,
+            controls_rx: Arc::new(Mutex::new(controls_rx)),
+            final_capture: Arc::new(Mutex::new(None)),
+            final_animation: Arc::new(Mutex::new(None)),
+            report: Arc::new(Mutex::new(StitchReport::new(None, StitchConfig::default()))),
         }
     }
 
-    pub fn start_with_viewport(&self, rect: Rect, viewport_rect: RectF, scale_hint: f32) {
+    /// Starts capturing `rect`. `mask` marks the parts of the selection that
+    /// do not scroll, in selection points. `app` picks the stitching profile
+    /// saved for the captured application. With the animation setting on,
+    /// the frames are also sampled into an animation of the session.
+    pub fn start_with_viewport(&self, rect: Rect, viewport_rect: RectF, scale_hint: f32, mask: StitchMask, app: Option<String>) {
         self.stop();
         self.clear_pending_events();
-        *self.final_image_slot() = None;
+        self.clear_pending_controls();
+        *self.final_capture_slot() = None;
+        *self.final_animation_slot() = None;
         self.active.store(true, Ordering::SeqCst);
 
         let active = self.active.clone();
         let tx = self.events_tx.clone();
-        let final_image = self.final_image.clone();
-        let target = CaptureFrameTarget::new(rect, viewport_rect, scale_hint);
+        let controls = self.controls_rx.clone();
+        let final_capture = self.final_capture.clone();
+        let final_animation = self.final_animation.clone();
+        let initial_target = CaptureFrameTarget::new(rect, viewport_rect, scale_hint);
+        let general = settings::general_settings();
+        let tuning = app
+            .as_ref()
+            .and_then(|app| general.long_capture_profiles.get(app))
+            .copied()
+            .unwrap_or_default();
+        let config = tuning.apply(StitchConfig {
+            max_length: Some(general.long_capture_max_length),
+            ..StitchConfig::default()
+        });
+        let animation_options = general.long_capture_animation.then(|| RecordingOptions {
+            frame_rate: general.recording_frame_rate,
+            max_duration: Duration::from_secs(u64::from(general.recording_max_seconds)),
+            max_bytes: MAX_RECORDING_BYTES,
+            format: general.recording_format,
+        });
+        *self.report_slot() = StitchReport::new(app, config);
+        let report = self.report.clone();
 
         crate::RUNTIME.spawn_blocking(move || {
             let _ = tx.send(LongCaptureEvent::Started);
 
-            let Some(monitor) = active_monitor() else {
+            let Some(surface) = active_capture_target().and_then(|target| target.open()) else {
                 let _ = tx.send(LongCaptureEvent::Warning {
                     text: "No active monitor found for long capture".to_string(),
                 });
@@ -83,18 +179,89 @@ impl LongCaptureRuntime {
                 return;
             };
 
-            let mut stitcher = ScrollStitcher::new();
+            let mut recorder = None;
+            let mut stitcher: Option<ScrollStitcher> = None;
             let mut low_confidence_streak = 0usize;
             let mut warned = false;
+            let mut full = false;
             let mut preview_emitted = false;
             let mut last_preview_emit = Instant::now();
+            let mut last_diagnostics_emit = Instant::now();
+            let mut auto_scroll: Option<(AutoScroller, ScrollDriver)> = None;
+            let mut tracked_window: Option<(Rect, WindowTracker)> = None;
+            let mut last_window_track = Instant::now();
+            let mut target = initial_target;
+            let mut sampler = animation_options.map(|options| AnimationSampler::new(options, PREVIEW_WIDTH));
+            let started = Instant::now();
 
             while active.load(Ordering::SeqCst) {
-                match monitor.capture_image() {
-                    Ok(full_screen) => {
+                while let Ok(control) = lock_capture_state(&controls, "control receiver").try_recv() {
+                    auto_scroll = match control {
+                        LongCaptureControl::StartAutoScroll(mut driver) => match driver(STEP_NOTCHES) {
+                            Ok(()) => Some((AutoScroller::default(), driver)),
+                            Err(err) => {
+                                warn!("Failed to start auto-scroll: {err}");
+                                let _ = tx.send(LongCaptureEvent::Warning {
+                                    text: format!("Automatic scrolling is unavailable: {err}"),
+                                });
+                                None
+                            }
+                        },
+                        LongCaptureControl::StopAutoScroll => None,
+                        LongCaptureControl::TrackWindow { bounds, locate } => {
+                            tracked_window = Some((bounds, locate));
+                            continue;
+                        }
+                    };
+                    let _ = tx.send(LongCaptureEvent::AutoScroll {
+                        active: auto_scroll.is_some(),
+                    });
+                }
+
+                if let Some((bounds, locate)) = tracked_window.as_mut()
+                    && last_window_track.elapsed() >= WINDOW_TRACK_INTERVAL
+                {
+                    last_window_track = Instant::now();
+                    if let Some(current) = locate() {
+                        let (dx, dy) = (current.x - bounds.x, current.y - bounds.y);
+                        let moved = initial_target.offset(dx, dy);
+                        if moved != target {
+                            target = moved;
+                            let _ = tx.send(LongCaptureEvent::TargetMoved { dx, dy });
+                        }
+                    }
+                }
+
+                match surface.capture_image() {
+                    Some(full_screen) => {
                         if let Some(cropped) = crop_frame_with_scale_candidates(&full_screen, target) {
+                            let stitcher = stitcher.get_or_insert_with(|| {
+                                let mask = mask.scaled(frame_scale(&cropped, target));
+                                recorder = start_recording(target, &mask, config);
+                                ScrollStitcher::with_config(config).with_mask(mask)
+                            });
+                            if let Some(active_recorder) = recorder.as_mut()
+                                && let Err(err) = active_recorder.record(&cropped)
+                            {
+                                warn!("Stopped long-capture recording: {err}");
+                                recorder = None;
+                            }
+                            if let Some(sampler) = sampler.as_mut() {
+                                sampler.offer(&cropped, started.elapsed());
+                            }
                             let result = stitcher.process_frame_detailed(cropped);
-                            match result.status {
+                            let status = result.status;
+                            {
+                                let mut session_report = lock_capture_state(&report, "stitch report");
+                                session_report.record(&result);
+                                if last_diagnostics_emit.elapsed() >= DIAGNOSTICS_EVENT_INTERVAL {
+                                    let _ = tx.send(LongCaptureEvent::Diagnostics {
+                                        summary: session_report.summary(),
+                                    });
+                                    last_diagnostics_emit = Instant::now();
+                                }
+                            }
+                            match status {
                                 StitchFrameStatus::Appended => {
                                     low_confidence_streak = 0;
                                     if warned {
@@ -103,34 +270,63 @@ impl LongCaptureRuntime {
                                     }
                                     let should_emit_preview = !preview_emitted || last_preview_emit.elapsed() >= PREVIEW_EVENT_INTERVAL;
                                     if should_emit_preview {
-                                        if let Some(thumbnail) = stitcher.make_thumbnail(500) {
+                                        if let Some(thumbnail) = stitcher.make_thumbnail(PREVIEW_WIDTH) {
                                             let _ = tx.send(LongCaptureEvent::Progress {
                                                 height: result.height,
                                                 preview_image: thumbnail,
+                                                memory_bytes: stitcher.memory().resident,
                                             });
                                         }
                                         preview_emitted = true;
                                         last_preview_emit = Instant::now();
                                     }
                                 }
-                                StitchFrameStatus::Stationary => {
+                                StitchFrameStatus::Stationary | StitchFrameStatus::Revisited => {
+                                    low_confidence_streak = 0;
+                                }
+                                StitchFrameStatus::Full => {
                                     low_confidence_streak = 0;
+                                    if !full {
+                                        full = true;
+                                        let _ = tx.send(LongCaptureEvent::Warning {
+                                            text: result.warning.unwrap_or_default(),
+                                        });
+                                    }
                                 }
                                 StitchFrameStatus::LowConfidence | StitchFrameStatus::Reverse => {
                                     low_confidence_streak += 1;
-                                    if low_confidence_streak >= 6 {
+                                    if low_confidence_streak >= LOW_CONFIDENCE_STREAK {
                                         warned = true;
                                         let _ = tx.send(LongCaptureEvent::Warning {
-                                            text: result
-                                                .warning
-                                                .unwrap_or_else(|| "Long capture is unstable; try smoother scrolling".to_string()),
+                                            text: streak_warning(&result),
                                         });
                                     }
                                 }
                             }
+
+                            if let Some((scroller, driver)) = auto_scroll.as_mut() {
+                                match scroller.on_frame(status) {
+                                    AutoScrollAction::Wait => {}
+                                    AutoScrollAction::Step => {
+                                        if let Err(err) = driver(STEP_NOTCHES) {
+                                            warn!("Stopped auto-scroll: {err}");
+                                            auto_scroll = None;
+                                            let _ = tx.send(LongCaptureEvent::Warning {
+                                                text: format!("Automatic scrolling stopped: {err}"),
+                                            });
+                                            let _ = tx.send(LongCaptureEvent::AutoScroll { active: false });
+                                        }
+                                    }
+                                    AutoScrollAction::Finish => {
+                                        info!("Auto-scroll finished the long capture");
+                                        let _ = tx.send(LongCaptureEvent::AutoScroll { active: false });
+                                        active.store(false, Ordering::SeqCst);
+                                    }
+                                }
+                            }
                         }
                     }
-                    Err(_) => {
+                    None => {
                         let _ = tx.send(LongCaptureEvent::Warning {
                             text: "Failed to capture screen frame".to_string(),
                         });
@@ -140,8 +336,15 @@ impl LongCaptureRuntime {
                 std::thread::sleep(CAPTURE_LOOP_INTERVAL);
             }
 
-            let final_img = stitcher.get_final_image();
-            *lock_capture_state(&final_image, "final image") = final_img;
+            if let Some(limit) = sampler.as_ref().and_then(AnimationSampler::limit) {
+                info!("Long-capture animation stopped early at its {limit:?} limit");
+            }
+            // Stored before the capture so whoever takes the capture finds
+            // the animation ready too.
+            *lock_capture_state(&final_animation, "final animation") = sampler.and_then(|sampler| sampler.finish(started.elapsed()));
+            *lock_capture_state(&final_capture, "final capture") = stitcher.and_then(ScrollStitcher::into_capture);
+            let summary = lock_capture_state(&report, "stitch report").summary();
+            let _ = tx.send(LongCaptureEvent::Diagnostics { summary });
             let _ = tx.send(LongCaptureEvent::Finished);
         });
     }
@@ -150,17 +353,34 @@ impl LongCaptureRuntime {
         self.active.store(false, Ordering::SeqCst);
     }
 
-    pub fn stop_and_take_result(&self, timeout: Duration) -> Option<RgbaImage> {
+    /// Scrolls the captured content with `driver`, one step each time the
+    /// previous step has settled, and finishes the capture once the content
+    /// stops moving.
+    pub fn start_auto_scroll(&self, driver: ScrollDriver) {
+        let _ = self.controls_tx.send(LongCaptureControl::StartAutoScroll(driver));
+    }
+
+    pub fn stop_auto_scroll(&self) {
+        let _ = self.controls_tx.send(LongCaptureControl::StopAutoScroll);
+    }
+
+    /// Keeps the capture on the window that was at `bounds` when the capture
+    /// started, moving the cropped area along when `locate` finds it elsewhere.
+    pub fn track_window(&self, bounds: Rect, locate: WindowTracker) {
+        let _ = self.controls_tx.send(LongCaptureControl::TrackWindow { bounds, locate });
+    }
+
+    pub fn stop_and_take_result(&self, timeout: Duration) -> Option<StitchedCapture> {
         self.stop();
 
-        if let Some(image) = self.take_result() {
-            return Some(image);
+        if let Some(capture) = self.take_result() {
+            return Some(capture);
         }
 
         let deadline = Instant::now() + timeout;
         while Instant::now() < deadline {
-            if let Some(image) = self.take_result() {
-                return Some(image);
+            if let Some(capture) = self.take_result() {
+                return Some(capture);
             }
 
             let remaining = deadline.saturating_duration_since(Instant::now());
@@ -176,8 +396,8 @@ impl LongCaptureRuntime {
 
             match recv_result {
                 Ok(LongCaptureEvent::Finished) => {
-                    if let Some(image) = self.take_result() {
-                        return Some(image);
+                    if let Some(capture) = self.take_result() {
+                        return Some(capture);
                     }
                 }
                 Ok(_) => {}
@@ -189,8 +409,18 @@ impl LongCaptureRuntime {
         self.take_result()
     }
 
-    pub fn take_result(&self) -> Option<RgbaImage> {
-        self.final_image_slot().take()
+    pub fn take_result(&self) -> Option<StitchedCapture> {
+        self.final_capture_slot().take()
+    }
+
+    /// The animation sampled from the last capture, once it has finished.
+    pub fn take_animation(&self) -> Option<Animation> {
+        self.final_animation_slot().take()
+    }
+
+    /// Diagnostics of the current or last capture, frame by frame.
+    pub fn report(&self) -> StitchReport {
+        self.report_slot().clone()
     }
 
     pub fn drain_events(&self) -> Vec<LongCaptureEvent> {
@@ -210,12 +440,26 @@ impl LongCaptureRuntime {
         while rx.try_recv().is_ok() {}
     }
 
+    fn clear_pending_controls(&self) {
+        let rx = lock_capture_state(&self.controls_rx, "control receiver");
+
+        while rx.try_recv().is_ok() {}
+    }
+
     fn event_receiver(&self) -> MutexGuard<'_, mpsc::Receiver<LongCaptureEvent>> {
         lock_capture_state(&self.events_rx, "event receiver")
     }
 
-    fn final_image_slot(&self) -> MutexGuard<'_, Option<RgbaImage>> {
-        lock_capture_state(&self.final_image, "final image")
+    fn final_capture_slot(&self) -> MutexGuard<'_, Option<StitchedCapture>> {
+        lock_capture_state(&self.final_capture, "final capture")
+    }
+
+    fn final_animation_slot(&self) -> MutexGuard<'_, Option<Animation>> {
+        lock_capture_state(&self.final_animation, "final animation")
+    }
+
+    fn report_slot(&self) -> MutexGuard<'_, StitchReport> {
+        lock_capture_state(&self.report, "stitch report")
     }
 }
 
@@ -231,7 +475,30 @@ fn lock_capture_state<'a, T>(mutex: &'a Mutex<T>, state_name: &str) -> MutexGuar
     }
 }
 
-fn crop_frame_with_scale_candidates(full_screen: &RgbaImage, target: CaptureFrameTarget) -> Option<RgbaImage> {
+/// The frame's own warning, with the match scores that made it fail.
+fn streak_warning(result: &StitchFrameResult) -> String {
+    let warning = result.warning.as_deref().unwrap_or("Long capture is unstable; try smoother scrolling");
+    match (result.diagnostics.score, result.diagnostics.score_gap) {
+        (Some(score), Some(gap)) => format!("{warning} (score {score:.2}, gap {gap:.2})"),
+        _ => warning.to_string(),
+    }
+}
+
+fn start_recording(target: CaptureFrameTarget, mask: &StitchMask, config: StitchConfig) -> Option<FrameRecorder> {
+    FrameRecorder::from_env(target, mask.clone(), config)?
+        .inspect(|recorder| info!("Recording long-capture frames to {}", recorder.dir().display()))
+        .inspect_err(|err| warn!("Failed to start long-capture recording: {err}"))
+        .ok()
+}
+
+/// Captured pixels per selection point, which maps a mask marked on the
+/// selection onto the frames.
+#[allow(clippy::cast_precision_loss)]
+fn frame_scale(frame: &RgbaImage, target: CaptureFrameTarget) -> f32 {
+    frame.width() as f32 / target.rect.width.max(1) as f32
+}
+
+pub(super) fn crop_frame_with_scale_candidates(full_screen: &RgbaImage, target: CaptureFrameTarget) -> Option<RgbaImage> {
     let candidates = build_scale_candidates(full_screen.width(), full_screen.height(), &target.viewport_rect, target.scale_hint);
     for scale in candidates {
         if let Some(mut cropped) = crop_scaled_region(full_screen, target.rect, scale) {
@@ -349,17 +616,28 @@ mod tests {
         assert_eq!(target.scale_hint, 2.5);
     }
 
+    #[test]
+    fn offset_target_moves_only_the_rect() {
+        let target = CaptureFrameTarget::new(Rect::new(10, 20, 300, 400), RectF::new(0.0, 0.0, 800.0, 600.0), 2.0);
+
+        let moved = target.offset(-5, 30);
+
+        assert_eq!(moved.rect, Rect::new(5, 50, 300, 400));
+        assert_eq!(moved.viewport_rect, target.viewport_rect);
+        assert_eq!(moved.scale_hint, target.scale_hint);
+    }
+
     #[test]
     fn runtime_recovers_and_clears_a_poisoned_result_lock() {
         let runtime = LongCaptureRuntime::new();
         let panic_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            let _slot = runtime.final_image.lock().expect("lock should start healthy");
-            panic!("poison final image slot for recovery test");
+            let _slot = runtime.final_capture.lock().expect("lock should start healthy");
+            panic!("poison final capture slot for recovery test");
         }));
 
         assert!(panic_result.is_err());
-        assert!(runtime.final_image.is_poisoned());
+        assert!(runtime.final_capture.is_poisoned());
         assert!(runtime.take_result().is_none());
-        assert!(!runtime.final_image.is_poisoned());
+        assert!(!runtime.final_capture.is_poisoned());
     }
 }
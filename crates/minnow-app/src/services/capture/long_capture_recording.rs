@@ -0,0 +1,259 @@
+//! Raw frame recordings of long-capture sessions, and offline replay of them
+//! through the stitcher, so stitching bugs can be reproduced without a screen.
+//!
+//! Recording is enabled by pointing `MINNOW_LONG_CAPTURE_RECORD_DIR` at a
+//! directory. Every session then gets its own subdirectory:
+//!
+//! | file              | content                                             |
+//! |-------------------|-----------------------------------------------------|
+//! | `session.toml`    | [`RecordingManifest`] with the target, mask, config |
+//! | `frame-NNNNN.png` | each cropped frame, in the order it was stitched    |
+//!
+//! `MinnowSnap replay-long-capture DIR` feeds a session back through
+//! [`ScrollStitcher`] and prints the result of every frame; `--report FILE`
+//! also writes the session's [`StitchReport`] as JSON.
+
+use super::long_capture::CaptureFrameTarget;
+use super::stitch_report::StitchReport;
+use super::stitcher::{ScrollStitcher, StitchConfig, StitchFrameResult, StitchMask};
+use crate::services::history::now_millis;
+use crate::services::paths::ensure_dir;
+use image::{ImageFormat, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread::{self, JoinHandle};
+use tracing::warn;
+
+const RECORD_DIR_ENV: &str = "MINNOW_LONG_CAPTURE_RECORD_DIR";
+const MANIFEST_FILE: &str = "session.toml";
+const FRAME_PREFIX: &str = "frame-";
+const MANIFEST_VERSION: u32 = 1;
+/// Frames waiting for the writer; capture blocks once this many are queued
+/// rather than dropping frames the replay would then miss.
+const FRAME_QUEUE: usize = 32;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordingManifest {
+    pub version: u32,
+    pub target: CaptureFrameTarget,
+    /// The stitch mask in frame pixels.
+    #[serde(default)]
+    pub mask: StitchMask,
+    /// The stitcher settings of the session; older recordings replay with
+    /// the defaults.
+    #[serde(default)]
+    pub config: StitchConfig,
+}
+
+/// Writes a session's frames as PNGs on its own thread, so encoding never
+/// holds up the capture loop.
+pub(super) struct FrameRecorder {
+    dir: PathBuf,
+    frames: Option<SyncSender<RgbaImage>>,
+    writer: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl FrameRecorder {
+    /// A recorder for a new session, if recording is enabled.
+    pub(super) fn from_env(target: CaptureFrameTarget, mask: StitchMask, config: StitchConfig) -> Option<io::Result<Self>> {
+        let root = std::env::var_os(RECORD_DIR_ENV).filter(|value| !value.is_empty())?;
+        let dir = PathBuf::from(root).join(format!("long-capture-{}", now_millis()));
+        Some(Self::create(dir, target, mask, config))
+    }
+
+    pub(super) fn create(dir: PathBuf, target: CaptureFrameTarget, mask: StitchMask, config: StitchConfig) -> io::Result<Self> {
+        ensure_dir(&dir)?;
+        let manifest = RecordingManifest {
+            version: MANIFEST_VERSION,
+            target,
+            mask,
+            config,
+        };
+        let content = toml::to_string(&manifest).map_err(io::Error::other)?;
+        fs::write(dir.join(MANIFEST_FILE), content)?;
+
+        let (frames, receiver) = mpsc::sync_channel(FRAME_QUEUE);
+        let writer = thread::Builder::new().name("minnow-long-capture-recorder".to_string()).spawn({
+            let dir = dir.clone();
+            move || write_frames(&dir, &receiver)
+        })?;
+        Ok(Self {
+            dir,
+            frames: Some(frames),
+            writer: Some(writer),
+        })
+    }
+
+    pub(super) fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Queues `frame` for the writer; fails with the writer's error once it
+    /// has stopped.
+    pub(super) fn record(&mut self, frame: &RgbaImage) -> io::Result<()> {
+        let sent = self.frames.as_ref().is_some_and(|frames| frames.send(frame.clone()).is_ok());
+        if sent {
+            return Ok(());
+        }
+        self.frames = None;
+        match self.join_writer() {
+            Err(err) => Err(err),
+            Ok(()) => Err(io::Error::other("the frame writer has stopped")),
+        }
+    }
+
+    /// Waits for the queued frames to be written.
+    pub(super) fn finish(mut self) -> io::Result<()> {
+        self.frames = None;
+        self.join_writer()
+    }
+
+    fn join_writer(&mut self) -> io::Result<()> {
+        match self.writer.take().map(JoinHandle::join) {
+            None => Ok(()),
+            Some(Ok(result)) => result,
+            Some(Err(_)) => Err(io::Error::other("the frame writer panicked")),
+        }
+    }
+}
+
+impl Drop for FrameRecorder {
+    fn drop(&mut self) {
+        self.frames = None;
+        if let Err(err) = self.join_writer() {
+            warn!("Long-capture recording ended with an error: {err}");
+        }
+    }
+}
+
+fn write_frames(dir: &Path, frames: &Receiver<RgbaImage>) -> io::Result<()> {
+    let mut next_frame = 0_u32;
+    while let Ok(frame) = frames.recv() {
+        let path = dir.join(format!("{FRAME_PREFIX}{next_frame:05}.png"));
+        frame.save_with_format(path, ImageFormat::Png).map_err(io::Error::other)?;
+        next_frame += 1;
+    }
+    Ok(())
+}
+
+pub struct ReplayOutcome {
+    pub manifest: RecordingManifest,
+    /// The stitcher's verdict on each frame, in recording order.
+    pub frames: Vec<StitchFrameResult>,
+    pub report: StitchReport,
+    pub image: Option<RgbaImage>,
+}
+
+/// Stitches a recorded session again, frame by frame.
+pub fn replay(dir: &Path) -> io::Result<ReplayOutcome> {
+    let content = fs::read_to_string(dir.join(MANIFEST_FILE))?;
+    let manifest = toml::from_str::<RecordingManifest>(&content).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    if manifest.version != MANIFEST_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unsupported recording version {}", manifest.version),
+        ));
+    }
+
+    let config = manifest.config;
+    let mut stitcher = ScrollStitcher::with_config(config).with_mask(manifest.mask.clone());
+    let mut report = StitchReport::new(None, config);
+    let mut frames = Vec::new();
+    for path in frame_paths(dir)? {
+        let frame = image::open(&path).map_err(io::Error::other)?.into_rgba8();
+        let result = stitcher.process_frame_detailed(frame);
+        report.record(&result);
+        frames.push(result);
+    }
+
+    Ok(ReplayOutcome {
+        manifest,
+        frames,
+        report,
+        image: stitcher.get_final_image(),
+    })
+}
+
+fn frame_paths(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_frame = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(FRAME_PREFIX) && name.ends_with(".png"));
+        if is_frame {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::capture::stitcher::StitchFrameStatus;
+    use crate::services::geometry::{Rect, RectF};
+    use image::Rgba;
+
+    fn session_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("minnowsnap-long-capture-{name}-{}", std::process::id()))
+    }
+
+    #[test]
+    fn recorded_sessions_replay_through_the_stitcher() {
+        let dir = session_dir("replay");
+        let _ = fs::remove_dir_all(&dir);
+        let target = CaptureFrameTarget::new(Rect::new(10, 20, 200, 160), RectF::new(0.0, 0.0, 800.0, 600.0), 2.0);
+        let frame = RgbaImage::from_fn(200, 160, |x, y| {
+            let value = (x.wrapping_mul(0x9E37_79B9) ^ y.wrapping_mul(0x85EB_CA6B)) >> 24;
+            Rgba([value as u8, value as u8, value as u8, 255])
+        });
+
+        let mask = StitchMask {
+            header: 12,
+            ..StitchMask::default()
+        };
+        let config = StitchConfig {
+            min_overlap: 16,
+            ..StitchConfig::default()
+        };
+        let mut recorder = FrameRecorder::create(dir.clone(), target, mask.clone(), config).expect("create recording");
+        for _ in 0..3 {
+            recorder.record(&frame).expect("record frame");
+        }
+        recorder.finish().expect("write frames");
+        fs::write(dir.join("notes.txt"), "not a frame").expect("write unrelated file");
+
+        let outcome = replay(&dir);
+        let _ = fs::remove_dir_all(&dir);
+        let outcome = outcome.expect("replay recording");
+
+        assert_eq!(outcome.manifest.target, target);
+        assert_eq!(outcome.manifest.mask, mask);
+        assert_eq!(outcome.manifest.config, config);
+        assert_eq!(outcome.report.config, config);
+        let statuses = outcome.frames.iter().map(|result| result.status).collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [StitchFrameStatus::Appended, StitchFrameStatus::Stationary, StitchFrameStatus::Stationary]
+        );
+        assert_eq!(outcome.report.summary().stationary, 2);
+        assert_eq!(outcome.image, Some(frame));
+    }
+
+    #[test]
+    fn replay_rejects_directories_without_a_manifest() {
+        let dir = session_dir("missing");
+        let _ = fs::remove_dir_all(&dir);
+        ensure_dir(&dir).expect("create dir");
+
+        let result = replay(&dir);
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(result.err().map(|err| err.kind()), Some(io::ErrorKind::NotFound));
+    }
+}
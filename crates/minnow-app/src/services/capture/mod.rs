@@ -1,13 +1,22 @@
 pub mod action;
+mod auto_scroll;
+pub mod filename;
 pub mod long_capture;
+pub mod long_capture_recording;
+pub(crate) mod monitor;
+pub mod screen_recording;
 pub mod service;
+mod stitch_report;
+mod stitched_capture;
 mod stitcher;
+mod strip_store;
 
 use crate::services::geometry::Rect;
+use crate::services::settings;
 use image::RgbaImage;
+use monitor::{CaptureTarget, MonitorLayout};
 use std::sync::{Arc, LazyLock, Mutex};
-use tracing::{debug, error};
-use xcap::Monitor;
+use tracing::{debug, error, info};
 
 pub(crate) const PREVIEW_SOURCE: &str = "image://minnow/preview";
 
@@ -19,9 +28,23 @@ fn normalize_virtual_source(source: &str) -> &str {
     strip_query_fragment(source)
 }
 
+/// What a quick capture grabs without opening the overlay.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum QuickCaptureMode {
+    /// The whole capture target under the cursor.
+    FullScreen,
+    WindowUnderCursor,
+    FocusedWindow,
+    /// The region most recently captured from the overlay.
+    LastRegion,
+}
+
 #[derive(Default)]
 struct CaptureRepository {
     preview_capture: Mutex<Option<Arc<RgbaImage>>>,
+    target: Mutex<Option<CaptureTarget>>,
+    /// Last overlay selection, in logical desktop coordinates.
+    last_region: Mutex<Option<Rect>>,
 }
 
 impl CaptureRepository {
@@ -35,6 +58,28 @@ impl CaptureRepository {
             *cache = Some(image);
         }
     }
+
+    #[must_use]
+    fn get_target(&self) -> Option<CaptureTarget> {
+        self.target.lock().ok().and_then(|target| target.clone())
+    }
+
+    fn set_target(&self, next: CaptureTarget) {
+        if let Ok(mut target) = self.target.lock() {
+            *target = Some(next);
+        }
+    }
+
+    #[must_use]
+    fn get_last_region(&self) -> Option<Rect> {
+        self.last_region.lock().ok().and_then(|region| *region)
+    }
+
+    fn set_last_region(&self, next: Rect) {
+        if let Ok(mut region) = self.last_region.lock() {
+            *region = Some(next);
+        }
+    }
 }
 
 static CAPTURE_REPOSITORY: LazyLock<CaptureRepository> = LazyLock::new(CaptureRepository::default);
@@ -47,9 +92,70 @@ fn set_preview_capture(image: Arc<RgbaImage>) {
     CAPTURE_REPOSITORY.set_preview_capture(image);
 }
 
+/// Pins the surface for the next capture session: the monitor under `cursor`,
+/// or the whole virtual desktop when the user opted into it.
+///
+/// Every later capture, crop and long-capture frame reads from this target
+/// until a new session selects again.
+pub(crate) fn select_capture_target(cursor: Option<(i32, i32)>) -> Option<CaptureTarget> {
+    let scope = settings::general_settings().capture_scope;
+    let target = CaptureTarget::resolve(scope, MonitorLayout::current(), cursor)?;
+    info!("Selected capture target {target:?} for cursor {cursor:?}");
+    CAPTURE_REPOSITORY.set_target(target.clone());
+    Some(target)
+}
+
+/// Pins the surface for a capture of `region`, given in logical desktop
+/// coordinates, and returns the region relative to that surface.
+pub(crate) fn select_region_target(region: Rect) -> Option<Rect> {
+    let target = CaptureTarget::for_region(MonitorLayout::current(), region)?;
+    let origin = target.logical_bounds();
+    info!("Selected capture target {target:?} for region {region:?}");
+    CAPTURE_REPOSITORY.set_target(target);
+    Some(Rect::new(region.x - origin.x, region.y - origin.y, region.width, region.height))
+}
+
+/// Remembers an overlay selection, relative to the active capture target, so
+/// a quick capture can grab the same screen area again.
+pub(crate) fn remember_selection(selection: Rect) {
+    if !selection.has_area() {
+        return;
+    }
+    let Some(target) = CAPTURE_REPOSITORY.get_target() else {
+        return;
+    };
+    let origin = target.logical_bounds();
+    CAPTURE_REPOSITORY.set_last_region(Rect::new(
+        selection.x + origin.x,
+        selection.y + origin.y,
+        selection.width,
+        selection.height,
+    ));
+}
+
+/// The last remembered overlay selection in logical desktop coordinates.
+#[must_use]
+pub(crate) fn last_selection() -> Option<Rect> {
+    CAPTURE_REPOSITORY.get_last_region()
+}
+
+/// Maps an OS cursor position into logical desktop coordinates.
+#[must_use]
+pub(crate) fn logical_cursor_position(cursor: (i32, i32)) -> Option<(i32, i32)> {
+    let layout = MonitorLayout::current();
+    let monitor = layout.monitor_at(Some(cursor))?;
+    let point = monitor.to_logical(Rect::new(cursor.0, cursor.1, 1, 1));
+    Some((point.x, point.y))
+}
+
+#[must_use]
+pub(crate) fn active_capture_target() -> Option<CaptureTarget> {
+    CAPTURE_REPOSITORY.get_target().or_else(|| select_capture_target(None))
+}
+
 #[must_use]
 pub(crate) fn active_monitor_scale() -> f32 {
-    active_monitor().and_then(|m| m.scale_factor().ok()).unwrap_or(1.0)
+    active_capture_target().map_or(1.0, |target| target.scale())
 }
 
 #[must_use]
@@ -93,8 +199,8 @@ fn crop_scaled_region(image: &RgbaImage, rect: Rect, scale: f32) -> Option<RgbaI
     Some(sub_image.to_image())
 }
 
-/// Crops a user selection using the monitor scale and the capture coordinate
-/// recovery policy.
+/// Crops a user selection using the capture target scale and the capture
+/// coordinate recovery policy.
 pub(crate) fn crop_selection(image: &RgbaImage, rect: Rect) -> Option<RgbaImage> {
     crop_selection_with_scale(image, rect, active_monitor_scale())
 }
@@ -130,24 +236,13 @@ fn crop_selection_with_scale(image: &RgbaImage, rect: Rect, scale: f32) -> Optio
 }
 
 #[must_use]
-fn capture_active_monitor() -> Option<RgbaImage> {
-    let Some(monitor) = active_monitor() else {
+fn capture_active_target() -> Option<RgbaImage> {
+    let Some(target) = active_capture_target() else {
         error!("No monitors found");
         return None;
     };
 
-    match monitor.capture_image() {
-        Ok(image) => Some(image),
-        Err(e) => {
-            error!("Failed to capture monitor: {e}");
-            None
-        }
-    }
-}
-
-#[must_use]
-fn active_monitor() -> Option<Monitor> {
-    Monitor::all().ok()?.into_iter().next()
+    target.open()?.capture_image()
 }
 
 #[cfg(test)]
@@ -170,6 +265,30 @@ mod tests {
         assert_eq!(cached.dimensions(), (2, 3));
     }
 
+    #[test]
+    fn repository_keeps_the_selected_capture_target() {
+        let repository = CaptureRepository::default();
+        assert!(repository.get_target().is_none());
+
+        let region = monitor::MonitorRegion::new(7, (1920, 0), (2560, 1440), 1.5, false);
+        repository.set_target(CaptureTarget::Monitor(region));
+
+        let target = repository.get_target().unwrap();
+        assert_eq!(target, CaptureTarget::Monitor(region));
+        assert!((target.scale() - 1.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn repository_keeps_the_last_region() {
+        let repository = CaptureRepository::default();
+        assert!(repository.get_last_region().is_none());
+
+        repository.set_last_region(Rect::new(10, 20, 30, 40));
+        repository.set_last_region(Rect::new(-1920, 0, 300, 200));
+
+        assert_eq!(repository.get_last_region(), Some(Rect::new(-1920, 0, 300, 200)));
+    }
+
     #[test]
     fn scaled_region_crop_clamps_to_image_bounds() {
         let image = test_image(10, 10, 10);
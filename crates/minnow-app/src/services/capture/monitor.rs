@@ -0,0 +1,496 @@
+use crate::services::geometry::Rect;
+use crate::services::settings::CaptureScope;
+use image::{RgbaImage, imageops};
+use tracing::{error, warn};
+use xcap::Monitor;
+
+/// A display as reported by the platform capture backend.
+///
+/// `x`, `y`, `width` and `height` are desktop coordinates, i.e. the same space
+/// the OS cursor position is reported in. `logical_rect` converts them into the
+/// logical pixels used by overlay windows and selections.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct MonitorRegion {
+    pub id: u32,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale: f32,
+    pub primary: bool,
+    /// Top-left corner in logical desktop space. [`MonitorLayout`] places it
+    /// next to the displays this one touches, since dividing each origin by
+    /// its own scale would pull mixed-DPI neighbours apart or over each other.
+    pub logical_x: i32,
+    pub logical_y: i32,
+}
+
+impl MonitorRegion {
+    fn from_monitor(monitor: &Monitor) -> Option<Self> {
+        let region = (|| {
+            Ok::<_, xcap::XCapError>(Self::new(
+                monitor.id()?,
+                (monitor.x()?, monitor.y()?),
+                (monitor.width()?, monitor.height()?),
+                monitor.scale_factor().unwrap_or(1.0),
+                monitor.is_primary().unwrap_or(false),
+            ))
+        })();
+        match region {
+            Ok(region) => Some(region),
+            Err(err) => {
+                warn!("Skipping monitor with unreadable geometry: {err}");
+                None
+            }
+        }
+    }
+
+    /// A display at desktop `origin`, with its logical origin scaled from the
+    /// desktop one until a [`MonitorLayout`] places it.
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub(crate) fn new(id: u32, origin: (i32, i32), size: (u32, u32), scale: f32, primary: bool) -> Self {
+        let units = desktop_units_per_point(if scale > 0.0 { scale } else { 1.0 });
+        Self {
+            id,
+            x: origin.0,
+            y: origin.1,
+            width: size.0,
+            height: size.1,
+            scale,
+            primary,
+            logical_x: (origin.0 as f32 / units).round() as i32,
+            logical_y: (origin.1 as f32 / units).round() as i32,
+        }
+    }
+
+    pub(crate) fn contains(&self, x: i32, y: i32) -> bool {
+        let right = i64::from(self.x) + i64::from(self.width);
+        let bottom = i64::from(self.y) + i64::from(self.height);
+        x >= self.x && y >= self.y && i64::from(x) < right && i64::from(y) < bottom
+    }
+
+    pub(crate) fn scale(&self) -> f32 {
+        if self.scale > 0.0 { self.scale } else { 1.0 }
+    }
+
+    pub(crate) fn desktop_rect(&self) -> Rect {
+        Rect::new(
+            self.x,
+            self.y,
+            i32::try_from(self.width).unwrap_or(i32::MAX),
+            i32::try_from(self.height).unwrap_or(i32::MAX),
+        )
+    }
+
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub(crate) fn logical_rect(&self) -> Rect {
+        let units = desktop_units_per_point(self.scale());
+        Rect::new(
+            self.logical_x,
+            self.logical_y,
+            (self.width as f32 / units).round() as i32,
+            (self.height as f32 / units).round() as i32,
+        )
+    }
+
+    /// Moves this display's logical origin so it touches `placed` the way
+    /// their desktop rects touch, offset along the shared edge in the
+    /// placed display's units. Returns `false` when they do not share an
+    /// edge.
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    fn attach_to(&mut self, placed: &MonitorRegion) -> bool {
+        let (desktop, other) = (self.desktop_rect(), placed.desktop_rect());
+        let (logical, anchor) = (self.logical_rect(), placed.logical_rect());
+        let units = desktop_units_per_point(placed.scale());
+        let along = |offset: i32| (offset as f32 / units).round() as i32;
+        let rows_overlap = desktop.y < other.y + other.height && other.y < desktop.y + desktop.height;
+        let columns_overlap = desktop.x < other.x + other.width && other.x < desktop.x + desktop.width;
+
+        let origin = if rows_overlap && desktop.x == other.x + other.width {
+            (anchor.x + anchor.width, anchor.y + along(desktop.y - other.y))
+        } else if rows_overlap && desktop.x + desktop.width == other.x {
+            (anchor.x - logical.width, anchor.y + along(desktop.y - other.y))
+        } else if columns_overlap && desktop.y == other.y + other.height {
+            (anchor.x + along(desktop.x - other.x), anchor.y + anchor.height)
+        } else if columns_overlap && desktop.y + desktop.height == other.y {
+            (anchor.x + along(desktop.x - other.x), anchor.y - logical.height)
+        } else {
+            return false;
+        };
+        (self.logical_x, self.logical_y) = origin;
+        true
+    }
+
+    /// Maps a desktop rect on this monitor into logical desktop space, keeping
+    /// it anchored to the monitor's logical origin.
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub(crate) fn to_logical(self, rect: Rect) -> Rect {
+        let units = desktop_units_per_point(self.scale());
+        let origin = self.logical_rect();
+        Rect::new(
+            origin.x + ((rect.x - self.x) as f32 / units).round() as i32,
+            origin.y + ((rect.y - self.y) as f32 / units).round() as i32,
+            (rect.width as f32 / units).round().max(1.0) as i32,
+            (rect.height as f32 / units).round().max(1.0) as i32,
+        )
+    }
+
+    /// Maps a point in logical desktop space back to desktop coordinates
+    /// through this monitor, the inverse of [`Self::to_logical`].
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) fn to_desktop_point(self, (x, y): (f64, f64)) -> (i32, i32) {
+        let units = f64::from(desktop_units_per_point(self.scale()));
+        (
+            self.x + ((x - f64::from(self.logical_x)) * units).round() as i32,
+            self.y + ((y - f64::from(self.logical_y)) * units).round() as i32,
+        )
+    }
+}
+
+/// Desktop coordinates are physical pixels everywhere except macOS, where the
+/// window server already reports points.
+const fn desktop_units_per_point(scale: f32) -> f32 {
+    if cfg!(target_os = "macos") { 1.0 } else { scale }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct MonitorLayout {
+    regions: Vec<MonitorRegion>,
+}
+
+impl MonitorLayout {
+    pub(crate) fn current() -> Self {
+        match Monitor::all() {
+            Ok(monitors) => Self::from_regions(monitors.iter().filter_map(MonitorRegion::from_monitor).collect()),
+            Err(err) => {
+                error!("Failed to enumerate monitors: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    /// Lays the displays out in logical space, starting from the primary one
+    /// and attaching each remaining display to one it touches. Displays that
+    /// touch none keep their scaled desktop origin.
+    pub(crate) fn from_regions(mut regions: Vec<MonitorRegion>) -> Self {
+        let start = regions.iter().position(|region| region.primary).unwrap_or(0);
+        let mut placed: Vec<usize> = Vec::with_capacity(regions.len());
+        if !regions.is_empty() {
+            placed.push(start);
+        }
+        while placed.len() < regions.len() {
+            let attached = (0..regions.len()).filter(|index| !placed.contains(index)).find(|&index| {
+                let mut region = regions[index];
+                let attached = placed.iter().any(|&anchor| region.attach_to(&regions[anchor]));
+                regions[index] = region;
+                attached
+            });
+            let next = attached.unwrap_or_else(|| (0..regions.len()).find(|index| !placed.contains(index)).unwrap_or(0));
+            placed.push(next);
+        }
+        Self { regions }
+    }
+
+    pub(crate) fn regions(&self) -> &[MonitorRegion] {
+        &self.regions
+    }
+
+    pub(crate) fn primary(&self) -> Option<&MonitorRegion> {
+        self.regions.iter().find(|region| region.primary).or_else(|| self.regions.first())
+    }
+
+    /// Returns the monitor containing `point`, falling back to the primary
+    /// monitor when the point is unknown or lies in a gap between displays.
+    pub(crate) fn monitor_at(&self, point: Option<(i32, i32)>) -> Option<&MonitorRegion> {
+        point
+            .and_then(|(x, y)| self.regions.iter().find(|region| region.contains(x, y)))
+            .or_else(|| self.primary())
+    }
+
+    /// Returns the monitor whose logical rect contains `point`, falling back
+    /// to the primary monitor like [`Self::monitor_at`].
+    pub(crate) fn monitor_at_logical(&self, (x, y): (f64, f64)) -> Option<&MonitorRegion> {
+        self.regions
+            .iter()
+            .find(|region| {
+                let rect = region.logical_rect();
+                x >= f64::from(rect.x)
+                    && y >= f64::from(rect.y)
+                    && x < f64::from(rect.x) + f64::from(rect.width)
+                    && y < f64::from(rect.y) + f64::from(rect.height)
+            })
+            .or_else(|| self.primary())
+    }
+
+    /// Returns the monitor sharing the largest area with a desktop `rect`, so a
+    /// window straddling two displays resolves to the one showing most of it.
+    pub(crate) fn monitor_for_rect(&self, rect: Rect) -> Option<&MonitorRegion> {
+        self.regions
+            .iter()
+            .filter_map(|region| {
+                let overlap = region.desktop_rect().intersect(rect)?;
+                Some((region, i64::from(overlap.width) * i64::from(overlap.height)))
+            })
+            .max_by_key(|(_, area)| *area)
+            .map(|(region, _)| region)
+            .or_else(|| self.primary())
+    }
+
+    pub(crate) fn logical_bounds(&self) -> Rect {
+        let mut rects = self.regions.iter().map(MonitorRegion::logical_rect);
+        let Some(first) = rects.next() else {
+            return Rect::empty();
+        };
+        let (mut left, mut top) = (first.x, first.y);
+        let (mut right, mut bottom) = (first.x + first.width, first.y + first.height);
+        for rect in rects {
+            left = left.min(rect.x);
+            top = top.min(rect.y);
+            right = right.max(rect.x + rect.width);
+            bottom = bottom.max(rect.y + rect.height);
+        }
+        Rect::new(left, top, right - left, bottom - top)
+    }
+
+    pub(crate) fn max_scale(&self) -> f32 {
+        self.regions.iter().map(MonitorRegion::scale).fold(1.0, f32::max)
+    }
+}
+
+/// The surface a capture session reads pixels from.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum CaptureTarget {
+    Monitor(MonitorRegion),
+    /// Every monitor stitched into one canvas in logical desktop space. The
+    /// canvas uses the highest monitor scale so no display loses detail.
+    VirtualDesktop(MonitorLayout),
+}
+
+impl CaptureTarget {
+    pub(crate) fn resolve(scope: CaptureScope, layout: MonitorLayout, cursor: Option<(i32, i32)>) -> Option<Self> {
+        match scope {
+            CaptureScope::VirtualDesktop if layout.regions().len() > 1 => Some(Self::VirtualDesktop(layout)),
+            CaptureScope::VirtualDesktop | CaptureScope::CursorMonitor => layout.monitor_at(cursor).copied().map(Self::Monitor),
+        }
+    }
+
+    /// The target for a region in logical desktop coordinates: the monitor
+    /// showing all of it, or every monitor when it spans displays.
+    pub(crate) fn for_region(layout: MonitorLayout, region: Rect) -> Option<Self> {
+        if let Some(monitor) = layout.regions().iter().find(|monitor| region.is_inside(monitor.logical_rect())) {
+            return Some(Self::Monitor(*monitor));
+        }
+        Self::resolve(CaptureScope::VirtualDesktop, layout, None)
+    }
+
+    /// Physical pixels per logical pixel in images produced by this target.
+    pub(crate) fn scale(&self) -> f32 {
+        match self {
+            Self::Monitor(region) => region.scale(),
+            Self::VirtualDesktop(layout) => layout.max_scale(),
+        }
+    }
+
+    pub(crate) fn logical_bounds(&self) -> Rect {
+        match self {
+            Self::Monitor(region) => region.logical_rect(),
+            Self::VirtualDesktop(layout) => layout.logical_bounds(),
+        }
+    }
+
+    /// Binds the target to live monitor handles so repeated captures (long
+    /// capture polls every frame) avoid re-enumerating displays.
+    pub(crate) fn open(&self) -> Option<CaptureSurface> {
+        let monitors = Monitor::all().inspect_err(|err| error!("Failed to enumerate monitors: {err}")).ok()?;
+        let wanted: Vec<MonitorRegion> = match self {
+            Self::Monitor(region) => vec![*region],
+            Self::VirtualDesktop(layout) => layout.regions().to_vec(),
+        };
+        let bound: Vec<(MonitorRegion, Monitor)> = wanted
+            .into_iter()
+            .filter_map(|region| {
+                let monitor = monitors.iter().find(|monitor| monitor.id().is_ok_and(|id| id == region.id))?;
+                Some((region, monitor.clone()))
+            })
+            .collect();
+        if bound.is_empty() {
+            error!("Capture target no longer matches any connected monitor");
+            return None;
+        }
+        Some(CaptureSurface {
+            target: self.clone(),
+            monitors: bound,
+        })
+    }
+}
+
+pub(crate) struct CaptureSurface {
+    target: CaptureTarget,
+    monitors: Vec<(MonitorRegion, Monitor)>,
+}
+
+impl CaptureSurface {
+    pub(crate) fn capture_image(&self) -> Option<RgbaImage> {
+        let mut parts = Vec::with_capacity(self.monitors.len());
+        for (region, monitor) in &self.monitors {
+            match monitor.capture_image() {
+                Ok(image) => parts.push((*region, image)),
+                Err(err) => {
+                    error!("Failed to capture monitor {}: {err}", region.id);
+                    return None;
+                }
+            }
+        }
+
+        match &self.target {
+            CaptureTarget::Monitor(_) => parts.pop().map(|(_, image)| image),
+            CaptureTarget::VirtualDesktop(layout) => compose_virtual_desktop(&parts, layout.logical_bounds(), layout.max_scale()),
+        }
+    }
+}
+
+/// Places each monitor capture at its logical position on a canvas rendered at
+/// `scale`, resampling displays whose own scale differs from the canvas.
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn compose_virtual_desktop(parts: &[(MonitorRegion, RgbaImage)], bounds: Rect, scale: f32) -> Option<RgbaImage> {
+    if !bounds.has_area() || parts.is_empty() {
+        return None;
+    }
+    let canvas_w = (bounds.width as f32 * scale).round() as u32;
+    let canvas_h = (bounds.height as f32 * scale).round() as u32;
+    let mut canvas = RgbaImage::from_pixel(canvas_w, canvas_h, image::Rgba([0, 0, 0, 255]));
+
+    for (region, image) in parts {
+        let logical = region.logical_rect();
+        let target_w = (logical.width as f32 * scale).round().max(1.0) as u32;
+        let target_h = (logical.height as f32 * scale).round().max(1.0) as u32;
+        let offset_x = ((logical.x - bounds.x) as f32 * scale).round() as i64;
+        let offset_y = ((logical.y - bounds.y) as f32 * scale).round() as i64;
+
+        if image.dimensions() == (target_w, target_h) {
+            imageops::replace(&mut canvas, image, offset_x, offset_y);
+        } else {
+            let resized = imageops::resize(image, target_w, target_h, imageops::FilterType::Triangle);
+            imageops::replace(&mut canvas, &resized, offset_x, offset_y);
+        }
+    }
+
+    Some(canvas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(id: u32, x: i32, y: i32, width: u32, height: u32, scale: f32) -> MonitorRegion {
+        MonitorRegion::new(id, (x, y), (width, height), scale, id == 1)
+    }
+
+    fn desktop(x: i32, width: u32, scale: f32) -> (i32, u32) {
+        let units = desktop_units_per_point(scale);
+        ((x as f32 * units) as i32, (width as f32 * units) as u32)
+    }
+
+    #[test]
+    fn monitor_at_prefers_the_display_under_the_cursor() {
+        let layout = MonitorLayout::from_regions(vec![region(1, 0, 0, 100, 100, 1.0), region(2, 100, 0, 100, 100, 1.0)]);
+
+        assert_eq!(layout.monitor_at(Some((150, 50))).map(|m| m.id), Some(2));
+        assert_eq!(layout.monitor_at(Some((-20, 50))).map(|m| m.id), Some(1));
+        assert_eq!(layout.monitor_at(None).map(|m| m.id), Some(1));
+    }
+
+    #[test]
+    fn monitor_for_rect_picks_the_display_with_the_largest_overlap() {
+        let layout = MonitorLayout::from_regions(vec![region(1, 0, 0, 100, 100, 1.0), region(2, 100, 0, 100, 100, 1.0)]);
+
+        assert_eq!(layout.monitor_for_rect(Rect::new(80, 10, 40, 40)).map(|m| m.id), Some(2));
+        assert_eq!(layout.monitor_for_rect(Rect::new(60, 10, 50, 40)).map(|m| m.id), Some(1));
+        assert_eq!(layout.monitor_for_rect(Rect::new(500, 500, 10, 10)).map(|m| m.id), Some(1));
+    }
+
+    #[test]
+    fn to_logical_keeps_rects_anchored_to_their_monitor() {
+        let (x, width) = desktop(100, 100, 2.0);
+        let secondary = region(2, x, 0, width, 100, 2.0);
+        let units = desktop_units_per_point(2.0) as i32;
+
+        let logical = secondary.to_logical(Rect::new(x + 20 * units, 10 * units, 40 * units, 30 * units));
+
+        assert_eq!(logical, Rect::new(120, 10, 40, 30));
+        assert_eq!(secondary.to_desktop_point((120.0, 10.0)), (x + 20 * units, 10 * units));
+    }
+
+    #[test]
+    fn virtual_scope_with_one_monitor_falls_back_to_that_monitor() {
+        let layout = MonitorLayout::from_regions(vec![region(1, 0, 0, 100, 100, 1.0)]);
+
+        let target = CaptureTarget::resolve(CaptureScope::VirtualDesktop, layout, None);
+
+        assert!(matches!(target, Some(CaptureTarget::Monitor(region)) if region.id == 1));
+    }
+
+    #[test]
+    fn region_target_prefers_the_monitor_showing_the_whole_region() {
+        let layout = MonitorLayout::from_regions(vec![region(1, 0, 0, 100, 100, 1.0), region(2, 100, 0, 100, 100, 1.0)]);
+
+        let inside = CaptureTarget::for_region(layout.clone(), Rect::new(120, 10, 30, 30));
+        assert!(matches!(inside, Some(CaptureTarget::Monitor(region)) if region.id == 2));
+
+        let spanning = CaptureTarget::for_region(layout, Rect::new(80, 10, 40, 30));
+        assert!(matches!(spanning, Some(CaptureTarget::VirtualDesktop(_))));
+    }
+
+    #[test]
+    fn touching_monitors_stay_touching_in_logical_space() {
+        let units = desktop_units_per_point(2.0);
+        let (_, left_w) = desktop(0, 100, 1.0);
+        let (_, right_w) = desktop(0, 50, 2.0);
+        let (_, below_w) = desktop(0, 100, 2.0);
+        let left = region(1, 0, 0, left_w, 40, 1.0);
+        let right = region(2, left_w as i32, 20, right_w, (40.0 * units) as u32, 2.0);
+        let below = region(3, 10, 40, below_w, (30.0 * units) as u32, 2.0);
+        let beside_below = region(4, 10 + below_w as i32, 40, 50, 30, 1.0);
+
+        let layout = MonitorLayout::from_regions(vec![beside_below, below, right, left]);
+        let logical: Vec<Rect> = layout.regions().iter().map(MonitorRegion::logical_rect).collect();
+
+        assert_eq!(
+            logical,
+            [
+                Rect::new(110, 40, 50, 30),
+                Rect::new(10, 40, 100, 30),
+                Rect::new(100, 20, 50, 40),
+                Rect::new(0, 0, 100, 40),
+            ]
+        );
+        assert_eq!(layout.logical_bounds(), Rect::new(0, 0, 160, 70));
+    }
+
+    #[test]
+    fn virtual_desktop_canvas_uses_the_highest_scale_for_mixed_dpi_layouts() {
+        let (left_x, left_w) = desktop(0, 100, 1.0);
+        let (_, right_w) = desktop(0, 50, 2.0);
+        let right_x = left_x + left_w as i32;
+        let left = region(1, left_x, 0, left_w, 40, 1.0);
+        let right = MonitorRegion {
+            height: (40.0 * desktop_units_per_point(2.0)) as u32,
+            ..region(2, right_x, 0, right_w, 40, 2.0)
+        };
+        let layout = MonitorLayout::from_regions(vec![left, right]);
+        assert_eq!(layout.logical_bounds(), Rect::new(0, 0, 150, 40));
+
+        let [left, right] = [layout.regions()[0], layout.regions()[1]];
+        let parts = vec![
+            (left, RgbaImage::from_pixel(100, 40, image::Rgba([255, 0, 0, 255]))),
+            (right, RgbaImage::from_pixel(100, 80, image::Rgba([0, 0, 255, 255]))),
+        ];
+        let canvas = compose_virtual_desktop(&parts, layout.logical_bounds(), layout.max_scale()).unwrap();
+
+        assert_eq!(canvas.dimensions(), (300, 80));
+        assert_eq!(canvas.get_pixel(10, 10).0, [255, 0, 0, 255]);
+        assert_eq!(canvas.get_pixel(199, 79).0, [255, 0, 0, 255]);
+        assert_eq!(canvas.get_pixel(200, 0).0, [0, 0, 255, 255]);
+        assert_eq!(canvas.get_pixel(299, 79).0, [0, 0, 255, 255]);
+    }
+}
@@ -0,0 +1,560 @@
+//! Screen recording of a selected region: frames are sampled at a fixed rate,
+//! kept in memory while recording and encoded into an animated GIF or APNG
+//! once it stops.
+
+use super::active_capture_target;
+use super::long_capture::{CaptureFrameTarget, crop_frame_with_scale_candidates};
+use crate::services::geometry::{Rect, RectF};
+use crate::services::settings::RecordingFormat;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::imageops::{self, FilterType};
+use image::{Delay, Frame, RgbaImage};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, mpsc};
+use std::time::{Duration, Instant};
+use tracing::error;
+
+/// Estimated size of the saved file a recording may reach before it stops.
+pub const MAX_RECORDING_BYTES: u64 = 256 * 1024 * 1024;
+/// Frame memory a recording may fill before it stops, however well its
+/// frames would compress.
+const MAX_RECORDING_MEMORY: u64 = 2 * 1024 * 1024 * 1024;
+/// Trades GIF palette quality for encoding time; 1 is the slowest and best.
+const GIF_ENCODE_SPEED: i32 = 10;
+const PROGRESS_EVENT_INTERVAL: Duration = Duration::from_millis(200);
+/// Shortest delay a frame is written with; viewers treat zero as "as fast
+/// as possible".
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(10);
+/// Gives the overlay time to close so it does not show in the first frame.
+const START_DELAY: Duration = Duration::from_millis(200);
+/// Longest the loop sleeps between checks for a stop request.
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+struct AnimationFrame {
+    image: RgbaImage,
+    /// When the frame starts showing, from the start of the recording.
+    start: Duration,
+}
+
+/// Recorded frames with their timing, ready to be encoded.
+#[derive(Default)]
+pub struct Animation {
+    frames: Vec<AnimationFrame>,
+    end: Duration,
+    memory_bytes: u64,
+    /// Pixels of the kept frames that differ from their left neighbour, and
+    /// those that repeat it; runs of repeated pixels compress to very little.
+    busy_pixels: u64,
+    flat_pixels: u64,
+}
+
+impl Animation {
+    /// Adds a frame shown from `at` on. A frame identical to the last one
+    /// only extends it, so a still screen costs no memory. Returns whether
+    /// the frame was kept.
+    pub fn push(&mut self, image: RgbaImage, at: Duration) -> bool {
+        self.end = self.end.max(at);
+        let image = match self.frames.first() {
+            Some(first) if first.image.dimensions() != image.dimensions() => {
+                let (width, height) = first.image.dimensions();
+                imageops::resize(&image, width, height, FilterType::Triangle)
+            }
+            _ => image,
+        };
+        if self.frames.last().is_some_and(|last| last.image == image) {
+            return false;
+        }
+        let busy = busy_pixels(&image);
+        self.memory_bytes += image.len() as u64;
+        self.busy_pixels += busy;
+        self.flat_pixels += u64::from(image.width()) * u64::from(image.height()) - busy;
+        self.frames.push(AnimationFrame { image, start: at });
+        true
+    }
+
+    /// Marks when the last frame stops showing.
+    pub fn finish(&mut self, at: Duration) {
+        self.end = self.end.max(at);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.frames.first().map_or((0, 0), |frame| frame.image.dimensions())
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.frames.first().map_or(Duration::ZERO, |first| self.end.saturating_sub(first.start))
+    }
+
+    pub fn memory_bytes(&self) -> u64 {
+        self.memory_bytes
+    }
+
+    /// Rough size of the animation once encoded in `format`, from how much
+    /// of each frame is made of runs of one colour.
+    pub fn estimated_size(&self, format: RecordingFormat) -> u64 {
+        // Bytes per busy pixel, and flat pixels per byte.
+        let (busy_cost, flat_per_byte) = match format {
+            RecordingFormat::Gif => (1, 8),
+            RecordingFormat::Apng => (3, 16),
+        };
+        self.busy_pixels * busy_cost + self.flat_pixels / flat_per_byte
+    }
+
+    pub fn write(&self, path: &Path, format: RecordingFormat) -> io::Result<()> {
+        self.encode(BufWriter::new(File::create(path)?), format)
+    }
+
+    pub fn encode(&self, writer: impl Write, format: RecordingFormat) -> io::Result<()> {
+        if self.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "the recording has no frames"));
+        }
+        match format {
+            RecordingFormat::Gif => self.encode_gif(writer),
+            RecordingFormat::Apng => self.encode_apng(writer),
+        }
+    }
+
+    fn encode_gif(&self, writer: impl Write) -> io::Result<()> {
+        let mut encoder = GifEncoder::new_with_speed(writer, GIF_ENCODE_SPEED);
+        encoder.set_repeat(Repeat::Infinite).map_err(io::Error::other)?;
+        for (frame, delay) in self.frames.iter().zip(self.delays()) {
+            encoder
+                .encode_frame(Frame::from_parts(frame.image.clone(), 0, 0, Delay::from_saturating_duration(delay)))
+                .map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+
+    fn encode_apng(&self, writer: impl Write) -> io::Result<()> {
+        let (width, height) = self.dimensions();
+        let mut encoder = png::Encoder::new(writer, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let frames = u32::try_from(self.frames.len()).map_err(io::Error::other)?;
+        encoder.set_animated(frames, 0).map_err(io::Error::other)?;
+        let mut png = encoder.write_header().map_err(io::Error::other)?;
+        for (frame, delay) in self.frames.iter().zip(self.delays()) {
+            let millis = u16::try_from(delay.as_millis()).unwrap_or(u16::MAX);
+            png.set_frame_delay(millis, 1000).map_err(io::Error::other)?;
+            png.write_image_data(frame.image.as_raw()).map_err(io::Error::other)?;
+        }
+        png.finish().map_err(io::Error::other)
+    }
+
+    /// How long each frame shows: until the next one starts, or until the
+    /// end of the recording for the last.
+    fn delays(&self) -> impl Iterator<Item = Duration> + '_ {
+        let ends = self.frames.iter().skip(1).map(|frame| frame.start).chain([self.end]);
+        self.frames
+            .iter()
+            .zip(ends)
+            .map(|(frame, end)| end.saturating_sub(frame.start).max(MIN_FRAME_DELAY))
+    }
+}
+
+/// Why a recording stopped on its own.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RecordingLimit {
+    Duration,
+    Size,
+}
+
+/// Pixels of `image` that differ from the one to their left.
+fn busy_pixels(image: &RgbaImage) -> u64 {
+    let row_bytes = image.width() as usize * 4;
+    image
+        .as_raw()
+        .chunks_exact(row_bytes.max(4))
+        .map(|row| {
+            let pixels = row.chunks_exact(4);
+            1 + pixels.clone().zip(pixels.skip(1)).filter(|(left, pixel)| left != pixel).count() as u64
+        })
+        .sum()
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RecordingOptions {
+    pub frame_rate: u32,
+    pub max_duration: Duration,
+    /// Largest estimated size of the saved file, in `format`.
+    pub max_bytes: u64,
+    pub format: RecordingFormat,
+}
+
+impl RecordingOptions {
+    /// Whether `animation` has outgrown the size limit or the frame memory
+    /// a recording may use.
+    fn size_reached(&self, animation: &Animation) -> bool {
+        animation.estimated_size(self.format) >= self.max_bytes || animation.memory_bytes() >= MAX_RECORDING_MEMORY
+    }
+}
+
+/// Builds an animation out of frames captured for another purpose, such as
+/// a long capture, keeping only as many as the frame rate asks for and
+/// stopping at the limits in its options.
+pub struct AnimationSampler {
+    animation: Animation,
+    options: RecordingOptions,
+    frame_interval: Duration,
+    next_sample: Duration,
+    max_width: u32,
+    limit: Option<RecordingLimit>,
+}
+
+impl AnimationSampler {
+    /// Frames wider than `max_width` are scaled down to it.
+    pub fn new(options: RecordingOptions, max_width: u32) -> Self {
+        Self {
+            animation: Animation::default(),
+            options,
+            frame_interval: Duration::from_secs(1) / options.frame_rate.max(1),
+            next_sample: Duration::ZERO,
+            max_width: max_width.max(1),
+            limit: None,
+        }
+    }
+
+    /// Offers a frame captured `at` into the session; it is only copied when
+    /// the next sample is due. Returns whether it was kept.
+    pub fn offer(&mut self, frame: &RgbaImage, at: Duration) -> bool {
+        if self.limit.is_some() || at < self.next_sample {
+            return false;
+        }
+        if at >= self.options.max_duration {
+            self.limit = Some(RecordingLimit::Duration);
+            return false;
+        }
+
+        self.next_sample += self.frame_interval;
+        if self.next_sample <= at {
+            // Frames came in too slowly; skip the missed samples rather than
+            // taking them in a burst.
+            self.next_sample = at + self.frame_interval;
+        }
+        let (width, height) = frame.dimensions();
+        let image = if width > self.max_width {
+            let scaled_height = (u64::from(height) * u64::from(self.max_width) / u64::from(width)).max(1);
+            imageops::resize(frame, self.max_width, scaled_height as u32, FilterType::Triangle)
+        } else {
+            frame.clone()
+        };
+        let kept = self.animation.push(image, at);
+        if self.options.size_reached(&self.animation) {
+            self.limit = Some(RecordingLimit::Size);
+        }
+        kept
+    }
+
+    /// Why sampling stopped before the session did, if it did.
+    pub fn limit(&self) -> Option<RecordingLimit> {
+        self.limit
+    }
+
+    /// The sampled animation, ending `at`, or `None` if no frame was kept.
+    pub fn finish(mut self, at: Duration) -> Option<Animation> {
+        self.animation.finish(at.min(self.options.max_duration));
+        (!self.animation.is_empty()).then_some(self.animation)
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordingEvent {
+    Started,
+    Progress { elapsed: Duration, frames: usize },
+    Warning { text: String },
+    LimitReached { limit: RecordingLimit },
+    Finished,
+}
+
+#[derive(Clone)]
+pub struct ScreenRecordingRuntime {
+    active: Arc<AtomicBool>,
+    events_tx: mpsc::Sender<RecordingEvent>,
+    events_rx: Arc<Mutex<mpsc::Receiver<RecordingEvent>>>,
+    result: Arc<Mutex<Option<Animation>>>,
+}
+
+impl Default for ScreenRecordingRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScreenRecordingRuntime {
+    pub fn new() -> Self {
+        let (events_tx, events_rx) = mpsc::channel();
+        Self {
+            active: Arc::new(AtomicBool::new(false)),
+            events_tx,
+            events_rx: Arc::new(Mutex::new(events_rx)),
+            result: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Starts recording `rect`, a selection in the coordinates of
+    /// `viewport_rect`, until [`Self::stop`] or one of the limits in
+    /// `options` is reached.
+    pub fn start(&self, rect: Rect, viewport_rect: RectF, scale_hint: f32, options: RecordingOptions) {
+        self.stop();
+        *lock_recording_state(&self.result, "recording result") = None;
+        self.active.store(true, Ordering::SeqCst);
+
+        let active = self.active.clone();
+        let tx = self.events_tx.clone();
+        let result = self.result.clone();
+        let target = CaptureFrameTarget::new(rect, viewport_rect, scale_hint);
+        let frame_interval = Duration::from_secs(1) / options.frame_rate.max(1);
+
+        crate::RUNTIME.spawn_blocking(move || {
+            let _ = tx.send(RecordingEvent::Started);
+            std::thread::sleep(START_DELAY);
+
+            let Some(surface) = active_capture_target().and_then(|target| target.open()) else {
+                let _ = tx.send(RecordingEvent::Warning {
+                    text: "No active monitor found for recording".to_string(),
+                });
+                let _ = tx.send(RecordingEvent::Finished);
+                return;
+            };
+
+            let mut animation = Animation::default();
+            let started = Instant::now();
+            let mut next_frame = started;
+            let mut last_progress_emit: Option<Instant> = None;
+
+            while active.load(Ordering::SeqCst) {
+                let elapsed = started.elapsed();
+                if elapsed >= options.max_duration {
+                    let _ = tx.send(RecordingEvent::LimitReached {
+                        limit: RecordingLimit::Duration,
+                    });
+                    break;
+                }
+
+                match surface.capture_image() {
+                    Some(full_screen) => {
+                        if let Some(frame) = crop_frame_with_scale_candidates(&full_screen, target) {
+                            animation.push(frame, elapsed);
+                        }
+                    }
+                    None => {
+                        let _ = tx.send(RecordingEvent::Warning {
+                            text: "Failed to capture screen frame".to_string(),
+                        });
+                    }
+                }
+
+                if last_progress_emit.is_none_or(|emitted| emitted.elapsed() >= PROGRESS_EVENT_INTERVAL) {
+                    let _ = tx.send(RecordingEvent::Progress {
+                        elapsed,
+                        frames: animation.len(),
+                    });
+                    last_progress_emit = Some(Instant::now());
+                }
+                if options.size_reached(&animation) {
+                    let _ = tx.send(RecordingEvent::LimitReached { limit: RecordingLimit::Size });
+                    break;
+                }
+
+                next_frame += frame_interval;
+                let now = Instant::now();
+                if next_frame <= now {
+                    // Capturing fell behind; skip the missed frames rather
+                    // than recording them in a burst.
+                    next_frame = now;
+                }
+                while active.load(Ordering::SeqCst) {
+                    let remaining = next_frame.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        break;
+                    }
+                    std::thread::sleep(remaining.min(STOP_POLL_INTERVAL));
+                }
+            }
+
+            animation.finish(started.elapsed().min(options.max_duration));
+            active.store(false, Ordering::SeqCst);
+            *lock_recording_state(&result, "recording result") = (!animation.is_empty()).then_some(animation);
+            let _ = tx.send(RecordingEvent::Finished);
+        });
+    }
+
+    pub fn stop(&self) {
+        self.active.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    /// Stops the recording and waits up to `timeout` for its frames.
+    pub fn stop_and_take_result(&self, timeout: Duration) -> Option<Animation> {
+        self.stop();
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(animation) = self.take_result() {
+                return Some(animation);
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    pub fn take_result(&self) -> Option<Animation> {
+        lock_recording_state(&self.result, "recording result").take()
+    }
+
+    pub fn drain_events(&self) -> Vec<RecordingEvent> {
+        let receiver = lock_recording_state(&self.events_rx, "event receiver");
+        receiver.try_iter().collect()
+    }
+}
+
+fn lock_recording_state<'a, T>(mutex: &'a Mutex<T>, state_name: &str) -> MutexGuard<'a, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => {
+            error!("Screen recording {state_name} lock was poisoned; recovering state");
+            let guard = poisoned.into_inner();
+            mutex.clear_poison();
+            guard
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{AnimationDecoder, Rgba};
+
+    fn solid(value: u8) -> RgbaImage {
+        RgbaImage::from_pixel(8, 6, Rgba([value, 0, 255 - value, 255]))
+    }
+
+    fn recorded() -> Animation {
+        let mut animation = Animation::default();
+        assert!(animation.push(solid(0), Duration::ZERO));
+        assert!(!animation.push(solid(0), Duration::from_millis(100)));
+        assert!(animation.push(solid(200), Duration::from_millis(200)));
+        animation.finish(Duration::from_millis(300));
+        animation
+    }
+
+    #[test]
+    fn identical_frames_extend_the_previous_one() {
+        let animation = recorded();
+
+        assert_eq!(animation.len(), 2);
+        assert_eq!(animation.duration(), Duration::from_millis(300));
+        assert_eq!(animation.memory_bytes(), 2 * 8 * 6 * 4);
+        assert_eq!(
+            animation.delays().collect::<Vec<_>>(),
+            [Duration::from_millis(200), Duration::from_millis(100)]
+        );
+    }
+
+    #[test]
+    fn frames_of_another_size_are_scaled_to_the_first() {
+        let mut animation = Animation::default();
+        animation.push(solid(0), Duration::ZERO);
+        animation.push(RgbaImage::from_pixel(16, 12, Rgba([9, 9, 9, 255])), Duration::from_millis(50));
+
+        assert_eq!(animation.len(), 2);
+        assert_eq!(animation.dimensions(), (8, 6));
+    }
+
+    #[test]
+    fn gif_keeps_every_frame_and_its_timing() {
+        let mut bytes = Vec::new();
+        recorded().encode(&mut bytes, RecordingFormat::Gif).expect("encode gif");
+
+        let decoder = image::codecs::gif::GifDecoder::new(io::Cursor::new(bytes)).expect("decode gif");
+        let frames = decoder.into_frames().collect_frames().expect("gif frames");
+        assert_eq!(frames.len(), 2);
+        assert_eq!(Duration::from(frames[0].delay()), Duration::from_millis(200));
+        assert_eq!(frames[1].buffer().dimensions(), (8, 6));
+    }
+
+    #[test]
+    fn apng_declares_its_frames() {
+        let mut bytes = Vec::new();
+        recorded().encode(&mut bytes, RecordingFormat::Apng).expect("encode apng");
+
+        let reader = png::Decoder::new(io::Cursor::new(bytes)).read_info().expect("decode apng");
+        let control = reader.info().animation_control().expect("animated png");
+        assert_eq!(control.num_frames, 2);
+        assert_eq!(control.num_plays, 0);
+    }
+
+    #[test]
+    fn sampler_keeps_scaled_frames_at_its_rate_until_a_limit() {
+        let options = RecordingOptions {
+            frame_rate: 10,
+            max_duration: Duration::from_millis(350),
+            max_bytes: MAX_RECORDING_BYTES,
+            format: RecordingFormat::Gif,
+        };
+        let mut sampler = AnimationSampler::new(options, 4);
+
+        assert!(sampler.offer(&solid(0), Duration::ZERO));
+        assert!(!sampler.offer(&solid(50), Duration::from_millis(16)));
+        assert!(sampler.offer(&solid(100), Duration::from_millis(112)));
+        assert!(sampler.offer(&solid(150), Duration::from_millis(300)));
+        assert!(!sampler.offer(&solid(200), Duration::from_millis(400)));
+        assert_eq!(sampler.limit(), Some(RecordingLimit::Duration));
+
+        let animation = sampler.finish(Duration::from_secs(1)).expect("sampled frames");
+        assert_eq!(animation.len(), 3);
+        assert_eq!(animation.dimensions(), (4, 3));
+        assert_eq!(animation.duration(), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn size_limit_follows_the_estimated_file_size() {
+        let noise = RgbaImage::from_fn(64, 48, |x, y| Rgba([((x * 37) ^ (y * 91)) as u8, (x * y) as u8, 0, 255]));
+        let mut busy = Animation::default();
+        busy.push(noise.clone(), Duration::ZERO);
+        let mut flat = Animation::default();
+        flat.push(RgbaImage::from_pixel(64, 48, Rgba([9, 9, 9, 255])), Duration::ZERO);
+
+        assert!(flat.estimated_size(RecordingFormat::Gif) * 4 < busy.estimated_size(RecordingFormat::Gif));
+        assert!(busy.estimated_size(RecordingFormat::Gif) < busy.estimated_size(RecordingFormat::Apng));
+        assert!(busy.estimated_size(RecordingFormat::Apng) < busy.memory_bytes());
+
+        let options = RecordingOptions {
+            frame_rate: 10,
+            max_duration: Duration::from_secs(10),
+            max_bytes: busy.estimated_size(RecordingFormat::Gif) * 2,
+            format: RecordingFormat::Gif,
+        };
+        let mut sampler = AnimationSampler::new(options, 64);
+        for (index, at) in [0, 100, 200].into_iter().enumerate() {
+            let mut frame = noise.clone();
+            frame.put_pixel(0, 0, Rgba([index as u8, 0, 0, 255]));
+            sampler.offer(&frame, Duration::from_millis(at));
+        }
+        assert_eq!(sampler.limit(), Some(RecordingLimit::Size));
+        assert_eq!(sampler.finish(Duration::from_secs(1)).map(|animation| animation.len()), Some(2));
+    }
+
+    #[test]
+    fn empty_recordings_are_not_encoded() {
+        let error = Animation::default()
+            .encode(Vec::new(), RecordingFormat::Gif)
+            .expect_err("nothing to encode");
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
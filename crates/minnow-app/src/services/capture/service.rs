@@ -5,7 +5,7 @@ use std::sync::Arc;
 use tracing::{error, info};
 
 use super::{
-    active_monitor_scale, capture_active_monitor, crop_scaled_region, crop_selection, get_preview_capture, normalize_virtual_source,
+    active_monitor_scale, capture_active_target, crop_scaled_region, crop_selection, get_preview_capture, normalize_virtual_source,
     set_preview_capture,
 };
 
@@ -73,13 +73,13 @@ impl CaptureService {
         let scale_factor = active_monitor_scale();
 
         if rect.has_area() {
-            if let Some(monitor_img) = capture_active_monitor() {
+            if let Some(monitor_img) = capture_active_target() {
                 crop_scaled_region(&monitor_img, rect, scale_factor)
             } else {
                 None
             }
         } else {
-            capture_active_monitor()
+            capture_active_target()
         }
     }
 
@@ -89,6 +89,12 @@ impl CaptureService {
         Some(image)
     }
 
+    /// Makes `image` the frozen preview later crops read from, e.g. a canvas
+    /// rebuilt from capture history.
+    pub(crate) fn replace_preview(image: Arc<RgbaImage>) {
+        set_preview_capture(image);
+    }
+
     pub(crate) fn resolve_capture_image(path: &str, rect: Rect, input_mode: CaptureInputMode) -> Option<ResolvedCaptureImage> {
         let image = Self::resolve_source_image(path)?;
         if Self::is_full_request(rect, input_mode) {
@@ -0,0 +1,370 @@
+//! What the stitcher saw during a long capture: per-frame diagnostics,
+//! collected into a session report that can be exported as JSON and turned
+//! into a per-application [`StitchTuning`].
+
+use super::stitcher::{StitchConfig, StitchFrameResult, StitchFrameStatus};
+use crate::services::history::now_millis;
+use crate::services::paths::{app_paths, ensure_parent_dir};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Scored frames a report needs before it suggests a tuning.
+const MIN_TUNING_SAMPLES: usize = 8;
+/// Share of matched frames whose scores may fall under a suggested bound.
+const TUNING_PERCENTILE: f32 = 0.1;
+/// Headroom kept under the observed scores when suggesting bounds.
+const TUNING_MARGIN: f32 = 0.05;
+/// Lowest bounds a suggestion goes to; below them wrong matches slip through.
+const MIN_SUGGESTED_THRESHOLD: f32 = 0.35;
+const MIN_SUGGESTED_GAP: f32 = 0.02;
+/// Frame records a report keeps. Frames past it still count in the summary,
+/// so a capture left running does not grow the report without bound.
+const MAX_FRAME_RECORDS: usize = 4096;
+
+/// How the stitcher matched one frame against the one before it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct FrameDiagnostics {
+    /// Rows the content moved since the previous frame, positive when it
+    /// scrolled forward. Missing when no shift could be estimated.
+    pub shift: Option<i32>,
+    /// ZNCC score of the chosen shift, and its lead over the runner-up.
+    pub score: Option<f32>,
+    pub score_gap: Option<f32>,
+    /// Sticky rows at the top and bottom of the frame, detected or marked.
+    pub fixed_top: u32,
+    pub fixed_bottom: u32,
+    /// Frame row the appended content starts at, when the frame appended.
+    pub seam_row: Option<u32>,
+    pub elapsed_micros: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FrameRecord {
+    pub status: StitchFrameStatus,
+    /// Consecutive frames this record stands for; a run of stationary frames
+    /// collapses into its first one.
+    pub repeat: u32,
+    pub height: i32,
+    pub warning: Option<String>,
+    #[serde(flatten)]
+    pub diagnostics: FrameDiagnostics,
+}
+
+/// Overrides for [`StitchConfig`], kept per application in the settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StitchTuning {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub low_confidence_threshold: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub low_confidence_gap: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_stable_blocks: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zncc_search_radius: Option<i32>,
+}
+
+impl StitchTuning {
+    #[must_use]
+    pub fn apply(&self, config: StitchConfig) -> StitchConfig {
+        StitchConfig {
+            low_confidence_threshold: self.low_confidence_threshold.unwrap_or(config.low_confidence_threshold),
+            low_confidence_gap: self.low_confidence_gap.unwrap_or(config.low_confidence_gap),
+            min_stable_blocks: self.min_stable_blocks.unwrap_or(config.min_stable_blocks),
+            zncc_search_radius: self.zncc_search_radius.unwrap_or(config.zncc_search_radius),
+            ..config
+        }
+    }
+
+    /// This tuning with the overrides of `newer` on top.
+    #[must_use]
+    pub fn merge(self, newer: Self) -> Self {
+        Self {
+            low_confidence_threshold: newer.low_confidence_threshold.or(self.low_confidence_threshold),
+            low_confidence_gap: newer.low_confidence_gap.or(self.low_confidence_gap),
+            min_stable_blocks: newer.min_stable_blocks.or(self.min_stable_blocks),
+            zncc_search_radius: newer.zncc_search_radius.or(self.zncc_search_radius),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Counts and averages over a session, for display while capturing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct StitchSummary {
+    pub frames: usize,
+    pub appended: usize,
+    pub stationary: usize,
+    /// Low-confidence and reverse frames.
+    pub rejected: usize,
+    pub mean_score: Option<f32>,
+    pub min_score_gap: Option<f32>,
+    pub mean_elapsed_micros: u64,
+}
+
+impl StitchSummary {
+    /// Share of moving frames the stitcher could place, from 0 to 1.
+    pub fn confidence(&self) -> Option<f32> {
+        let placed = self.frames - self.stationary - self.rejected;
+        let moving = placed + self.rejected;
+        (moving > 0).then(|| placed as f32 / moving as f32)
+    }
+}
+
+/// Running counts behind [`StitchSummary`], updated per frame so the summary
+/// does not rescan the records.
+#[derive(Debug, Clone, Copy, Default)]
+struct SummaryTotals {
+    frames: usize,
+    appended: usize,
+    stationary: usize,
+    rejected: usize,
+    matched_scores: usize,
+    matched_score_sum: f64,
+    min_score_gap: Option<f32>,
+    elapsed_micros: u64,
+}
+
+impl SummaryTotals {
+    fn add(&mut self, result: &StitchFrameResult) {
+        self.frames += 1;
+        self.elapsed_micros += result.diagnostics.elapsed_micros;
+        match result.status {
+            StitchFrameStatus::Appended => self.appended += 1,
+            StitchFrameStatus::Stationary => self.stationary += 1,
+            StitchFrameStatus::LowConfidence | StitchFrameStatus::Reverse => self.rejected += 1,
+            StitchFrameStatus::Revisited | StitchFrameStatus::Full => {}
+        }
+        if matches!(result.status, StitchFrameStatus::Appended | StitchFrameStatus::Revisited) {
+            if let Some(score) = result.diagnostics.score {
+                self.matched_scores += 1;
+                self.matched_score_sum += f64::from(score);
+            }
+            if let Some(gap) = result.diagnostics.score_gap {
+                self.min_score_gap = Some(self.min_score_gap.map_or(gap, |min| min.min(gap)));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StitchReport {
+    /// Application the captured window belongs to, when known.
+    pub app: Option<String>,
+    pub config: StitchConfig,
+    pub frames: Vec<FrameRecord>,
+    /// Frames counted after [`MAX_FRAME_RECORDS`] records were kept.
+    pub dropped_frames: usize,
+    #[serde(skip)]
+    totals: SummaryTotals,
+}
+
+impl StitchReport {
+    pub fn new(app: Option<String>, config: StitchConfig) -> Self {
+        Self {
+            app,
+            config,
+            frames: Vec::new(),
+            dropped_frames: 0,
+            totals: SummaryTotals::default(),
+        }
+    }
+
+    pub fn record(&mut self, result: &StitchFrameResult) {
+        self.totals.add(result);
+        if result.status == StitchFrameStatus::Stationary
+            && let Some(last) = self.frames.last_mut()
+            && last.status == StitchFrameStatus::Stationary
+        {
+            last.repeat = last.repeat.saturating_add(1);
+            return;
+        }
+        if self.frames.len() >= MAX_FRAME_RECORDS {
+            self.dropped_frames += 1;
+            return;
+        }
+        self.frames.push(FrameRecord {
+            status: result.status,
+            repeat: 1,
+            height: result.height,
+            warning: result.warning.clone(),
+            diagnostics: result.diagnostics,
+        });
+    }
+
+    pub fn summary(&self) -> StitchSummary {
+        let totals = &self.totals;
+        StitchSummary {
+            frames: totals.frames,
+            appended: totals.appended,
+            stationary: totals.stationary,
+            rejected: totals.rejected,
+            mean_score: (totals.matched_scores > 0).then(|| (totals.matched_score_sum / totals.matched_scores as f64) as f32),
+            min_score_gap: totals.min_score_gap,
+            mean_elapsed_micros: totals.elapsed_micros / totals.frames.max(1) as u64,
+        }
+    }
+
+    /// Confidence bounds just under what this session's frames scored, so a
+    /// page that matches consistently but weakly stops being rejected.
+    /// Empty when there are too few frames or the defaults already fit.
+    pub fn suggested_tuning(&self) -> StitchTuning {
+        let suggest = |values: Vec<f32>, current: f32, floor: f32| {
+            if values.len() < MIN_TUNING_SAMPLES {
+                return None;
+            }
+            let bound = (percentile(values, TUNING_PERCENTILE) - TUNING_MARGIN).max(floor);
+            (bound < current).then_some(bound)
+        };
+        StitchTuning {
+            low_confidence_threshold: suggest(
+                self.scored(|diagnostics| diagnostics.score),
+                self.config.low_confidence_threshold,
+                MIN_SUGGESTED_THRESHOLD,
+            ),
+            low_confidence_gap: suggest(
+                self.scored(|diagnostics| diagnostics.score_gap),
+                self.config.low_confidence_gap,
+                MIN_SUGGESTED_GAP,
+            ),
+            ..StitchTuning::default()
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        #[derive(Serialize)]
+        struct Export<'a> {
+            #[serde(flatten)]
+            report: &'a StitchReport,
+            summary: StitchSummary,
+            suggested_tuning: StitchTuning,
+        }
+
+        serde_json::to_string_pretty(&Export {
+            report: self,
+            summary: self.summary(),
+            suggested_tuning: self.suggested_tuning(),
+        })
+    }
+
+    pub fn export(&self, path: &Path) -> io::Result<()> {
+        ensure_parent_dir(path)?;
+        fs::write(path, self.to_json().map_err(io::Error::other)?)
+    }
+
+    /// Writes the report next to the application logs.
+    pub fn export_to_logs(&self) -> io::Result<PathBuf> {
+        let path = app_paths().logs_dir().join(format!("long-capture-report-{}.json", now_millis()));
+        self.export(&path)?;
+        Ok(path)
+    }
+
+    /// Values of every frame that moved, placed or rejected for a weak match.
+    fn scored(&self, value: impl Fn(&FrameDiagnostics) -> Option<f32>) -> Vec<f32> {
+        self.frames
+            .iter()
+            .filter(|frame| frame.status != StitchFrameStatus::Stationary)
+            .filter_map(|frame| value(&frame.diagnostics))
+            .collect()
+    }
+}
+
+fn percentile(mut values: Vec<f32>, fraction: f32) -> f32 {
+    values.sort_by(f32::total_cmp);
+    let index = ((values.len() - 1) as f32 * fraction).round() as usize;
+    values[index]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(status: StitchFrameStatus, score: f32, score_gap: f32) -> StitchFrameResult {
+        StitchFrameResult {
+            status,
+            height: 100,
+            warning: None,
+            diagnostics: FrameDiagnostics {
+                shift: Some(24),
+                score: Some(score),
+                score_gap: Some(score_gap),
+                elapsed_micros: 500,
+                ..FrameDiagnostics::default()
+            },
+        }
+    }
+
+    #[test]
+    fn summary_counts_frames_and_averages_matched_scores() {
+        let mut report = StitchReport::new(None, StitchConfig::default());
+        report.record(&result(StitchFrameStatus::Appended, 0.9, 0.3));
+        report.record(&result(StitchFrameStatus::Appended, 0.7, 0.1));
+        report.record(&result(StitchFrameStatus::Stationary, 0.0, 0.0));
+        report.record(&result(StitchFrameStatus::LowConfidence, 0.4, 0.01));
+
+        let summary = report.summary();
+
+        assert_eq!((summary.frames, summary.appended, summary.stationary, summary.rejected), (4, 2, 1, 1));
+        assert!((summary.mean_score.expect("score") - 0.8).abs() < 1e-6);
+        assert_eq!(summary.min_score_gap, Some(0.1));
+        assert_eq!(summary.mean_elapsed_micros, 500);
+        assert!((summary.confidence().expect("confidence") - 2.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn weak_but_consistent_matches_suggest_lower_bounds() {
+        let mut report = StitchReport::new(Some("Reader".to_string()), StitchConfig::default());
+        for _ in 0..MIN_TUNING_SAMPLES {
+            report.record(&result(StitchFrameStatus::LowConfidence, 0.5, 0.2));
+        }
+
+        let tuning = report.suggested_tuning();
+
+        let threshold = tuning.low_confidence_threshold.expect("threshold");
+        assert!((threshold - 0.45).abs() < 1e-6);
+        assert_eq!(tuning.low_confidence_gap, None);
+        assert_eq!(tuning.apply(StitchConfig::default()).low_confidence_threshold, threshold);
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().expect("json")).expect("parse");
+        assert_eq!(json["app"], "Reader");
+        assert_eq!(json["frames"][0]["status"], "low-confidence");
+        assert_eq!(json["frames"][0]["shift"], 24);
+        assert_eq!(json["summary"]["rejected"], MIN_TUNING_SAMPLES);
+        assert!(json["suggested_tuning"]["low_confidence_threshold"].is_number());
+    }
+
+    #[test]
+    fn stationary_runs_collapse_and_records_stop_at_the_cap() {
+        let mut report = StitchReport::new(None, StitchConfig::default());
+        for _ in 0..3 {
+            report.record(&result(StitchFrameStatus::Stationary, 0.0, 0.0));
+        }
+        report.record(&result(StitchFrameStatus::Appended, 0.9, 0.3));
+        report.record(&result(StitchFrameStatus::Stationary, 0.0, 0.0));
+
+        let repeats: Vec<u32> = report.frames.iter().map(|frame| frame.repeat).collect();
+        assert_eq!(repeats, [3, 1, 1]);
+
+        for _ in 0..MAX_FRAME_RECORDS {
+            report.record(&result(StitchFrameStatus::Appended, 0.9, 0.3));
+        }
+
+        assert_eq!(report.frames.len(), MAX_FRAME_RECORDS);
+        assert_eq!(report.dropped_frames, 3);
+        assert_eq!(report.summary().frames, MAX_FRAME_RECORDS + 5);
+        assert_eq!(report.summary().stationary, 4);
+    }
+
+    #[test]
+    fn few_frames_suggest_nothing() {
+        let mut report = StitchReport::new(None, StitchConfig::default());
+        report.record(&result(StitchFrameStatus::LowConfidence, 0.4, 0.01));
+
+        assert!(report.suggested_tuning().is_empty());
+    }
+}
@@ -0,0 +1,538 @@
+//! The frame strips a long capture is stitched from, kept so the result can
+//! be reviewed and rebuilt: a bad segment can be dropped, a seam nudged and
+//! the ends trimmed without capturing again.
+//!
+//! Everything here works in scroll space, like the stitcher: the scroll axis
+//! runs down the rows, and horizontal captures are transposed back on render.
+
+use super::stitcher::ScrollAxis;
+use super::strip_store::{DEFAULT_MEMORY_BUDGET, Strip, StripMemory, StripStore};
+use image::RgbaImage;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use tracing::error;
+
+/// Rows kept above the appended part of each strip, so its seam can move up
+/// into the overlap the stitcher cut away.
+const SEAM_MARGIN: u32 = 64;
+/// Rows read from the strips at a time when copying them out.
+const READ_CHUNK_ROWS: u32 = 256;
+/// Upper bound for one band of a horizontal capture while it is streamed to
+/// PNG; every band reads the whole capture once.
+const PNG_BAND_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Clone)]
+pub(super) struct StitchSegment {
+    strip: Strip,
+    /// Rows of `strip` that are part of the capture.
+    start: u32,
+    end: u32,
+}
+
+impl StitchSegment {
+    /// Rows `start..end` of a scroll-space frame, with up to [`SEAM_MARGIN`]
+    /// rows above them.
+    pub(super) fn from_frame(store: &StripStore, frame: &RgbaImage, start: u32, end: u32) -> Self {
+        let end = end.min(frame.height());
+        let top = start.min(end).saturating_sub(SEAM_MARGIN);
+        let row_bytes = frame.width() as usize * 4;
+        let pixels = frame.as_raw()[top as usize * row_bytes..end as usize * row_bytes].to_vec();
+        Self {
+            strip: store.insert(frame.width(), end - top, pixels),
+            start: start.min(end) - top,
+            end: end - top,
+        }
+    }
+
+    pub(super) fn len(&self) -> u32 {
+        self.end - self.start
+    }
+
+    pub(super) fn width(&self) -> u32 {
+        self.strip.width()
+    }
+}
+
+/// Hands `count` rows of the segments, starting `from` rows into them, to
+/// `visit` a chunk at a time.
+pub(super) fn read_rows(segments: &[StitchSegment], from: u32, count: u32, mut visit: impl FnMut(&[u8]) -> io::Result<()>) -> io::Result<()> {
+    let Some(width) = segments.first().map(|segment| segment.strip.width()) else {
+        return Ok(());
+    };
+    let row_bytes = width as usize * 4;
+    let mut buffer = vec![0; row_bytes * READ_CHUNK_ROWS.min(count) as usize];
+    let mut skip = from;
+    let mut remaining = count;
+    for segment in segments {
+        if remaining == 0 {
+            break;
+        }
+        let skipped = skip.min(segment.len());
+        skip -= skipped;
+        let mut y = segment.start + skipped;
+        let end = segment.start + skipped + (segment.len() - skipped).min(remaining);
+        while y < end {
+            let rows = (end - y).min(READ_CHUNK_ROWS);
+            let chunk = &mut buffer[..row_bytes * rows as usize];
+            segment.strip.read_rows(y, chunk)?;
+            visit(chunk)?;
+            y += rows;
+            remaining -= rows;
+        }
+    }
+    Ok(())
+}
+
+/// Copies rows of the segments, starting `from` rows into them, over the
+/// whole of `out`.
+pub(super) fn copy_rows(segments: &[StitchSegment], from: u32, out: &mut RgbaImage) -> io::Result<()> {
+    let rows = out.height();
+    let out: &mut [u8] = out.as_mut();
+    let mut offset = 0;
+    read_rows(segments, from, rows, |chunk| {
+        out[offset..offset + chunk.len()].copy_from_slice(chunk);
+        offset += chunk.len();
+        Ok(())
+    })
+}
+
+/// Cuts the segments back to `length` rows, dropping segments that end up
+/// empty. Used when the stitcher trims the canvas before an append.
+pub(super) fn truncate_segments(segments: &mut Vec<StitchSegment>, length: u32) {
+    let mut remaining = length;
+    let mut keep = 0;
+    for segment in segments.iter_mut() {
+        if remaining == 0 {
+            break;
+        }
+        let len = segment.len().min(remaining);
+        segment.end = segment.start + len;
+        remaining -= len;
+        keep += 1;
+    }
+    segments.truncate(keep);
+}
+
+/// An editable long capture. Offsets are along the scroll axis, in pixels of
+/// the rendered image.
+#[derive(Clone)]
+pub struct StitchedCapture {
+    axis: ScrollAxis,
+    store: StripStore,
+    segments: Vec<StitchSegment>,
+    trim_start: u32,
+    trim_end: u32,
+}
+
+impl StitchedCapture {
+    pub(super) fn new(axis: ScrollAxis, store: StripStore, segments: Vec<StitchSegment>) -> Option<Self> {
+        let capture = Self {
+            axis,
+            store,
+            segments,
+            trim_start: 0,
+            trim_end: 0,
+        };
+        (capture.full_length() > 0).then_some(capture)
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        self.axis == ScrollAxis::Horizontal
+    }
+
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Bytes held by the strips of this capture and any earlier version of
+    /// it that is still around.
+    pub fn memory(&self) -> StripMemory {
+        self.store.memory()
+    }
+
+    /// Whether part of the capture outgrew memory and lives on disk; such
+    /// captures are better streamed with [`Self::write_png`] than rendered.
+    pub fn is_spilled(&self) -> bool {
+        self.memory().spilled > 0
+    }
+
+    /// Length of the rendered image along the scroll axis.
+    pub fn length(&self) -> u32 {
+        self.full_length() - self.trim_start - self.trim_end
+    }
+
+    /// Where each segment starts and ends in the rendered image. Spans of
+    /// trimmed segments reach outside `0..length`.
+    pub fn segment_spans(&self) -> Vec<(i64, i64)> {
+        let mut offset = -i64::from(self.trim_start);
+        self.segments
+            .iter()
+            .map(|segment| {
+                let start = offset;
+                offset += i64::from(segment.len());
+                (start, offset)
+            })
+            .collect()
+    }
+
+    /// The segment under `offset` in the rendered image.
+    pub fn segment_at(&self, offset: u32) -> usize {
+        let offset = i64::from(offset.min(self.length().saturating_sub(1)));
+        self.segment_spans()
+            .iter()
+            .position(|(_, end)| offset < *end)
+            .unwrap_or(self.segments.len() - 1)
+    }
+
+    /// Removes a segment; the last remaining one is kept.
+    pub fn drop_segment(&mut self, index: usize) -> bool {
+        if index >= self.segments.len() || self.segments.len() == 1 {
+            return false;
+        }
+        self.segments.remove(index);
+        self.clamp_trim();
+        true
+    }
+
+    /// Moves the seam at the top of segment `index` by `delta` rows of its
+    /// own frame: positive skips rows the stitcher repeated, negative brings
+    /// back rows it cut. The first segment has no seam.
+    pub fn nudge_seam(&mut self, index: usize, delta: i32) -> bool {
+        if index == 0 {
+            return false;
+        }
+        let Some(segment) = self.segments.get_mut(index) else {
+            return false;
+        };
+        let start = i64::from(segment.start) + i64::from(delta);
+        let start = start.clamp(0, i64::from(segment.end) - 1) as u32;
+        if start == segment.start {
+            return false;
+        }
+        segment.start = start;
+        self.clamp_trim();
+        true
+    }
+
+    /// Keeps only `from..to` of the rendered image.
+    pub fn crop(&mut self, from: u32, to: u32) -> bool {
+        let length = self.length();
+        let to = to.min(length);
+        if from >= to || (from == 0 && to == length) {
+            return false;
+        }
+        self.trim_start += from;
+        self.trim_end += length - to;
+        true
+    }
+
+    pub fn render(&self) -> Option<RgbaImage> {
+        let width = self.width();
+        let length = self.length();
+        if width == 0 || length == 0 {
+            return None;
+        }
+
+        let mut image = RgbaImage::new(width, length);
+        if let Err(err) = copy_rows(&self.segments, self.trim_start, &mut image) {
+            error!("Failed to read long-capture strips: {err}");
+            return None;
+        }
+        Some(self.axis.orient(image))
+    }
+
+    /// The rendered image scaled down so its extent across the scroll axis
+    /// is at most `max_extent`. Rows are averaged straight from the strips,
+    /// so only the scaled image is ever held in memory.
+    pub fn preview(&self, max_extent: u32) -> Option<RgbaImage> {
+        let width = self.width();
+        let length = self.length();
+        if width == 0 || length == 0 || max_extent == 0 {
+            return None;
+        }
+
+        let scale = (f64::from(max_extent) / f64::from(width)).min(1.0);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let scaled = |value: u32| ((f64::from(value) * scale).round() as u32).clamp(1, value);
+        let (preview_width, preview_length) = (scaled(width), scaled(length));
+        let columns: Vec<usize> = (0..width)
+            .map(|x| (u64::from(x) * u64::from(preview_width) / u64::from(width)) as usize)
+            .collect();
+
+        let mut image = RgbaImage::new(preview_width, preview_length);
+        let mut row = PreviewRow::new(preview_width);
+        let mut source_y = 0_u32;
+        let mut preview_y = 0;
+        let read = read_rows(&self.segments, self.trim_start, length, |chunk| {
+            for source_row in chunk.chunks_exact(width as usize * 4) {
+                let y = (u64::from(source_y) * u64::from(preview_length) / u64::from(length)) as u32;
+                if y != preview_y {
+                    row.flush_into(&mut image, preview_y);
+                    preview_y = y;
+                }
+                row.add(source_row, &columns);
+                source_y += 1;
+            }
+            Ok(())
+        });
+        if let Err(err) = read {
+            error!("Failed to read long-capture strips: {err}");
+            return None;
+        }
+        row.flush_into(&mut image, preview_y);
+        Some(self.axis.orient(image))
+    }
+
+    /// Size of the rendered image.
+    pub fn dimensions(&self) -> (u32, u32) {
+        match self.axis {
+            ScrollAxis::Vertical => (self.width(), self.length()),
+            ScrollAxis::Horizontal => (self.length(), self.width()),
+        }
+    }
+
+    /// Encodes the rendered image as PNG straight from the strips, without
+    /// building the whole image in memory.
+    pub fn write_png(&self, path: &Path) -> io::Result<()> {
+        let width = self.width();
+        let length = self.length();
+        let (image_width, image_height) = self.dimensions();
+        let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), image_width, image_height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut stream = encoder
+            .write_header()
+            .and_then(png::Writer::into_stream_writer)
+            .map_err(io::Error::other)?;
+
+        match self.axis {
+            ScrollAxis::Vertical => read_rows(&self.segments, self.trim_start, length, |rows| stream.write_all(rows))?,
+            ScrollAxis::Horizontal => self.write_transposed(length, width, &mut stream)?,
+        }
+        stream.finish().map_err(io::Error::other)
+    }
+
+    /// Writes the capture column by column: each band of image rows is a
+    /// band of scroll-space columns, gathered in one pass over the strips.
+    fn write_transposed(&self, length: u32, width: u32, out: &mut impl Write) -> io::Result<()> {
+        let length_px = length as usize;
+        let band_rows = (PNG_BAND_BYTES / (u64::from(length) * 4)).clamp(1, u64::from(width)) as u32;
+        let mut band = vec![0; length_px * band_rows as usize * 4];
+        for first in (0..width).step_by(band_rows as usize) {
+            let rows = band_rows.min(width - first) as usize;
+            let mut x = 0;
+            read_rows(&self.segments, self.trim_start, length, |chunk| {
+                for source_row in chunk.chunks_exact(width as usize * 4) {
+                    let columns = &source_row[first as usize * 4..(first as usize + rows) * 4];
+                    for (y, pixel) in columns.chunks_exact(4).enumerate() {
+                        let at = (y * length_px + x) * 4;
+                        band[at..at + 4].copy_from_slice(pixel);
+                    }
+                    x += 1;
+                }
+                Ok(())
+            })?;
+            out.write_all(&band[..rows * length_px * 4])?;
+        }
+        Ok(())
+    }
+
+    fn width(&self) -> u32 {
+        self.segments.first().map_or(0, StitchSegment::width)
+    }
+
+    fn full_length(&self) -> u32 {
+        self.segments.iter().map(StitchSegment::len).sum()
+    }
+
+    /// Keeps at least one row after segments shrink under the trims.
+    fn clamp_trim(&mut self) {
+        let full = self.full_length();
+        self.trim_start = self.trim_start.min(full.saturating_sub(1));
+        self.trim_end = self.trim_end.min(full - self.trim_start - 1);
+    }
+}
+
+/// Channel sums of the source pixels that fall into one row of a preview.
+struct PreviewRow {
+    sums: Vec<u32>,
+    counts: Vec<u32>,
+}
+
+impl PreviewRow {
+    fn new(width: u32) -> Self {
+        Self {
+            sums: vec![0; width as usize * 4],
+            counts: vec![0; width as usize],
+        }
+    }
+
+    fn add(&mut self, source_row: &[u8], columns: &[usize]) {
+        for (pixel, &x) in source_row.chunks_exact(4).zip(columns) {
+            self.counts[x] += 1;
+            for (sum, &channel) in self.sums[x * 4..x * 4 + 4].iter_mut().zip(pixel) {
+                *sum += u32::from(channel);
+            }
+        }
+    }
+
+    /// Writes the averaged row to row `y` of `image` and starts over.
+    fn flush_into(&mut self, image: &mut RgbaImage, y: u32) {
+        for (x, &count) in self.counts.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let pixel = image.get_pixel_mut(x as u32, y);
+            for (channel, sum) in pixel.0.iter_mut().zip(&self.sums[x * 4..x * 4 + 4]) {
+                *channel = ((sum + count / 2) / count) as u8;
+            }
+        }
+        self.sums.fill(0);
+        self.counts.fill(0);
+    }
+}
+
+/// A single-segment capture of an already stitched image.
+impl From<RgbaImage> for StitchedCapture {
+    fn from(image: RgbaImage) -> Self {
+        let height = image.height();
+        let store = StripStore::new(DEFAULT_MEMORY_BUDGET);
+        Self {
+            axis: ScrollAxis::Vertical,
+            segments: vec![StitchSegment::from_frame(&store, &image, 0, height)],
+            store,
+            trim_start: 0,
+            trim_end: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    /// A frame whose every row holds its own index, so rendered rows show
+    /// where they came from.
+    fn rows(first: u32, count: u32) -> RgbaImage {
+        RgbaImage::from_fn(2, count, |_, y| Rgba([(first + y) as u8, 0, 0, 255]))
+    }
+
+    fn row_values(image: &RgbaImage) -> Vec<u32> {
+        (0..image.height()).map(|y| u32::from(image.get_pixel(0, y)[0])).collect()
+    }
+
+    fn capture() -> StitchedCapture {
+        capture_in(StripStore::new(DEFAULT_MEMORY_BUDGET))
+    }
+
+    fn capture_in(store: StripStore) -> StitchedCapture {
+        let segments = vec![
+            StitchSegment::from_frame(&store, &rows(0, 10), 0, 10),
+            StitchSegment::from_frame(&store, &rows(4, 10), 6, 10),
+            StitchSegment::from_frame(&store, &rows(8, 10), 6, 10),
+        ];
+        StitchedCapture::new(ScrollAxis::Vertical, store, segments).expect("capture")
+    }
+
+    #[test]
+    fn segments_render_back_to_back() {
+        let capture = capture();
+
+        assert_eq!(row_values(&capture.render().expect("render")), (0..18).collect::<Vec<_>>());
+        assert_eq!(capture.segment_spans(), [(0, 10), (10, 14), (14, 18)]);
+        assert_eq!(capture.segment_at(12), 1);
+    }
+
+    #[test]
+    fn truncating_drops_rows_from_the_end() {
+        let store = StripStore::new(DEFAULT_MEMORY_BUDGET);
+        let mut segments = vec![
+            StitchSegment::from_frame(&store, &rows(0, 10), 0, 10),
+            StitchSegment::from_frame(&store, &rows(4, 10), 6, 10),
+        ];
+
+        truncate_segments(&mut segments, 8);
+
+        let capture = StitchedCapture::new(ScrollAxis::Vertical, store, segments).expect("capture");
+        assert_eq!(capture.segment_count(), 1);
+        assert_eq!(row_values(&capture.render().expect("render")), (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn nudging_a_seam_skips_or_restores_overlapping_rows() {
+        let mut capture = capture();
+
+        assert!(capture.nudge_seam(1, 2));
+        let image = capture.render().expect("render");
+        assert_eq!(row_values(&image)[8..], [8, 9, 12, 13, 14, 15, 16, 17]);
+
+        assert!(capture.nudge_seam(1, -4));
+        let image = capture.render().expect("render");
+        assert_eq!(row_values(&image)[8..14], [8, 9, 8, 9, 10, 11]);
+        assert!(!capture.nudge_seam(0, 1));
+    }
+
+    #[test]
+    fn dropping_and_cropping_rebuild_the_image() {
+        let mut capture = capture();
+
+        assert!(capture.drop_segment(1));
+        assert!(capture.crop(3, 12));
+        assert_eq!(row_values(&capture.render().expect("render")), [3, 4, 5, 6, 7, 8, 9, 14, 15]);
+        assert_eq!(capture.segment_spans(), [(-3, 7), (7, 11)]);
+
+        assert!(capture.drop_segment(0));
+        assert!(!capture.drop_segment(0));
+        assert_eq!(capture.length(), 1);
+    }
+
+    #[test]
+    fn previews_average_rows_without_rendering_the_capture() {
+        let mut capture = capture_in(StripStore::new(0));
+
+        assert_eq!(capture.preview(8), capture.render());
+        let preview = capture.preview(1).expect("preview");
+        assert_eq!(preview.dimensions(), (1, 9));
+        assert_eq!(row_values(&preview), [1, 3, 5, 7, 9, 11, 13, 15, 17]);
+
+        assert!(capture.crop(2, 14));
+        assert_eq!(row_values(&capture.preview(1).expect("preview")), [3, 5, 7, 9, 11, 13]);
+    }
+
+    #[test]
+    fn horizontal_captures_render_transposed() {
+        let store = StripStore::new(DEFAULT_MEMORY_BUDGET);
+        let segments = vec![StitchSegment::from_frame(&store, &rows(0, 5), 0, 5)];
+        let capture = StitchedCapture::new(ScrollAxis::Horizontal, store, segments).expect("capture");
+
+        let image = capture.render().expect("render");
+
+        assert_eq!(image.dimensions(), (5, 2));
+        assert_eq!(image.get_pixel(3, 1)[0], 3);
+    }
+
+    #[test]
+    fn spilled_captures_render_and_stream_the_same_image() {
+        let mut capture = capture_in(StripStore::new(0));
+        assert!(capture.is_spilled());
+        assert!(capture.crop(2, 15));
+        let expected = capture.render().expect("render");
+        assert_eq!(row_values(&expected), (2..15).collect::<Vec<_>>());
+
+        let path = std::env::temp_dir().join(format!("minnowsnap_stitched_capture_test_{}.png", std::process::id()));
+        capture.write_png(&path).expect("write png");
+        let written = image::open(&path).expect("open png").into_rgba8();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(written, expected);
+
+        let store = StripStore::new(0);
+        let segments = vec![StitchSegment::from_frame(&store, &rows(0, 5), 0, 5)];
+        let capture = StitchedCapture::new(ScrollAxis::Horizontal, store, segments).expect("capture");
+        capture.write_png(&path).expect("write png");
+        let written = image::open(&path).expect("open png").into_rgba8();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(Some(written), capture.render());
+    }
+}
@@ -1,23 +1,135 @@
+use super::stitch_report::FrameDiagnostics;
+use super::stitched_capture::{StitchSegment, StitchedCapture, copy_rows, truncate_segments};
+use super::strip_store::{DEFAULT_MEMORY_BUDGET, StripMemory, StripStore};
+use crate::services::geometry::Rect;
 use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tracing::error;
 
-const CANVAS_RESIZE_HEADROOM: u32 = 2_000;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum StitchFrameStatus {
     Appended,
     Stationary,
+    /// The viewport scrolled back over content that is already captured.
+    Revisited,
     LowConfidence,
+    /// The viewport scrolled back past the start of the capture.
     Reverse,
+    /// The capture reached its maximum length; new content is dropped.
+    Full,
 }
 
 #[derive(Debug, Clone)]
 pub struct StitchFrameResult {
     pub status: StitchFrameStatus,
+    /// Captured length along the scroll axis.
     pub height: i32,
     pub warning: Option<String>,
+    pub diagnostics: FrameDiagnostics,
+}
+
+/// Direction the captured content scrolls in.
+///
+/// Frames are stitched in scroll space: horizontal frames are transposed on
+/// the way in, so their columns become rows and every row-based step below
+/// (signatures, sticky bands, seams) works on columns instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScrollAxis {
+    Vertical,
+    Horizontal,
+}
+
+impl ScrollAxis {
+    /// Turns a frame into scroll space, or a scroll-space image back.
+    pub(super) fn orient(self, image: RgbaImage) -> RgbaImage {
+        match self {
+            Self::Vertical => image,
+            Self::Horizontal => transpose(&image),
+        }
+    }
+}
+
+fn transpose(image: &RgbaImage) -> RgbaImage {
+    image::imageops::flip_horizontal(&image::imageops::rotate90(image))
+}
+
+/// Parts of the frame the user marked as not scrolling with the page, in
+/// frame pixels. Marked bands widen whatever the stitcher detects itself.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StitchMask {
+    /// Rows pinned to the top of the viewport, such as a fixed header. Only
+    /// the first frame's copy ends up in the capture.
+    pub header: u32,
+    /// Rows pinned to the bottom; only the last frame's copy is kept.
+    pub footer: u32,
+    /// Columns pinned to the sides, such as sidebars. They are ignored when
+    /// matching frames.
+    pub left: u32,
+    pub right: u32,
+    /// Areas that change on their own, such as ads and animations. They are
+    /// ignored when matching frames.
+    pub dynamic: Vec<Rect>,
+}
+
+impl StitchMask {
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn scaled(&self, factor: f32) -> Self {
+        let scale_len = |value: u32| (value as f32 * factor).round() as u32;
+        let scale_pos = |value: i32| (value as f32 * factor).round() as i32;
+        Self {
+            header: scale_len(self.header),
+            footer: scale_len(self.footer),
+            left: scale_len(self.left),
+            right: scale_len(self.right),
+            dynamic: self
+                .dynamic
+                .iter()
+                .map(|rect| Rect::new(scale_pos(rect.x), scale_pos(rect.y), scale_pos(rect.width), scale_pos(rect.height)))
+                .collect(),
+        }
+    }
+
+    /// The mask in scroll space, where the scroll axis runs down the rows:
+    /// for horizontal scrolls the sidebars become the fixed bands.
+    fn oriented(&self, axis: ScrollAxis) -> Self {
+        match axis {
+            ScrollAxis::Vertical => self.clone(),
+            ScrollAxis::Horizontal => Self {
+                header: self.left,
+                footer: self.right,
+                left: self.header,
+                right: self.footer,
+                dynamic: self
+                    .dynamic
+                    .iter()
+                    .map(|rect| Rect::new(rect.y, rect.x, rect.height, rect.width))
+                    .collect(),
+            },
+        }
+    }
+
+    /// Whether the columns `x0..x1` of rows `y0..y1` touch a marked area.
+    fn excludes(&self, width: usize, x0: usize, x1: usize, y0: usize, y1: usize) -> bool {
+        let overlaps = |start: i32, len: i32, from: usize, to: usize| {
+            let start = i64::from(start);
+            let end = start + i64::from(len.max(0));
+            start < to as i64 && end > from as i64
+        };
+        x0 < self.left as usize
+            || x1 > width.saturating_sub(self.right as usize)
+            || self
+                .dynamic
+                .iter()
+                .any(|rect| overlaps(rect.x, rect.width, x0, x1) && overlaps(rect.y, rect.height, y0, y1))
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StitchConfig {
     pub min_overlap: u32,
     pub min_scroll_threshold: u32,
@@ -30,6 +142,12 @@ pub struct StitchConfig {
     pub low_confidence_threshold: f32,
     pub low_confidence_gap: f32,
     pub seam_margin_divisor: u32,
+    /// Fixed scroll axis; `None` detects it from the first scroll.
+    pub axis: Option<ScrollAxis>,
+    /// Longest capture along the scroll axis; `None` has no limit.
+    pub max_length: Option<u32>,
+    /// Strip bytes kept in memory before older strips spill to disk.
+    pub memory_budget: u64,
 }
 
 impl Default for StitchConfig {
@@ -46,6 +164,9 @@ impl Default for StitchConfig {
             low_confidence_threshold: 0.52,
             low_confidence_gap: 0.06,
             seam_margin_divisor: 5,
+            axis: None,
+            max_length: None,
+            memory_budget: DEFAULT_MEMORY_BUDGET,
         }
     }
 }
@@ -100,10 +221,33 @@ struct FramePairRef<'a> {
     next: &'a [f32],
 }
 
+#[derive(Debug, Clone, Copy)]
+struct FrameMotion {
+    region: StitchRegion,
+    delta: i32,
+}
+
+#[derive(Debug, Clone)]
+struct FrameRejection {
+    status: StitchFrameStatus,
+    warning: Option<&'static str>,
+}
+
+impl FrameRejection {
+    fn low_confidence(warning: &'static str) -> Self {
+        Self {
+            status: StitchFrameStatus::LowConfidence,
+            warning: Some(warning),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct StitchAppendPlan {
     trim_amount: u32,
     append_start_y: u32,
+    /// Includes the frame's footer, so the capture always ends in exactly one
+    /// copy of it; the next append trims it again.
     append_end_y: u32,
     fixed_bottom: u32,
 }
@@ -144,13 +288,25 @@ impl ThumbnailCache {
 }
 
 pub struct ScrollStitcher {
-    canvas: Option<RgbaImage>,
+    /// Captured length along the scroll axis: the rows of all segments.
     valid_height: u32,
+    axis: Option<ScrollAxis>,
     last_analysis: Option<FrameAnalysis>,
+    /// Transposed analysis of the previous frame, kept until the axis is known.
+    last_horizontal_analysis: Option<FrameAnalysis>,
     last_footer_height: u32,
+    /// How far the viewport has scrolled back from the captured end.
+    rewind: u32,
     config: StitchConfig,
+    mask: StitchMask,
+    store: StripStore,
+    /// The captured rows, as strips of the frames they came from. They stay
+    /// separate so the capture can be edited afterwards.
+    segments: Vec<StitchSegment>,
     scratch: StitchScratch,
     thumbnail: ThumbnailCache,
+    /// What the frame being processed measured so far.
+    diagnostics: FrameDiagnostics,
 }
 
 impl Default for ScrollStitcher {
@@ -166,34 +322,78 @@ impl ScrollStitcher {
 
     pub fn with_config(config: StitchConfig) -> Self {
         Self {
-            canvas: None,
             valid_height: 0,
+            axis: config.axis,
             last_analysis: None,
+            last_horizontal_analysis: None,
             last_footer_height: 0,
+            rewind: 0,
             config,
+            mask: StitchMask::default(),
+            store: StripStore::new(config.memory_budget),
+            segments: Vec::new(),
             scratch: StitchScratch::default(),
             thumbnail: ThumbnailCache::default(),
+            diagnostics: FrameDiagnostics::default(),
         }
     }
 
-    pub fn current_image(&self) -> Option<(&RgbaImage, u32)> {
-        self.canvas.as_ref().map(|img| (img, self.valid_height))
+    #[must_use]
+    pub fn with_mask(mut self, mask: StitchMask) -> Self {
+        self.mask = mask;
+        self
     }
 
     pub fn get_final_image(&self) -> Option<RgbaImage> {
-        let (canvas, h) = self.current_image()?;
-        if h == 0 || canvas.width() == 0 {
+        let image = self.scroll_space_rows(0, self.valid_height)?;
+        Some(self.restore_orientation(image))
+    }
+
+    /// The capture as the frame strips it was stitched from.
+    pub fn into_capture(self) -> Option<StitchedCapture> {
+        StitchedCapture::new(self.axis.unwrap_or(ScrollAxis::Vertical), self.store, self.segments)
+    }
+
+    /// Bytes the captured strips take up in memory and on disk.
+    pub fn memory(&self) -> StripMemory {
+        self.store.memory()
+    }
+
+    fn width(&self) -> u32 {
+        self.segments.first().map_or(0, StitchSegment::width)
+    }
+
+    /// Rows `from..from + height` of the capture, still in scroll space.
+    fn scroll_space_rows(&self, from: u32, height: u32) -> Option<RgbaImage> {
+        let width = self.width();
+        if height == 0 || width == 0 {
+            return None;
+        }
+        let mut image = RgbaImage::new(width, height);
+        if let Err(err) = copy_rows(&self.segments, from, &mut image) {
+            error!("Failed to read long-capture strips: {err}");
             return None;
         }
-        let mut final_img = RgbaImage::new(canvas.width(), h);
-        Self::copy_region(canvas, 0, &mut final_img, 0, h);
-        Some(final_img)
+        Some(image)
     }
 
+    /// Scales the capture so its extent across the scroll axis is
+    /// `target_width`.
     pub fn make_thumbnail(&mut self, target_width: u32) -> Option<RgbaImage> {
-        let canvas = self.canvas.as_ref()?;
+        let thumbnail = self.make_scroll_space_thumbnail(target_width)?;
+        Some(self.restore_orientation(thumbnail))
+    }
+
+    fn restore_orientation(&self, image: RgbaImage) -> RgbaImage {
+        match self.axis {
+            Some(ScrollAxis::Horizontal) => transpose(&image),
+            _ => image,
+        }
+    }
+
+    fn make_scroll_space_thumbnail(&mut self, target_width: u32) -> Option<RgbaImage> {
         let valid_h = self.valid_height;
-        let source_width = canvas.width();
+        let source_width = self.width();
         if target_width == 0 || valid_h == 0 || source_width == 0 {
             return None;
         }
@@ -210,8 +410,7 @@ impl ScrollStitcher {
             || valid_h < self.thumbnail.source_height;
 
         if requires_full_render {
-            let mut cropped = RgbaImage::new(source_width, valid_h);
-            Self::copy_region(canvas, 0, &mut cropped, 0, valid_h);
+            let cropped = self.scroll_space_rows(0, valid_h)?;
             self.thumbnail.image = Some(image::imageops::resize(
                 &cropped,
                 target_width,
@@ -253,7 +452,7 @@ impl ScrollStitcher {
         let target_h = target_height.saturating_sub(target_start);
 
         if source_h > 0 && target_h > 0 {
-            let strip = image::imageops::crop_imm(canvas, 0, source_start, source_width, source_h).to_image();
+            let strip = self.scroll_space_rows(source_start, source_h)?;
             let resized_strip = image::imageops::resize(&strip, target_width, target_h, image::imageops::FilterType::Triangle);
             Self::copy_region(&resized_strip, 0, &mut next_thumbnail, target_start, target_h);
         }
@@ -267,38 +466,94 @@ impl ScrollStitcher {
     }
 
     pub fn process_frame_detailed(&mut self, new_image: RgbaImage) -> StitchFrameResult {
-        if self.canvas.is_none() {
-            self.initialize_canvas(new_image);
-            return StitchFrameResult {
-                status: StitchFrameStatus::Appended,
-                height: self.valid_height as i32,
-                warning: None,
-            };
+        let started = Instant::now();
+        self.diagnostics = FrameDiagnostics::default();
+        let mut result = self.process_frame(new_image);
+        result.diagnostics = FrameDiagnostics {
+            elapsed_micros: u64::try_from(started.elapsed().as_micros()).unwrap_or(u64::MAX),
+            ..self.diagnostics
+        };
+        result
+    }
+
+    fn process_frame(&mut self, new_image: RgbaImage) -> StitchFrameResult {
+        if self.segments.is_empty() {
+            self.initialize_capture(new_image);
+            return self.result(StitchFrameStatus::Appended, None);
         }
 
+        let Some(axis) = self.axis else {
+            return self.process_frame_detecting_axis(new_image);
+        };
+
+        let new_image = axis.orient(new_image);
         let Some(prev_analysis) = self.last_analysis.take() else {
-            self.initialize_canvas(new_image);
-            return StitchFrameResult {
-                status: StitchFrameStatus::Appended,
-                height: self.valid_height as i32,
-                warning: None,
-            };
+            self.initialize_capture(new_image);
+            return self.result(StitchFrameStatus::Appended, None);
         };
 
         let next_analysis = FrameAnalysis::from_image(&new_image);
+        match self.measure_motion(axis, &prev_analysis, &next_analysis) {
+            Ok(motion) => self.apply_motion(motion, &prev_analysis, next_analysis, &new_image),
+            Err(rejection) => self.reject_frame(rejection, next_analysis),
+        }
+    }
+
+    /// Tries the vertical axis first, then the transposed frames, and locks
+    /// the stitcher to whichever shows a confident scroll. Nothing has been
+    /// appended yet, so switching to horizontal only transposes the first
+    /// frame already captured.
+    fn process_frame_detecting_axis(&mut self, new_image: RgbaImage) -> StitchFrameResult {
+        let (Some(prev_vertical), Some(prev_horizontal)) = (self.last_analysis.take(), self.last_horizontal_analysis.take()) else {
+            self.initialize_capture(new_image);
+            return self.result(StitchFrameStatus::Appended, None);
+        };
+
+        let next_vertical = FrameAnalysis::from_image(&new_image);
+        let vertical_rejection = match self.measure_motion(ScrollAxis::Vertical, &prev_vertical, &next_vertical) {
+            Ok(motion) => {
+                self.axis = Some(ScrollAxis::Vertical);
+                return self.apply_motion(motion, &prev_vertical, next_vertical, &new_image);
+            }
+            Err(rejection) => rejection,
+        };
+        // A rejected frame reports what the vertical match measured.
+        let vertical_diagnostics = std::mem::take(&mut self.diagnostics);
+
+        let horizontal_image = transpose(&new_image);
+        let next_horizontal = FrameAnalysis::from_image(&horizontal_image);
+        if let Ok(motion) = self.measure_motion(ScrollAxis::Horizontal, &prev_horizontal, &next_horizontal) {
+            let first_frame = self.get_final_image();
+            self.axis = Some(ScrollAxis::Horizontal);
+            if let Some(first_frame) = first_frame {
+                self.reset_capture(&transpose(&first_frame));
+            }
+            return self.apply_motion(motion, &prev_horizontal, next_horizontal, &horizontal_image);
+        }
+
+        self.last_horizontal_analysis = Some(next_horizontal);
+        self.diagnostics = vertical_diagnostics;
+        self.reject_frame(vertical_rejection, next_vertical)
+    }
+
+    fn measure_motion(
+        &mut self,
+        axis: ScrollAxis,
+        prev_analysis: &FrameAnalysis,
+        next_analysis: &FrameAnalysis,
+    ) -> Result<FrameMotion, FrameRejection> {
         if prev_analysis.width != next_analysis.width || prev_analysis.height != next_analysis.height {
-            self.last_analysis = Some(next_analysis);
-            self.last_footer_height = 0;
-            return StitchFrameResult {
-                status: StitchFrameStatus::LowConfidence,
-                height: self.valid_height as i32,
-                warning: Some("Frame geometry changed while scrolling".to_string()),
-            };
+            return Err(FrameRejection::low_confidence("Frame geometry changed while scrolling"));
         }
 
         let width = prev_analysis.width;
         let height = prev_analysis.height;
-        let (fixed_top, fixed_bottom) = self.detect_sticky_regions(&prev_analysis.gray, &next_analysis.gray, width, height);
+        let mask = self.mask.oriented(axis);
+        let (detected_top, detected_bottom) = self.detect_sticky_regions(&prev_analysis.gray, &next_analysis.gray, width, height);
+        let fixed_top = detected_top.max(mask.header.min(height as u32));
+        let fixed_bottom = detected_bottom.max(mask.footer.min(height as u32));
+        self.diagnostics.fixed_top = fixed_top;
+        self.diagnostics.fixed_bottom = fixed_bottom;
         let region = StitchRegion {
             width,
             height,
@@ -307,29 +562,19 @@ impl ScrollStitcher {
         };
         let valid_h = (height as u32).saturating_sub(fixed_top + fixed_bottom);
         if valid_h < self.config.min_overlap {
-            self.last_analysis = Some(next_analysis);
-            self.last_footer_height = fixed_bottom;
-            return StitchFrameResult {
-                status: StitchFrameStatus::LowConfidence,
-                height: self.valid_height as i32,
-                warning: Some("Insufficient scrollable content in selection".to_string()),
-            };
+            return Err(FrameRejection::low_confidence("Insufficient scrollable content in selection"));
         }
 
         let gray_pair = FramePairRef {
             prev: &prev_analysis.gray,
             next: &next_analysis.gray,
         };
-        Self::detect_stable_blocks(self.config, gray_pair, region, &mut self.scratch.stable_blocks);
+        Self::detect_stable_blocks(self.config, gray_pair, region, &mask, &mut self.scratch.stable_blocks);
 
         if self.scratch.stable_blocks.len() < self.config.min_stable_blocks {
-            self.last_analysis = Some(next_analysis);
-            self.last_footer_height = fixed_bottom;
-            return StitchFrameResult {
-                status: StitchFrameStatus::LowConfidence,
-                height: self.valid_height as i32,
-                warning: Some("Dynamic content dominates viewport; wait for a stable frame".to_string()),
-            };
+            return Err(FrameRejection::low_confidence(
+                "Dynamic content dominates viewport; wait for a stable frame",
+            ));
         }
 
         Self::row_signature(
@@ -368,121 +613,156 @@ impl ScrollStitcher {
         );
 
         let Some((delta, best_score, second_score)) = refine else {
-            self.last_analysis = Some(next_analysis);
-            self.last_footer_height = fixed_bottom;
-            return StitchFrameResult {
-                status: StitchFrameStatus::LowConfidence,
-                height: self.valid_height as i32,
-                warning: Some("Unable to estimate reliable overlap".to_string()),
-            };
+            return Err(FrameRejection::low_confidence("Unable to estimate reliable overlap"));
         };
+        self.diagnostics.shift = Some(delta);
+        self.diagnostics.score = Some(best_score);
+        self.diagnostics.score_gap = Some(best_score - second_score);
 
-        if delta < 0 {
-            self.last_analysis = Some(next_analysis);
-            self.last_footer_height = fixed_bottom;
-            return StitchFrameResult {
-                status: StitchFrameStatus::Reverse,
-                height: self.valid_height as i32,
-                warning: Some("Detected reverse scrolling; capture paused".to_string()),
-            };
-        }
-
-        let delta = delta as u32;
-        if delta < self.config.min_scroll_threshold {
-            self.last_analysis = Some(next_analysis);
-            self.last_footer_height = fixed_bottom;
-            return StitchFrameResult {
+        if delta.unsigned_abs() < self.config.min_scroll_threshold {
+            return Err(FrameRejection {
                 status: StitchFrameStatus::Stationary,
-                height: self.valid_height as i32,
                 warning: None,
-            };
+            });
         }
 
         let confidence_gap = best_score - second_score;
         if best_score < self.config.low_confidence_threshold || confidence_gap < self.config.low_confidence_gap {
-            self.last_analysis = Some(next_analysis);
-            self.last_footer_height = fixed_bottom;
-            return StitchFrameResult {
-                status: StitchFrameStatus::LowConfidence,
-                height: self.valid_height as i32,
-                warning: Some("Low confidence overlap match; keep scrolling smoothly".to_string()),
-            };
+            return Err(FrameRejection::low_confidence("Low confidence overlap match; keep scrolling smoothly"));
         }
 
-        let valid_h_usize = valid_h as usize;
-        let delta_usize = delta as usize;
-        if delta_usize >= valid_h_usize {
-            self.last_analysis = Some(next_analysis);
-            self.last_footer_height = fixed_bottom;
-            return StitchFrameResult {
-                status: StitchFrameStatus::LowConfidence,
-                height: self.valid_height as i32,
-                warning: Some("Overlap collapsed due to unstable motion".to_string()),
-            };
+        let distance = delta.unsigned_abs();
+        if distance >= valid_h {
+            return Err(FrameRejection::low_confidence("Overlap collapsed due to unstable motion"));
         }
 
-        let overlap_valid = valid_h_usize.saturating_sub(delta_usize);
-        if overlap_valid < self.config.min_overlap as usize {
-            self.last_analysis = Some(next_analysis);
-            self.last_footer_height = fixed_bottom;
-            return StitchFrameResult {
-                status: StitchFrameStatus::LowConfidence,
-                height: self.valid_height as i32,
-                warning: Some("Overlap too small; scroll slower".to_string()),
-            };
+        if valid_h - distance < self.config.min_overlap {
+            return Err(FrameRejection::low_confidence("Overlap too small; scroll slower"));
         }
 
-        let cut_valid = self.find_smart_seam(gray_pair, region, overlap_valid, &self.scratch.stable_blocks);
-
-        let trim_prev = overlap_valid.saturating_sub(cut_valid);
-        let append_start = fixed_top as usize + cut_valid;
-        let append_end = (new_image.height().saturating_sub(fixed_bottom)) as usize;
+        Ok(FrameMotion { region, delta })
+    }
 
-        if append_end <= append_start {
+    /// Backward motion only moves the viewport over content that is already
+    /// captured; forward motion appends once it passes the captured end.
+    fn apply_motion(
+        &mut self,
+        motion: FrameMotion,
+        prev_analysis: &FrameAnalysis,
+        next_analysis: FrameAnalysis,
+        new_image: &RgbaImage,
+    ) -> StitchFrameResult {
+        let distance = motion.delta.unsigned_abs();
+        if motion.delta < 0 {
+            self.rewind += distance;
             self.last_analysis = Some(next_analysis);
-            self.last_footer_height = fixed_bottom;
-            return StitchFrameResult {
-                status: StitchFrameStatus::LowConfidence,
-                height: self.valid_height as i32,
-                warning: Some("No appendable content after sticky region filtering".to_string()),
-            };
+            if self.rewind > self.max_rewind(motion.region) {
+                return self.result(
+                    StitchFrameStatus::Reverse,
+                    Some("Scrolled back past the start of the capture; scroll forward to continue"),
+                );
+            }
+            return self.result(StitchFrameStatus::Revisited, None);
         }
 
-        let plan = StitchAppendPlan {
-            trim_amount: trim_prev as u32,
-            append_start_y: append_start as u32,
-            append_end_y: append_end as u32,
-            fixed_bottom,
-        };
+        if distance <= self.rewind {
+            self.rewind -= distance;
+            self.last_analysis = Some(next_analysis);
+            return self.result(StitchFrameStatus::Revisited, None);
+        }
 
-        if self.execute_stitch(&new_image, plan) {
+        if self.max_length().is_some_and(|max_length| self.valid_height >= max_length) {
             self.last_analysis = Some(next_analysis);
-            StitchFrameResult {
-                status: StitchFrameStatus::Appended,
-                height: self.valid_height as i32,
-                warning: None,
+            return self.result(
+                StitchFrameStatus::Full,
+                Some("Reached the maximum long capture length; finish the capture"),
+            );
+        }
+
+        let fixed_bottom = motion.region.fixed_bottom as u32;
+        let plan = if self.rewind > 0 {
+            let fresh = distance - self.rewind;
+            let content_end = new_image.height().saturating_sub(fixed_bottom);
+            StitchAppendPlan {
+                trim_amount: 0,
+                append_start_y: content_end.saturating_sub(fresh),
+                append_end_y: new_image.height(),
+                fixed_bottom,
             }
         } else {
-            self.last_analysis = Some(next_analysis);
-            self.last_footer_height = fixed_bottom;
-            StitchFrameResult {
-                status: StitchFrameStatus::LowConfidence,
-                height: self.valid_height as i32,
-                warning: Some("Failed to append frame into scroll canvas".to_string()),
+            let gray_pair = FramePairRef {
+                prev: &prev_analysis.gray,
+                next: &next_analysis.gray,
+            };
+            let overlap_valid = motion.region.valid_height() - distance as usize;
+            let cut_valid = self.find_smart_seam(gray_pair, motion.region, overlap_valid, &self.scratch.stable_blocks);
+            let append_start = motion.region.fixed_top + cut_valid;
+            let content_end = new_image.height().saturating_sub(fixed_bottom) as usize;
+
+            if content_end <= append_start {
+                return self.reject_frame(
+                    FrameRejection::low_confidence("No appendable content after sticky region filtering"),
+                    next_analysis,
+                );
+            }
+
+            StitchAppendPlan {
+                trim_amount: overlap_valid.saturating_sub(cut_valid) as u32,
+                append_start_y: append_start as u32,
+                append_end_y: new_image.height(),
+                fixed_bottom,
             }
+        };
+
+        if self.execute_stitch(new_image, plan) {
+            self.rewind = 0;
+            self.last_analysis = Some(next_analysis);
+            self.result(StitchFrameStatus::Appended, None)
+        } else {
+            self.reject_frame(FrameRejection::low_confidence("Failed to append frame into the capture"), next_analysis)
         }
     }
 
-    fn initialize_canvas(&mut self, first_image: RgbaImage) {
-        let w = first_image.width();
-        let h = first_image.height();
-        let mut canvas = RgbaImage::new(w, h * 3);
-        Self::copy_region(&first_image, 0, &mut canvas, 0, h);
+    /// Furthest the viewport can scroll back before it leaves the capture.
+    fn max_rewind(&self, region: StitchRegion) -> u32 {
+        self.valid_height
+            .saturating_sub(self.last_footer_height)
+            .saturating_sub((region.fixed_top + region.valid_height()) as u32)
+    }
 
-        self.canvas = Some(canvas);
-        self.valid_height = h;
+    fn max_length(&self) -> Option<u32> {
+        self.config.max_length.filter(|max_length| *max_length > 0)
+    }
+
+    fn reject_frame(&mut self, rejection: FrameRejection, next_analysis: FrameAnalysis) -> StitchFrameResult {
+        self.last_analysis = Some(next_analysis);
+        self.result(rejection.status, rejection.warning)
+    }
+
+    fn result(&self, status: StitchFrameStatus, warning: Option<&str>) -> StitchFrameResult {
+        StitchFrameResult {
+            status,
+            height: self.valid_height as i32,
+            warning: warning.map(str::to_string),
+            diagnostics: FrameDiagnostics::default(),
+        }
+    }
+
+    fn initialize_capture(&mut self, first_image: RgbaImage) {
+        self.reset_capture(&first_image);
+        if self.axis.is_none() {
+            self.last_horizontal_analysis = Some(FrameAnalysis::from_image(&transpose(&first_image)));
+        }
         self.last_analysis = Some(FrameAnalysis::from_image(&first_image));
-        self.last_footer_height = 0;
+    }
+
+    fn reset_capture(&mut self, first_image: &RgbaImage) {
+        let h = first_image.height().min(self.max_length().unwrap_or(u32::MAX));
+        self.valid_height = h;
+        self.segments = vec![StitchSegment::from_frame(&self.store, first_image, 0, h)];
+        let axis = self.axis.unwrap_or(ScrollAxis::Vertical);
+        self.last_footer_height = self.mask.oriented(axis).footer.min(h);
+        self.rewind = 0;
         self.thumbnail.reset();
     }
 
@@ -552,7 +832,7 @@ impl ScrollStitcher {
         (top as u32, bottom as u32)
     }
 
-    fn detect_stable_blocks(config: StitchConfig, pair: FramePairRef<'_>, region: StitchRegion, out: &mut Vec<usize>) {
+    fn detect_stable_blocks(config: StitchConfig, pair: FramePairRef<'_>, region: StitchRegion, mask: &StitchMask, out: &mut Vec<usize>) {
         let block = config.dynamic_block_size.max(8);
         let block_count = (region.width / block).max(1);
         let y_start = region.fixed_top.min(region.height);
@@ -566,7 +846,7 @@ impl ScrollStitcher {
         for b in 0..block_count {
             let x0 = b * block;
             let x1 = ((b + 1) * block).min(region.width);
-            if x1 <= x0 {
+            if x1 <= x0 || mask.excludes(region.width, x0, x1, y_start, y_end) {
                 continue;
             }
 
@@ -959,10 +1239,6 @@ impl ScrollStitcher {
     }
 
     fn execute_stitch(&mut self, new_image: &RgbaImage, plan: StitchAppendPlan) -> bool {
-        let Some(canvas) = self.canvas.as_mut() else {
-            return false;
-        };
-
         let previous_valid_height = self.valid_height;
         let content_end = self.valid_height.saturating_sub(self.last_footer_height);
         if plan.trim_amount > content_end {
@@ -970,23 +1246,19 @@ impl ScrollStitcher {
         }
 
         let keep_h = content_end - plan.trim_amount;
-        let append_h = plan.append_end_y.saturating_sub(plan.append_start_y);
+        let append_room = self.max_length().map_or(u32::MAX, |max_length| max_length.saturating_sub(keep_h));
+        let append_h = plan.append_end_y.saturating_sub(plan.append_start_y).min(append_room);
         let new_total_h = keep_h + append_h;
 
-        if new_total_h > canvas.height() {
-            let new_cap = (canvas.height() * 2).max(new_total_h + CANVAS_RESIZE_HEADROOM);
-            let width = canvas.width();
-            let mut new_canvas = RgbaImage::new(width, new_cap);
-            Self::copy_region(canvas, 0, &mut new_canvas, 0, keep_h);
-            self.canvas = Some(new_canvas);
-        }
-
-        let Some(canvas) = self.canvas.as_mut() else {
-            return false;
-        };
-
+        truncate_segments(&mut self.segments, keep_h);
+        self.diagnostics.seam_row = Some(plan.append_start_y);
         if append_h > 0 {
-            Self::copy_region(new_image, plan.append_start_y, canvas, keep_h, append_h);
+            self.segments.push(StitchSegment::from_frame(
+                &self.store,
+                new_image,
+                plan.append_start_y,
+                plan.append_start_y + append_h,
+            ));
         }
 
         self.valid_height = new_total_h;
@@ -1031,10 +1303,52 @@ mod tests {
         img
     }
 
+    fn noise(x: u32, y: u32) -> u32 {
+        let mut h = x.wrapping_mul(0x9E37_79B9) ^ y.wrapping_mul(0x85EB_CA6B);
+        h ^= h >> 15;
+        h = h.wrapping_mul(0xC2B2_AE35);
+        h ^ (h >> 13)
+    }
+
+    /// Faint texture whose strength changes every few rows on the left, so
+    /// those blocks stay stable between scrolled frames while still giving
+    /// signatures and ZNCC an exact offset; busy noise on the right keeps every
+    /// row changing, so no band is mistaken for a sticky header or footer.
+    fn scrolling_page(width: u32, height: u32) -> RgbaImage {
+        let mut img = RgbaImage::new(width, height);
+        for y in 0..height {
+            let strength = 1 + noise(7, y / 3) % 16;
+            for x in 0..width {
+                let v = if x < width * 2 / 3 {
+                    120 + (noise(x, y) % strength) as u8
+                } else {
+                    (noise(x, y) & 0xff) as u8
+                };
+                img.put_pixel(x, y, Rgba([v, v, v, 255]));
+            }
+        }
+        img
+    }
+
     fn crop_frame(source: &RgbaImage, y: u32, h: u32) -> RgbaImage {
         imageops::crop_imm(source, 0, y, source.width(), h).to_image()
     }
 
+    fn stack(parts: &[&RgbaImage]) -> RgbaImage {
+        let height = parts.iter().map(|part| part.height()).sum();
+        let mut image = RgbaImage::new(parts[0].width(), height);
+        let mut y = 0;
+        for part in parts {
+            imageops::replace(&mut image, *part, 0, i64::from(y));
+            y += part.height();
+        }
+        image
+    }
+
+    fn crop_columns(source: &RgbaImage, x: u32, w: u32) -> RgbaImage {
+        imageops::crop_imm(source, x, 0, w, source.height()).to_image()
+    }
+
     #[test]
     fn stitcher_appends_on_forward_scroll() {
         let mut stitcher = ScrollStitcher::new();
@@ -1069,4 +1383,191 @@ mod tests {
         let detail = stitcher.process_frame_detailed(reverse);
         assert!(matches!(detail.status, StitchFrameStatus::Reverse | StitchFrameStatus::LowConfidence));
     }
+
+    #[test]
+    fn stitcher_appends_on_horizontal_scroll() {
+        let mut stitcher = ScrollStitcher::new();
+        let src = transpose(&scrolling_page(240, 420));
+
+        for x in [0, 24, 48, 72] {
+            let detail = stitcher.process_frame_detailed(crop_columns(&src, x, 180));
+            assert_eq!(detail.status, StitchFrameStatus::Appended, "frame at x={x}");
+        }
+
+        assert_eq!(stitcher.axis, Some(ScrollAxis::Horizontal));
+        assert_eq!(stitcher.get_final_image(), Some(crop_columns(&src, 0, 252)));
+        let thumbnail = stitcher.make_thumbnail(120).expect("thumbnail");
+        assert_eq!((thumbnail.width(), thumbnail.height()), (126, 120));
+        let capture = stitcher.into_capture().expect("capture");
+        assert!(capture.is_horizontal());
+        assert_eq!(capture.render(), Some(crop_columns(&src, 0, 252)));
+    }
+
+    #[test]
+    fn stitcher_ignores_frames_scrolled_back_over_captured_content() {
+        let mut stitcher = ScrollStitcher::new();
+        let src = scrolling_page(240, 420);
+
+        let statuses = [0, 24, 48, 24, 0, 24, 48, 72].map(|y| stitcher.process_frame_detailed(crop_frame(&src, y, 180)).status);
+
+        use StitchFrameStatus::{Appended, Revisited};
+        assert_eq!(
+            statuses,
+            [Appended, Appended, Appended, Revisited, Revisited, Revisited, Revisited, Appended]
+        );
+        assert_eq!(stitcher.axis, Some(ScrollAxis::Vertical));
+        assert_eq!(stitcher.get_final_image(), Some(crop_frame(&src, 0, 252)));
+        assert_eq!(
+            stitcher.into_capture().and_then(|capture| capture.render()),
+            Some(crop_frame(&src, 0, 252))
+        );
+    }
+
+    #[test]
+    fn stitcher_keeps_every_row_when_pausing_between_scroll_steps() {
+        let mut stitcher = ScrollStitcher::new();
+        let src = scrolling_page(240, 420);
+
+        let statuses = [0, 24, 24, 24, 48, 48, 72].map(|y| stitcher.process_frame_detailed(crop_frame(&src, y, 180)).status);
+
+        use StitchFrameStatus::{Appended, Stationary};
+        assert_eq!(statuses, [Appended, Appended, Stationary, Stationary, Appended, Stationary, Appended]);
+        assert_eq!(stitcher.get_final_image(), Some(crop_frame(&src, 0, 252)));
+    }
+
+    #[test]
+    fn stitcher_reports_reverse_past_the_capture_start() {
+        let mut stitcher = ScrollStitcher::new();
+        let src = scrolling_page(200, 420);
+
+        assert_eq!(
+            stitcher.process_frame_detailed(crop_frame(&src, 80, 160)).status,
+            StitchFrameStatus::Appended
+        );
+        let detail = stitcher.process_frame_detailed(crop_frame(&src, 30, 160));
+        assert_eq!(detail.status, StitchFrameStatus::Reverse);
+        assert_eq!(detail.height, 160);
+    }
+
+    #[test]
+    fn stitcher_keeps_one_copy_of_a_marked_header_and_footer() {
+        let page = scrolling_page(240, 420);
+        let header = RgbaImage::from_fn(240, 20, |x, _| Rgba([200, (x % 7 * 30) as u8, 40, 255]));
+        let footer = RgbaImage::from_fn(240, 16, |x, _| Rgba([40, (x % 5 * 40) as u8, 200, 255]));
+        let mask = StitchMask {
+            header: 20,
+            footer: 16,
+            ..StitchMask::default()
+        };
+        let mut stitcher = ScrollStitcher::new().with_mask(mask);
+
+        for y in [0, 24, 48, 72] {
+            let frame = stack(&[&header, &crop_frame(&page, y, 180), &footer]);
+            assert_eq!(
+                stitcher.process_frame_detailed(frame).status,
+                StitchFrameStatus::Appended,
+                "frame at y={y}"
+            );
+        }
+
+        let expected = stack(&[&header, &crop_frame(&page, 0, 252), &footer]);
+        assert_eq!(stitcher.get_final_image().as_ref(), Some(&expected));
+        let capture = stitcher.into_capture().expect("capture");
+        assert!(capture.segment_count() > 1);
+        assert_eq!(capture.render(), Some(expected));
+    }
+
+    #[test]
+    fn stitcher_keeps_one_marked_footer_when_pausing_between_scroll_steps() {
+        let page = scrolling_page(240, 420);
+        let header = RgbaImage::from_fn(240, 20, |x, _| Rgba([200, (x % 7 * 30) as u8, 40, 255]));
+        let footer = RgbaImage::from_fn(240, 16, |x, _| Rgba([40, (x % 5 * 40) as u8, 200, 255]));
+        let mask = StitchMask {
+            header: 20,
+            footer: 16,
+            ..StitchMask::default()
+        };
+        let mut stitcher = ScrollStitcher::new().with_mask(mask);
+
+        let statuses = [0, 24, 24, 48, 72].map(|y| {
+            let frame = stack(&[&header, &crop_frame(&page, y, 180), &footer]);
+            stitcher.process_frame_detailed(frame).status
+        });
+
+        use StitchFrameStatus::{Appended, Stationary};
+        assert_eq!(statuses, [Appended, Appended, Stationary, Appended, Appended]);
+        let expected = stack(&[&header, &crop_frame(&page, 0, 252), &footer]);
+        assert_eq!(stitcher.get_final_image(), Some(expected));
+    }
+
+    #[test]
+    fn stitcher_reports_how_it_matched_each_frame() {
+        let page = scrolling_page(240, 420);
+        let header = RgbaImage::from_fn(240, 20, |x, _| Rgba([200, (x % 7 * 30) as u8, 40, 255]));
+        let footer = RgbaImage::from_fn(240, 16, |x, _| Rgba([40, (x % 5 * 40) as u8, 200, 255]));
+        let mask = StitchMask {
+            header: 20,
+            footer: 16,
+            ..StitchMask::default()
+        };
+        let mut stitcher = ScrollStitcher::new().with_mask(mask);
+        let frame = |y| stack(&[&header, &crop_frame(&page, y, 180), &footer]);
+
+        let first = stitcher.process_frame_detailed(frame(0));
+        assert_eq!(first.diagnostics.shift, None);
+        let detail = stitcher.process_frame_detailed(frame(24));
+        let diagnostics = detail.diagnostics;
+
+        assert_eq!(detail.status, StitchFrameStatus::Appended);
+        assert_eq!(diagnostics.shift, Some(24));
+        assert!(diagnostics.score.is_some_and(|score| score > 0.9));
+        assert!(diagnostics.score_gap.is_some());
+        assert!(diagnostics.fixed_top >= 20 && diagnostics.fixed_bottom >= 16);
+        let seam_row = diagnostics.seam_row.expect("seam row");
+        assert!((diagnostics.fixed_top..200).contains(&seam_row));
+
+        let stationary = stitcher.process_frame_detailed(frame(24));
+        assert_eq!(stationary.status, StitchFrameStatus::Stationary);
+        assert_eq!(stationary.diagnostics.seam_row, None);
+    }
+
+    #[test]
+    fn stitcher_stops_at_the_maximum_length_and_spills_past_its_budget() {
+        let config = StitchConfig {
+            max_length: Some(220),
+            memory_budget: 0,
+            ..StitchConfig::default()
+        };
+        let mut stitcher = ScrollStitcher::with_config(config);
+        let src = scrolling_page(240, 420);
+
+        let statuses = [0, 24, 48, 72].map(|y| stitcher.process_frame_detailed(crop_frame(&src, y, 180)).status);
+
+        use StitchFrameStatus::{Appended, Full};
+        assert_eq!(statuses, [Appended, Appended, Appended, Full]);
+        assert_eq!(stitcher.memory().resident, 0);
+        assert!(stitcher.memory().spilled > 0);
+        assert_eq!(stitcher.get_final_image(), Some(crop_frame(&src, 0, 220)));
+    }
+
+    #[test]
+    fn stitch_mask_turns_sidebars_into_fixed_bands_for_horizontal_scrolls() {
+        let mask = StitchMask {
+            header: 10,
+            footer: 20,
+            left: 30,
+            right: 40,
+            dynamic: vec![Rect::new(50, 100, 20, 10)],
+        };
+
+        let oriented = mask.oriented(ScrollAxis::Horizontal);
+
+        assert_eq!((oriented.header, oriented.footer, oriented.left, oriented.right), (30, 40, 10, 20));
+        assert_eq!(oriented.dynamic, [Rect::new(100, 50, 10, 20)]);
+        assert!(oriented.excludes(200, 0, 16, 0, 40));
+        assert!(oriented.excludes(200, 176, 192, 0, 40));
+        assert!(oriented.excludes(200, 96, 112, 60, 80));
+        assert!(!oriented.excludes(200, 96, 112, 0, 40));
+        assert_eq!(mask.scaled(1.5).dynamic, [Rect::new(75, 150, 30, 15)]);
+    }
 }
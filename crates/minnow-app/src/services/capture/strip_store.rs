@@ -0,0 +1,257 @@
+//! Pixel storage for long captures. Stitched strips stay in memory up to a
+//! budget; past it the oldest strips move to a spill file in the temp
+//! directory and are read back row by row when the capture is rendered or
+//! saved.
+
+use crate::services::paths::app_paths;
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+use tracing::{error, warn};
+
+/// Strip bytes a capture keeps in memory before it starts spilling.
+pub(super) const DEFAULT_MEMORY_BUDGET: u64 = 256 * 1024 * 1024;
+
+static NEXT_SPILL_FILE: AtomicUsize = AtomicUsize::new(0);
+
+/// Bytes taken up by the strips of a capture.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StripMemory {
+    pub resident: u64,
+    pub spilled: u64,
+}
+
+/// Owns the strips of one capture. Clones share the same budget and spill
+/// file, which is removed once the store and all its strips are gone.
+#[derive(Clone)]
+pub(super) struct StripStore {
+    inner: Arc<StoreInner>,
+}
+
+struct StoreInner {
+    budget: u64,
+    resident: AtomicU64,
+    spilled: AtomicU64,
+    /// Strips that are still in memory, oldest first.
+    resident_strips: Mutex<VecDeque<Weak<StripData>>>,
+    spill: Mutex<Option<SpillFile>>,
+}
+
+struct SpillFile {
+    path: PathBuf,
+    file: File,
+    len: u64,
+}
+
+/// Rows of RGBA pixels, shared between the stitcher and the captures built
+/// from it.
+#[derive(Clone)]
+pub(super) struct Strip {
+    data: Arc<StripData>,
+}
+
+struct StripData {
+    store: Arc<StoreInner>,
+    width: u32,
+    height: u32,
+    pixels: Mutex<Pixels>,
+}
+
+enum Pixels {
+    Memory(Vec<u8>),
+    /// Byte offset of the strip in the spill file.
+    Spilled(u64),
+}
+
+impl StripStore {
+    pub(super) fn new(budget: u64) -> Self {
+        Self {
+            inner: Arc::new(StoreInner {
+                budget,
+                resident: AtomicU64::new(0),
+                spilled: AtomicU64::new(0),
+                resident_strips: Mutex::new(VecDeque::new()),
+                spill: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Stores `height` rows of `pixels`, spilling older strips when the store
+    /// goes over its budget.
+    pub(super) fn insert(&self, width: u32, height: u32, pixels: Vec<u8>) -> Strip {
+        debug_assert_eq!(pixels.len(), row_bytes(width) * height as usize);
+        self.inner.resident.fetch_add(pixels.len() as u64, Ordering::Relaxed);
+        let data = Arc::new(StripData {
+            store: self.inner.clone(),
+            width,
+            height,
+            pixels: Mutex::new(Pixels::Memory(pixels)),
+        });
+        lock(&self.inner.resident_strips).push_back(Arc::downgrade(&data));
+        self.enforce_budget();
+        Strip { data }
+    }
+
+    pub(super) fn memory(&self) -> StripMemory {
+        StripMemory {
+            resident: self.inner.resident.load(Ordering::Relaxed),
+            spilled: self.inner.spilled.load(Ordering::Relaxed),
+        }
+    }
+
+    fn enforce_budget(&self) {
+        while self.inner.resident.load(Ordering::Relaxed) > self.inner.budget {
+            let Some(oldest) = lock(&self.inner.resident_strips).pop_front() else {
+                return;
+            };
+            let Some(strip) = oldest.upgrade() else {
+                continue;
+            };
+            if let Err(err) = strip.spill() {
+                // The strip stays in memory; the capture still works, it just
+                // outgrows the budget.
+                warn!("Failed to spill long-capture strip to disk: {err}");
+                return;
+            }
+        }
+    }
+}
+
+impl Strip {
+    pub(super) fn width(&self) -> u32 {
+        self.data.width
+    }
+
+    /// Fills `out` with whole rows starting at row `y`.
+    pub(super) fn read_rows(&self, y: u32, out: &mut [u8]) -> io::Result<()> {
+        let start = row_bytes(self.data.width) * y as usize;
+        let pixels = lock(&self.data.pixels);
+        match &*pixels {
+            Pixels::Memory(data) => {
+                let rows = data
+                    .get(start..start + out.len())
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "rows outside the strip"))?;
+                out.copy_from_slice(rows);
+                Ok(())
+            }
+            Pixels::Spilled(offset) => {
+                let mut spill = lock(&self.data.store.spill);
+                let spill = spill.as_mut().ok_or_else(|| io::Error::other("spill file is missing"))?;
+                spill.file.seek(SeekFrom::Start(offset + start as u64))?;
+                spill.file.read_exact(out)
+            }
+        }
+    }
+}
+
+impl StripData {
+    fn spill(&self) -> io::Result<()> {
+        let mut pixels = lock(&self.pixels);
+        let Pixels::Memory(data) = &*pixels else {
+            return Ok(());
+        };
+
+        let mut spill = lock(&self.store.spill);
+        let spill = match &mut *spill {
+            Some(spill) => spill,
+            slot => slot.insert(SpillFile::create()?),
+        };
+        let offset = spill.len;
+        spill.file.seek(SeekFrom::Start(offset))?;
+        spill.file.write_all(data)?;
+        spill.len += data.len() as u64;
+
+        let bytes = data.len() as u64;
+        *pixels = Pixels::Spilled(offset);
+        self.store.resident.fetch_sub(bytes, Ordering::Relaxed);
+        self.store.spilled.fetch_add(bytes, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+impl Drop for StripData {
+    fn drop(&mut self) {
+        let bytes = row_bytes(self.width) as u64 * u64::from(self.height);
+        match self.pixels.get_mut().unwrap_or_else(PoisonError::into_inner) {
+            Pixels::Memory(_) => self.store.resident.fetch_sub(bytes, Ordering::Relaxed),
+            Pixels::Spilled(_) => self.store.spilled.fetch_sub(bytes, Ordering::Relaxed),
+        };
+    }
+}
+
+impl SpillFile {
+    fn create() -> io::Result<Self> {
+        let name = format!(
+            "minnowsnap_long_capture_{}_{}.bin",
+            std::process::id(),
+            NEXT_SPILL_FILE.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = app_paths().temp_file(&name);
+        let file = File::options().read(true).write(true).create(true).truncate(true).open(&path)?;
+        Ok(Self { path, file, len: 0 })
+    }
+}
+
+impl Drop for StoreInner {
+    fn drop(&mut self) {
+        let Some(SpillFile { path, file, .. }) = self.spill.get_mut().unwrap_or_else(PoisonError::into_inner).take() else {
+            return;
+        };
+        // Closed first: an open file cannot be removed on Windows.
+        drop(file);
+        if let Err(err) = fs::remove_file(&path) {
+            error!("Failed to remove long-capture spill file {:?}: {err}", path);
+        }
+    }
+}
+
+fn row_bytes(width: u32) -> usize {
+    width as usize * 4
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(first: u8, count: u32) -> Vec<u8> {
+        (0..count).flat_map(|y| [first + y as u8; 8]).collect()
+    }
+
+    #[test]
+    fn strips_past_the_budget_spill_oldest_first_and_read_back() {
+        let store = StripStore::new(40);
+        let first = store.insert(2, 4, rows(0, 4));
+        let second = store.insert(2, 4, rows(10, 4));
+
+        assert_eq!(store.memory(), StripMemory { resident: 32, spilled: 32 });
+
+        let mut out = [0; 16];
+        first.read_rows(1, &mut out).expect("spilled rows");
+        assert_eq!(out, [&[1; 8][..], &[2; 8]].concat()[..]);
+        second.read_rows(2, &mut out).expect("resident rows");
+        assert_eq!(out, [&[12; 8][..], &[13; 8]].concat()[..]);
+
+        drop(first);
+        assert_eq!(store.memory(), StripMemory { resident: 32, spilled: 0 });
+    }
+
+    #[test]
+    fn the_spill_file_is_removed_with_the_last_strip() {
+        let store = StripStore::new(0);
+        let strip = store.insert(2, 1, rows(0, 1));
+        let path = lock(&store.inner.spill).as_ref().map(|spill| spill.path.clone()).expect("spill file");
+        assert!(path.exists());
+
+        drop(store);
+        assert!(path.exists());
+        drop(strip);
+        assert!(!path.exists());
+    }
+}
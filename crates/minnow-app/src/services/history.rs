@@ -0,0 +1,402 @@
+//! Recent captures kept on disk so they can be copied, saved, pinned or
+//! annotated again after the overlay is gone.
+//!
+//! Every entry is a directory under [`AppPaths::history_dir`] named by its id:
+//!
+//! | file               | content                                              |
+//! |--------------------|------------------------------------------------------|
+//! | `entry.toml`       | [`HistoryEntry`] metadata, written last              |
+//! | `capture.png`      | the image exactly as it was copied, saved or pinned  |
+//! | `base.png`         | the crop before annotations were flattened into it   |
+//! | `annotations.toml` | the overlay's annotation document for `base.png`     |
+//!
+//! The last two only exist for annotated captures. A directory without a
+//! readable `entry.toml` is still being written and is ignored.
+//!
+//! [`AppPaths::history_dir`]: crate::services::paths::AppPaths::history_dir
+
+mod writer;
+
+use crate::services::capture::filename::CaptureOrigin;
+use crate::services::geometry::Rect;
+use crate::services::paths::{app_paths, ensure_dir};
+use crate::services::settings::HistorySettings;
+use image::{ImageFormat, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+use writer::HistoryWriter;
+pub use writer::PendingRecord;
+
+const ENTRY_FILE: &str = "entry.toml";
+const CAPTURE_FILE: &str = "capture.png";
+const BASE_FILE: &str = "base.png";
+const ANNOTATIONS_FILE: &str = "annotations.toml";
+const MILLIS_PER_DAY: u64 = 86_400_000;
+
+static STORE: LazyLock<HistoryStore> = LazyLock::new(|| HistoryStore::new(app_paths().history_dir().to_path_buf()));
+static WRITER: LazyLock<HistoryWriter> = LazyLock::new(HistoryWriter::new);
+static NEXT_SEQUENCE: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub created_at_millis: u64,
+    pub width: u32,
+    pub height: u32,
+    /// Where the capture sat in the capture overlay, in logical pixels.
+    pub source_bounds: Option<Rect>,
+    pub app_name: Option<String>,
+    pub window_title: Option<String>,
+    pub ocr_text: Option<String>,
+    pub annotated: bool,
+}
+
+impl HistoryEntry {
+    pub fn origin(&self) -> CaptureOrigin {
+        CaptureOrigin {
+            app_name: self.app_name.clone(),
+            window_title: self.window_title.clone(),
+        }
+    }
+}
+
+/// Everything needed to write one history entry.
+pub struct HistoryRecord<'a> {
+    pub image: &'a RgbaImage,
+    /// The crop before annotations were flattened in, kept for re-annotation.
+    pub base_image: Option<&'a RgbaImage>,
+    pub annotations: Option<&'a str>,
+    pub source_bounds: Option<Rect>,
+    pub origin: &'a CaptureOrigin,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HistoryLimits {
+    pub max_entries: usize,
+    /// `0` disables the age limit.
+    pub retention_days: u32,
+}
+
+impl From<&HistorySettings> for HistoryLimits {
+    fn from(settings: &HistorySettings) -> Self {
+        Self {
+            max_entries: settings.max_entries.max(1) as usize,
+            retention_days: settings.retention_days,
+        }
+    }
+}
+
+pub struct HistoryStore {
+    root: PathBuf,
+}
+
+pub fn store() -> &'static HistoryStore {
+    &STORE
+}
+
+/// Queues `record` on the history writer, then prunes to its limits.
+pub fn record_in_background(record: PendingRecord) {
+    WRITER.record(record);
+}
+
+/// Queues OCR text for an entry; runs after any pending write of that entry.
+pub fn set_ocr_text_in_background(id: String, text: String) {
+    WRITER.set_ocr_text(id, text);
+}
+
+pub fn now_millis() -> u64 {
+    u64::try_from(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis()).unwrap_or(u64::MAX)
+}
+
+/// A new entry id; unique within the process even for captures taken in the
+/// same millisecond.
+pub fn next_entry_id(created_at_millis: u64) -> String {
+    let sequence = NEXT_SEQUENCE.fetch_add(1, Ordering::Relaxed) % 1000;
+    format!("{created_at_millis}-{sequence:03}")
+}
+
+/// Ids come back from the UI; only accept what [`next_entry_id`] produces so
+/// an id can never point outside the history directory.
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty() && id.bytes().all(|byte| byte.is_ascii_digit() || byte == b'-')
+}
+
+fn invalid_id(id: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, format!("invalid history entry id: {id:?}"))
+}
+
+fn write_png(image: &RgbaImage, path: &Path) -> io::Result<()> {
+    image.save_with_format(path, ImageFormat::Png).map_err(io::Error::other)
+}
+
+impl HistoryStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn entry_dir(&self, id: &str) -> io::Result<PathBuf> {
+        if is_valid_id(id) { Ok(self.root.join(id)) } else { Err(invalid_id(id)) }
+    }
+
+    fn existing_file(&self, id: &str, file_name: &str) -> Option<PathBuf> {
+        let path = self.entry_dir(id).ok()?.join(file_name);
+        path.is_file().then_some(path)
+    }
+
+    pub fn record(&self, id: &str, created_at_millis: u64, record: &HistoryRecord<'_>) -> io::Result<HistoryEntry> {
+        let dir = self.entry_dir(id)?;
+        ensure_dir(&dir)?;
+
+        let entry = HistoryEntry {
+            id: id.to_string(),
+            created_at_millis,
+            width: record.image.width(),
+            height: record.image.height(),
+            source_bounds: record.source_bounds.filter(|bounds| bounds.has_area()),
+            app_name: record.origin.app_name.clone(),
+            window_title: record.origin.window_title.clone(),
+            ocr_text: None,
+            annotated: record.base_image.is_some() && record.annotations.is_some(),
+        };
+
+        let written = (|| {
+            write_png(record.image, &dir.join(CAPTURE_FILE))?;
+            if let (Some(base_image), Some(annotations)) = (record.base_image, record.annotations) {
+                write_png(base_image, &dir.join(BASE_FILE))?;
+                fs::write(dir.join(ANNOTATIONS_FILE), annotations)?;
+            }
+            Self::write_entry(&dir, &entry)
+        })();
+
+        if let Err(err) = written {
+            let _ = fs::remove_dir_all(&dir);
+            return Err(err);
+        }
+        Ok(entry)
+    }
+
+    fn write_entry(dir: &Path, entry: &HistoryEntry) -> io::Result<()> {
+        let content = toml::to_string(entry).map_err(io::Error::other)?;
+        fs::write(dir.join(ENTRY_FILE), content)
+    }
+
+    fn read_entry(dir: &Path) -> Option<HistoryEntry> {
+        let content = fs::read_to_string(dir.join(ENTRY_FILE)).ok()?;
+        match toml::from_str::<HistoryEntry>(&content) {
+            Ok(entry) => Some(entry),
+            Err(err) => {
+                warn!("Skipping unreadable history entry {}: {err}", dir.display());
+                None
+            }
+        }
+    }
+
+    /// All complete entries, newest first.
+    pub fn entries(&self) -> Vec<HistoryEntry> {
+        let Ok(read_dir) = fs::read_dir(&self.root) else {
+            return Vec::new();
+        };
+
+        let mut entries: Vec<HistoryEntry> = read_dir
+            .filter_map(Result::ok)
+            .filter(|dir_entry| dir_entry.file_name().to_str().is_some_and(is_valid_id))
+            .filter_map(|dir_entry| Self::read_entry(&dir_entry.path()))
+            .collect();
+        entries.sort_by(|a, b| b.created_at_millis.cmp(&a.created_at_millis).then_with(|| b.id.cmp(&a.id)));
+        entries
+    }
+
+    pub fn entry(&self, id: &str) -> Option<HistoryEntry> {
+        Self::read_entry(&self.entry_dir(id).ok()?)
+    }
+
+    pub fn capture_path(&self, id: &str) -> Option<PathBuf> {
+        self.existing_file(id, CAPTURE_FILE)
+    }
+
+    /// The un-annotated crop and its annotation document, when the capture
+    /// was annotated.
+    pub fn annotation_source(&self, id: &str) -> Option<(PathBuf, String)> {
+        let base = self.existing_file(id, BASE_FILE)?;
+        let annotations = fs::read_to_string(self.existing_file(id, ANNOTATIONS_FILE)?).ok()?;
+        Some((base, annotations))
+    }
+
+    pub fn set_ocr_text(&self, id: &str, text: &str) -> io::Result<()> {
+        let dir = self.entry_dir(id)?;
+        let mut entry = Self::read_entry(&dir).ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no history entry {id}")))?;
+        entry.ocr_text = (!text.trim().is_empty()).then(|| text.to_string());
+        Self::write_entry(&dir, &entry)
+    }
+
+    pub fn remove(&self, id: &str) -> io::Result<()> {
+        match fs::remove_dir_all(self.entry_dir(id)?) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Drops entries beyond `limits`, oldest first. Returns how many were removed.
+    pub fn prune(&self, limits: HistoryLimits, now_millis: u64) -> usize {
+        let max_age_millis = (limits.retention_days > 0).then(|| u64::from(limits.retention_days) * MILLIS_PER_DAY);
+        let mut removed = 0;
+
+        for (index, entry) in self.entries().into_iter().enumerate() {
+            let expired = max_age_millis.is_some_and(|max_age| now_millis.saturating_sub(entry.created_at_millis) > max_age);
+            if index < limits.max_entries && !expired {
+                continue;
+            }
+            match self.remove(&entry.id) {
+                Ok(()) => removed += 1,
+                Err(err) => warn!("Failed to prune history entry {}: {err}", entry.id),
+            }
+        }
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU64;
+
+    static TEST_ROOT_ID: AtomicU64 = AtomicU64::new(0);
+
+    struct TestStore(HistoryStore);
+
+    impl TestStore {
+        fn new() -> Self {
+            let id = TEST_ROOT_ID.fetch_add(1, Ordering::Relaxed);
+            let root = std::env::temp_dir().join(format!("minnowsnap-history-{}-{id}", std::process::id()));
+            Self(HistoryStore::new(root))
+        }
+    }
+
+    impl Drop for TestStore {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0.root);
+        }
+    }
+
+    fn image(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, image::Rgba([20, 40, 60, 255]))
+    }
+
+    fn record(store: &HistoryStore, id: &str, created_at_millis: u64) -> HistoryEntry {
+        let image = image(4, 3);
+        let origin = CaptureOrigin::default();
+        store
+            .record(
+                id,
+                created_at_millis,
+                &HistoryRecord {
+                    image: &image,
+                    base_image: None,
+                    annotations: None,
+                    source_bounds: None,
+                    origin: &origin,
+                },
+            )
+            .expect("record history entry")
+    }
+
+    #[test]
+    fn entries_are_listed_newest_first() {
+        let store = TestStore::new();
+        record(&store.0, "1000-000", 1_000);
+        record(&store.0, "3000-000", 3_000);
+        record(&store.0, "2000-000", 2_000);
+
+        let ids: Vec<String> = store.0.entries().into_iter().map(|entry| entry.id).collect();
+
+        assert_eq!(ids, ["3000-000", "2000-000", "1000-000"]);
+        assert!(store.0.capture_path("2000-000").is_some());
+    }
+
+    #[test]
+    fn annotated_capture_keeps_its_base_and_document() {
+        let store = TestStore::new();
+        let capture = image(8, 6);
+        let base = image(8, 6);
+        let origin = CaptureOrigin {
+            app_name: Some("Editor".to_string()),
+            window_title: None,
+        };
+
+        let entry = store
+            .0
+            .record(
+                "5-000",
+                5,
+                &HistoryRecord {
+                    image: &capture,
+                    base_image: Some(&base),
+                    annotations: Some("items = []\n"),
+                    source_bounds: Some(Rect::new(10, 20, 8, 6)),
+                    origin: &origin,
+                },
+            )
+            .expect("record annotated entry");
+
+        assert!(entry.annotated);
+        assert_eq!(store.0.entry("5-000"), Some(entry.clone()));
+        assert_eq!(entry.origin(), origin);
+        let (base_path, annotations) = store.0.annotation_source("5-000").expect("annotation source");
+        assert_eq!(image::open(base_path).expect("decode base").into_rgba8(), base);
+        assert_eq!(annotations, "items = []\n");
+    }
+
+    #[test]
+    fn ocr_text_is_attached_to_an_existing_entry() {
+        let store = TestStore::new();
+        record(&store.0, "7-000", 7);
+
+        store.0.set_ocr_text("7-000", "hello").expect("attach OCR text");
+
+        assert_eq!(store.0.entry("7-000").and_then(|entry| entry.ocr_text).as_deref(), Some("hello"));
+        assert!(store.0.set_ocr_text("8-000", "missing").is_err());
+    }
+
+    #[test]
+    fn prune_applies_count_and_age_limits() {
+        let store = TestStore::new();
+        let now = 10 * MILLIS_PER_DAY;
+        for (id, created) in [
+            ("1-000", now - 3 * MILLIS_PER_DAY),
+            ("2-000", now - 2),
+            ("3-000", now - 1),
+            ("4-000", now),
+        ] {
+            record(&store.0, id, created);
+        }
+
+        let removed = store.0.prune(
+            HistoryLimits {
+                max_entries: 3,
+                retention_days: 2,
+            },
+            now,
+        );
+
+        let ids: Vec<String> = store.0.entries().into_iter().map(|entry| entry.id).collect();
+        assert_eq!(removed, 1);
+        assert_eq!(ids, ["4-000", "3-000", "2-000"]);
+    }
+
+    #[test]
+    fn ids_outside_the_history_directory_are_rejected() {
+        let store = TestStore::new();
+
+        assert!(store.0.remove("../config").is_err());
+        assert!(store.0.capture_path("..").is_none());
+        assert!(next_entry_id(42).starts_with("42-"));
+        assert!(is_valid_id(&next_entry_id(42)));
+    }
+}
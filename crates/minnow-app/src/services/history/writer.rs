@@ -0,0 +1,100 @@
+use super::{HistoryLimits, HistoryRecord, HistoryStore, now_millis};
+use crate::services::capture::filename::CaptureOrigin;
+use crate::services::geometry::Rect;
+use image::RgbaImage;
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use tracing::{error, info};
+
+/// An owned [`HistoryRecord`] queued for the writer.
+pub struct PendingRecord {
+    pub id: String,
+    pub created_at_millis: u64,
+    pub image: Arc<RgbaImage>,
+    pub base_image: Option<RgbaImage>,
+    pub annotations: Option<String>,
+    pub source_bounds: Option<Rect>,
+    pub origin: CaptureOrigin,
+    pub limits: HistoryLimits,
+}
+
+enum WriterCommand {
+    Record(Box<PendingRecord>),
+    OcrText { id: String, text: String },
+}
+
+/// Runs history writes on one worker so PNG encoding stays off the UI thread
+/// and an OCR result can never reach an entry before the entry itself.
+pub(super) struct HistoryWriter {
+    sender: Option<Sender<WriterCommand>>,
+}
+
+impl HistoryWriter {
+    pub(super) fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        match thread::Builder::new()
+            .name("minnow-history-writer".to_string())
+            .spawn(move || writer_loop(&receiver))
+        {
+            Ok(_) => Self { sender: Some(sender) },
+            Err(err) => {
+                error!("Failed to start history writer; writes will run inline: {err}");
+                Self { sender: None }
+            }
+        }
+    }
+
+    pub(super) fn record(&self, record: PendingRecord) {
+        self.send(WriterCommand::Record(Box::new(record)));
+    }
+
+    pub(super) fn set_ocr_text(&self, id: String, text: String) {
+        self.send(WriterCommand::OcrText { id, text });
+    }
+
+    fn send(&self, command: WriterCommand) {
+        let command = match &self.sender {
+            Some(sender) => match sender.send(command) {
+                Ok(()) => return,
+                Err(mpsc::SendError(command)) => command,
+            },
+            None => command,
+        };
+        error!("History writer is unavailable; writing inline");
+        apply(super::store(), command);
+    }
+}
+
+fn writer_loop(receiver: &Receiver<WriterCommand>) {
+    while let Ok(command) = receiver.recv() {
+        apply(super::store(), command);
+    }
+}
+
+fn apply(store: &HistoryStore, command: WriterCommand) {
+    match command {
+        WriterCommand::Record(pending) => {
+            let record = HistoryRecord {
+                image: &pending.image,
+                base_image: pending.base_image.as_ref(),
+                annotations: pending.annotations.as_deref(),
+                source_bounds: pending.source_bounds,
+                origin: &pending.origin,
+            };
+            if let Err(err) = store.record(&pending.id, pending.created_at_millis, &record) {
+                error!("Failed to record capture history entry {}: {err}", pending.id);
+                return;
+            }
+            let pruned = store.prune(pending.limits, now_millis());
+            if pruned > 0 {
+                info!("Pruned {pruned} capture history entries");
+            }
+        }
+        WriterCommand::OcrText { id, text } => {
+            if let Err(err) = store.set_ocr_text(&id, &text) {
+                error!("Failed to attach OCR text to history entry {id}: {err}");
+            }
+        }
+    }
+}
@@ -1,64 +1,193 @@
 use crate::services::settings::ShortcutSettings;
 use gpui::Keystroke;
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
 pub const DEFAULT_CAPTURE_SHORTCUT: &str = "F1";
 pub const DEFAULT_QUICK_CAPTURE_SHORTCUT: &str = "F2";
+pub const DEFAULT_DELAYED_CAPTURE_SHORTCUT: &str = "Shift+F1";
+pub const DEFAULT_WINDOW_CAPTURE_SHORTCUT: &str = "Shift+F2";
+pub const DEFAULT_FOCUSED_WINDOW_CAPTURE_SHORTCUT: &str = "Ctrl+Shift+F2";
+pub const DEFAULT_REPEAT_REGION_CAPTURE_SHORTCUT: &str = "Ctrl+F2";
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum HotkeyAction {
+    /// Opens the capture overlay.
     Capture,
     QuickCapture,
+    /// Captures after the configured countdown so menus and hover states can be opened first.
+    DelayedCapture,
+    /// Quick capture of the window under the cursor.
+    WindowCapture,
+    /// Quick capture of the window that has keyboard focus.
+    FocusedWindowCapture,
+    /// Quick capture of the last region selected in the overlay.
+    RepeatRegionCapture,
+    PinClipboardImage,
+    CloseAllPins,
+    /// Hides every pin without closing it, or shows them again.
+    TogglePinVisibility,
+    /// Opens the overlay and starts a long capture once a region is selected.
+    LongCapture,
+    /// Recognizes text in the newest history capture and copies it.
+    OcrLastCapture,
 }
 
 impl HotkeyAction {
-    fn default_shortcut(self) -> &'static str {
+    pub const ALL: [Self; 11] = [
+        Self::Capture,
+        Self::QuickCapture,
+        Self::DelayedCapture,
+        Self::WindowCapture,
+        Self::FocusedWindowCapture,
+        Self::RepeatRegionCapture,
+        Self::PinClipboardImage,
+        Self::CloseAllPins,
+        Self::TogglePinVisibility,
+        Self::LongCapture,
+        Self::OcrLastCapture,
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Capture => "capture",
+            Self::QuickCapture => "quick-capture",
+            Self::DelayedCapture => "delayed-capture",
+            Self::WindowCapture => "window-capture",
+            Self::FocusedWindowCapture => "focused-window-capture",
+            Self::RepeatRegionCapture => "repeat-region-capture",
+            Self::PinClipboardImage => "pin-clipboard-image",
+            Self::CloseAllPins => "close-all-pins",
+            Self::TogglePinVisibility => "toggle-pin-visibility",
+            Self::LongCapture => "long-capture",
+            Self::OcrLastCapture => "ocr-last-capture",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.as_str() == value)
+    }
+
+    /// The shortcut a fresh install binds to this action, if any.
+    pub const fn default_shortcut(self) -> Option<&'static str> {
         match self {
-            Self::Capture => DEFAULT_CAPTURE_SHORTCUT,
-            Self::QuickCapture => DEFAULT_QUICK_CAPTURE_SHORTCUT,
+            Self::Capture => Some(DEFAULT_CAPTURE_SHORTCUT),
+            Self::QuickCapture => Some(DEFAULT_QUICK_CAPTURE_SHORTCUT),
+            Self::DelayedCapture => Some(DEFAULT_DELAYED_CAPTURE_SHORTCUT),
+            Self::WindowCapture => Some(DEFAULT_WINDOW_CAPTURE_SHORTCUT),
+            Self::FocusedWindowCapture => Some(DEFAULT_FOCUSED_WINDOW_CAPTURE_SHORTCUT),
+            Self::RepeatRegionCapture => Some(DEFAULT_REPEAT_REGION_CAPTURE_SHORTCUT),
+            Self::PinClipboardImage | Self::CloseAllPins | Self::TogglePinVisibility | Self::LongCapture | Self::OcrLastCapture => None,
         }
     }
 }
 
+/// One row of the shortcut table.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ShortcutBinding {
+    /// Global hotkey in `Ctrl+Shift+A` form; blank while the row is unbound.
+    pub shortcut: String,
+    pub action: HotkeyAction,
+}
+
+impl ShortcutBinding {
+    pub fn new(shortcut: impl Into<String>, action: HotkeyAction) -> Self {
+        Self {
+            shortcut: shortcut.into(),
+            action,
+        }
+    }
+
+    pub fn is_bound(&self) -> bool {
+        !self.shortcut.trim().is_empty()
+    }
+}
+
+/// Every action that has a default shortcut, bound to it.
+pub fn default_shortcut_table() -> Vec<ShortcutBinding> {
+    HotkeyAction::ALL
+        .into_iter()
+        .filter_map(|action| action.default_shortcut().map(|shortcut| ShortcutBinding::new(shortcut, action)))
+        .collect()
+}
+
+/// The global shortcut table. Any shortcut may run any action, and an action
+/// may have several shortcuts; only the shortcuts themselves must be unique.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ShortcutBindings {
-    pub capture: String,
-    pub quick_capture: String,
+    entries: Vec<ShortcutBinding>,
 }
 
 impl Default for ShortcutBindings {
     fn default() -> Self {
-        Self {
-            capture: DEFAULT_CAPTURE_SHORTCUT.to_string(),
-            quick_capture: DEFAULT_QUICK_CAPTURE_SHORTCUT.to_string(),
-        }
+        Self::new(default_shortcut_table())
     }
 }
 
 impl ShortcutBindings {
+    pub fn new(entries: Vec<ShortcutBinding>) -> Self {
+        let entries = entries
+            .into_iter()
+            .map(|binding| ShortcutBinding::new(binding.shortcut.trim(), binding.action))
+            .collect();
+        Self { entries }
+    }
+
     pub fn from_settings(settings: &ShortcutSettings) -> Self {
-        Self {
-            capture: resolve_shortcut(&settings.capture, HotkeyAction::Capture),
-            quick_capture: resolve_shortcut(&settings.quick_capture, HotkeyAction::QuickCapture),
+        Self::new(settings.bindings.clone())
+    }
+
+    pub fn entries(&self) -> &[ShortcutBinding] {
+        &self.entries
+    }
+
+    pub fn into_entries(self) -> Vec<ShortcutBinding> {
+        self.entries
+    }
+
+    /// Rows that have a shortcut and therefore get registered.
+    pub fn bound(&self) -> impl Iterator<Item = &ShortcutBinding> {
+        self.entries.iter().filter(|binding| binding.is_bound())
+    }
+
+    pub fn with_shortcut(&self, index: usize, shortcut: &str) -> Self {
+        let mut next = self.clone();
+        if let Some(binding) = next.entries.get_mut(index) {
+            binding.shortcut = shortcut.trim().to_string();
         }
+        next
     }
 
-    pub fn with_capture(&self, shortcut: &str) -> Self {
-        Self {
-            capture: resolve_shortcut(shortcut, HotkeyAction::Capture),
-            quick_capture: self.quick_capture.clone(),
+    pub fn with_action(&self, index: usize, action: HotkeyAction) -> Self {
+        let mut next = self.clone();
+        if let Some(binding) = next.entries.get_mut(index) {
+            binding.action = action;
         }
+        next
     }
 
-    pub fn with_quick_capture(&self, shortcut: &str) -> Self {
-        Self {
-            capture: self.capture.clone(),
-            quick_capture: resolve_shortcut(shortcut, HotkeyAction::QuickCapture),
+    /// Appends an unbound row for `action`, ready to record a shortcut.
+    pub fn with_added(&self, action: HotkeyAction) -> Self {
+        let mut next = self.clone();
+        next.entries.push(ShortcutBinding::new(String::new(), action));
+        next
+    }
+
+    pub fn without(&self, index: usize) -> Self {
+        let mut next = self.clone();
+        if index < next.entries.len() {
+            next.entries.remove(index);
         }
+        next
     }
 
     pub fn has_conflict(&self) -> bool {
-        shortcuts_conflict(&self.capture, &self.quick_capture)
+        let shortcuts = self.bound().map(|binding| binding.shortcut.as_str()).collect::<Vec<_>>();
+        shortcuts
+            .iter()
+            .enumerate()
+            .any(|(index, shortcut)| shortcuts[index + 1..].iter().any(|other| shortcuts_conflict(shortcut, other)))
     }
 }
 
@@ -84,14 +213,17 @@ pub fn parse_hotkey(shortcut: &str) -> Option<global_hotkey::hotkey::HotKey> {
 pub fn resolve_shortcut(shortcut: &str, action: HotkeyAction) -> String {
     let trimmed = shortcut.trim();
     if trimmed.is_empty() {
-        action.default_shortcut().to_string()
+        action.default_shortcut().unwrap_or_default().to_string()
     } else {
         trimmed.to_string()
     }
 }
 
-pub fn shortcuts_conflict(capture: &str, quick_capture: &str) -> bool {
-    normalize_shortcut_for_compare(capture, HotkeyAction::Capture) == normalize_shortcut_for_compare(quick_capture, HotkeyAction::QuickCapture)
+/// Whether both shortcuts parse to the same hotkey, so modifier order and
+/// case do not hide a clash that registration would reject.
+pub fn shortcuts_conflict(first: &str, second: &str) -> bool {
+    let hotkey_id = |shortcut: &str| parse_hotkey(shortcut.trim()).map(|hotkey| hotkey.id());
+    matches!((hotkey_id(first), hotkey_id(second)), (Some(first), Some(second)) if first == second)
 }
 
 pub fn format_keystroke(keystroke: &Keystroke) -> Option<String> {
@@ -124,14 +256,6 @@ pub fn format_keystroke(keystroke: &Keystroke) -> Option<String> {
     Some(tokens.join("+"))
 }
 
-fn normalize_shortcut_for_compare(shortcut: &str, action: HotkeyAction) -> String {
-    resolve_shortcut(shortcut, action)
-        .chars()
-        .filter(|ch| !ch.is_whitespace())
-        .collect::<String>()
-        .to_ascii_lowercase()
-}
-
 fn is_modifier_only_key(key: &str) -> bool {
     matches!(
         key.trim().to_ascii_lowercase().as_str(),
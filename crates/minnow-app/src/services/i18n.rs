@@ -73,6 +73,7 @@ pub mod common {
         ocr => "common.actions.ocr",
         scan_qr => "common.actions.scan_qr",
         scroll => "common.actions.scroll",
+        record => "common.actions.record",
     }
 }
 
@@ -87,6 +88,8 @@ pub mod overlay {
         annotation_tool_counter => "overlay.annotation.tool.counter",
         annotation_tool_text => "overlay.annotation.tool.text",
         annotation_tool_mosaic => "overlay.annotation.tool.mosaic",
+        annotation_tool_pen => "overlay.annotation.tool.pen",
+        annotation_tool_highlighter => "overlay.annotation.tool.highlighter",
         annotation_undo => "overlay.annotation.actions.undo",
         annotation_redo => "overlay.annotation.actions.redo",
         annotation_toggle_fill => "overlay.annotation.actions.toggle_fill",
@@ -97,16 +100,47 @@ pub mod overlay {
         annotation_mosaic_mode_blur => "overlay.annotation.actions.mosaic_mode_blur",
         annotation_mosaic_intensity_up => "overlay.annotation.actions.mosaic_intensity_up",
         annotation_mosaic_intensity_down => "overlay.annotation.actions.mosaic_intensity_down",
+        annotation_line_plain => "overlay.annotation.actions.line_plain",
+        annotation_line_arrow => "overlay.annotation.actions.line_arrow",
+        annotation_line_double_arrow => "overlay.annotation.actions.line_double_arrow",
+        annotation_line_dashed => "overlay.annotation.actions.line_dashed",
+        annotation_line_curved => "overlay.annotation.actions.line_curved",
         qr_not_found => "overlay.notify.qr_not_found",
         long_capture_processing => "overlay.long_capture.processing",
         long_capture_scroll_hint => "overlay.long_capture.scroll_hint",
         long_capture_empty => "overlay.long_capture.empty",
+        long_capture_auto_scroll => "overlay.long_capture.auto_scroll",
+        long_capture_mask => "overlay.long_capture.mask",
+        long_capture_mask_hint => "overlay.long_capture.mask_hint",
+        long_capture_review => "overlay.long_capture.review",
+        long_capture_review_hint => "overlay.long_capture.review_hint",
+        long_capture_review_remove => "overlay.long_capture.review_remove",
+        long_capture_review_shift_back => "overlay.long_capture.review_shift_back",
+        long_capture_review_shift_forward => "overlay.long_capture.review_shift_forward",
+        long_capture_review_trim_before => "overlay.long_capture.review_trim_before",
+        long_capture_review_trim_after => "overlay.long_capture.review_trim_after",
+        long_capture_review_reset => "overlay.long_capture.review_reset",
+        long_capture_diagnostics => "overlay.long_capture.diagnostics",
+        long_capture_diagnostics_waiting => "overlay.long_capture.diagnostics_waiting",
+        long_capture_diagnostics_export => "overlay.long_capture.diagnostics_export",
+        long_capture_diagnostics_export_failed => "overlay.long_capture.diagnostics_export_failed",
+        long_capture_diagnostics_tune => "overlay.long_capture.diagnostics_tune",
+        long_capture_diagnostics_tune_unchanged => "overlay.long_capture.diagnostics_tune_unchanged",
+        recording_stop => "overlay.recording.stop",
+        recording_empty => "overlay.recording.empty",
+        recording_encoding => "overlay.recording.encoding",
+        recording_duration_limit => "overlay.recording.duration_limit",
+        recording_size_limit => "overlay.recording.size_limit",
     }
 
     i18n_fns_with_args! {
         picker_value_and_format(value, format) => "overlay.picker.value_and_format",
         picker_coordinates(x, y) => "overlay.picker.coordinates",
         picker_shortcuts(copy_key, cycle_key) => "overlay.picker.shortcuts",
+        long_capture_diagnostics_summary(confidence, rejected) => "overlay.long_capture.diagnostics_summary",
+        long_capture_diagnostics_exported(path) => "overlay.long_capture.diagnostics_exported",
+        long_capture_diagnostics_tuned(app) => "overlay.long_capture.diagnostics_tuned",
+        recording_status(elapsed, frames) => "overlay.recording.status",
     }
 }
 
@@ -119,10 +153,19 @@ pub mod notify {
         copied_text => "notify.capture.copied_text",
         quick_capture_copied => "notify.capture.quick_capture_copied",
         quick_capture_failed => "notify.capture.quick_capture_failed",
+        quick_capture_no_window => "notify.capture.quick_capture_no_window",
+        quick_capture_no_region => "notify.capture.quick_capture_no_region",
+        clipboard_no_image => "notify.capture.clipboard_no_image",
+        no_capture_history => "notify.capture.no_capture_history",
+        ocr_unavailable => "notify.capture.ocr_unavailable",
+        ocr_failed => "notify.capture.ocr_failed",
+        ocr_no_text => "notify.capture.ocr_no_text",
     }
 
     i18n_fns_with_args! {
         saved_image(path) => "notify.capture.saved_image",
+        saved_recording(path) => "notify.capture.saved_recording",
+        delayed_capture_countdown(seconds) => "notify.capture.delayed_capture_countdown",
     }
 }
 
@@ -168,10 +211,60 @@ pub mod preferences {
         save_directory => "preferences.fields.save_directory",
         image_compression => "preferences.fields.image_compression",
         image_compression_description => "preferences.fields.image_compression_description",
-        capture_shortcut => "preferences.fields.capture_shortcut",
-        quick_capture_shortcut => "preferences.fields.quick_capture_shortcut",
+        capture_scope => "preferences.fields.capture_scope",
+        capture_scope_description => "preferences.fields.capture_scope_description",
+        capture_delay => "preferences.fields.capture_delay",
+        capture_delay_description => "preferences.fields.capture_delay_description",
+        delayed_capture_mode => "preferences.fields.delayed_capture_mode",
+        delayed_capture_mode_description => "preferences.fields.delayed_capture_mode_description",
+        quick_capture_destination => "preferences.fields.quick_capture_destination",
+        long_capture_max_length => "preferences.fields.long_capture_max_length",
+        long_capture_max_length_description => "preferences.fields.long_capture_max_length_description",
+        long_capture_animation => "preferences.fields.long_capture_animation",
+        long_capture_animation_description => "preferences.fields.long_capture_animation_description",
+        recording_format => "preferences.fields.recording_format",
+        recording_format_description => "preferences.fields.recording_format_description",
+        recording_frame_rate => "preferences.fields.recording_frame_rate",
+        recording_frame_rate_description => "preferences.fields.recording_frame_rate_description",
+        recording_max_seconds => "preferences.fields.recording_max_seconds",
+        recording_max_seconds_description => "preferences.fields.recording_max_seconds_description",
+        quick_capture_destination_description => "preferences.fields.quick_capture_destination_description",
+        image_format => "preferences.fields.image_format",
+        image_format_description => "preferences.fields.image_format_description",
+        jpeg_quality => "preferences.fields.jpeg_quality",
+        jpeg_quality_description => "preferences.fields.jpeg_quality_description",
+        filename_template => "preferences.fields.filename_template",
+        filename_template_description => "preferences.fields.filename_template_description",
+        history_enabled => "preferences.fields.history_enabled",
+        history_enabled_description => "preferences.fields.history_enabled_description",
+        history_max_entries => "preferences.fields.history_max_entries",
+        history_max_entries_description => "preferences.fields.history_max_entries_description",
+        history_retention => "preferences.fields.history_retention",
+        history_retention_description => "preferences.fields.history_retention_description",
+        history_browse => "preferences.fields.history_browse",
+        history_browse_description => "preferences.fields.history_browse_description",
+        capture_shortcut => "preferences.shortcut_actions.capture",
+        quick_capture_shortcut => "preferences.shortcut_actions.quick_capture",
+        delayed_capture_shortcut => "preferences.shortcut_actions.delayed_capture",
+        window_capture_shortcut => "preferences.shortcut_actions.window_capture",
+        focused_window_capture_shortcut => "preferences.shortcut_actions.focused_window_capture",
+        repeat_region_capture_shortcut => "preferences.shortcut_actions.repeat_region_capture",
+        pin_clipboard_image_shortcut => "preferences.shortcut_actions.pin_clipboard_image",
+        close_all_pins_shortcut => "preferences.shortcut_actions.close_all_pins",
+        toggle_pin_visibility_shortcut => "preferences.shortcut_actions.toggle_pin_visibility",
+        long_capture_shortcut => "preferences.shortcut_actions.long_capture",
+        ocr_last_capture_shortcut => "preferences.shortcut_actions.ocr_last_capture",
         capture_shortcut_description => "preferences.fields.capture_shortcut_description",
         quick_capture_shortcut_description => "preferences.fields.quick_capture_shortcut_description",
+        delayed_capture_shortcut_description => "preferences.fields.delayed_capture_shortcut_description",
+        window_capture_shortcut_description => "preferences.fields.window_capture_shortcut_description",
+        focused_window_capture_shortcut_description => "preferences.fields.focused_window_capture_shortcut_description",
+        repeat_region_capture_shortcut_description => "preferences.fields.repeat_region_capture_shortcut_description",
+        pin_clipboard_image_shortcut_description => "preferences.fields.pin_clipboard_image_shortcut_description",
+        close_all_pins_shortcut_description => "preferences.fields.close_all_pins_shortcut_description",
+        toggle_pin_visibility_shortcut_description => "preferences.fields.toggle_pin_visibility_shortcut_description",
+        long_capture_shortcut_description => "preferences.fields.long_capture_shortcut_description",
+        ocr_last_capture_shortcut_description => "preferences.fields.ocr_last_capture_shortcut_description",
         ocr_enabled_description => "preferences.fields.ocr_enabled_description",
         select_save_directory => "preferences.actions.select_save_directory",
         browse => "preferences.actions.browse",
@@ -179,6 +272,9 @@ pub mod preferences {
         shortcuts_recording => "preferences.actions.shortcuts_recording",
         shortcuts_recording_hint => "preferences.actions.shortcuts_recording_hint",
         shortcuts_restore_defaults => "preferences.actions.shortcuts_restore_defaults",
+        shortcuts_add => "preferences.actions.shortcuts_add",
+        shortcuts_remove => "preferences.actions.shortcuts_remove",
+        shortcuts_unbound => "preferences.actions.shortcuts_unbound",
         ocr_download_action => "preferences.actions.ocr_download",
         ocr_redownload_action => "preferences.actions.ocr_redownload",
         ocr_download_in_progress => "preferences.actions.ocr_download_in_progress",
@@ -187,6 +283,20 @@ pub mod preferences {
         theme_dark => "preferences.options.dark",
         language_zh_cn => "preferences.options.zh_cn",
         language_en_us => "preferences.options.en_us",
+        capture_scope_cursor_monitor => "preferences.options.capture_scope_cursor_monitor",
+        capture_scope_virtual_desktop => "preferences.options.capture_scope_virtual_desktop",
+        delayed_capture_mode_overlay => "preferences.options.delayed_capture_mode_overlay",
+        delayed_capture_mode_quick_capture => "preferences.options.delayed_capture_mode_quick_capture",
+        quick_capture_destination_clipboard => "preferences.options.quick_capture_destination_clipboard",
+        quick_capture_destination_file => "preferences.options.quick_capture_destination_file",
+        quick_capture_destination_pin => "preferences.options.quick_capture_destination_pin",
+        format_png => "preferences.options.format_png",
+        format_jpeg => "preferences.options.format_jpeg",
+        format_webp => "preferences.options.format_webp",
+        history_retention_forever => "preferences.options.history_retention_forever",
+        long_capture_max_length_unlimited => "preferences.options.long_capture_max_length_unlimited",
+        recording_format_gif => "preferences.options.recording_format_gif",
+        recording_format_apng => "preferences.options.recording_format_apng",
         about_summary => "preferences.about.summary",
         version_label => "preferences.about.version",
         github_repository => "preferences.about.github_repository",
@@ -207,6 +317,12 @@ pub mod preferences {
         default_path_with_value(path) => "preferences.fields.default_path_with_value",
         ocr_status_downloading(progress) => "preferences.ocr.status_downloading",
         ocr_status_failed(message) => "preferences.ocr.status_failed",
+        history_entries_count(count) => "preferences.options.history_entries_count",
+        capture_delay_seconds(seconds) => "preferences.options.capture_delay_seconds",
+        history_retention_days(days) => "preferences.options.history_retention_days",
+        long_capture_max_length_pixels(pixels) => "preferences.options.long_capture_max_length_pixels",
+        recording_frame_rate_fps(fps) => "preferences.options.recording_frame_rate_fps",
+        recording_max_seconds_value(seconds) => "preferences.options.recording_max_seconds_value",
     }
 }
 
@@ -218,12 +334,28 @@ pub mod pin {
     }
 }
 
+pub mod history {
+    use rust_i18n::t;
+
+    i18n_fns! {
+        title => "history.window.title",
+        empty => "history.empty",
+        disabled => "history.disabled",
+        annotate => "history.actions.annotate",
+        copy_text => "history.actions.copy_text",
+        delete => "history.actions.delete",
+        reopen_failed => "history.errors.reopen_failed",
+    }
+}
+
 pub mod tray {
     use rust_i18n::t;
 
     i18n_fns! {
         capture_overlay => "tray.actions.capture_overlay",
         quick_capture => "tray.actions.quick_capture",
+        delayed_capture => "tray.actions.delayed_capture",
+        history => "tray.actions.history",
         preferences => "tray.actions.preferences",
         exit => "tray.actions.exit",
     }
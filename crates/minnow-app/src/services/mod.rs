@@ -3,6 +3,7 @@ pub mod assets;
 pub mod capture;
 pub mod fonts;
 pub mod geometry;
+pub mod history;
 pub mod hotkeys;
 pub mod i18n;
 pub mod ocr;
@@ -15,6 +15,7 @@ const LOGS_DIR_NAME: &str = "logs";
 #[cfg(feature = "portable")]
 const TEMP_DIR_NAME: &str = "temp";
 const OCR_MODELS_DIR_NAME: &str = "ocr_models";
+const HISTORY_DIR_NAME: &str = "history";
 
 #[derive(Debug)]
 pub struct AppPaths {
@@ -22,6 +23,7 @@ pub struct AppPaths {
     logs_dir: PathBuf,
     temp_dir: PathBuf,
     ocr_models_dir: PathBuf,
+    history_dir: PathBuf,
 }
 
 static APP_PATHS: OnceLock<AppPaths> = OnceLock::new();
@@ -43,6 +45,7 @@ fn resolve_app_paths() -> AppPaths {
             logs_dir: data_dir.join(LOGS_DIR_NAME),
             temp_dir: data_dir.join(TEMP_DIR_NAME),
             ocr_models_dir: data_dir.join(OCR_MODELS_DIR_NAME),
+            history_dir: data_dir.join(HISTORY_DIR_NAME),
         }
     }
 
@@ -62,6 +65,7 @@ fn resolve_app_paths() -> AppPaths {
         AppPaths {
             logs_dir: data_dir.join(LOGS_DIR_NAME),
             ocr_models_dir: data_dir.join(OCR_MODELS_DIR_NAME),
+            history_dir: data_dir.join(HISTORY_DIR_NAME),
             config_file,
             temp_dir: env::temp_dir(),
         }
@@ -103,4 +107,8 @@ impl AppPaths {
     pub fn ocr_models_dir(&self) -> &Path {
         &self.ocr_models_dir
     }
+
+    pub fn history_dir(&self) -> &Path {
+        &self.history_dir
+    }
 }
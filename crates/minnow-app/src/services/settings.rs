@@ -1,7 +1,10 @@
 mod persistence;
 
-use crate::services::{hotkeys, paths::ensure_parent_dir};
+use crate::services::capture::long_capture::StitchTuning;
+use crate::services::hotkeys::{self, HotkeyAction, ShortcutBinding};
+use crate::services::paths::ensure_parent_dir;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{LazyLock, Mutex, MutexGuard};
@@ -51,6 +54,10 @@ pub fn notification_settings() -> NotificationSettings {
     snapshot().notification
 }
 
+pub fn history_settings() -> HistorySettings {
+    snapshot().history
+}
+
 pub fn language() -> String {
     general_settings().language
 }
@@ -69,23 +76,174 @@ pub fn flush() {
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SettingsAction {
     SavePath(String),
     OxipngEnabled(bool),
+    OutputFormat(OutputFormat),
+    JpegQuality(u8),
+    FilenameTemplate(String),
     FontFamily(String),
     Theme(String),
     Language(String),
     AutoStart(bool),
-    Shortcuts { capture: String, quick_capture: String },
+    CaptureScope(CaptureScope),
+    CaptureDelay(u32),
+    DelayedCaptureMode(DelayedCaptureMode),
+    QuickCaptureDestination(QuickCaptureDestination),
+    LongCaptureMaxLength(u32),
+    LongCaptureAnimation(bool),
+    RecordingFormat(RecordingFormat),
+    RecordingFrameRate(u32),
+    RecordingMaxSeconds(u32),
+    /// Sets how long captures of `app` are stitched; an empty tuning
+    /// removes the profile.
+    LongCaptureProfile {
+        app: String,
+        tuning: StitchTuning,
+    },
+    /// Replaces the whole shortcut table.
+    Shortcuts(Vec<ShortcutBinding>),
     OcrEnabled(bool),
     NotificationEnabled(bool),
     SaveNotification(bool),
     CopyNotification(bool),
     QrCodeNotification(bool),
     ShutterSound(bool),
+    HistoryEnabled(bool),
+    HistoryMaxEntries(u32),
+    HistoryRetentionDays(u32),
+}
+
+/// Which displays a capture session covers.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CaptureScope {
+    /// The monitor under the cursor when the capture starts.
+    #[default]
+    CursorMonitor,
+    /// All monitors stitched into one virtual-desktop image.
+    VirtualDesktop,
+}
+
+impl CaptureScope {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::CursorMonitor => "cursor-monitor",
+            Self::VirtualDesktop => "virtual-desktop",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "cursor-monitor" => Some(Self::CursorMonitor),
+            "virtual-desktop" => Some(Self::VirtualDesktop),
+            _ => None,
+        }
+    }
 }
 
+/// What a delayed capture does once its countdown ends.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DelayedCaptureMode {
+    /// Opens the capture overlay on the frame taken after the delay.
+    #[default]
+    Overlay,
+    /// Runs the quick-capture workflow on the delayed frame.
+    QuickCapture,
+}
+
+impl DelayedCaptureMode {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Overlay => "overlay",
+            Self::QuickCapture => "quick-capture",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "overlay" => Some(Self::Overlay),
+            "quick-capture" => Some(Self::QuickCapture),
+            _ => None,
+        }
+    }
+}
+
+/// Where a quick capture (tray, hotkeys, control commands) delivers its image.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuickCaptureDestination {
+    #[default]
+    Clipboard,
+    /// Saved to the capture folder using the filename template.
+    File,
+    /// Opened as a pin at the captured position.
+    Pin,
+}
+
+impl QuickCaptureDestination {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Clipboard => "clipboard",
+            Self::File => "file",
+            Self::Pin => "pin",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "clipboard" => Some(Self::Clipboard),
+            "file" => Some(Self::File),
+            "pin" => Some(Self::Pin),
+            _ => None,
+        }
+    }
+}
+
+/// Animation format screen recordings are encoded to.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RecordingFormat {
+    #[default]
+    Gif,
+    /// Animated PNG: full color, larger files.
+    Apng,
+}
+
+impl RecordingFormat {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Gif => "gif",
+            Self::Apng => "apng",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "gif" => Some(Self::Gif),
+            "apng" => Some(Self::Apng),
+            _ => None,
+        }
+    }
+
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Gif => "gif",
+            Self::Apng => "png",
+        }
+    }
+}
+
+pub const DEFAULT_CAPTURE_DELAY_SECONDS: u32 = 3;
+pub const MAX_CAPTURE_DELAY_SECONDS: u32 = 60;
+pub const DEFAULT_LONG_CAPTURE_MAX_LENGTH: u32 = 50_000;
+pub const DEFAULT_RECORDING_FRAME_RATE: u32 = 10;
+pub const MAX_RECORDING_FRAME_RATE: u32 = 30;
+pub const DEFAULT_RECORDING_MAX_SECONDS: u32 = 30;
+pub const MAX_RECORDING_MAX_SECONDS: u32 = 300;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(default)]
 pub struct GeneralSettings {
@@ -93,6 +251,23 @@ pub struct GeneralSettings {
     pub language: String,
     pub font_family: Option<String>,
     pub auto_start: bool,
+    pub capture_scope: CaptureScope,
+    pub capture_delay_seconds: u32,
+    pub delayed_capture_mode: DelayedCaptureMode,
+    pub quick_capture_destination: QuickCaptureDestination,
+    /// Longest long capture along the scroll axis, in pixels; 0 has no
+    /// limit.
+    pub long_capture_max_length: u32,
+    /// Stitching overrides for long captures, by application name.
+    pub long_capture_profiles: BTreeMap<String, StitchTuning>,
+    /// Also saves a long capture as an animation of the page scrolling,
+    /// using the recording format, frame rate and limit.
+    pub long_capture_animation: bool,
+    pub recording_format: RecordingFormat,
+    /// Frames per second sampled while recording.
+    pub recording_frame_rate: u32,
+    /// Longest screen recording; recording stops once it is reached.
+    pub recording_max_seconds: u32,
 }
 
 impl Default for GeneralSettings {
@@ -102,31 +277,119 @@ impl Default for GeneralSettings {
             language: "System".to_string(),
             font_family: None,
             auto_start: false,
+            capture_scope: CaptureScope::default(),
+            capture_delay_seconds: DEFAULT_CAPTURE_DELAY_SECONDS,
+            delayed_capture_mode: DelayedCaptureMode::default(),
+            quick_capture_destination: QuickCaptureDestination::default(),
+            long_capture_max_length: DEFAULT_LONG_CAPTURE_MAX_LENGTH,
+            long_capture_profiles: BTreeMap::new(),
+            long_capture_animation: false,
+            recording_format: RecordingFormat::default(),
+            recording_frame_rate: DEFAULT_RECORDING_FRAME_RATE,
+            recording_max_seconds: DEFAULT_RECORDING_MAX_SECONDS,
         }
     }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(default)]
+#[serde(from = "StoredShortcutSettings")]
 pub struct ShortcutSettings {
-    pub capture: String,
-    pub quick_capture: String,
+    pub bindings: Vec<ShortcutBinding>,
 }
 
 impl Default for ShortcutSettings {
     fn default() -> Self {
         Self {
-            capture: hotkeys::DEFAULT_CAPTURE_SHORTCUT.to_string(),
-            quick_capture: hotkeys::DEFAULT_QUICK_CAPTURE_SHORTCUT.to_string(),
+            bindings: hotkeys::default_shortcut_table(),
         }
     }
 }
 
+/// On-disk shape of `[shortcuts]`. Older configs stored `capture` and
+/// `quick_capture` fields instead of a `bindings` table; those fields are
+/// folded into the default table so customized shortcuts survive the upgrade.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct StoredShortcutSettings {
+    bindings: Option<Vec<ShortcutBinding>>,
+    capture: Option<String>,
+    quick_capture: Option<String>,
+}
+
+impl From<StoredShortcutSettings> for ShortcutSettings {
+    fn from(stored: StoredShortcutSettings) -> Self {
+        if let Some(bindings) = stored.bindings {
+            return Self { bindings };
+        }
+
+        let legacy = [
+            (HotkeyAction::Capture, stored.capture),
+            (HotkeyAction::QuickCapture, stored.quick_capture),
+        ];
+        let bindings = hotkeys::default_shortcut_table()
+            .into_iter()
+            .map(|binding| match legacy.iter().find(|(action, _)| *action == binding.action) {
+                Some((action, Some(shortcut))) => ShortcutBinding::new(hotkeys::resolve_shortcut(shortcut, *action), *action),
+                _ => binding,
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+/// File format used when saving captures to disk.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    #[default]
+    Png,
+    /// Lossy JPEG at `OutputSettings::jpeg_quality`; transparency is dropped.
+    Jpeg,
+    /// Lossless WebP.
+    Webp,
+}
+
+impl OutputFormat {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpeg",
+            Self::Webp => "webp",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "png" => Some(Self::Png),
+            "jpeg" | "jpg" => Some(Self::Jpeg),
+            "webp" => Some(Self::Webp),
+            _ => None,
+        }
+    }
+
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Webp => "webp",
+        }
+    }
+}
+
+pub const DEFAULT_JPEG_QUALITY: u8 = 90;
+pub const MIN_JPEG_QUALITY: u8 = 1;
+pub const MAX_JPEG_QUALITY: u8 = 100;
+/// Matches the names saved before templates existed.
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "snap_{timestamp}";
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(default)]
 pub struct OutputSettings {
     pub save_path: Option<String>,
     pub oxipng_enabled: bool,
+    pub format: OutputFormat,
+    pub jpeg_quality: u8,
+    pub filename_template: String,
 }
 
 impl Default for OutputSettings {
@@ -134,6 +397,9 @@ impl Default for OutputSettings {
         Self {
             save_path: None,
             oxipng_enabled: true,
+            format: OutputFormat::default(),
+            jpeg_quality: DEFAULT_JPEG_QUALITY,
+            filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
         }
     }
 }
@@ -176,6 +442,29 @@ impl Default for NotificationSettings {
     }
 }
 
+pub const DEFAULT_HISTORY_MAX_ENTRIES: u32 = 50;
+pub const MAX_HISTORY_MAX_ENTRIES: u32 = 500;
+pub const DEFAULT_HISTORY_RETENTION_DAYS: u32 = 30;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct HistorySettings {
+    pub enabled: bool,
+    pub max_entries: u32,
+    /// Days an entry is kept; `0` keeps entries until `max_entries` pushes them out.
+    pub retention_days: u32,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: DEFAULT_HISTORY_MAX_ENTRIES,
+            retention_days: DEFAULT_HISTORY_RETENTION_DAYS,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(default)]
 pub struct AppSettings {
@@ -184,6 +473,7 @@ pub struct AppSettings {
     pub output: OutputSettings,
     pub ocr: OcrSettings,
     pub notification: NotificationSettings,
+    pub history: HistorySettings,
 }
 
 pub struct SettingsStore {
@@ -266,6 +556,21 @@ impl SettingsStore {
             SettingsAction::OxipngEnabled(enabled) => {
                 self.update(|c| c.output.oxipng_enabled = enabled);
             }
+            SettingsAction::OutputFormat(format) => {
+                self.update(|c| c.output.format = format);
+            }
+            SettingsAction::JpegQuality(quality) => {
+                self.update(|c| c.output.jpeg_quality = quality.clamp(MIN_JPEG_QUALITY, MAX_JPEG_QUALITY));
+            }
+            SettingsAction::FilenameTemplate(template) => {
+                self.update(|c| {
+                    c.output.filename_template = if template.trim().is_empty() {
+                        DEFAULT_FILENAME_TEMPLATE.to_string()
+                    } else {
+                        template
+                    };
+                });
+            }
             SettingsAction::FontFamily(font_family) => {
                 self.update(|c| {
                     c.general.font_family = if font_family.is_empty() { None } else { Some(font_family) };
@@ -280,12 +585,45 @@ impl SettingsStore {
             SettingsAction::AutoStart(enabled) => {
                 self.update(|c| c.general.auto_start = enabled);
             }
-            SettingsAction::Shortcuts { capture, quick_capture } => {
+            SettingsAction::CaptureScope(scope) => {
+                self.update(|c| c.general.capture_scope = scope);
+            }
+            SettingsAction::CaptureDelay(seconds) => {
+                self.update(|c| c.general.capture_delay_seconds = seconds.clamp(1, MAX_CAPTURE_DELAY_SECONDS));
+            }
+            SettingsAction::DelayedCaptureMode(mode) => {
+                self.update(|c| c.general.delayed_capture_mode = mode);
+            }
+            SettingsAction::QuickCaptureDestination(destination) => {
+                self.update(|c| c.general.quick_capture_destination = destination);
+            }
+            SettingsAction::LongCaptureMaxLength(length) => {
+                self.update(|c| c.general.long_capture_max_length = length);
+            }
+            SettingsAction::LongCaptureAnimation(enabled) => {
+                self.update(|c| c.general.long_capture_animation = enabled);
+            }
+            SettingsAction::RecordingFormat(format) => {
+                self.update(|c| c.general.recording_format = format);
+            }
+            SettingsAction::RecordingFrameRate(frame_rate) => {
+                self.update(|c| c.general.recording_frame_rate = frame_rate.clamp(1, MAX_RECORDING_FRAME_RATE));
+            }
+            SettingsAction::RecordingMaxSeconds(seconds) => {
+                self.update(|c| c.general.recording_max_seconds = seconds.clamp(1, MAX_RECORDING_MAX_SECONDS));
+            }
+            SettingsAction::LongCaptureProfile { app, tuning } => {
                 self.update(|c| {
-                    c.shortcuts.capture = capture;
-                    c.shortcuts.quick_capture = quick_capture;
+                    if tuning.is_empty() {
+                        c.general.long_capture_profiles.remove(&app);
+                    } else {
+                        c.general.long_capture_profiles.insert(app, tuning);
+                    }
                 });
             }
+            SettingsAction::Shortcuts(bindings) => {
+                self.update(|c| c.shortcuts.bindings = bindings);
+            }
             SettingsAction::OcrEnabled(enabled) => {
                 self.update(|c| c.ocr.enabled = enabled);
             }
@@ -304,6 +642,15 @@ impl SettingsStore {
             SettingsAction::ShutterSound(enabled) => {
                 self.update(|c| c.notification.shutter_sound = enabled);
             }
+            SettingsAction::HistoryEnabled(enabled) => {
+                self.update(|c| c.history.enabled = enabled);
+            }
+            SettingsAction::HistoryMaxEntries(max_entries) => {
+                self.update(|c| c.history.max_entries = max_entries.clamp(1, MAX_HISTORY_MAX_ENTRIES));
+            }
+            SettingsAction::HistoryRetentionDays(days) => {
+                self.update(|c| c.history.retention_days = days);
+            }
         }
     }
 
@@ -364,7 +711,7 @@ mod tests {
         let settings = SettingsStore::load_config_from(&path);
 
         assert_eq!(settings.general.theme, THEME_SYSTEM);
-        assert_eq!(settings.shortcuts.capture, hotkeys::DEFAULT_CAPTURE_SHORTCUT);
+        assert_eq!(settings.shortcuts.bindings, hotkeys::default_shortcut_table());
         assert!(path.parent().is_some_and(Path::exists));
         cleanup_config_path(&path);
     }
@@ -393,28 +740,78 @@ mod tests {
 
         assert_eq!(settings.general.theme, THEME_DARK);
         assert_eq!(settings.general.language, "System");
-        assert_eq!(settings.shortcuts.capture, hotkeys::DEFAULT_CAPTURE_SHORTCUT);
+        assert_eq!(settings.shortcuts.bindings, hotkeys::default_shortcut_table());
         assert!(settings.output.oxipng_enabled);
         assert!(settings.notification.enabled);
         cleanup_config_path(&path);
     }
 
     #[test]
-    fn set_shortcuts_updates_both_bindings_with_one_save() {
+    fn set_shortcuts_updates_all_bindings_with_one_save() {
         let mut store = test_store();
+        let bindings = vec![
+            ShortcutBinding::new("Ctrl+Shift+1", HotkeyAction::Capture),
+            ShortcutBinding::new("Ctrl+Shift+2", HotkeyAction::PinClipboardImage),
+            ShortcutBinding::new("Ctrl+Shift+3", HotkeyAction::PinClipboardImage),
+        ];
 
-        store.apply(SettingsAction::Shortcuts {
-            capture: "Ctrl+Shift+1".to_string(),
-            quick_capture: "Ctrl+Shift+2".to_string(),
-        });
+        store.apply(SettingsAction::Shortcuts(bindings.clone()));
 
-        let settings = store.get();
-        assert_eq!(settings.shortcuts.capture, "Ctrl+Shift+1");
-        assert_eq!(settings.shortcuts.quick_capture, "Ctrl+Shift+2");
+        assert_eq!(store.get().shortcuts.bindings, bindings);
         assert_eq!(store.save_count, 1);
         cleanup_store(store);
     }
 
+    #[test]
+    fn shortcut_table_round_trips_through_config() {
+        let path = test_config_path("shortcut-table");
+        ensure_parent_dir(&path).expect("create config test directory");
+        let settings = AppSettings {
+            shortcuts: ShortcutSettings {
+                bindings: vec![
+                    ShortcutBinding::new("Ctrl+Alt+P", HotkeyAction::PinClipboardImage),
+                    ShortcutBinding::new("", HotkeyAction::OcrLastCapture),
+                ],
+            },
+            ..AppSettings::default()
+        };
+        std::fs::write(&path, toml::to_string_pretty(&settings).expect("encode settings")).expect("write shortcut config");
+
+        let loaded = SettingsStore::load_config_from(&path);
+
+        assert_eq!(loaded.shortcuts.bindings, settings.shortcuts.bindings);
+        cleanup_config_path(&path);
+    }
+
+    #[test]
+    fn legacy_shortcut_fields_migrate_into_the_table() {
+        let path = test_config_path("legacy-shortcuts");
+        ensure_parent_dir(&path).expect("create config test directory");
+        std::fs::write(&path, "[shortcuts]\ncapture = \"Ctrl+Alt+A\"\nquick_capture = \"\"\n").expect("write legacy config");
+
+        let settings = SettingsStore::load_config_from(&path);
+
+        let shortcut_for = |action| {
+            settings
+                .shortcuts
+                .bindings
+                .iter()
+                .find(|binding| binding.action == action)
+                .map(|binding| binding.shortcut.clone())
+        };
+        assert_eq!(shortcut_for(HotkeyAction::Capture).as_deref(), Some("Ctrl+Alt+A"));
+        assert_eq!(
+            shortcut_for(HotkeyAction::QuickCapture).as_deref(),
+            Some(hotkeys::DEFAULT_QUICK_CAPTURE_SHORTCUT)
+        );
+        assert_eq!(
+            shortcut_for(HotkeyAction::DelayedCapture).as_deref(),
+            Some(hotkeys::DEFAULT_DELAYED_CAPTURE_SHORTCUT)
+        );
+        assert_eq!(settings.shortcuts.bindings.len(), hotkeys::default_shortcut_table().len());
+        cleanup_config_path(&path);
+    }
+
     #[test]
     fn settings_action_updates_general_and_output_settings() {
         let mut store = test_store();
@@ -441,10 +838,150 @@ mod tests {
 
         store.apply(SettingsAction::FontFamily(String::new()));
         store.apply(SettingsAction::SavePath(String::new()));
+        store.apply(SettingsAction::FilenameTemplate("  ".to_string()));
 
         let settings = store.get();
         assert_eq!(settings.general.font_family, None);
         assert_eq!(settings.output.save_path, None);
+        assert_eq!(settings.output.filename_template, DEFAULT_FILENAME_TEMPLATE);
+        cleanup_store(store);
+    }
+
+    #[test]
+    fn capture_scope_round_trips_through_config() {
+        let path = test_config_path("capture-scope");
+        ensure_parent_dir(&path).expect("create config test directory");
+        std::fs::write(&path, "[general]\ncapture_scope = \"virtual-desktop\"\n").expect("write scope config");
+
+        let settings = SettingsStore::load_config_from(&path);
+
+        assert_eq!(settings.general.capture_scope, CaptureScope::VirtualDesktop);
+        assert_eq!(
+            CaptureScope::parse(CaptureScope::VirtualDesktop.as_str()),
+            Some(CaptureScope::VirtualDesktop)
+        );
+        assert_eq!(AppSettings::default().general.capture_scope, CaptureScope::CursorMonitor);
+        cleanup_config_path(&path);
+    }
+
+    #[test]
+    fn output_format_and_quality_round_trip_through_config() {
+        let path = test_config_path("output-format");
+        ensure_parent_dir(&path).expect("create config test directory");
+        std::fs::write(&path, "[output]\nformat = \"webp\"\njpeg_quality = 75\n").expect("write output config");
+
+        let settings = SettingsStore::load_config_from(&path);
+
+        assert_eq!(settings.output.format, OutputFormat::Webp);
+        assert_eq!(settings.output.jpeg_quality, 75);
+        assert!(settings.output.oxipng_enabled);
+        assert_eq!(OutputFormat::parse("jpg"), Some(OutputFormat::Jpeg));
+        assert_eq!(OutputFormat::Jpeg.extension(), "jpg");
+        cleanup_config_path(&path);
+    }
+
+    #[test]
+    fn jpeg_quality_action_clamps_to_valid_range() {
+        let mut store = test_store();
+
+        store.apply(SettingsAction::OutputFormat(OutputFormat::Jpeg));
+        store.apply(SettingsAction::JpegQuality(0));
+
+        let settings = store.get();
+        assert_eq!(settings.output.format, OutputFormat::Jpeg);
+        assert_eq!(settings.output.jpeg_quality, MIN_JPEG_QUALITY);
+        cleanup_store(store);
+    }
+
+    #[test]
+    fn history_max_entries_keeps_at_least_one_capture() {
+        let mut store = test_store();
+
+        store.apply(SettingsAction::HistoryMaxEntries(0));
+        assert_eq!(store.get().history.max_entries, 1);
+
+        store.apply(SettingsAction::HistoryMaxEntries(10_000));
+        assert_eq!(store.get().history.max_entries, MAX_HISTORY_MAX_ENTRIES);
+        cleanup_store(store);
+    }
+
+    #[test]
+    fn capture_delay_action_clamps_and_mode_round_trips() {
+        let mut store = test_store();
+
+        store.apply(SettingsAction::CaptureDelay(0));
+        assert_eq!(store.get().general.capture_delay_seconds, 1);
+
+        store.apply(SettingsAction::CaptureDelay(600));
+        store.apply(SettingsAction::DelayedCaptureMode(DelayedCaptureMode::QuickCapture));
+        let settings = store.get();
+        assert_eq!(settings.general.capture_delay_seconds, MAX_CAPTURE_DELAY_SECONDS);
+        assert_eq!(settings.general.delayed_capture_mode, DelayedCaptureMode::QuickCapture);
+        assert_eq!(
+            DelayedCaptureMode::parse(DelayedCaptureMode::QuickCapture.as_str()),
+            Some(DelayedCaptureMode::QuickCapture)
+        );
+        cleanup_store(store);
+    }
+
+    #[test]
+    fn quick_capture_destination_defaults_to_clipboard_and_round_trips() {
+        let mut store = test_store();
+        assert_eq!(store.get().general.quick_capture_destination, QuickCaptureDestination::Clipboard);
+
+        store.apply(SettingsAction::QuickCaptureDestination(QuickCaptureDestination::Pin));
+        assert_eq!(store.get().general.quick_capture_destination, QuickCaptureDestination::Pin);
+        for destination in [
+            QuickCaptureDestination::Clipboard,
+            QuickCaptureDestination::File,
+            QuickCaptureDestination::Pin,
+        ] {
+            assert_eq!(QuickCaptureDestination::parse(destination.as_str()), Some(destination));
+        }
+        cleanup_store(store);
+    }
+
+    #[test]
+    fn recording_settings_default_to_short_gifs_and_clamp_their_limits() {
+        let mut store = test_store();
+        let general = store.get().general;
+        assert_eq!(general.recording_format, RecordingFormat::Gif);
+        assert_eq!(general.recording_frame_rate, DEFAULT_RECORDING_FRAME_RATE);
+
+        store.apply(SettingsAction::RecordingFormat(RecordingFormat::Apng));
+        store.apply(SettingsAction::RecordingFrameRate(120));
+        store.apply(SettingsAction::RecordingMaxSeconds(0));
+        let general = store.get().general;
+        assert_eq!(general.recording_format, RecordingFormat::Apng);
+        assert_eq!(general.recording_frame_rate, MAX_RECORDING_FRAME_RATE);
+        assert_eq!(general.recording_max_seconds, 1);
+        for format in [RecordingFormat::Gif, RecordingFormat::Apng] {
+            assert_eq!(RecordingFormat::parse(format.as_str()), Some(format));
+        }
+        cleanup_store(store);
+    }
+
+    #[test]
+    fn long_capture_profiles_load_by_app_and_empty_tunings_remove_them() {
+        let path = test_config_path("long-capture-profiles");
+        ensure_parent_dir(&path).expect("create config test directory");
+        std::fs::write(&path, "[general.long_capture_profiles.\"Web Reader\"]\nlow_confidence_threshold = 0.4\n").expect("write profile config");
+
+        let settings = SettingsStore::load_config_from(&path);
+        let tuning = settings.general.long_capture_profiles.get("Web Reader").copied().expect("profile");
+        assert_eq!(tuning.low_confidence_threshold, Some(0.4));
+        assert_eq!(tuning.low_confidence_gap, None);
+        cleanup_config_path(&path);
+
+        let mut store = test_store();
+        let app = "Web Reader".to_string();
+        store.apply(SettingsAction::LongCaptureProfile { app: app.clone(), tuning });
+        assert_eq!(store.get().general.long_capture_profiles.get(&app), Some(&tuning));
+        store.apply(SettingsAction::LongCaptureProfile {
+            app: app.clone(),
+            tuning: StitchTuning::default(),
+        });
+        assert!(store.get().general.long_capture_profiles.is_empty());
         cleanup_store(store);
     }
 
@@ -452,7 +989,18 @@ mod tests {
     fn default_shortcuts_stay_aligned_with_hotkeys_constants() {
         let settings = ShortcutSettings::default();
 
-        assert_eq!(settings.capture, hotkeys::DEFAULT_CAPTURE_SHORTCUT);
-        assert_eq!(settings.quick_capture, hotkeys::DEFAULT_QUICK_CAPTURE_SHORTCUT);
+        assert_eq!(
+            settings.bindings[0],
+            ShortcutBinding::new(hotkeys::DEFAULT_CAPTURE_SHORTCUT, HotkeyAction::Capture)
+        );
+        for binding in &settings.bindings {
+            assert_eq!(binding.action.default_shortcut(), Some(binding.shortcut.as_str()));
+        }
+        assert!(
+            HotkeyAction::ALL
+                .into_iter()
+                .filter(|action| action.default_shortcut().is_some())
+                .all(|action| settings.bindings.iter().any(|binding| binding.action == action))
+        );
     }
 }
@@ -0,0 +1,38 @@
+mod state;
+mod view;
+
+use crate::platform::shell::{self, PopupWindowSpec};
+use crate::ui::support::appearance;
+use gpui::{App, AppContext, Bounds, WindowBounds, WindowKind, WindowOptions, px, size};
+use gpui_component::Root;
+use view::HistoryView;
+
+fn window_options(cx: &App) -> WindowOptions {
+    let bounds = Bounds::centered(None, size(px(720.0), px(520.0)), cx);
+
+    shell::popup_window_options(PopupWindowSpec {
+        window_bounds: Some(WindowBounds::Windowed(bounds)),
+        kind: WindowKind::PopUp,
+        focus: true,
+        show: true,
+        is_movable: true,
+        is_resizable: true,
+        is_minimizable: false,
+        display_id: cx.displays().first().map(|display| display.id()),
+        window_min_size: Some(size(px(560.0), px(380.0))),
+    })
+}
+
+pub fn open_window(cx: &mut App) {
+    let options = window_options(cx);
+
+    if let Err(err) = cx.open_window(options, |window, cx| {
+        appearance::apply_saved_preferences(Some(window), cx);
+        shell::configure_window(window, cx, true);
+        let focus_handle = cx.focus_handle();
+        let view = cx.new(move |_| HistoryView::new(focus_handle));
+        cx.new(move |cx| Root::new(view, window, cx))
+    }) {
+        tracing::error!("Failed to open history window: {err}");
+    }
+}
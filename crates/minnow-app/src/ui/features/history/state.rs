@@ -0,0 +1,116 @@
+use crate::platform::shell;
+use crate::services::capture::filename::local_date_time;
+use crate::services::history::{self, HistoryEntry};
+use crate::services::{i18n, settings};
+use gpui::SharedString;
+use std::path::PathBuf;
+
+const OCR_PREVIEW_CHARS: usize = 80;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct HistoryRowProps {
+    pub(super) id: SharedString,
+    pub(super) title: SharedString,
+    pub(super) detail: SharedString,
+    pub(super) ocr_preview: Option<SharedString>,
+    pub(super) capture_path: PathBuf,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct HistoryFrame {
+    pub(super) notice: Option<SharedString>,
+    pub(super) rows: Vec<HistoryRowProps>,
+}
+
+pub(super) fn build() -> HistoryFrame {
+    let store = history::store();
+    let utc_offset_seconds = shell::local_utc_offset_seconds();
+    let rows = store
+        .entries()
+        .into_iter()
+        .filter_map(|entry| {
+            let capture_path = store.capture_path(&entry.id)?;
+            Some(row_props(&entry, capture_path, utc_offset_seconds))
+        })
+        .collect();
+
+    HistoryFrame {
+        notice: (!settings::history_settings().enabled).then(|| i18n::history::disabled().into()),
+        rows,
+    }
+}
+
+fn row_props(entry: &HistoryEntry, capture_path: PathBuf, utc_offset_seconds: i32) -> HistoryRowProps {
+    HistoryRowProps {
+        id: entry.id.clone().into(),
+        title: entry_title(entry.created_at_millis, utc_offset_seconds).into(),
+        detail: entry_detail(entry).into(),
+        ocr_preview: entry.ocr_text.as_deref().and_then(ocr_preview).map(SharedString::from),
+        capture_path,
+    }
+}
+
+fn entry_title(created_at_millis: u64, utc_offset_seconds: i32) -> String {
+    let (date, time) = local_date_time(u128::from(created_at_millis), utc_offset_seconds);
+    format!("{date} {}", time.replace('-', ":"))
+}
+
+fn entry_detail(entry: &HistoryEntry) -> String {
+    let size = format!("{} × {}", entry.width, entry.height);
+    match entry.app_name.as_deref().or(entry.window_title.as_deref()) {
+        Some(source) if !source.trim().is_empty() => format!("{size} · {}", source.trim()),
+        _ => size,
+    }
+}
+
+/// First non-blank line of the recognized text, shortened for a list row.
+fn ocr_preview(text: &str) -> Option<String> {
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+    if line.chars().count() <= OCR_PREVIEW_CHARS {
+        return Some(line.to_string());
+    }
+    let mut preview: String = line.chars().take(OCR_PREVIEW_CHARS).collect();
+    preview.push('…');
+    Some(preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(app_name: Option<&str>, window_title: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            id: "1-000".to_string(),
+            created_at_millis: 0,
+            width: 640,
+            height: 480,
+            source_bounds: None,
+            app_name: app_name.map(str::to_string),
+            window_title: window_title.map(str::to_string),
+            ocr_text: None,
+            annotated: false,
+        }
+    }
+
+    #[test]
+    fn entry_title_uses_local_time_with_colons() {
+        assert_eq!(entry_title(1_700_000_000_000, 0), "2023-11-14 22:13:20");
+        assert_eq!(entry_title(1_700_000_000_000, 8 * 3600), "2023-11-15 06:13:20");
+    }
+
+    #[test]
+    fn entry_detail_prefers_app_name_and_falls_back_to_size() {
+        assert_eq!(entry_detail(&entry(Some("Firefox"), Some("Docs"))), "640 × 480 · Firefox");
+        assert_eq!(entry_detail(&entry(None, Some("Docs"))), "640 × 480 · Docs");
+        assert_eq!(entry_detail(&entry(None, None)), "640 × 480");
+    }
+
+    #[test]
+    fn ocr_preview_takes_first_non_blank_line() {
+        assert_eq!(ocr_preview("\n  hello world \nsecond").as_deref(), Some("hello world"));
+        assert_eq!(ocr_preview(" \n "), None);
+
+        let long = "x".repeat(OCR_PREVIEW_CHARS + 5);
+        assert_eq!(ocr_preview(&long).map(|preview| preview.chars().count()), Some(OCR_PREVIEW_CHARS + 1));
+    }
+}
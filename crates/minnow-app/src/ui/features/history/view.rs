@@ -0,0 +1,305 @@
+use super::state::{self, HistoryFrame, HistoryRowProps};
+use crate::app::workflows;
+use crate::platform::shell::{self, PopupDragBehavior, PopupDragRegionExt};
+use crate::services::assets::asset_paths;
+use crate::services::capture::action::{ActionContext, CaptureAction, PinCaptureRequest};
+use crate::services::{history, i18n};
+use crate::ui::features::overlay;
+use crate::ui::features::pin::{self, PinRequest};
+use crate::ui::support::capture_actions::{self, CaptureActionHost, CaptureActionHostKind};
+use gpui::{
+    AnyElement, App, ClickEvent, Context, FocusHandle, InteractiveElement, IntoElement, ObjectFit, ParentElement, Render, SharedString, Styled,
+    StyledImage, Window, div, img, px,
+};
+use gpui_component::{
+    ActiveTheme as _, Icon, IconNamed, Sizable, Size,
+    button::{Button, ButtonVariants},
+    h_flex,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use std::borrow::BorrowMut;
+
+const TITLEBAR_HEIGHT: f32 = 48.0;
+const THUMBNAIL_WIDTH: f32 = 160.0;
+const THUMBNAIL_HEIGHT: f32 = 96.0;
+
+enum HistoryIcon {
+    Close,
+}
+
+impl IconNamed for HistoryIcon {
+    fn path(self) -> SharedString {
+        match self {
+            Self::Close => asset_paths::icons::CLOSE.into(),
+        }
+    }
+}
+
+pub(super) struct HistoryView {
+    focus_handle: FocusHandle,
+    frame: HistoryFrame,
+}
+
+impl HistoryView {
+    pub(super) fn new(focus_handle: FocusHandle) -> Self {
+        Self {
+            focus_handle,
+            frame: state::build(),
+        }
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        self.frame = state::build();
+        cx.notify();
+    }
+
+    fn request_close(window: &mut Window, cx: &mut App) {
+        window.defer(cx, |window, _| {
+            window.remove_window();
+        });
+    }
+
+    fn execute_capture_action(&mut self, action: CaptureAction, id: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let store = history::store();
+        let (Some(entry), Some(path)) = (store.entry(id), store.capture_path(id)) else {
+            self.reload(cx);
+            return;
+        };
+
+        let context = ActionContext::full_image(path.to_string_lossy().into_owned()).with_origin(entry.origin());
+        let result = workflows::execute_capture_action(action, context);
+        let effect = capture_actions::interpret(action, result, CaptureActionHostKind::History);
+        capture_actions::apply_host_effect(self, effect, window, BorrowMut::borrow_mut(cx));
+    }
+
+    fn annotate(&mut self, id: &str, window: &mut Window, cx: &mut Context<Self>) {
+        if overlay::open_history_capture(cx, id) {
+            Self::request_close(window, cx);
+        } else {
+            tracing::warn!("{}: {id}", i18n::history::reopen_failed());
+            self.reload(cx);
+        }
+    }
+
+    fn copy_text(&mut self, id: &str) {
+        let Some(text) = history::store().entry(id).and_then(|entry| entry.ocr_text) else {
+            return;
+        };
+        if shell::copy_text_to_clipboard(text) {
+            shell::show_notification(&i18n::app::capture_name(), &i18n::notify::copied_text(), shell::NotificationType::Copy);
+        }
+    }
+
+    fn delete(&mut self, id: &str, cx: &mut Context<Self>) {
+        if let Err(err) = history::store().remove(id) {
+            tracing::error!("Failed to delete history entry {id}: {err}");
+        }
+        self.reload(cx);
+    }
+
+    fn action_button(&self, action: &str, label: impl Into<SharedString>, row: &HistoryRowProps) -> Button {
+        Button::new(SharedString::from(format!("history-{action}-{}", row.id)))
+            .label(label.into())
+            .outline()
+            .with_size(Size::Small)
+    }
+
+    fn render_row(&self, row: &HistoryRowProps, cx: &mut Context<Self>) -> AnyElement {
+        let id = row.id.clone();
+
+        let mut listener = |action: CaptureAction| {
+            let id = id.clone();
+            cx.listener(move |this: &mut Self, _: &ClickEvent, window, cx| this.execute_capture_action(action, &id, window, cx))
+        };
+        let copy = listener(CaptureAction::Copy);
+        let save = listener(CaptureAction::Save);
+        let pin = listener(CaptureAction::Pin);
+        let annotate = {
+            let id = id.clone();
+            cx.listener(move |this: &mut Self, _: &ClickEvent, window, cx| this.annotate(&id, window, cx))
+        };
+        let copy_text = {
+            let id = id.clone();
+            cx.listener(move |this: &mut Self, _: &ClickEvent, _, _| this.copy_text(&id))
+        };
+        let delete = {
+            let id = id.clone();
+            cx.listener(move |this: &mut Self, _: &ClickEvent, _, cx| this.delete(&id, cx))
+        };
+
+        let mut actions = h_flex()
+            .flex_wrap()
+            .gap_2()
+            .child(self.action_button("copy", i18n::common::copy(), row).on_click(copy))
+            .child(self.action_button("save", i18n::common::save(), row).on_click(save))
+            .child(self.action_button("pin", i18n::common::pin(), row).on_click(pin))
+            .child(self.action_button("annotate", i18n::history::annotate(), row).on_click(annotate));
+        if row.ocr_preview.is_some() {
+            actions = actions.child(self.action_button("copy-text", i18n::history::copy_text(), row).on_click(copy_text));
+        }
+        actions = actions.child(self.action_button("delete", i18n::history::delete(), row).on_click(delete));
+
+        let theme = cx.theme();
+        let mut details = v_flex()
+            .flex_1()
+            .min_w(px(0.))
+            .gap_1()
+            .child(div().text_sm().child(row.title.clone()))
+            .child(div().text_xs().text_color(theme.muted_foreground).child(row.detail.clone()));
+        if let Some(preview) = row.ocr_preview.clone() {
+            details = details.child(
+                div()
+                    .text_xs()
+                    .text_color(theme.muted_foreground)
+                    .overflow_hidden()
+                    .line_clamp(1)
+                    .text_ellipsis()
+                    .child(preview),
+            );
+        }
+
+        h_flex()
+            .w_full()
+            .min_w(px(0.))
+            .gap_4()
+            .p_3()
+            .rounded(theme.radius_lg)
+            .border_1()
+            .border_color(theme.border)
+            .bg(theme.background)
+            .child(
+                div()
+                    .w(px(THUMBNAIL_WIDTH))
+                    .h(px(THUMBNAIL_HEIGHT))
+                    .flex_none()
+                    .rounded(theme.radius)
+                    .overflow_hidden()
+                    .bg(theme.muted)
+                    .child(img(row.capture_path.clone()).size_full().object_fit(ObjectFit::Contain)),
+            )
+            .child(details.child(actions))
+            .into_any_element()
+    }
+
+    fn render_title_bar(&self, cx: &App) -> AnyElement {
+        let theme = cx.theme();
+
+        h_flex()
+            .h(px(TITLEBAR_HEIGHT))
+            .flex_none()
+            .items_center()
+            .gap_3()
+            .px_4()
+            .border_b_1()
+            .border_color(theme.border.alpha(0.7))
+            .child(
+                div()
+                    .flex_1()
+                    .min_w(px(0.))
+                    .h_full()
+                    .flex()
+                    .items_center()
+                    .popup_drag_region(PopupDragBehavior::SystemMove)
+                    .child(i18n::history::title()),
+            )
+            .child(
+                Button::new("history-close")
+                    .ghost()
+                    .compact()
+                    .icon(Icon::new(HistoryIcon::Close).small().text_color(theme.muted_foreground))
+                    .tooltip(i18n::common::close())
+                    .on_click(|_, window, cx| Self::request_close(window, cx)),
+            )
+            .into_any_element()
+    }
+
+    fn render_body(&self, cx: &mut Context<Self>) -> AnyElement {
+        let notice = self.frame.notice.clone().map(|notice| {
+            let theme = cx.theme();
+            div()
+                .w_full()
+                .px_3()
+                .py_2()
+                .rounded(theme.radius)
+                .border_1()
+                .border_color(theme.primary.alpha(0.35))
+                .bg(theme.primary.alpha(0.1))
+                .text_sm()
+                .child(notice)
+        });
+
+        let list = if self.frame.rows.is_empty() {
+            div()
+                .w_full()
+                .py_8()
+                .flex()
+                .justify_center()
+                .text_sm()
+                .text_color(cx.theme().muted_foreground)
+                .child(i18n::history::empty())
+                .into_any_element()
+        } else {
+            let rows: Vec<_> = self.frame.rows.iter().map(|row| self.render_row(row, cx)).collect();
+            v_flex().w_full().gap_3().children(rows).into_any_element()
+        };
+
+        div()
+            .flex_1()
+            .min_w(px(0.))
+            .min_h(px(0.))
+            .overflow_y_scrollbar()
+            .child(v_flex().w_full().px_5().py_4().gap_4().children(notice).child(list))
+            .into_any_element()
+    }
+}
+
+impl CaptureActionHost for HistoryView {
+    fn close_capture(&self, _window: &mut Window, _cx: &mut App) {}
+
+    fn refresh_capture(&self, window: &mut Window, _cx: &mut App) {
+        window.refresh();
+    }
+
+    fn open_pin(&self, request: PinCaptureRequest, cx: &mut App) {
+        let request = PinRequest::from_capture(request);
+        cx.defer(move |cx| {
+            pin::open_window(cx, request);
+        });
+    }
+
+    fn show_warning(&self, message: String, _window: &mut Window, _cx: &mut App) {
+        tracing::warn!("History capture action warning: {message}");
+    }
+}
+
+impl Render for HistoryView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let title_bar = self.render_title_bar(cx);
+        let body = self.render_body(cx);
+        let theme = cx.theme();
+
+        let mut panel = div()
+            .id("history-view")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(theme.transparent)
+            .child(
+                v_flex()
+                    .size_full()
+                    .rounded(theme.radius_lg)
+                    .border_1()
+                    .border_color(theme.border)
+                    .bg(theme.popover)
+                    .overflow_hidden()
+                    .child(title_bar)
+                    .child(body),
+            );
+
+        if theme.shadow {
+            panel = panel.shadow_lg();
+        }
+
+        panel
+    }
+}
@@ -1,28 +1,83 @@
 use super::LongCaptureRequest;
-use super::layout::frame_visibility_after_click_through;
-use crate::services::capture::long_capture::{LongCaptureEvent, LongCaptureRuntime};
+use super::layout::stitch_mask_from_bands;
+use crate::platform::shell;
+use crate::services::capture::filename::CaptureOrigin;
+use crate::services::capture::long_capture::{LongCaptureEvent, LongCaptureRuntime, StitchMask, StitchSummary, StitchedCapture};
+use crate::services::capture::monitor::MonitorLayout;
+use crate::services::capture::screen_recording::Animation;
+use crate::services::geometry::RectF;
+use crate::services::settings::{self, SettingsAction};
+use crate::ui::features::overlay::window_catalog;
 use crate::ui::support::render_image;
-use gpui::{AnyWindowHandle, AppContext, AsyncWindowContext, Context, RenderImage, WeakEntity, Window, WindowId};
-use image::RgbaImage;
+use crate::ui::support::session_windows::{self, SessionWindowKind, SessionWindows, WindowSession, frame_visibility_after_click_through};
+use gpui::{AnyWindowHandle, AppContext, AsyncWindowContext, Context, RenderImage, Window, WindowId};
+use std::io;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::Duration;
 
-const COORDINATOR_POLL_INTERVAL: Duration = Duration::from_millis(16);
-
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub(crate) enum LongCaptureWindowKind {
-    Frame,
-    Toolbar,
-    Preview,
-}
+/// How long entering mask mode waits for the running capture to wind down.
+const MASK_STOP_TIMEOUT: Duration = Duration::from_millis(260);
+/// Rows a single seam nudge moves a segment by.
+const SEAM_NUDGE_STEP: i32 = 1;
+/// Extent across the scroll axis that review images are scaled down to, like
+/// the progress previews.
+const REVIEW_PREVIEW_EXTENT: u32 = 500;
 
 #[derive(Clone)]
 pub(crate) struct LongCaptureSnapshot {
     pub(crate) preview_image: Option<Arc<RenderImage>>,
     pub(crate) preview_height_px: i32,
+    /// Bytes of the running capture held in memory.
+    pub(crate) memory_bytes: u64,
     pub(crate) warning_text: String,
     pub(crate) busy: bool,
     pub(crate) frame_visible: bool,
+    pub(crate) auto_scrolling: bool,
+    /// The capture is paused while the user marks bands on the frame.
+    pub(crate) masking: bool,
+    /// Marked bands, in selection points.
+    pub(crate) mask_bands: Vec<RectF>,
+    /// The stopped capture being reviewed before copy, save or pin.
+    pub(crate) review: Option<LongCaptureReview>,
+    /// How well frames have matched, once any were stitched.
+    pub(crate) diagnostics: Option<StitchSummary>,
+    pub(crate) diagnostics_visible: bool,
+}
+
+#[derive(Clone)]
+pub(crate) struct LongCaptureReview {
+    /// Scaled-down view of the capture.
+    pub(crate) image: Arc<RenderImage>,
+    /// Size of the full capture, which all offsets below refer to.
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) horizontal: bool,
+    /// Where each segment starts and ends along the scroll axis, in image
+    /// pixels; trimmed segments reach past the image.
+    pub(crate) segments: Vec<(i64, i64)>,
+    pub(crate) selected: usize,
+    /// Trim position picked on the image, along the scroll axis.
+    pub(crate) marker: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ReviewEdit {
+    RemoveSegment,
+    /// Shifts the selected segment against the one before it, toward the
+    /// start of the capture when `back`.
+    ShiftSegment {
+        back: bool,
+    },
+    TrimBeforeMarker,
+    TrimAfterMarker,
+    Reset,
+}
+
+struct ReviewSession {
+    original: StitchedCapture,
+    selected: usize,
+    marker: Option<u32>,
 }
 
 impl Default for LongCaptureSnapshot {
@@ -30,27 +85,31 @@ impl Default for LongCaptureSnapshot {
         Self {
             preview_image: None,
             preview_height_px: 0,
+            memory_bytes: 0,
             warning_text: String::new(),
             busy: false,
             frame_visible: true,
+            auto_scrolling: false,
+            masking: false,
+            mask_bands: Vec::new(),
+            review: None,
+            diagnostics: None,
+            diagnostics_visible: false,
         }
     }
 }
 
-#[derive(Clone, Default)]
-struct LongCaptureWindowHandles {
-    frame: Option<AnyWindowHandle>,
-    toolbar: Option<AnyWindowHandle>,
-    preview: Option<AnyWindowHandle>,
-}
-
 #[derive(Default)]
 struct LongCaptureCoordinatorState {
     snapshot: LongCaptureSnapshot,
-    capture_image: Option<RgbaImage>,
-    handles: LongCaptureWindowHandles,
+    capture: Option<StitchedCapture>,
+    review: Option<ReviewSession>,
+    windows: SessionWindows,
+    /// How far the tracked window has moved the capture rect, and how far the
+    /// capture's own windows have followed it, in logical pixels.
+    target_offset: (i32, i32),
+    windows_offset: (i32, i32),
     revision: u64,
-    poller_running: bool,
 }
 
 impl LongCaptureCoordinatorState {
@@ -58,17 +117,22 @@ impl LongCaptureCoordinatorState {
         self.revision = self.revision.saturating_add(1);
     }
 
-    fn apply_runtime_events(&mut self, events: Vec<LongCaptureEvent>, final_image: Option<RgbaImage>) {
+    fn apply_runtime_events(&mut self, events: Vec<LongCaptureEvent>, final_capture: Option<StitchedCapture>) {
         let mut changed = false;
-        let mut final_image = final_image;
+        let mut final_capture = final_capture;
 
         for event in events {
             match event {
                 LongCaptureEvent::Started => {
                     changed = true;
                 }
-                LongCaptureEvent::Progress { height, preview_image } => {
+                LongCaptureEvent::Progress {
+                    height,
+                    preview_image,
+                    memory_bytes,
+                } => {
                     self.snapshot.preview_height_px = height;
+                    self.snapshot.memory_bytes = memory_bytes;
                     self.snapshot.preview_image = Some(render_image::from_rgba(preview_image));
                     changed = true;
                 }
@@ -76,11 +140,24 @@ impl LongCaptureCoordinatorState {
                     self.snapshot.warning_text = text;
                     changed = true;
                 }
+                LongCaptureEvent::AutoScroll { active } => {
+                    self.snapshot.auto_scrolling = active;
+                    changed = true;
+                }
+                LongCaptureEvent::TargetMoved { dx, dy } => {
+                    self.target_offset = (dx, dy);
+                    changed = true;
+                }
+                LongCaptureEvent::Diagnostics { summary } => {
+                    changed |= self.snapshot.diagnostics != Some(summary);
+                    self.snapshot.diagnostics = Some(summary);
+                }
                 LongCaptureEvent::Finished => {
-                    if let Some(image) = final_image.take() {
-                        self.capture_image = Some(image);
+                    if let Some(capture) = final_capture.take() {
+                        self.capture = Some(capture);
                     }
                     self.snapshot.busy = false;
+                    self.snapshot.auto_scrolling = false;
                     changed = true;
                 }
             }
@@ -95,28 +172,16 @@ impl LongCaptureCoordinatorState {
         self.snapshot.clone()
     }
 
-    fn has_registered_windows(&self) -> bool {
-        self.handles.frame.is_some() || self.handles.toolbar.is_some() || self.handles.preview.is_some()
+    /// How far the capture's windows still have to move to follow the
+    /// tracked window; they count as moved from here on.
+    fn take_window_shift(&mut self) -> Option<(i32, i32)> {
+        let shift = (self.target_offset.0 - self.windows_offset.0, self.target_offset.1 - self.windows_offset.1);
+        self.windows_offset = self.target_offset;
+        (shift != (0, 0)).then_some(shift)
     }
 
-    fn clear_dead_windows(&mut self, frame_alive: bool, toolbar_alive: bool, preview_alive: bool) {
-        if !frame_alive {
-            self.handles.frame = None;
-        }
-        if !toolbar_alive {
-            self.handles.toolbar = None;
-        }
-        if !preview_alive {
-            self.handles.preview = None;
-        }
-    }
-
-    fn register_window(&mut self, kind: LongCaptureWindowKind, handle: AnyWindowHandle) {
-        match kind {
-            LongCaptureWindowKind::Frame => self.handles.frame = Some(handle),
-            LongCaptureWindowKind::Toolbar => self.handles.toolbar = Some(handle),
-            LongCaptureWindowKind::Preview => self.handles.preview = Some(handle),
-        }
+    fn register_window(&mut self, kind: SessionWindowKind, handle: AnyWindowHandle) {
+        self.windows.register(kind, handle);
         self.bump_revision();
     }
 
@@ -137,216 +202,397 @@ impl LongCaptureCoordinatorState {
         self.bump_revision();
     }
 
-    fn take_capture_image(&mut self) -> Option<RgbaImage> {
-        let image = self.capture_image.take();
-        if image.is_some() {
-            self.bump_revision();
+    /// Drops the progress of the paused capture; it restarts with the mask
+    /// once marking is done.
+    fn begin_masking(&mut self) {
+        self.snapshot.masking = true;
+        self.snapshot.auto_scrolling = false;
+        self.snapshot.preview_image = None;
+        self.snapshot.preview_height_px = 0;
+        self.snapshot.memory_bytes = 0;
+        self.snapshot.diagnostics = None;
+        self.snapshot.warning_text.clear();
+        self.capture = None;
+        self.bump_revision();
+    }
+
+    fn toggle_diagnostics(&mut self) {
+        self.snapshot.diagnostics_visible = !self.snapshot.diagnostics_visible;
+        self.bump_revision();
+    }
+
+    fn finish_masking(&mut self) {
+        self.snapshot.masking = false;
+        self.bump_revision();
+    }
+
+    fn add_mask_band(&mut self, band: RectF) {
+        self.snapshot.mask_bands.push(band);
+        self.bump_revision();
+    }
+
+    fn remove_mask_band_at(&mut self, x: f64, y: f64) -> bool {
+        let Some(index) = self.snapshot.mask_bands.iter().rposition(|band| band.contains_point(x, y)) else {
+            return false;
+        };
+        self.snapshot.mask_bands.remove(index);
+        self.bump_revision();
+        true
+    }
+
+    /// Starts reviewing the finished capture, or `capture` when the runtime
+    /// had not handed it over yet.
+    fn begin_review(&mut self, capture: Option<StitchedCapture>) -> bool {
+        if capture.is_some() {
+            self.capture = capture;
         }
-        image
+        let Some(original) = self.capture.clone() else {
+            return false;
+        };
+        self.review = Some(ReviewSession {
+            original,
+            selected: 0,
+            marker: None,
+        });
+        self.snapshot.auto_scrolling = false;
+        self.refresh_review(true);
+        true
     }
 
-    fn start_poller(&mut self) -> bool {
-        if self.poller_running {
+    /// Selects the segment under `offset` and puts the trim marker there.
+    fn pick_review_point(&mut self, offset: u32) {
+        let (Some(capture), Some(review)) = (self.capture.as_ref(), self.review.as_mut()) else {
+            return;
+        };
+        review.selected = capture.segment_at(offset);
+        review.marker = Some(offset.min(capture.length().saturating_sub(1)));
+        self.refresh_review(false);
+    }
+
+    fn edit_review(&mut self, edit: ReviewEdit) -> bool {
+        let (Some(capture), Some(review)) = (self.capture.as_mut(), self.review.as_mut()) else {
+            return false;
+        };
+        let changed = match edit {
+            ReviewEdit::RemoveSegment => capture.drop_segment(review.selected),
+            ReviewEdit::ShiftSegment { back } => {
+                let delta = if back { SEAM_NUDGE_STEP } else { -SEAM_NUDGE_STEP };
+                capture.nudge_seam(review.selected, delta)
+            }
+            ReviewEdit::TrimBeforeMarker => review.marker.is_some_and(|marker| capture.crop(marker, capture.length())),
+            ReviewEdit::TrimAfterMarker => review.marker.is_some_and(|marker| capture.crop(0, marker + 1)),
+            ReviewEdit::Reset => {
+                *capture = review.original.clone();
+                true
+            }
+        };
+        if !changed {
             return false;
         }
-        self.poller_running = true;
+
+        if !matches!(edit, ReviewEdit::ShiftSegment { .. }) {
+            review.marker = None;
+        }
+        review.selected = review.selected.min(capture.segment_count() - 1);
+        self.refresh_review(true);
         true
     }
 
-    fn stop_poller(&mut self) {
-        self.poller_running = false;
+    /// Mirrors the review session into the snapshot, rendering the capture
+    /// again when it changed.
+    fn refresh_review(&mut self, rerender: bool) {
+        let (Some(capture), Some(review)) = (self.capture.as_ref(), self.review.as_ref()) else {
+            self.snapshot.review = None;
+            self.bump_revision();
+            return;
+        };
+
+        let rendered = if rerender || self.snapshot.review.is_none() {
+            capture.preview(REVIEW_PREVIEW_EXTENT).map(|preview| {
+                let (width, height) = capture.dimensions();
+                (width, height, render_image::from_rgba(preview))
+            })
+        } else {
+            self.snapshot
+                .review
+                .as_ref()
+                .map(|current| (current.width, current.height, current.image.clone()))
+        };
+        self.snapshot.review = rendered.map(|(width, height, image)| LongCaptureReview {
+            image,
+            width,
+            height,
+            horizontal: capture.is_horizontal(),
+            segments: capture.segment_spans(),
+            selected: review.selected,
+            marker: review.marker,
+        });
+        self.bump_revision();
     }
 
-    fn retain_windows_except(&mut self, except: Option<WindowId>) {
-        self.handles.frame = self
-            .handles
-            .frame
-            .take()
-            .filter(|handle| except.is_some_and(|id| id == handle.window_id()));
-        self.handles.toolbar = self
-            .handles
-            .toolbar
-            .take()
-            .filter(|handle| except.is_some_and(|id| id == handle.window_id()));
-        self.handles.preview = self
-            .handles
-            .preview
-            .take()
-            .filter(|handle| except.is_some_and(|id| id == handle.window_id()));
+    fn take_capture(&mut self) -> Option<StitchedCapture> {
+        let capture = self.capture.take()?;
+        self.review = None;
+        self.snapshot.review = None;
+        self.bump_revision();
+        Some(capture)
     }
 }
 
 pub(crate) struct LongCaptureCoordinator {
     runtime: LongCaptureRuntime,
+    request: LongCaptureRequest,
     state: Mutex<LongCaptureCoordinatorState>,
 }
 
 impl LongCaptureCoordinator {
     pub(crate) fn new(request: LongCaptureRequest) -> Self {
-        let runtime = LongCaptureRuntime::new();
-        runtime.start_with_viewport(
+        let coordinator = Self {
+            runtime: LongCaptureRuntime::new(),
+            request,
+            state: Mutex::new(LongCaptureCoordinatorState {
+                revision: 1,
+                ..LongCaptureCoordinatorState::default()
+            }),
+        };
+        coordinator.start_runtime(StitchMask::default());
+        coordinator
+    }
+
+    fn start_runtime(&self, mask: StitchMask) {
+        let request = &self.request;
+        self.runtime.start_with_viewport(
             request.selection_rect,
-            crate::services::geometry::RectF::new(
+            RectF::new(
                 request.viewport_rect.x,
                 request.viewport_rect.y,
                 request.viewport_rect.width,
                 request.viewport_rect.height,
             ),
             request.viewport_scale as f32,
+            mask,
+            request.origin.app_name.clone(),
         );
-
-        Self {
-            runtime,
-            state: Mutex::new(LongCaptureCoordinatorState {
-                revision: 1,
-                ..LongCaptureCoordinatorState::default()
-            }),
+        if let Some(window) = request.window {
+            self.runtime
+                .track_window(window.bounds, Box::new(move || window_catalog::locate_window(window.id)));
         }
     }
 
-    fn revision(&self) -> u64 {
-        self.state_guard().revision
+    fn state_guard(&self) -> MutexGuard<'_, LongCaptureCoordinatorState> {
+        session_windows::lock_state(&self.state, "Long-capture")
     }
 
-    fn state_guard(&self) -> MutexGuard<'_, LongCaptureCoordinatorState> {
-        match self.state.lock() {
-            Ok(guard) => guard,
-            Err(poisoned) => {
-                tracing::error!("Long-capture coordinator state lock was poisoned; recovering state");
-                let guard = poisoned.into_inner();
-                self.state.clear_poison();
-                guard
+    pub(crate) fn snapshot(&self) -> LongCaptureSnapshot {
+        self.state_guard().snapshot()
+    }
+
+    /// Moves the frame, toolbar and preview along with the tracked window.
+    fn follow_target<C: AppContext>(&self, cx: &mut C) {
+        let (windows, shift) = {
+            let mut state = self.state_guard();
+            (state.windows, state.take_window_shift())
+        };
+        let Some((dx, dy)) = shift else {
+            return;
+        };
+
+        for handle in windows.handles() {
+            if let Ok(Err(err)) = handle.update(cx, |_, window, _| shell::move_window_by(window, dx, dy)) {
+                tracing::debug!("Failed to move long-capture window with its target: {err}");
             }
         }
     }
 
-    fn poll_runtime_events(&self) -> u64 {
-        let events = self.runtime.drain_events();
-        if events.is_empty() {
-            return self.revision();
-        }
+    pub(crate) fn ensure_runtime_poller<V>(self: &Arc<Self>, window: &mut Window, cx: &mut Context<V>)
+    where
+        V: 'static,
+    {
+        session_windows::ensure_poller(self, window, cx);
+    }
 
-        let final_image = events
-            .iter()
-            .any(|event| matches!(event, LongCaptureEvent::Finished))
-            .then(|| self.runtime.take_result())
-            .flatten();
-        let mut state = self.state_guard();
-        state.apply_runtime_events(events, final_image);
-        state.revision
+    pub(crate) fn register_window(&self, kind: SessionWindowKind, handle: AnyWindowHandle) {
+        self.state_guard().register_window(kind, handle);
     }
 
-    pub(crate) fn snapshot(&self) -> LongCaptureSnapshot {
-        self.state_guard().snapshot()
+    pub(crate) fn on_frame_click_through_result(&self, success: bool) {
+        self.state_guard().set_frame_visibility(success);
     }
 
-    fn has_registered_windows(&self) -> bool {
-        self.state_guard().has_registered_windows()
+    pub(crate) fn start_capture_action(&self) {
+        self.state_guard().start_capture_action();
     }
 
-    fn notify_registered_windows<C: AppContext>(&self, cx: &mut C) -> bool {
-        let handles = self.state_guard().handles.clone();
+    pub(crate) fn finish_capture_action_with_warning(&self, warning_text: String) {
+        self.state_guard().finish_capture_action_with_warning(warning_text);
+    }
 
-        let frame_alive = handles
-            .frame
-            .is_some_and(|handle| handle.update(cx, |_, window, _| window.refresh()).is_ok());
-        let toolbar_alive = handles
-            .toolbar
-            .is_some_and(|handle| handle.update(cx, |_, window, _| window.refresh()).is_ok());
-        let preview_alive = handles
-            .preview
-            .is_some_and(|handle| handle.update(cx, |_, window, _| window.refresh()).is_ok());
+    pub(crate) fn toggle_auto_scroll(&self) {
+        if self.snapshot().auto_scrolling {
+            self.runtime.stop_auto_scroll();
+        } else {
+            let offset = self.state_guard().target_offset;
+            let Some(anchor) = self.request.scroll_anchor(offset, &MonitorLayout::current()) else {
+                tracing::warn!("No monitor to send auto-scroll input to");
+                return;
+            };
+            self.runtime
+                .start_auto_scroll(Box::new(move |notches| shell::scroll_wheel_at(anchor, notches)));
+        }
+    }
 
-        self.state_guard().clear_dead_windows(frame_alive, toolbar_alive, preview_alive);
+    /// Pauses the capture so bands can be drawn on the frame, or restarts it
+    /// with the marked bands as its stitch mask.
+    pub(crate) fn toggle_masking<C: AppContext>(&self, cx: &mut C) {
+        if self.snapshot().masking {
+            self.set_frame_click_through(true, cx);
+            let mask = {
+                let mut state = self.state_guard();
+                state.finish_masking();
+                let selection = self.request.selection_rectf();
+                stitch_mask_from_bands(selection.width, selection.height, &state.snapshot.mask_bands)
+            };
+            self.start_runtime(mask);
+        } else if self.set_frame_click_through(false, cx) {
+            let _ = self.runtime.stop_and_take_result(MASK_STOP_TIMEOUT);
+            self.state_guard().begin_masking();
+        }
+    }
 
-        frame_alive || toolbar_alive || preview_alive
+    /// Stops the capture and opens its seams for review. Copy, save and pin
+    /// then use the edited capture.
+    pub(crate) fn begin_review(&self, timeout: Duration) -> bool {
+        let capture = if self.state_guard().capture.is_some() {
+            None
+        } else {
+            self.runtime.stop_and_take_result(timeout)
+        };
+        self.state_guard().begin_review(capture)
     }
 
-    fn mark_poller_stopped(&self) {
-        self.state_guard().stop_poller();
+    pub(crate) fn pick_review_point(&self, offset: u32) {
+        self.state_guard().pick_review_point(offset);
     }
 
-    pub(crate) fn ensure_runtime_poller<V>(self: &Arc<Self>, window: &mut Window, cx: &mut Context<V>)
-    where
-        V: 'static,
-    {
-        let should_spawn = self.state_guard().start_poller();
+    pub(crate) fn edit_review(&self, edit: ReviewEdit) -> bool {
+        self.state_guard().edit_review(edit)
+    }
 
-        if !should_spawn {
-            return;
-        }
+    pub(crate) fn add_mask_band(&self, band: RectF) {
+        self.state_guard().add_mask_band(band);
+    }
 
-        let coordinator = self.clone();
-        cx.spawn_in(window, move |_this: WeakEntity<V>, cx: &mut AsyncWindowContext| {
-            let mut cx = cx.clone();
-            async move {
-                let mut revision = coordinator.revision();
-                loop {
-                    cx.background_executor().timer(COORDINATOR_POLL_INTERVAL).await;
-                    let next_revision = coordinator.poll_runtime_events();
-                    if next_revision != revision {
-                        revision = next_revision;
-                        if !coordinator.notify_registered_windows(&mut cx) {
-                            break;
-                        }
-                    } else if !coordinator.has_registered_windows() {
-                        break;
-                    }
-                }
-                coordinator.mark_poller_stopped();
+    pub(crate) fn remove_mask_band_at(&self, x: f64, y: f64) -> bool {
+        self.state_guard().remove_mask_band_at(x, y)
+    }
+
+    fn set_frame_click_through<C: AppContext>(&self, enabled: bool, cx: &mut C) -> bool {
+        let Some(frame) = self.state_guard().windows.frame() else {
+            return false;
+        };
+        match frame.update(cx, |_, window, _| shell::set_click_through(window, enabled)) {
+            Ok(Ok(())) => true,
+            Ok(Err(err)) => {
+                tracing::warn!("Failed to change long-capture frame click-through: {err}");
+                false
             }
-        })
-        .detach();
+            Err(_) => false,
+        }
     }
 
-    pub(crate) fn register_window(&self, kind: LongCaptureWindowKind, handle: AnyWindowHandle) {
-        self.state_guard().register_window(kind, handle);
+    pub(crate) fn toggle_diagnostics(&self) {
+        self.state_guard().toggle_diagnostics();
     }
 
-    pub(crate) fn on_frame_click_through_result(&self, success: bool) {
-        self.state_guard().set_frame_visibility(success);
+    /// Writes the diagnostics of the capture next to the logs.
+    pub(crate) fn export_report(&self) -> io::Result<PathBuf> {
+        self.runtime.report().export_to_logs()
     }
 
-    pub(crate) fn start_capture_action(&self) {
-        self.state_guard().start_capture_action();
+    pub(crate) fn origin(&self) -> &CaptureOrigin {
+        &self.request.origin
     }
 
-    pub(crate) fn finish_capture_action_with_warning(&self, warning_text: String) {
-        self.state_guard().finish_capture_action_with_warning(warning_text);
+    /// Application the captured window belongs to, which stitching profiles
+    /// are kept for.
+    pub(crate) fn app_name(&self) -> Option<&str> {
+        self.request.origin.app_name.as_deref()
+    }
+
+    /// Saves the tuning this capture suggests as the profile of its
+    /// application. False when the capture gave nothing to tune.
+    pub(crate) fn save_app_profile(&self) -> bool {
+        let Some(app) = self.app_name() else {
+            return false;
+        };
+        let suggested = self.runtime.report().suggested_tuning();
+        if suggested.is_empty() {
+            return false;
+        }
+        let saved = settings::general_settings().long_capture_profiles.get(app).copied().unwrap_or_default();
+        settings::apply(SettingsAction::LongCaptureProfile {
+            app: app.to_string(),
+            tuning: saved.merge(suggested),
+        });
+        true
     }
 
     pub(crate) fn cancel_capture(&self) {
         self.runtime.stop();
     }
 
-    pub(crate) fn take_capture_image(&self, timeout: Duration) -> Option<RgbaImage> {
-        if let Some(image) = self.state_guard().take_capture_image() {
-            return Some(image);
-        }
+    /// The finished capture, including any review edits, for a capture
+    /// action.
+    pub(crate) fn take_capture(&self, timeout: Duration) -> Option<StitchedCapture> {
+        let capture = self.state_guard().take_capture();
+        capture.or_else(|| self.runtime.stop_and_take_result(timeout))
+    }
 
-        self.runtime.stop_and_take_result(timeout)
+    /// The animation sampled during the session, if the setting asked for
+    /// one. It is ready once [`Self::take_capture`] has returned.
+    pub(crate) fn take_animation(&self) -> Option<Animation> {
+        self.runtime.take_animation()
     }
 
     pub(crate) fn close_windows_except<C: AppContext>(&self, except: Option<WindowId>, cx: &mut C) {
-        let handles = self.state_guard().handles.clone();
+        session_windows::close_windows_except(self, except, cx);
+    }
+}
 
-        for handle in [handles.frame, handles.toolbar, handles.preview].into_iter().flatten() {
-            if except.is_some_and(|id| id == handle.window_id()) {
-                continue;
-            }
-            let _ = handle.update(cx, |_, window, _| {
-                window.remove_window();
-            });
+impl WindowSession for LongCaptureCoordinator {
+    fn revision(&self) -> u64 {
+        self.state_guard().revision
+    }
+
+    fn poll_runtime_events(&self) -> u64 {
+        let events = self.runtime.drain_events();
+        if events.is_empty() {
+            return self.revision();
         }
 
-        self.state_guard().retain_windows_except(except);
+        let final_capture = events
+            .iter()
+            .any(|event| matches!(event, LongCaptureEvent::Finished))
+            .then(|| self.runtime.take_result())
+            .flatten();
+        let mut state = self.state_guard();
+        state.apply_runtime_events(events, final_capture);
+        state.revision
+    }
+
+    fn update_windows<R>(&self, update: impl FnOnce(&mut SessionWindows) -> R) -> R {
+        update(&mut self.state_guard().windows)
+    }
+
+    fn before_refresh(&self, cx: &mut AsyncWindowContext) {
+        self.follow_target(cx);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use image::Rgba;
+    use image::{Rgba, RgbaImage};
 
     #[test]
     fn runtime_event_batch_updates_state_with_one_revision() {
@@ -362,13 +608,103 @@ mod tests {
                 },
                 LongCaptureEvent::Finished,
             ],
-            Some(final_image),
+            Some(StitchedCapture::from(final_image)),
         );
 
         assert_eq!(state.revision, 1);
         assert!(!state.snapshot.busy);
         assert_eq!(state.snapshot.warning_text, "unstable");
-        assert_eq!(state.capture_image.as_ref().map(RgbaImage::dimensions), Some((4, 3)));
+        assert_eq!(state.capture.as_ref().map(StitchedCapture::length), Some(3));
+    }
+
+    #[test]
+    fn masking_resets_progress_and_tracks_marked_bands() {
+        let mut state = LongCaptureCoordinatorState::default();
+        state.capture = Some(RgbaImage::new(2, 2).into());
+        state.snapshot.preview_height_px = 300;
+
+        state.begin_masking();
+        state.add_mask_band(RectF::new(0.0, 0.0, 100.0, 20.0));
+        state.add_mask_band(RectF::new(10.0, 10.0, 30.0, 30.0));
+
+        assert!(state.snapshot.masking);
+        assert!(state.capture.is_none());
+        assert_eq!(state.snapshot.preview_height_px, 0);
+        assert!(state.remove_mask_band_at(15.0, 15.0));
+        assert!(!state.remove_mask_band_at(90.0, 90.0));
+        assert_eq!(state.snapshot.mask_bands, [RectF::new(0.0, 0.0, 100.0, 20.0)]);
+
+        state.finish_masking();
+        assert!(!state.snapshot.masking);
+    }
+
+    #[test]
+    fn review_edits_rebuild_the_image_used_by_capture_actions() {
+        let mut state = LongCaptureCoordinatorState::default();
+        assert!(!state.begin_review(None));
+
+        assert!(state.begin_review(Some(RgbaImage::new(4, 10).into())));
+        assert_eq!(state.snapshot.review.as_ref().map(|review| (review.width, review.height)), Some((4, 10)));
+        assert!(!state.edit_review(ReviewEdit::TrimBeforeMarker));
+
+        state.pick_review_point(3);
+        assert_eq!(state.snapshot.review.as_ref().and_then(|review| review.marker), Some(3));
+        assert!(state.edit_review(ReviewEdit::TrimBeforeMarker));
+        assert_eq!(state.snapshot.review.as_ref().map(|review| review.height), Some(7));
+        assert!(!state.edit_review(ReviewEdit::RemoveSegment));
+
+        assert!(state.edit_review(ReviewEdit::Reset));
+        state.pick_review_point(5);
+        assert!(state.edit_review(ReviewEdit::TrimAfterMarker));
+        assert_eq!(state.take_capture().map(|capture| capture.dimensions()), Some((4, 6)));
+        assert!(state.snapshot.review.is_none());
+    }
+
+    #[test]
+    fn auto_scroll_state_follows_the_runtime_until_capture_finishes() {
+        let mut state = LongCaptureCoordinatorState::default();
+
+        state.apply_runtime_events(vec![LongCaptureEvent::AutoScroll { active: true }], None);
+        assert!(state.snapshot.auto_scrolling);
+
+        state.apply_runtime_events(vec![LongCaptureEvent::Finished], None);
+        assert!(!state.snapshot.auto_scrolling);
+    }
+
+    #[test]
+    fn windows_follow_the_tracked_window_by_the_distance_left() {
+        let mut state = LongCaptureCoordinatorState::default();
+        assert_eq!(state.take_window_shift(), None);
+
+        state.apply_runtime_events(vec![LongCaptureEvent::TargetMoved { dx: 30, dy: -10 }], None);
+        assert_eq!(state.revision, 1);
+        assert_eq!(state.take_window_shift(), Some((30, -10)));
+        assert_eq!(state.take_window_shift(), None);
+
+        state.apply_runtime_events(vec![LongCaptureEvent::TargetMoved { dx: 25, dy: 0 }], None);
+        assert_eq!(state.take_window_shift(), Some((-5, 10)));
+    }
+
+    #[test]
+    fn diagnostics_follow_the_runtime_and_reset_with_masking() {
+        let mut state = LongCaptureCoordinatorState::default();
+        let summary = StitchSummary {
+            frames: 4,
+            appended: 3,
+            ..StitchSummary::default()
+        };
+
+        state.apply_runtime_events(vec![LongCaptureEvent::Diagnostics { summary }], None);
+        assert_eq!(state.snapshot.diagnostics, Some(summary));
+        assert_eq!(state.revision, 1);
+
+        state.apply_runtime_events(vec![LongCaptureEvent::Diagnostics { summary }], None);
+        assert_eq!(state.revision, 1);
+
+        state.toggle_diagnostics();
+        assert!(state.snapshot.diagnostics_visible);
+        state.begin_masking();
+        assert_eq!(state.snapshot.diagnostics, None);
     }
 
     #[test]
@@ -400,6 +736,14 @@ mod tests {
     fn coordinator_recovers_and_clears_a_poisoned_state_lock() {
         let coordinator = LongCaptureCoordinator {
             runtime: LongCaptureRuntime::new(),
+            request: LongCaptureRequest {
+                selection_rect: crate::services::geometry::Rect::new(0, 0, 100, 100),
+                viewport_rect: RectF::new(0.0, 0.0, 800.0, 600.0),
+                viewport_scale: 1.0,
+                viewport_origin_screen: (0.0, 0.0),
+                origin: CaptureOrigin::default(),
+                window: None,
+            },
             state: Mutex::new(LongCaptureCoordinatorState::default()),
         };
 
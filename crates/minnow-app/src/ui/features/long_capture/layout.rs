@@ -1,19 +1,21 @@
 use super::LongCaptureRequest;
-use crate::services::geometry::RectF;
+use crate::services::capture::long_capture::StitchMask;
+use crate::services::geometry::{Rect, RectF};
 use crate::ui::support::panel_layout;
-use gpui::{Bounds, Pixels, point, px, size};
 
 const PREVIEW_WIDTH: f64 = 320.0;
 const PREVIEW_HEIGHT: f64 = 230.0;
 const PREVIEW_MARGIN: f64 = 20.0;
 const WARNING_HEIGHT: f64 = 34.0;
 pub(crate) const TOOLBAR_TOP_RESERVED: f64 = WARNING_HEIGHT + 8.0;
+/// How close to a selection edge a band must start to be pinned to it.
+const MASK_EDGE_SNAP: f64 = 12.0;
 
 #[derive(Clone, Copy, Debug)]
 pub(crate) struct LongCaptureWindowLayout {
-    pub(crate) frame_bounds: Bounds<Pixels>,
-    pub(crate) toolbar_bounds: Bounds<Pixels>,
-    pub(crate) preview_bounds: Bounds<Pixels>,
+    pub(crate) frame_rect: RectF,
+    pub(crate) toolbar_rect: RectF,
+    pub(crate) preview_rect: RectF,
 }
 
 pub(crate) fn compute_window_layout(request: &LongCaptureRequest, toolbar_action_count: usize) -> LongCaptureWindowLayout {
@@ -22,14 +24,43 @@ pub(crate) fn compute_window_layout(request: &LongCaptureRequest, toolbar_action
     let preview_local = compute_preview_window_local_rect(request);
 
     LongCaptureWindowLayout {
-        frame_bounds: rectf_to_bounds(request.map_local_rect_to_screen(frame_local)),
-        toolbar_bounds: rectf_to_bounds(request.map_local_rect_to_screen(toolbar_local)),
-        preview_bounds: rectf_to_bounds(request.map_local_rect_to_screen(preview_local)),
+        frame_rect: request.map_local_rect_to_screen(frame_local),
+        toolbar_rect: request.map_local_rect_to_screen(toolbar_local),
+        preview_rect: request.map_local_rect_to_screen(preview_local),
     }
 }
 
-pub(crate) fn frame_visibility_after_click_through(success: bool) -> bool {
-    success
+/// Turns bands drawn over a `width` x `height` selection, in selection
+/// points, into a stitch mask. Bands against the top or bottom edge that span
+/// most of the width are a fixed header or footer, bands against a side that
+/// span most of the height are sidebars, and anything else is dynamic content.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub(crate) fn stitch_mask_from_bands(width: f64, height: f64, bands: &[RectF]) -> StitchMask {
+    let mut mask = StitchMask::default();
+    for band in bands {
+        let spans_width = band.width >= width / 2.0;
+        let spans_height = band.height >= height / 2.0;
+        let right = band.x + band.width;
+        let bottom = band.y + band.height;
+
+        if spans_width && band.y <= MASK_EDGE_SNAP {
+            mask.header = mask.header.max(bottom.ceil() as u32);
+        } else if spans_width && bottom >= height - MASK_EDGE_SNAP {
+            mask.footer = mask.footer.max((height - band.y).ceil() as u32);
+        } else if spans_height && band.x <= MASK_EDGE_SNAP {
+            mask.left = mask.left.max(right.ceil() as u32);
+        } else if spans_height && right >= width - MASK_EDGE_SNAP {
+            mask.right = mask.right.max((width - band.x).ceil() as u32);
+        } else {
+            mask.dynamic.push(Rect::new(
+                band.x.floor() as i32,
+                band.y.floor() as i32,
+                band.width.ceil() as i32,
+                band.height.ceil() as i32,
+            ));
+        }
+    }
+    mask
 }
 
 fn compute_toolbar_window_local_rect(request: &LongCaptureRequest, action_count: usize) -> RectF {
@@ -67,13 +98,6 @@ fn compute_preview_window_local_rect(request: &LongCaptureRequest) -> RectF {
     RectF::new(x, y, PREVIEW_WIDTH, PREVIEW_HEIGHT)
 }
 
-fn rectf_to_bounds(rect: RectF) -> Bounds<Pixels> {
-    Bounds::new(
-        point(px(rect.x as f32), px(rect.y as f32)),
-        size(px(rect.width.max(1.0) as f32), px(rect.height.max(1.0) as f32)),
-    )
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -86,6 +110,8 @@ mod tests {
             viewport_rect: RectF::new(0.0, 0.0, 1200.0, 800.0),
             viewport_scale: 1.0,
             viewport_origin_screen: (0.0, 0.0),
+            origin: crate::services::capture::filename::CaptureOrigin::default(),
+            window: None,
         };
 
         let preview = compute_preview_window_local_rect(&request);
@@ -102,8 +128,18 @@ mod tests {
     }
 
     #[test]
-    fn frame_hides_when_click_through_setup_fails() {
-        assert!(frame_visibility_after_click_through(true));
-        assert!(!frame_visibility_after_click_through(false));
+    fn bands_against_the_edges_become_fixed_regions() {
+        let bands = [
+            RectF::new(0.0, 4.0, 380.0, 40.5),
+            RectF::new(10.0, 560.0, 390.0, 40.0),
+            RectF::new(0.0, 0.0, 60.0, 600.0),
+            RectF::new(370.0, 100.0, 30.0, 400.0),
+            RectF::new(150.0, 200.0, 80.0, 60.0),
+        ];
+
+        let mask = stitch_mask_from_bands(400.0, 600.0, &bands);
+
+        assert_eq!((mask.header, mask.footer, mask.left, mask.right), (45, 40, 60, 30));
+        assert_eq!(mask.dynamic, [Rect::new(150, 200, 80, 60)]);
     }
 }
@@ -2,13 +2,16 @@ mod coordinator;
 mod layout;
 mod view;
 
-use crate::platform::shell::{self, PopupWindowSpec};
+use crate::platform::shell;
+use crate::services::capture::filename::CaptureOrigin;
+use crate::services::capture::monitor::MonitorLayout;
 use crate::services::geometry::{Rect, RectF};
 use crate::ui::support::appearance;
-use gpui::{App, AppContext, WindowBackgroundAppearance, WindowBounds, WindowKind, WindowOptions};
+use crate::ui::support::session_windows::SessionWindowKind;
+use gpui::{App, AppContext, WindowBackgroundAppearance};
 use std::sync::Arc;
 use view::{FrameWindowView, LongCaptureToolbarAction, PreviewWindowView, ToolbarWindowView};
-use {coordinator::LongCaptureCoordinator, coordinator::LongCaptureWindowKind, layout::compute_window_layout};
+use {coordinator::LongCaptureCoordinator, layout::compute_window_layout};
 
 #[derive(Clone)]
 pub struct LongCaptureRequest {
@@ -16,6 +19,20 @@ pub struct LongCaptureRequest {
     pub viewport_rect: RectF,
     pub viewport_scale: f64,
     pub viewport_origin_screen: (f64, f64),
+    /// The window the selection snapped to, used to name the saved file and
+    /// to pick the stitching profile of its application.
+    pub origin: CaptureOrigin,
+    /// Set when the capture was started on a snapped window; the capture
+    /// then follows the window if it moves.
+    pub window: Option<CapturedWindow>,
+}
+
+/// The window a long capture runs on, with its bounds when the selection
+/// was taken, in the same coordinates as the selection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapturedWindow {
+    pub id: u32,
+    pub bounds: Rect,
 }
 
 impl LongCaptureRequest {
@@ -38,13 +55,27 @@ impl LongCaptureRequest {
             rect.height,
         )
     }
+
+    /// Center of the selection in desktop coordinates, where automatic
+    /// scrolling sends its wheel input, after the captured window moved by
+    /// `offset` logical pixels. Mapped through the monitor showing it, since
+    /// logical origins are not desktop origins divided by a single scale.
+    #[must_use]
+    pub(crate) fn scroll_anchor(&self, offset: (i32, i32), layout: &MonitorLayout) -> Option<(i32, i32)> {
+        let selection = self.map_local_rect_to_screen(self.selection_rectf());
+        let center = (
+            selection.x + selection.width / 2.0 + f64::from(offset.0),
+            selection.y + selection.height / 2.0 + f64::from(offset.1),
+        );
+        layout.monitor_at_logical(center).map(|monitor| monitor.to_desktop_point(center))
+    }
 }
 
 pub fn open_window(cx: &mut App, request: LongCaptureRequest) {
-    let layout = compute_window_layout(&request, LongCaptureToolbarAction::ORDERED.len());
+    let layout = compute_window_layout(&request, LongCaptureToolbarAction::available().len());
     let coordinator = Arc::new(LongCaptureCoordinator::new(request.clone()));
 
-    if let Err(err) = cx.open_window(window_options(layout.frame_bounds, false), {
+    if let Err(err) = cx.open_window(shell::fixed_popup_window_options(layout.frame_rect, false), {
         let request = request.clone();
         let coordinator = coordinator.clone();
         move |window, cx| {
@@ -62,7 +93,7 @@ pub fn open_window(cx: &mut App, request: LongCaptureRequest) {
                     window.remove_window();
                 });
             } else {
-                coordinator.register_window(LongCaptureWindowKind::Frame, window.window_handle());
+                coordinator.register_window(SessionWindowKind::Frame, window.window_handle());
             }
 
             cx.new(|cx| FrameWindowView::new(request, coordinator, window, cx))
@@ -72,7 +103,7 @@ pub fn open_window(cx: &mut App, request: LongCaptureRequest) {
         coordinator.on_frame_click_through_result(false);
     }
 
-    if let Err(err) = cx.open_window(window_options(layout.toolbar_bounds, true), {
+    if let Err(err) = cx.open_window(shell::fixed_popup_window_options(layout.toolbar_rect, true), {
         let coordinator = coordinator.clone();
         move |window, cx| {
             appearance::apply_saved_preferences(Some(window), cx);
@@ -82,7 +113,7 @@ pub fn open_window(cx: &mut App, request: LongCaptureRequest) {
                 tracing::warn!("Failed to set toolbar window level: {err}");
             }
             let focus_handle = cx.focus_handle();
-            coordinator.register_window(LongCaptureWindowKind::Toolbar, window.window_handle());
+            coordinator.register_window(SessionWindowKind::Toolbar, window.window_handle());
             cx.new(|cx| ToolbarWindowView::new(coordinator, focus_handle, window, cx))
         }
     }) {
@@ -92,37 +123,24 @@ pub fn open_window(cx: &mut App, request: LongCaptureRequest) {
         return;
     }
 
-    if let Err(err) = cx.open_window(window_options(layout.preview_bounds, false), move |window, cx| {
+    if let Err(err) = cx.open_window(shell::fixed_popup_window_options(layout.preview_rect, false), move |window, cx| {
         appearance::apply_saved_preferences(Some(window), cx);
         shell::configure_window(window, cx, false);
         window.set_background_appearance(WindowBackgroundAppearance::Transparent);
         if let Err(err) = shell::set_always_on_top(window) {
             tracing::warn!("Failed to set preview window level: {err}");
         }
-        coordinator.register_window(LongCaptureWindowKind::Preview, window.window_handle());
+        coordinator.register_window(SessionWindowKind::Preview, window.window_handle());
         cx.new(|cx| PreviewWindowView::new(coordinator, window, cx))
     }) {
         tracing::error!("Failed to open long capture preview window: {err}");
     }
 }
 
-fn window_options(bounds: gpui::Bounds<gpui::Pixels>, focus: bool) -> WindowOptions {
-    shell::popup_window_options(PopupWindowSpec {
-        window_bounds: Some(WindowBounds::Windowed(bounds)),
-        kind: WindowKind::PopUp,
-        focus,
-        show: true,
-        is_movable: false,
-        is_resizable: false,
-        is_minimizable: false,
-        display_id: None,
-        window_min_size: None,
-    })
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::capture::monitor::MonitorRegion;
 
     #[test]
     fn request_maps_local_rect_to_screen_coordinates() {
@@ -131,6 +149,8 @@ mod tests {
             viewport_rect: RectF::new(0.0, 0.0, 1200.0, 800.0),
             viewport_scale: 1.0,
             viewport_origin_screen: (320.0, -80.0),
+            origin: CaptureOrigin::default(),
+            window: None,
         };
 
         let local = RectF::new(50.0, 70.0, 200.0, 100.0);
@@ -138,4 +158,37 @@ mod tests {
 
         assert_eq!(mapped, RectF::new(370.0, -10.0, 200.0, 100.0));
     }
+
+    #[test]
+    fn scroll_anchor_is_the_selection_center_in_desktop_pixels() {
+        // macOS reports desktop coordinates in points, so there the anchor
+        // stays in logical units whatever the display scale.
+        let points = cfg!(target_os = "macos");
+        let desktop = |logical: u32, scale: u32| if points { logical } else { logical * scale };
+        let request = |selection_rect, viewport_origin_screen| LongCaptureRequest {
+            selection_rect,
+            viewport_rect: RectF::new(0.0, 0.0, 1200.0, 800.0),
+            viewport_scale: 2.0,
+            viewport_origin_screen,
+            origin: CaptureOrigin::default(),
+            window: None,
+        };
+
+        let single = MonitorLayout::from_regions(vec![MonitorRegion::new(1, (0, 0), (desktop(1000, 2), desktop(800, 2)), 2.0, true)]);
+        let on_primary = request(Rect::new(10, 20, 100, 120), (320.0, -80.0));
+        let expected = if points { [(380, 0), (390, -20)] } else { [(760, 0), (780, -40)] };
+        assert_eq!(on_primary.scroll_anchor((0, 0), &single), Some(expected[0]));
+        assert_eq!(on_primary.scroll_anchor((10, -20), &single), Some(expected[1]));
+
+        // A 2x display right of a 1x primary, 200 desktop units lower: its
+        // logical origin is (1000, 200), not its desktop origin halved.
+        let mixed = MonitorLayout::from_regions(vec![
+            MonitorRegion::new(1, (0, 0), (1000, 800), 1.0, true),
+            MonitorRegion::new(2, (1000, 200), (desktop(500, 2), desktop(400, 2)), 2.0, false),
+        ]);
+        let on_secondary = request(Rect::new(50, 40, 100, 120), (1000.0, 200.0));
+        let expected = if points { (1100, 300) } else { (1200, 400) };
+        assert_eq!(on_secondary.scroll_anchor((0, 0), &mixed), Some(expected));
+        assert_eq!(on_primary.scroll_anchor((0, 0), &mixed), Some((380, 0)));
+    }
 }
@@ -1,19 +1,71 @@
 use super::super::LongCaptureRequest;
+use crate::services::geometry::RectF;
+use crate::services::i18n;
 use crate::ui::features::long_capture::coordinator::LongCaptureCoordinator;
 use crate::ui::features::long_capture::layout::TOOLBAR_TOP_RESERVED;
-use gpui::{Context, InteractiveElement, IntoElement, ParentElement, Render, Styled, Window, div, px};
+use gpui::{
+    Context, InteractiveElement, IntoElement, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement, Pixels, Point, Render,
+    Styled, Window, div, px,
+};
 use gpui_component::ActiveTheme as _;
 use std::sync::Arc;
 
+/// Drags smaller than this in either direction do not mark a band.
+const MIN_BAND_SIZE: f64 = 4.0;
+
 pub(crate) struct FrameWindowView {
     request: LongCaptureRequest,
     coordinator: Arc<LongCaptureCoordinator>,
+    /// Start and current point of the band being drawn, in selection points.
+    band_drag: Option<((f64, f64), (f64, f64))>,
 }
 
 impl FrameWindowView {
     pub(crate) fn new(request: LongCaptureRequest, coordinator: Arc<LongCaptureCoordinator>, window: &mut Window, cx: &mut Context<Self>) -> Self {
         coordinator.ensure_runtime_poller(window, cx);
-        Self { request, coordinator }
+        Self {
+            request,
+            coordinator,
+            band_drag: None,
+        }
+    }
+
+    fn selection_point(&self, position: Point<Pixels>) -> (f64, f64) {
+        let selection = self.request.selection_rectf();
+        (
+            (position.x.to_f64() - selection.x).clamp(0.0, selection.width),
+            (position.y.to_f64() - selection.y).clamp(0.0, selection.height),
+        )
+    }
+
+    fn on_mouse_down(&mut self, event: &MouseDownEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        if !self.coordinator.snapshot().masking {
+            return;
+        }
+        let point = self.selection_point(event.position);
+        if !self.coordinator.remove_mask_band_at(point.0, point.1) {
+            self.band_drag = Some((point, point));
+        }
+        cx.notify();
+    }
+
+    fn on_mouse_move(&mut self, event: &MouseMoveEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        let point = self.selection_point(event.position);
+        if let Some((_, current)) = self.band_drag.as_mut() {
+            *current = point;
+            cx.notify();
+        }
+    }
+
+    fn on_mouse_up(&mut self, _event: &MouseUpEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some((start, end)) = self.band_drag.take() else {
+            return;
+        };
+        let band = band_rect(start, end);
+        if band.width >= MIN_BAND_SIZE && band.height >= MIN_BAND_SIZE {
+            self.coordinator.add_mask_band(band);
+        }
+        cx.notify();
     }
 }
 
@@ -44,21 +96,53 @@ impl Render for FrameWindowView {
             );
         }
 
-        if !snapshot.warning_text.is_empty() {
+        if snapshot.masking {
+            let drawing = self.band_drag.map(|(start, end)| band_rect(start, end));
+            for band in snapshot.mask_bands.iter().copied().chain(drawing) {
+                root = root.child(
+                    div()
+                        .absolute()
+                        .left(px((selection.x + band.x) as f32))
+                        .top(px((selection.y + band.y) as f32))
+                        .w(px(band.width as f32))
+                        .h(px(band.height as f32))
+                        .bg(theme.warning.alpha(0.35))
+                        .border_1()
+                        .border_color(theme.warning),
+                );
+            }
+            root = root
+                .on_mouse_down(MouseButton::Left, cx.listener(Self::on_mouse_down))
+                .on_mouse_move(cx.listener(Self::on_mouse_move))
+                .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up));
+        }
+
+        let banner = if snapshot.masking {
+            Some((i18n::overlay::long_capture_mask_hint(), theme.popover, theme.popover_foreground))
+        } else if !snapshot.warning_text.is_empty() {
+            Some((snapshot.warning_text, theme.danger, theme.danger_foreground))
+        } else {
+            None
+        };
+        if let Some((text, background, foreground)) = banner {
             root = root.child(
                 div()
                     .absolute()
                     .left(px(selection.x as f32))
                     .top(px((selection.y - TOOLBAR_TOP_RESERVED).max(12.0) as f32))
                     .rounded(theme.radius_lg)
-                    .bg(theme.danger)
+                    .bg(background)
                     .px_3()
                     .py_1()
-                    .text_color(theme.danger_foreground)
-                    .child(snapshot.warning_text),
+                    .text_color(foreground)
+                    .child(text),
             );
         }
 
         root
     }
 }
+
+fn band_rect(start: (f64, f64), end: (f64, f64)) -> RectF {
+    RectF::new(start.0.min(end.0), start.1.min(end.1), (start.0 - end.0).abs(), (start.1 - end.1).abs())
+}
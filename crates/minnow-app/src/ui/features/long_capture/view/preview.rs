@@ -1,25 +1,166 @@
+mod actions;
+
 use crate::services::i18n;
-use crate::ui::features::long_capture::coordinator::LongCaptureCoordinator;
-use gpui::{Context, InteractiveElement, IntoElement, ObjectFit, ParentElement, Render, Styled, StyledImage, Window, div, img, px};
-use gpui_component::ActiveTheme as _;
+use crate::ui::features::long_capture::coordinator::{LongCaptureCoordinator, LongCaptureReview};
+use actions::ReviewAction;
+use gpui::{
+    ClickEvent, Context, Div, Hsla, InteractiveElement, IntoElement, MouseButton, MouseDownEvent, ObjectFit, ParentElement, Render, ScrollHandle,
+    StatefulInteractiveElement, Styled, StyledImage, Window, div, img, px,
+};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme as _, Disableable, Icon, Sizable, h_flex, v_flex};
 use std::sync::Arc;
 
+const REVIEW_BAR_HEIGHT: f32 = 32.0;
+
 pub(crate) struct PreviewWindowView {
     coordinator: Arc<LongCaptureCoordinator>,
+    review_scroll: ScrollHandle,
+    /// Displayed size of one capture pixel in the last rendered review.
+    review_scale: f32,
 }
 
 impl PreviewWindowView {
     pub(crate) fn new(coordinator: Arc<LongCaptureCoordinator>, window: &mut Window, cx: &mut Context<Self>) -> Self {
         coordinator.ensure_runtime_poller(window, cx);
-        Self { coordinator }
+        Self {
+            coordinator,
+            review_scroll: ScrollHandle::new(),
+            review_scale: 1.0,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn on_review_mouse_down(&mut self, event: &MouseDownEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        let Some(review) = self.coordinator.snapshot().review else {
+            return;
+        };
+        let bounds = self.review_scroll.bounds();
+        let scroll = self.review_scroll.offset();
+        let position = if review.horizontal {
+            event.position.x - bounds.origin.x - scroll.x
+        } else {
+            event.position.y - bounds.origin.y - scroll.y
+        };
+        let offset = (position.to_f64() / f64::from(self.review_scale)).max(0.0) as u32;
+        self.coordinator.pick_review_point(offset);
+        cx.notify();
+    }
+
+    fn on_review_action(&mut self, action: ReviewAction, cx: &mut Context<Self>) {
+        if self.coordinator.edit_review(action.edit()) {
+            cx.notify();
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn render_review(&mut self, review: &LongCaptureReview, window: &Window, cx: &mut Context<Self>) -> Div {
+        let viewport = window.viewport_size();
+        // The panel border takes a pixel on each side.
+        let area_width = f32::from(viewport.width) - 2.0;
+        let area_height = f32::from(viewport.height) - REVIEW_BAR_HEIGHT - 2.0;
+        self.review_scale = if review.horizontal {
+            area_height / review.height.max(1) as f32
+        } else {
+            area_width / review.width.max(1) as f32
+        };
+        let scale = self.review_scale;
+        let length = if review.horizontal { review.width } else { review.height };
+        let theme = cx.theme();
+
+        let mut content = div()
+            .relative()
+            .flex_none()
+            .w(px(review.width as f32 * scale))
+            .h(px(review.height as f32 * scale))
+            .child(img(review.image.clone()).size_full().object_fit(ObjectFit::Fill));
+
+        let clip = |start: i64, end: i64| (start.clamp(0, i64::from(length)) as f32, end.clamp(0, i64::from(length)) as f32);
+        if let Some(&(start, end)) = review.segments.get(review.selected) {
+            let (start, end) = clip(start, end);
+            content = content.child(axis_band(
+                review.horizontal,
+                start * scale,
+                (end - start) * scale,
+                theme.selection.alpha(0.3),
+            ));
+        }
+        for &(start, _) in review.segments.iter().skip(1) {
+            if start > 0 && start < i64::from(length) {
+                content = content.child(axis_band(review.horizontal, start as f32 * scale, 1.0, theme.warning));
+            }
+        }
+        if let Some(marker) = review.marker {
+            content = content.child(axis_band(review.horizontal, marker as f32 * scale, 2.0, theme.danger));
+        }
+
+        let scroll_area = div()
+            .id("long-capture-review-scroll")
+            .flex_1()
+            .w_full()
+            .track_scroll(&self.review_scroll)
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::on_review_mouse_down))
+            .child(content);
+        let scroll_area = if review.horizontal {
+            scroll_area.overflow_x_scroll()
+        } else {
+            scroll_area.overflow_y_scroll()
+        };
+
+        let mut action_bar = h_flex()
+            .h(px(REVIEW_BAR_HEIGHT))
+            .flex_none()
+            .items_center()
+            .justify_center()
+            .gap_0p5()
+            .border_t_1()
+            .border_color(theme.border);
+        for action in ReviewAction::ORDERED {
+            action_bar = action_bar.child(self.review_button(action, review, cx));
+        }
+
+        let mut column = v_flex().size_full().child(scroll_area).child(action_bar);
+        if review.marker.is_none() {
+            let theme = cx.theme();
+            column = column.child(
+                div()
+                    .absolute()
+                    .left_2()
+                    .top_2()
+                    .px_2()
+                    .py_0p5()
+                    .rounded(theme.radius_lg)
+                    .bg(theme.popover.alpha(0.9))
+                    .text_color(theme.muted_foreground)
+                    .text_size(px(12.0))
+                    .child(i18n::overlay::long_capture_review_hint()),
+            );
+        }
+        column
+    }
+
+    fn review_button(&self, action: ReviewAction, review: &LongCaptureReview, cx: &mut Context<Self>) -> Button {
+        let button = Button::new(action.id())
+            .compact()
+            .ghost()
+            .disabled(!action.enabled(review))
+            .on_click(cx.listener(move |this, _: &ClickEvent, _window, cx| this.on_review_action(action, cx)));
+
+        match action.icon() {
+            Some(icon) => button
+                .icon(Icon::new(icon).small().text_color(cx.theme().popover_foreground))
+                .tooltip(action.text()),
+            None => button.small().label(action.text()),
+        }
     }
 }
 
 impl Render for PreviewWindowView {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let snapshot = self.coordinator.snapshot();
-        let theme = cx.theme();
 
+        let review = snapshot.review.as_ref().map(|review| self.render_review(review, window, cx));
+        let theme = cx.theme();
         let mut panel = div()
             .id("long-capture-preview")
             .size_full()
@@ -33,7 +174,9 @@ impl Render for PreviewWindowView {
             panel = panel.shadow_lg();
         }
 
-        panel = if let Some(image) = snapshot.preview_image {
+        panel = if let Some(review) = review {
+            panel.child(review)
+        } else if let Some(image) = snapshot.preview_image {
             panel.child(img(image).size_full().object_fit(ObjectFit::Contain))
         } else {
             panel.child(
@@ -47,20 +190,48 @@ impl Render for PreviewWindowView {
             )
         };
 
+        let length_px = match &snapshot.review {
+            Some(review) if review.horizontal => i64::from(review.width),
+            Some(review) => i64::from(review.height),
+            None => i64::from(snapshot.preview_height_px.max(0)),
+        };
+        let badge = if snapshot.review.is_none() && snapshot.memory_bytes > 0 {
+            let megabytes = snapshot.memory_bytes.div_ceil(1024 * 1024);
+            format!("{length_px} px · {megabytes} MB")
+        } else {
+            format!("{length_px} px")
+        };
+        let badge_bottom = if snapshot.review.is_some() {
+            px(REVIEW_BAR_HEIGHT + 8.0)
+        } else {
+            px(8.0)
+        };
+
         div().size_full().bg(gpui::transparent_black()).child(
             panel.child(
                 div()
                     .absolute()
                     .right_2()
-                    .bottom_2()
+                    .bottom(badge_bottom)
                     .px_2()
                     .py_0p5()
                     .rounded(theme.radius_lg)
                     .bg(theme.primary)
                     .text_color(theme.primary_foreground)
                     .text_size(px(12.0))
-                    .child(format!("{} px", snapshot.preview_height_px.max(0))),
+                    .child(badge),
             ),
         )
     }
 }
+
+/// A band across the capture at `start` along the scroll axis, in displayed
+/// pixels.
+fn axis_band(horizontal: bool, start: f32, length: f32, color: Hsla) -> Div {
+    let band = div().absolute().bg(color);
+    if horizontal {
+        band.top_0().bottom_0().left(px(start)).w(px(length))
+    } else {
+        band.left_0().right_0().top(px(start)).h(px(length))
+    }
+}
@@ -0,0 +1,97 @@
+use crate::services::assets::asset_paths;
+use crate::services::i18n;
+use crate::ui::features::long_capture::coordinator::{LongCaptureReview, ReviewEdit};
+use gpui_component::IconNamed;
+
+#[derive(Clone, Copy)]
+pub(super) enum ReviewIcon {
+    Remove,
+    ShiftBack,
+    ShiftForward,
+    Reset,
+}
+
+impl IconNamed for ReviewIcon {
+    fn path(self) -> gpui::SharedString {
+        match self {
+            Self::Remove => asset_paths::icons::CLOSE.into(),
+            Self::ShiftBack => asset_paths::icons::ARROW_DROP_UP.into(),
+            Self::ShiftForward => asset_paths::icons::ARROW_DROP_DOWN.into(),
+            Self::Reset => asset_paths::icons::UNDO.into(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum ReviewAction {
+    Remove,
+    ShiftBack,
+    ShiftForward,
+    TrimBefore,
+    TrimAfter,
+    Reset,
+}
+
+impl ReviewAction {
+    pub(super) const ORDERED: [Self; 6] = [
+        Self::Remove,
+        Self::ShiftBack,
+        Self::ShiftForward,
+        Self::TrimBefore,
+        Self::TrimAfter,
+        Self::Reset,
+    ];
+
+    pub(super) fn id(self) -> &'static str {
+        match self {
+            Self::Remove => "long-capture-review-remove",
+            Self::ShiftBack => "long-capture-review-shift-back",
+            Self::ShiftForward => "long-capture-review-shift-forward",
+            Self::TrimBefore => "long-capture-review-trim-before",
+            Self::TrimAfter => "long-capture-review-trim-after",
+            Self::Reset => "long-capture-review-reset",
+        }
+    }
+
+    /// Trims are labelled buttons; the other actions are icons.
+    pub(super) fn icon(self) -> Option<ReviewIcon> {
+        match self {
+            Self::Remove => Some(ReviewIcon::Remove),
+            Self::ShiftBack => Some(ReviewIcon::ShiftBack),
+            Self::ShiftForward => Some(ReviewIcon::ShiftForward),
+            Self::TrimBefore | Self::TrimAfter => None,
+            Self::Reset => Some(ReviewIcon::Reset),
+        }
+    }
+
+    pub(super) fn text(self) -> String {
+        match self {
+            Self::Remove => i18n::overlay::long_capture_review_remove(),
+            Self::ShiftBack => i18n::overlay::long_capture_review_shift_back(),
+            Self::ShiftForward => i18n::overlay::long_capture_review_shift_forward(),
+            Self::TrimBefore => i18n::overlay::long_capture_review_trim_before(),
+            Self::TrimAfter => i18n::overlay::long_capture_review_trim_after(),
+            Self::Reset => i18n::overlay::long_capture_review_reset(),
+        }
+    }
+
+    pub(super) fn edit(self) -> ReviewEdit {
+        match self {
+            Self::Remove => ReviewEdit::RemoveSegment,
+            Self::ShiftBack => ReviewEdit::ShiftSegment { back: true },
+            Self::ShiftForward => ReviewEdit::ShiftSegment { back: false },
+            Self::TrimBefore => ReviewEdit::TrimBeforeMarker,
+            Self::TrimAfter => ReviewEdit::TrimAfterMarker,
+            Self::Reset => ReviewEdit::Reset,
+        }
+    }
+
+    pub(super) fn enabled(self, review: &LongCaptureReview) -> bool {
+        match self {
+            Self::Remove => review.segments.len() > 1,
+            Self::ShiftBack | Self::ShiftForward => review.selected > 0,
+            Self::TrimBefore | Self::TrimAfter => review.marker.is_some(),
+            Self::Reset => true,
+        }
+    }
+}
@@ -3,18 +3,19 @@ mod actions;
 pub(crate) use actions::LongCaptureToolbarAction;
 
 use crate::app::workflows;
-use crate::services::capture::action::{ActionContext, CaptureAction, PinCaptureRequest};
-use crate::services::i18n;
-use crate::ui::features::long_capture::coordinator::LongCaptureCoordinator;
+use crate::platform::shell::{self, NotificationType};
+use crate::services::capture::action::{ActionContext, CaptureAction, HistorySource, PinCaptureRequest};
+use crate::services::{i18n, settings};
+use crate::ui::features::long_capture::coordinator::{LongCaptureCoordinator, LongCaptureSnapshot};
 use crate::ui::features::long_capture::layout::TOOLBAR_TOP_RESERVED;
 use crate::ui::features::pin::{self, PinRequest};
-use crate::ui::support::capture_actions::{self, CaptureActionHost, CaptureActionHostKind};
+use crate::ui::support::capture_actions::{self, CaptureActionEffect, CaptureActionHost, CaptureActionHostKind};
 use crate::ui::support::panel_layout::{self, PanelLayout};
 use actions::LongCaptureToolbarIcon;
 use gpui::InteractiveElement;
 use gpui::{App, ClickEvent, Context, Div, FocusHandle, IntoElement, KeyDownEvent, ParentElement, Render, Styled, Window, div, px};
 use gpui_component::button::{Button, ButtonVariants};
-use gpui_component::{ActiveTheme as _, Disableable, Icon, Sizable, h_flex};
+use gpui_component::{ActiveTheme as _, Disableable, Icon, Selectable, Sizable, h_flex};
 use std::borrow::BorrowMut;
 use std::sync::Arc;
 use std::time::Duration;
@@ -33,15 +34,26 @@ impl ToolbarWindowView {
         Self { coordinator, focus_handle }
     }
 
-    fn toolbar_button(&self, action: LongCaptureToolbarAction, busy: bool, cx: &mut Context<Self>) -> Button {
+    fn toolbar_button(&self, action: LongCaptureToolbarAction, snapshot: &LongCaptureSnapshot, cx: &mut Context<Self>) -> Button {
         let button = Button::new(action.id())
             .compact()
             .icon(toolbar_icon(cx, action.icon()))
             .tooltip(action.tooltip())
             .ghost()
-            .disabled(busy && action.disabled_when_busy());
+            .selected(match action {
+                LongCaptureToolbarAction::AutoScroll => snapshot.auto_scrolling,
+                LongCaptureToolbarAction::Mask => snapshot.masking,
+                LongCaptureToolbarAction::Review => snapshot.review.is_some(),
+                LongCaptureToolbarAction::Diagnostics => snapshot.diagnostics_visible,
+                _ => false,
+            })
+            .disabled((snapshot.busy && action.disabled_when_busy()) || disabled_by_mode(action, snapshot));
 
         match action {
+            LongCaptureToolbarAction::AutoScroll => button.on_click(cx.listener(Self::on_auto_scroll)),
+            LongCaptureToolbarAction::Mask => button.on_click(cx.listener(Self::on_mask)),
+            LongCaptureToolbarAction::Review => button.on_click(cx.listener(Self::on_review)),
+            LongCaptureToolbarAction::Diagnostics => button.on_click(cx.listener(Self::on_diagnostics)),
             LongCaptureToolbarAction::Save => button.on_click(cx.listener(Self::on_save)),
             LongCaptureToolbarAction::Pin => button.on_click(cx.listener(Self::on_pin)),
             LongCaptureToolbarAction::Copy => button.on_click(cx.listener(Self::on_copy)),
@@ -66,6 +78,94 @@ impl ToolbarWindowView {
         });
     }
 
+    fn on_auto_scroll(&mut self, _: &ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        self.coordinator.toggle_auto_scroll();
+        cx.notify();
+    }
+
+    fn on_mask(&mut self, _: &ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        self.coordinator.toggle_masking(cx);
+        cx.notify();
+    }
+
+    fn on_review(&mut self, _: &ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.coordinator.snapshot().review.is_some() {
+            return;
+        }
+        if !self.coordinator.begin_review(CAPTURE_ACTION_TIMEOUT) {
+            self.coordinator.finish_capture_action_with_warning(i18n::overlay::long_capture_empty());
+        }
+        cx.notify();
+    }
+
+    fn on_diagnostics(&mut self, _: &ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        self.coordinator.toggle_diagnostics();
+        cx.notify();
+    }
+
+    fn on_export_report(&mut self, _: &ClickEvent, _window: &mut Window, _cx: &mut Context<Self>) {
+        let message = match self.coordinator.export_report() {
+            Ok(path) => i18n::overlay::long_capture_diagnostics_exported(path.display()),
+            Err(err) => {
+                tracing::error!("Failed to export long-capture diagnostics: {err}");
+                i18n::overlay::long_capture_diagnostics_export_failed()
+            }
+        };
+        shell::show_notification(&i18n::app::capture_name(), &message, NotificationType::Info);
+    }
+
+    fn on_tune_app(&mut self, _: &ClickEvent, _window: &mut Window, _cx: &mut Context<Self>) {
+        let message = match self.coordinator.app_name() {
+            Some(app) if self.coordinator.save_app_profile() => i18n::overlay::long_capture_diagnostics_tuned(app),
+            _ => i18n::overlay::long_capture_diagnostics_tune_unchanged(),
+        };
+        shell::show_notification(&i18n::app::capture_name(), &message, NotificationType::Info);
+    }
+
+    /// Match summary with export and tuning buttons, shown above the toolbar.
+    fn diagnostics_strip(&self, snapshot: &LongCaptureSnapshot, width: f64, cx: &mut Context<Self>) -> Div {
+        let summary = snapshot
+            .diagnostics
+            .and_then(|summary| Some((summary.confidence()?, summary.rejected)))
+            .map_or_else(i18n::overlay::long_capture_diagnostics_waiting, |(confidence, rejected)| {
+                i18n::overlay::long_capture_diagnostics_summary((confidence * 100.0).round(), rejected)
+            });
+        let export = Button::new("long-capture-diagnostics-export")
+            .compact()
+            .ghost()
+            .xsmall()
+            .label(i18n::overlay::long_capture_diagnostics_export())
+            .disabled(snapshot.diagnostics.is_none())
+            .on_click(cx.listener(Self::on_export_report));
+        let tune = Button::new("long-capture-diagnostics-tune")
+            .compact()
+            .ghost()
+            .xsmall()
+            .label(i18n::overlay::long_capture_diagnostics_tune())
+            .disabled(snapshot.diagnostics.is_none() || self.coordinator.app_name().is_none())
+            .on_click(cx.listener(Self::on_tune_app));
+
+        let theme = cx.theme();
+        h_flex()
+            .absolute()
+            .left(px(0.0))
+            .top(px(0.0))
+            .w(px(width as f32))
+            .h(px(WARNING_HEIGHT as f32))
+            .items_center()
+            .gap_1()
+            .rounded(theme.radius_lg)
+            .bg(theme.popover)
+            .border_1()
+            .border_color(theme.border)
+            .px_2()
+            .text_size(px(12.0))
+            .text_color(theme.muted_foreground)
+            .child(div().flex_1().overflow_hidden().whitespace_nowrap().text_ellipsis().child(summary))
+            .child(export)
+            .child(tune)
+    }
+
     fn on_save(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
         self.execute_capture_action(CaptureAction::Save, window, cx);
     }
@@ -89,17 +189,49 @@ impl ToolbarWindowView {
 
         self.coordinator.start_capture_action();
 
-        let image = self.coordinator.take_capture_image(CAPTURE_ACTION_TIMEOUT);
-        let Some(image) = image else {
+        let result = self.coordinator.take_capture(CAPTURE_ACTION_TIMEOUT).and_then(|capture| {
+            // Captures that spilled to disk are streamed to the file rather
+            // than rendered into one image.
+            if action == CaptureAction::Save && capture.is_spilled() {
+                return Some(workflows::save_long_capture(&capture, self.coordinator.origin()));
+            }
+            // Copy, Pin and the history need the whole image.
+            let context = ActionContext::full_image_data(Arc::new(capture.render()?))
+                .with_origin(self.coordinator.origin().clone())
+                .with_history(HistorySource::default());
+            Some(workflows::execute_capture_action(action, context))
+        });
+        let Some(result) = result else {
             self.coordinator.finish_capture_action_with_warning(i18n::overlay::long_capture_empty());
             cx.notify();
             return;
         };
-
-        let result = workflows::execute_capture_action(action, ActionContext::full_image_data(Arc::new(image)));
         let effect = capture_actions::interpret(action, result, CaptureActionHostKind::LongCapture);
+        if action == CaptureAction::Save && matches!(effect, CaptureActionEffect::NotifyAndClose(_)) {
+            self.save_animation(cx);
+        }
         capture_actions::apply_host_effect(self, effect, window, BorrowMut::borrow_mut(cx));
     }
+
+    /// Saves the animation of the session next to the saved still. It is
+    /// encoded in the background and reports back once the capture has
+    /// closed.
+    fn save_animation(&self, cx: &mut Context<Self>) {
+        let Some(animation) = self.coordinator.take_animation() else {
+            return;
+        };
+        let format = settings::general_settings().recording_format;
+        let origin = self.coordinator.origin().clone();
+        let encode = cx
+            .background_executor()
+            .spawn(async move { workflows::save_recording(&animation, format, &origin) });
+        cx.spawn(async move |_, _| {
+            let result = encode.await;
+            let effect = capture_actions::interpret(CaptureAction::Save, result, CaptureActionHostKind::SessionAnimation);
+            capture_actions::apply_detached_effect(effect);
+        })
+        .detach();
+    }
 }
 
 impl CaptureActionHost for ToolbarWindowView {
@@ -130,7 +262,8 @@ impl CaptureActionHost for ToolbarWindowView {
 impl Render for ToolbarWindowView {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let snapshot = self.coordinator.snapshot();
-        let (toolbar_width, toolbar_height) = panel_layout::toolbar_size(LongCaptureToolbarAction::ORDERED.len());
+        let actions = LongCaptureToolbarAction::available();
+        let (toolbar_width, toolbar_height) = panel_layout::toolbar_size(actions.len());
         let layout = PanelLayout {
             x: 0.0,
             y: TOOLBAR_TOP_RESERVED,
@@ -139,11 +272,10 @@ impl Render for ToolbarWindowView {
         };
 
         let mut action_row = h_flex().items_center().gap_0p5();
-        for action in LongCaptureToolbarAction::ORDERED {
-            action_row = action_row.child(self.toolbar_button(action, snapshot.busy, cx));
+        for action in actions {
+            action_row = action_row.child(self.toolbar_button(action, &snapshot, cx));
         }
 
-        let theme = cx.theme();
         let mut root = div()
             .id("long-capture-toolbar")
             .track_focus(&self.focus_handle)
@@ -153,6 +285,7 @@ impl Render for ToolbarWindowView {
             .child(toolbar_panel(cx, layout).child(action_row));
 
         if snapshot.busy {
+            let theme = cx.theme();
             root = root.child(
                 div()
                     .absolute()
@@ -169,12 +302,25 @@ impl Render for ToolbarWindowView {
                     .text_color(theme.muted_foreground)
                     .child(i18n::overlay::long_capture_processing()),
             );
+        } else if snapshot.diagnostics_visible {
+            root = root.child(self.diagnostics_strip(&snapshot, toolbar_width, cx));
         }
 
         root
     }
 }
 
+/// Marking bands and reviewing seams each rule out the other modes: the
+/// capture is paused while marking and stopped for good while reviewing.
+fn disabled_by_mode(action: LongCaptureToolbarAction, snapshot: &LongCaptureSnapshot) -> bool {
+    match action {
+        LongCaptureToolbarAction::AutoScroll => snapshot.masking || snapshot.review.is_some(),
+        LongCaptureToolbarAction::Mask => snapshot.review.is_some(),
+        LongCaptureToolbarAction::Review => snapshot.masking,
+        _ => false,
+    }
+}
+
 fn toolbar_icon(app_ctx: &App, icon_name: LongCaptureToolbarIcon) -> Icon {
     let theme = app_ctx.theme();
     Icon::new(icon_name).small().text_color(theme.popover_foreground)
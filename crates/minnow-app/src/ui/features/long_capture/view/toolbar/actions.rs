@@ -1,9 +1,14 @@
+use crate::platform::shell;
 use crate::services::assets::asset_paths;
 use crate::services::i18n;
 use gpui_component::IconNamed;
 
 #[derive(Clone, Copy)]
 pub(super) enum LongCaptureToolbarIcon {
+    AutoScroll,
+    Mask,
+    Review,
+    Diagnostics,
     Save,
     Pin,
     Copy,
@@ -13,6 +18,10 @@ pub(super) enum LongCaptureToolbarIcon {
 impl IconNamed for LongCaptureToolbarIcon {
     fn path(self) -> gpui::SharedString {
         match self {
+            Self::AutoScroll => asset_paths::icons::SCROLL.into(),
+            Self::Mask => asset_paths::icons::SQUARE_FILL.into(),
+            Self::Review => asset_paths::icons::GRID_ON.into(),
+            Self::Diagnostics => asset_paths::icons::MONITORING.into(),
             Self::Save => asset_paths::icons::SAVE.into(),
             Self::Pin => asset_paths::icons::KEEP.into(),
             Self::Copy => asset_paths::icons::FILE_COPY.into(),
@@ -23,6 +32,10 @@ impl IconNamed for LongCaptureToolbarIcon {
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub(crate) enum LongCaptureToolbarAction {
+    AutoScroll,
+    Mask,
+    Review,
+    Diagnostics,
     Save,
     Pin,
     Copy,
@@ -30,10 +43,31 @@ pub(crate) enum LongCaptureToolbarAction {
 }
 
 impl LongCaptureToolbarAction {
-    pub(crate) const ORDERED: [Self; 4] = [Self::Save, Self::Pin, Self::Copy, Self::Cancel];
+    pub(crate) const ORDERED: [Self; 8] = [
+        Self::AutoScroll,
+        Self::Mask,
+        Self::Review,
+        Self::Diagnostics,
+        Self::Save,
+        Self::Pin,
+        Self::Copy,
+        Self::Cancel,
+    ];
+
+    /// The actions shown on this platform; auto-scroll needs synthetic wheel input.
+    pub(crate) fn available() -> Vec<Self> {
+        Self::ORDERED
+            .into_iter()
+            .filter(|action| *action != Self::AutoScroll || shell::can_scroll_wheel())
+            .collect()
+    }
 
     pub(super) fn id(self) -> &'static str {
         match self {
+            Self::AutoScroll => "long-capture-auto-scroll",
+            Self::Mask => "long-capture-mask",
+            Self::Review => "long-capture-review",
+            Self::Diagnostics => "long-capture-diagnostics",
             Self::Save => "long-capture-save",
             Self::Pin => "long-capture-pin",
             Self::Copy => "long-capture-copy",
@@ -43,6 +77,10 @@ impl LongCaptureToolbarAction {
 
     pub(super) fn icon(self) -> LongCaptureToolbarIcon {
         match self {
+            Self::AutoScroll => LongCaptureToolbarIcon::AutoScroll,
+            Self::Mask => LongCaptureToolbarIcon::Mask,
+            Self::Review => LongCaptureToolbarIcon::Review,
+            Self::Diagnostics => LongCaptureToolbarIcon::Diagnostics,
             Self::Save => LongCaptureToolbarIcon::Save,
             Self::Pin => LongCaptureToolbarIcon::Pin,
             Self::Copy => LongCaptureToolbarIcon::Copy,
@@ -52,6 +90,10 @@ impl LongCaptureToolbarAction {
 
     pub(super) fn tooltip(self) -> String {
         match self {
+            Self::AutoScroll => i18n::overlay::long_capture_auto_scroll(),
+            Self::Mask => i18n::overlay::long_capture_mask(),
+            Self::Review => i18n::overlay::long_capture_review(),
+            Self::Diagnostics => i18n::overlay::long_capture_diagnostics(),
             Self::Save => i18n::common::save(),
             Self::Pin => i18n::common::pin(),
             Self::Copy => i18n::common::copy(),
@@ -60,6 +102,6 @@ impl LongCaptureToolbarAction {
     }
 
     pub(super) fn disabled_when_busy(self) -> bool {
-        self != Self::Cancel
+        !matches!(self, Self::Diagnostics | Self::Cancel)
     }
 }
@@ -1,4 +1,6 @@
+pub mod history;
 pub mod long_capture;
 pub mod overlay;
 pub mod pin;
 pub mod preferences;
+pub mod recording;
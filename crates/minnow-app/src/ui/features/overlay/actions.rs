@@ -22,9 +22,16 @@ actions!(
         SelectCounterTool,
         SelectTextTool,
         SelectMosaicTool,
+        SelectPenTool,
+        SelectHighlighterTool,
         UndoAnnotationAction,
         RedoAnnotationAction,
         DeleteAnnotation,
+        SelectAllAnnotations,
+        BringAnnotationsToFront,
+        SendAnnotationsToBack,
+        GroupAnnotations,
+        UngroupAnnotations,
         CycleAnnotationColorAction,
         ToggleAnnotationFillAction,
         IncreaseAnnotationStroke,
@@ -54,10 +61,17 @@ pub fn bind_keys(cx: &mut App) {
         KeyBinding::new("4", SelectCounterTool, Some(OVERLAY_CONTEXT)),
         KeyBinding::new("5", SelectTextTool, Some(OVERLAY_CONTEXT)),
         KeyBinding::new("6", SelectMosaicTool, Some(OVERLAY_CONTEXT)),
+        KeyBinding::new("7", SelectPenTool, Some(OVERLAY_CONTEXT)),
+        KeyBinding::new("8", SelectHighlighterTool, Some(OVERLAY_CONTEXT)),
         KeyBinding::new("ctrl-z", UndoAnnotationAction, Some(OVERLAY_CONTEXT)),
         KeyBinding::new("ctrl-shift-z", RedoAnnotationAction, Some(OVERLAY_CONTEXT)),
         KeyBinding::new("backspace", DeleteAnnotation, Some(OVERLAY_CONTEXT)),
         KeyBinding::new("delete", DeleteAnnotation, Some(OVERLAY_CONTEXT)),
+        KeyBinding::new("ctrl-a", SelectAllAnnotations, Some(OVERLAY_CONTEXT)),
+        KeyBinding::new("ctrl-]", BringAnnotationsToFront, Some(OVERLAY_CONTEXT)),
+        KeyBinding::new("ctrl-[", SendAnnotationsToBack, Some(OVERLAY_CONTEXT)),
+        KeyBinding::new("ctrl-g", GroupAnnotations, Some(OVERLAY_CONTEXT)),
+        KeyBinding::new("ctrl-shift-g", UngroupAnnotations, Some(OVERLAY_CONTEXT)),
         KeyBinding::new("x", CycleAnnotationColorAction, Some(OVERLAY_CONTEXT)),
         KeyBinding::new("f", ToggleAnnotationFillAction, Some(OVERLAY_CONTEXT)),
         KeyBinding::new("]", IncreaseAnnotationStroke, Some(OVERLAY_CONTEXT)),
@@ -6,7 +6,7 @@ use super::hit_test::AnnotationHitProxy;
 use super::model::{AnnotationInteractionState, AnnotationItem, AnnotationKind, AnnotationStyleState, AnnotationTool, TextEditState};
 use super::ops::sync_style_from_item;
 use super::raster_cache::AnnotationRasterCache;
-use super::store::AnnotationStore;
+use super::store::{AnnotationStore, ChangeKind};
 
 mod document;
 mod interaction;
@@ -19,7 +19,9 @@ pub(crate) struct AnnotationEngine {
     store: AnnotationStore,
     tool: Option<AnnotationTool>,
     style: AnnotationStyleState,
-    selected_id: Option<u64>,
+    /// The selected items, the last of which is the one the properties panel
+    /// follows.
+    selected_ids: Vec<u64>,
     interaction: AnnotationInteractionState,
     text_editing: Option<TextEditState>,
     next_id: u64,
@@ -36,7 +38,7 @@ impl Default for AnnotationEngine {
             store: AnnotationStore::default(),
             tool: None,
             style: AnnotationStyleState::default(),
-            selected_id: None,
+            selected_ids: Vec::new(),
             interaction: AnnotationInteractionState::Idle,
             text_editing: None,
             next_id: 1,
@@ -54,7 +56,7 @@ impl AnnotationEngine {
         self.store.clear();
         self.tool = None;
         self.style = AnnotationStyleState::default();
-        self.selected_id = None;
+        self.selected_ids.clear();
         self.interaction = AnnotationInteractionState::Idle;
         self.text_editing = None;
         self.next_id = 1;
@@ -66,7 +68,7 @@ impl AnnotationEngine {
     }
 
     pub(crate) fn selected_item(&self) -> Option<&AnnotationItem> {
-        let id = self.selected_id?;
+        let id = self.primary_id()?;
         self.store.visible_item(id)
     }
 
@@ -109,14 +111,61 @@ impl AnnotationEngine {
 
     fn commit_item(&mut self, item: AnnotationItem) {
         self.store.push(item);
-        self.selected_id = self.store.visible_items().last().map(|annotation| annotation.id);
+        self.selected_ids = self.store.visible_items().last().map(|annotation| annotation.id).into_iter().collect();
         self.sync_style_from_selected();
         self.bump_committed();
     }
 
-    fn selected_item_mut(&mut self) -> Option<&mut AnnotationItem> {
-        let id = self.selected_id?;
-        self.store.visible_item_mut(id)
+    fn primary_id(&self) -> Option<u64> {
+        self.selected_ids.last().copied()
+    }
+
+    /// The only selected item, which is the one that shows handles.
+    fn sole_selected_item(&self) -> Option<&AnnotationItem> {
+        match self.selected_ids.as_slice() {
+            [id] => self.store.visible_item(*id),
+            _ => None,
+        }
+    }
+
+    /// `id` and the other items in its group, ending with `id`.
+    fn with_group(&self, id: u64) -> Vec<u64> {
+        let group = self.store.visible_item(id).and_then(|item| item.group);
+        let mut ids: Vec<u64> = self
+            .store
+            .visible_items()
+            .iter()
+            .filter(|item| group.is_some() && item.group == group && item.id != id)
+            .map(|item| item.id)
+            .collect();
+        ids.push(id);
+        ids
+    }
+
+    /// Selects the items in `ids` that still exist, each once at its last
+    /// position so the last one named stays the primary item.
+    fn set_selection(&mut self, ids: Vec<u64>) -> bool {
+        let mut selected: Vec<u64> = Vec::with_capacity(ids.len());
+        for id in ids.into_iter().rev() {
+            if !selected.contains(&id) && self.store.visible_contains(id) {
+                selected.push(id);
+            }
+        }
+        selected.reverse();
+        let changed = self.selected_ids != selected;
+        self.selected_ids = selected;
+        if self.text_editing.as_ref().is_some_and(|state| Some(state.id) != self.primary_id()) {
+            self.text_editing = None;
+            self.bump_transient();
+        }
+        self.sync_style_from_selected();
+        changed
+    }
+
+    /// Applies `update` to every selected item as one undo step.
+    fn update_selected(&mut self, kind: ChangeKind, update: impl FnMut(&mut AnnotationItem)) -> bool {
+        let ids = self.selected_ids.clone();
+        self.store.update_visible_items(&ids, kind, update)
     }
 
     fn sync_style_from_selected(&mut self) {
@@ -162,7 +211,7 @@ impl AnnotationEngine {
 
 #[cfg(test)]
 mod tests {
-    use super::super::model::{MosaicMode, TEXT_DEFAULT};
+    use super::super::model::{AnnotationHandle, ArrowHeads, BoxHandle, COLOR_PRESETS, LineDash, MosaicMode, TEXT_DEFAULT};
     use super::*;
     use image::RgbaImage;
     use std::sync::Arc;
@@ -500,4 +549,343 @@ mod tests {
         assert!(after.drawing_fast_path_hits >= before.drawing_fast_path_hits + changed_steps);
         assert_eq!(after.committed_rebuilds, before.committed_rebuilds);
     }
+
+    #[test]
+    fn pen_stroke_is_smoothed_hit_along_its_path_and_movable() {
+        let mut engine = AnnotationEngine::default();
+        let sel = Some(selection());
+
+        engine.set_tool(AnnotationTool::Pen);
+        assert!(engine.start_draw((40.0, 60.0), sel, true));
+        for x in 41..=120 {
+            engine.update_interaction((f64::from(x), 60.0), sel);
+        }
+        for y in 61..=100 {
+            engine.update_interaction((120.0, f64::from(y)), sel);
+        }
+        assert!(engine.finish_interaction(8.0));
+        let item = engine.selected_item().cloned().unwrap();
+        let AnnotationKind::Pen { points } = &item.kind else {
+            panic!("expected pen");
+        };
+        assert!(!points.contains(&(120.0, 60.0)), "the corner should be rounded off");
+        assert_eq!((points.first(), points.last()), (Some(&(40.0, 60.0)), Some(&(120.0, 100.0))));
+
+        assert_eq!(engine.hit_test((80.0, 61.0), sel, true), Some(item.id));
+        assert_eq!(engine.hit_test((60.0, 95.0), sel, true), None);
+
+        assert!(engine.start_move(item.id, (80.0, 60.0), sel, true));
+        assert!(engine.update_interaction((90.0, 70.0), sel));
+        assert!(engine.finish_interaction(8.0));
+        let AnnotationKind::Pen { points: moved } = &engine.selected_item().unwrap().kind else {
+            panic!("expected pen");
+        };
+        assert_eq!(moved.first(), Some(&(50.0, 70.0)));
+    }
+
+    #[test]
+    fn curved_arrow_is_hit_along_its_bend_and_can_be_reshaped_and_undone() {
+        let mut engine = AnnotationEngine::default();
+        let sel = Some(selection());
+
+        engine.set_tool(AnnotationTool::Arrow);
+        assert!(engine.toggle_line_curve());
+        assert!(engine.start_draw((40.0, 100.0), sel, true));
+        assert!(engine.update_interaction((160.0, 100.0), sel));
+        assert!(engine.finish_interaction(8.0));
+        let id = engine.selected_item().unwrap().id;
+        assert_eq!(
+            engine.kind_for(id),
+            Some(AnnotationKind::Arrow {
+                start: (40.0, 100.0),
+                end: (160.0, 100.0),
+                control: Some((100.0, 70.0)),
+            })
+        );
+        assert_eq!(engine.hit_test((100.0, 85.0), sel, true), Some(id));
+        assert_eq!(engine.hit_test((100.0, 101.0), sel, true), None);
+
+        assert_eq!(engine.handle_hit((103.0, 72.0), sel, true), Some((id, AnnotationHandle::Control)));
+        assert!(engine.start_reshape(id, AnnotationHandle::Control, (103.0, 72.0), sel, true));
+        assert!(engine.update_interaction((103.0, 52.0), sel));
+        assert!(engine.finish_interaction(8.0));
+        assert!(matches!(
+            engine.kind_for(id),
+            Some(AnnotationKind::Arrow {
+                control: Some((100.0, 50.0)),
+                ..
+            })
+        ));
+
+        assert!(engine.undo());
+        assert!(matches!(
+            engine.kind_for(id),
+            Some(AnnotationKind::Arrow {
+                control: Some((100.0, 70.0)),
+                ..
+            })
+        ));
+
+        assert_eq!(engine.handle_hit((160.0, 100.0), sel, true), Some((id, AnnotationHandle::End)));
+        assert!(engine.start_reshape(id, AnnotationHandle::End, (160.0, 100.0), sel, true));
+        assert!(engine.update_interaction((100.0, 100.0), sel));
+        assert!(engine.finish_interaction(8.0));
+        assert_eq!(
+            engine.kind_for(id),
+            Some(AnnotationKind::Arrow {
+                start: (40.0, 100.0),
+                end: (100.0, 100.0),
+                control: Some((70.0, 85.0)),
+            }),
+            "the bend should shrink with the line"
+        );
+    }
+
+    #[test]
+    fn box_handles_resize_and_turn_the_selected_rectangle() {
+        let mut engine = AnnotationEngine::default();
+        let sel = Some(selection());
+
+        engine.set_tool(AnnotationTool::Rectangle);
+        assert!(engine.start_draw((60.0, 50.0), sel, true));
+        assert!(engine.update_interaction((100.0, 80.0), sel));
+        assert!(engine.finish_interaction(8.0));
+        let id = engine.selected_item().unwrap().id;
+        let corner = AnnotationHandle::Resize(BoxHandle { x: 1, y: 1 });
+
+        assert_eq!(engine.handle_hit((101.0, 81.0), sel, true), Some((id, corner)));
+        assert!(engine.start_reshape(id, corner, (101.0, 81.0), sel, true));
+        assert!(engine.update_interaction((121.0, 91.0), sel));
+        assert!(engine.finish_interaction(8.0));
+        assert_eq!(engine.selected_item().unwrap().bounds(), RectF::new(60.0, 50.0, 60.0, 40.0));
+
+        assert!(engine.undo());
+        assert_eq!(engine.selected_item().unwrap().bounds(), RectF::new(60.0, 50.0, 40.0, 30.0));
+        assert!(engine.redo());
+
+        assert_eq!(engine.handle_hit((90.0, 26.0), sel, true), Some((id, AnnotationHandle::Rotate)));
+        assert!(engine.start_reshape(id, AnnotationHandle::Rotate, (90.0, 26.0), sel, true));
+        assert!(engine.update_interaction((134.0, 71.0), sel));
+        assert!(engine.finish_interaction(8.0));
+        let item = engine.selected_item().unwrap();
+        assert_eq!(item.frame(), Some((RectF::new(60.0, 50.0, 60.0, 40.0), std::f64::consts::FRAC_PI_2)));
+        let bounds = item.bounds();
+        assert!((bounds.x - 70.0).abs() < 1e-9 && (bounds.height - 60.0).abs() < 1e-9);
+        assert_eq!(engine.hit_test((90.0, 45.0), sel, true), Some(id));
+        assert_eq!(engine.hit_test((65.0, 70.0), sel, true), None);
+    }
+
+    #[test]
+    fn line_controls_restyle_the_selected_arrow() {
+        let mut engine = AnnotationEngine::default();
+        let sel = Some(selection());
+
+        engine.set_tool(AnnotationTool::Arrow);
+        assert!(engine.start_draw((40.0, 60.0), sel, true));
+        assert!(engine.update_interaction((160.0, 60.0), sel));
+        assert!(engine.finish_interaction(8.0));
+        let id = engine.selected_item().unwrap().id;
+
+        assert!(engine.set_arrow_heads(ArrowHeads::Both));
+        assert!(!engine.set_arrow_heads(ArrowHeads::Both));
+        assert!(engine.toggle_line_dash());
+        assert!(engine.toggle_line_curve());
+        let item = engine.selected_item().unwrap();
+        assert_eq!((item.style.arrow_heads, item.style.line_dash), (ArrowHeads::Both, LineDash::Dashed));
+        assert!(matches!(engine.kind_for(id), Some(AnnotationKind::Arrow { control: Some(_), .. })));
+
+        assert!(engine.toggle_line_curve());
+        assert!(matches!(engine.kind_for(id), Some(AnnotationKind::Arrow { control: None, .. })));
+    }
+
+    fn draw_rectangle(engine: &mut AnnotationEngine, from: (f64, f64), to: (f64, f64)) -> u64 {
+        let sel = Some(selection());
+        engine.tool = Some(AnnotationTool::Rectangle);
+        assert!(engine.start_draw(from, sel, true));
+        assert!(engine.update_interaction(to, sel));
+        assert!(engine.finish_interaction(8.0));
+        engine.selected_item().unwrap().id
+    }
+
+    #[test]
+    fn rubber_band_and_shift_click_select_several_items_to_move_restyle_and_delete() {
+        let mut engine = AnnotationEngine::default();
+        let sel = Some(selection());
+        let a = draw_rectangle(&mut engine, (30.0, 30.0), (60.0, 60.0));
+        let b = draw_rectangle(&mut engine, (80.0, 30.0), (110.0, 60.0));
+        let c = draw_rectangle(&mut engine, (130.0, 80.0), (170.0, 110.0));
+        engine.tool = None;
+
+        assert!(engine.select(None));
+        assert!(engine.start_rubber_band((25.0, 25.0), sel, true));
+        assert!(engine.update_interaction((90.0, 50.0), sel));
+        assert_eq!(
+            engine.ui_state(sel, None, 1.0, None).layer.rubber_band,
+            Some(RectF::new(25.0, 25.0, 65.0, 25.0))
+        );
+        assert!(engine.finish_interaction(8.0));
+        assert_eq!(engine.selected_ids, [a, b]);
+        assert!(engine.ui_state(sel, None, 1.0, None).layer.handles.is_empty());
+
+        assert!(engine.toggle_selected(c));
+        assert!(engine.toggle_selected(a));
+        assert_eq!(engine.selected_ids, [b, c]);
+
+        assert!(engine.start_move(b, (90.0, 40.0), sel, true));
+        assert!(engine.update_interaction((95.0, 50.0), sel));
+        assert!(engine.finish_interaction(8.0));
+        assert_eq!(engine.store.visible_item(b).unwrap().bounds().y, 40.0);
+        assert_eq!(engine.store.visible_item(c).unwrap().bounds().y, 90.0);
+        assert_eq!(engine.store.visible_item(a).unwrap().bounds().y, 30.0);
+
+        assert!(engine.set_color(0x2196f3ff));
+        assert!(
+            engine
+                .selected_ids
+                .iter()
+                .all(|id| engine.store.visible_item(*id).unwrap().style.stroke_color == 0x2196f3ff)
+        );
+        assert!(engine.undo());
+        assert_eq!(engine.store.visible_item(c).unwrap().style.stroke_color, COLOR_PRESETS[0]);
+
+        assert!(engine.delete_selected());
+        assert_eq!(engine.items().iter().map(|item| item.id).collect::<Vec<_>>(), [a]);
+        assert!(engine.undo());
+        assert_eq!(engine.items().len(), 3);
+    }
+
+    #[test]
+    fn grouped_items_are_selected_together_and_restack_as_one() {
+        let mut engine = AnnotationEngine::default();
+        let sel = Some(selection());
+        let a = draw_rectangle(&mut engine, (30.0, 30.0), (60.0, 60.0));
+        let b = draw_rectangle(&mut engine, (80.0, 30.0), (110.0, 60.0));
+        let c = draw_rectangle(&mut engine, (40.0, 40.0), (100.0, 100.0));
+
+        assert!(!engine.group_selected());
+        assert!(engine.select(Some(a)));
+        assert!(engine.toggle_selected(b));
+        assert!(engine.group_selected());
+        assert!(engine.select(None));
+
+        assert!(engine.start_move(b, (95.0, 45.0), sel, true));
+        assert_eq!(engine.selected_ids, [a, b]);
+        assert!(engine.update_interaction((105.0, 45.0), sel));
+        assert!(engine.finish_interaction(8.0));
+        assert_eq!(engine.store.visible_item(a).unwrap().bounds().x, 40.0);
+
+        let order = |engine: &AnnotationEngine| engine.items().iter().map(|item| item.id).collect::<Vec<_>>();
+        assert!(engine.bring_selected_to_front());
+        assert_eq!(order(&engine), [c, a, b]);
+        assert_eq!(engine.hit_test((45.0, 45.0), sel, true), Some(a));
+        assert!(engine.send_selected_to_back());
+        assert_eq!(order(&engine), [a, b, c]);
+        assert!(!engine.send_selected_to_back());
+
+        assert!(engine.ungroup_selected());
+        assert!(engine.select(Some(a)));
+        assert_eq!(engine.selected_ids, [a]);
+        assert!(engine.undo());
+        assert!(engine.select(Some(a)));
+        assert_eq!(engine.selected_ids, [b, a]);
+    }
+
+    #[test]
+    fn wheel_resizes_and_text_edits_undo_in_single_steps() {
+        let mut engine = AnnotationEngine::default();
+        let sel = Some(selection());
+        let id = draw_rectangle(&mut engine, (40.0, 40.0), (90.0, 80.0));
+        let width = engine.selected_item().unwrap().style.stroke_width;
+
+        for _ in 0..4 {
+            assert!(engine.adjust_selected_by_wheel((40.0, 60.0), 1.0, sel, true));
+        }
+        assert_eq!(engine.selected_item().unwrap().style.stroke_width, width + 4.0);
+        assert!(engine.undo());
+        assert_eq!(engine.selected_item().unwrap().style.stroke_width, width);
+        assert!(engine.undo());
+        assert_eq!(engine.kind_for(id), None);
+
+        engine.tool = Some(AnnotationTool::Text);
+        assert!(engine.start_draw((40.0, 60.0), sel, true));
+        assert!(engine.append_text_edit("!"));
+        assert!(engine.commit_text_edit());
+        assert!(engine.begin_text_edit_selected());
+        assert!(engine.append_text_edit("?"));
+        assert!(engine.commit_text_edit());
+        let text_id = engine.selected_item().unwrap().id;
+
+        assert!(engine.undo());
+        assert!(matches!(engine.kind_for(text_id), Some(AnnotationKind::Text { text, .. }) if text == TEXT_DEFAULT));
+        assert!(engine.redo());
+        assert!(matches!(engine.kind_for(text_id), Some(AnnotationKind::Text { text, .. }) if text == "Text!?"));
+    }
+
+    #[test]
+    fn restored_groups_do_not_clash_with_new_ones() {
+        let mut engine = AnnotationEngine::default();
+        let item = |group| AnnotationItem {
+            id: 40,
+            style: AnnotationStyleState::default(),
+            kind: AnnotationKind::Rectangle {
+                rect: RectF::new(30.0, 30.0, 20.0, 20.0),
+                rotation: 0.0,
+            },
+            group,
+        };
+        assert!(engine.restore_items(vec![item(Some(2)), item(Some(2)), item(None)]));
+
+        let groups: Vec<_> = engine.items().iter().map(|item| item.group).collect();
+        assert_eq!(groups, [Some(4), Some(4), None]);
+        assert!(engine.select(Some(3)));
+        assert!(engine.toggle_selected(1));
+        assert!(engine.group_selected());
+        assert_eq!(engine.store.visible_item(3).unwrap().group, Some(5));
+    }
+
+    #[test]
+    fn highlighter_multiplies_its_color_into_the_background_once() {
+        let mut background = RgbaImage::from_pixel(200, 120, image::Rgba([255, 255, 255, 255]));
+        background.put_pixel(100, 60, image::Rgba([0, 0, 0, 255]));
+        let item = AnnotationItem {
+            id: 1,
+            style: AnnotationStyleState {
+                stroke_color: 0xffff00ff,
+                ..AnnotationStyleState::default()
+            },
+            kind: AnnotationKind::Highlighter {
+                points: vec![(40.0, 60.0), (160.0, 60.0), (60.0, 60.0)],
+            },
+            group: None,
+        };
+
+        let composed = super::super::raster::compose_background_with_annotations(&background, &[item], 1.0);
+
+        assert_eq!(composed.get_pixel(80, 60).0, [255, 255, 77, 255]);
+        assert_eq!(composed.get_pixel(100, 60).0, [0, 0, 0, 255]);
+        assert_eq!(composed.get_pixel(80, 100).0, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn turned_mosaic_only_covers_its_own_region() {
+        let background = RgbaImage::from_fn(200, 120, |x, _| {
+            image::Rgba(if x % 2 == 0 { [0, 0, 0, 255] } else { [255, 255, 255, 255] })
+        });
+        let item = AnnotationItem {
+            id: 1,
+            style: AnnotationStyleState::default(),
+            kind: AnnotationKind::Mosaic {
+                rect: RectF::new(60.0, 40.0, 80.0, 40.0),
+                mode: MosaicMode::Pixelate,
+                intensity: 10.0,
+                rotation: std::f64::consts::FRAC_PI_4,
+            },
+            group: None,
+        };
+
+        let composed = super::super::raster::compose_background_with_annotations(&background, &[item], 1.0);
+
+        assert!(![0, 255].contains(&composed.get_pixel(100, 60).0[0]));
+        assert_eq!(composed.get_pixel(125, 40), background.get_pixel(125, 40));
+    }
 }
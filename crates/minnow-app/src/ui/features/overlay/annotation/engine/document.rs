@@ -1,16 +1,63 @@
+use std::collections::HashMap;
+
 use super::AnnotationEngine;
+use crate::ui::features::overlay::annotation::model::{AnnotationItem, AnnotationKind};
 
 impl AnnotationEngine {
+    pub(crate) fn items(&self) -> &[AnnotationItem] {
+        self.store.visible_items()
+    }
+
+    /// Starts a fresh document from `items`, e.g. a capture reopened from
+    /// history. The restored items are the undo baseline.
+    pub(crate) fn restore_items(&mut self, items: Vec<AnnotationItem>) -> bool {
+        self.clear();
+        if items.is_empty() {
+            return false;
+        }
+
+        let mut groups = HashMap::new();
+        let mut restored = Vec::with_capacity(items.len());
+        for mut item in items {
+            item.id = self.consume_id();
+            if let AnnotationKind::Counter { number, .. } = item.kind {
+                self.next_counter = self.next_counter.max(number.saturating_add(1));
+            }
+            restored.push(item);
+        }
+        for item in &mut restored {
+            item.group = item.group.map(|group| *groups.entry(group).or_insert_with(|| self.consume_id()));
+        }
+        self.store.reset_to(restored);
+        self.bump_committed();
+        true
+    }
+
+    /// Selects `id` together with its group, or nothing.
     pub(crate) fn select(&mut self, id: Option<u64>) -> bool {
-        let next = id.filter(|item_id| self.store.visible_contains(*item_id));
-        let changed = self.selected_id != next;
-        self.selected_id = next;
-        if self.text_editing.as_ref().is_some_and(|state| Some(state.id) != self.selected_id) {
-            self.text_editing = None;
-            self.bump_transient();
+        let ids = id.map(|id| self.with_group(id)).unwrap_or_default();
+        self.set_selection(ids)
+    }
+
+    /// Adds `id` and its group to the selection, or takes them out when
+    /// already selected.
+    pub(crate) fn toggle_selected(&mut self, id: u64) -> bool {
+        if !self.store.visible_contains(id) {
+            return false;
         }
-        self.sync_style_from_selected();
-        changed
+        let group = self.with_group(id);
+        let mut ids = self.selected_ids.clone();
+        if ids.contains(&id) {
+            ids.retain(|selected| !group.contains(selected));
+        } else {
+            ids.extend(group);
+        }
+        self.set_selection(ids)
+    }
+
+    pub(crate) fn select_all(&mut self) -> bool {
+        let ids = self.store.visible_items().iter().map(|item| item.id).collect();
+        self.set_selection(ids)
     }
 
     pub(crate) fn translate_all_annotations(&mut self, dx: f64, dy: f64) -> bool {
@@ -23,26 +70,58 @@ impl AnnotationEngine {
     }
 
     pub(crate) fn delete_selected(&mut self) -> bool {
-        let Some(id) = self.selected_id else {
-            return false;
-        };
-        if !self.store.remove_visible_by_id(id) {
+        if !self.store.remove_visible_by_ids(&self.selected_ids) {
             return false;
         }
-        self.selected_id = None;
+        self.selected_ids.clear();
         self.text_editing = None;
         self.bump_committed();
         true
     }
 
+    pub(crate) fn bring_selected_to_front(&mut self) -> bool {
+        let changed = self.store.bring_to_front(&self.selected_ids);
+        if changed {
+            self.bump_committed();
+        }
+        changed
+    }
+
+    pub(crate) fn send_selected_to_back(&mut self) -> bool {
+        let changed = self.store.send_to_back(&self.selected_ids);
+        if changed {
+            self.bump_committed();
+        }
+        changed
+    }
+
+    /// Puts the selected items, and any groups they belong to, into one new
+    /// group.
+    pub(crate) fn group_selected(&mut self) -> bool {
+        if self.selected_ids.len() < 2 {
+            return false;
+        }
+        let group = self.consume_id();
+        let changed = self.store.set_visible_group(&self.selected_ids, Some(group));
+        if changed {
+            self.bump_committed();
+        }
+        changed
+    }
+
+    pub(crate) fn ungroup_selected(&mut self) -> bool {
+        let changed = self.store.set_visible_group(&self.selected_ids, None);
+        if changed {
+            self.bump_committed();
+        }
+        changed
+    }
+
     pub(crate) fn undo(&mut self) -> bool {
         if !self.store.undo() {
             return false;
         }
-        self.selected_id = self.selected_id.filter(|id| self.store.visible_contains(*id));
-        if self.text_editing.as_ref().is_some_and(|state| !self.store.visible_contains(state.id)) {
-            self.text_editing = None;
-        }
+        self.forget_missing_items();
         self.bump_committed();
         true
     }
@@ -51,7 +130,16 @@ impl AnnotationEngine {
         if !self.store.redo() {
             return false;
         }
+        self.forget_missing_items();
         self.bump_committed();
         true
     }
+
+    /// Drops selected or edited items that an undo or redo took away.
+    fn forget_missing_items(&mut self) {
+        self.selected_ids.retain(|id| self.store.visible_contains(*id));
+        if self.text_editing.as_ref().is_some_and(|state| !self.store.visible_contains(state.id)) {
+            self.text_editing = None;
+        }
+    }
 }
@@ -1,7 +1,9 @@
 use crate::services::geometry::RectF;
 
-use super::super::model::{AnnotationInteractionState, AnnotationItem, AnnotationKind, AnnotationTool, TEXT_DEFAULT, TextEditState};
-use super::super::ops::{annotation_item_large_enough, build_drawing_item};
+use super::super::model::{
+    AnnotationHandle, AnnotationInteractionState, AnnotationItem, AnnotationKind, AnnotationTool, TEXT_DEFAULT, TextEditState,
+};
+use super::super::ops::{HANDLE_RADIUS, annotation_item_large_enough, build_drawing_item, normalize_draw_rect, rects_overlap};
 use super::AnnotationEngine;
 
 impl AnnotationEngine {
@@ -37,6 +39,7 @@ impl AnnotationEngine {
                     id,
                     style: self.style,
                     kind: AnnotationKind::Counter { center: point, number },
+                    group: None,
                 };
                 self.commit_item(item);
                 true
@@ -50,6 +53,7 @@ impl AnnotationEngine {
                         origin: point,
                         text: TEXT_DEFAULT.to_string(),
                     },
+                    group: None,
                 };
                 self.commit_item(item);
                 self.text_editing = Some(TextEditState {
@@ -64,6 +68,7 @@ impl AnnotationEngine {
                     tool,
                     start: point,
                     current: point,
+                    path: if tool.is_freehand() { vec![point] } else { Vec::new() },
                     style: self.style,
                 };
                 self.bump_transient();
@@ -76,24 +81,81 @@ impl AnnotationEngine {
         if !self.mode_enabled(selection, idle_mode) {
             return false;
         }
-        let Some(item) = self.store.visible_item(id) else {
+        if !self.store.visible_contains(id) {
             return false;
-        };
-        let anchor = item.kind.clone();
+        }
         let point = self.clamp_to_selection(point, selection);
-        self.selected_id = Some(id);
+        let mut ids = if self.selected_ids.contains(&id) {
+            self.selected_ids.clone()
+        } else {
+            self.with_group(id)
+        };
+        ids.retain(|selected| *selected != id);
+        ids.push(id);
+        self.selected_ids.clone_from(&ids);
         self.text_editing = None;
         self.interaction = AnnotationInteractionState::Moving {
+            ids,
+            start: point,
+            current: point,
+        };
+        self.sync_style_from_selected();
+        self.bump_transient();
+        true
+    }
+
+    /// The selected item and the handle of it nearest to `point`, when that
+    /// handle is close enough to grab and no other item is selected.
+    pub(crate) fn handle_hit(&self, point: (f64, f64), selection: Option<RectF>, idle_mode: bool) -> Option<(u64, AnnotationHandle)> {
+        if !self.mode_enabled(selection, idle_mode) || self.text_editing.is_some() {
+            return None;
+        }
+        let item = self.sole_selected_item()?;
+        item.handles(selection)
+            .into_iter()
+            .map(|(handle, at)| (handle, ((point.0 - at.0).powi(2) + (point.1 - at.1).powi(2)).sqrt()))
+            .filter(|(_, distance)| *distance <= HANDLE_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(handle, _)| (item.id, handle))
+    }
+
+    pub(crate) fn start_reshape(&mut self, id: u64, handle: AnnotationHandle, point: (f64, f64), selection: Option<RectF>, idle_mode: bool) -> bool {
+        if !self.mode_enabled(selection, idle_mode) {
+            return false;
+        }
+        let Some(origin) = self.kind_for(id) else {
+            return false;
+        };
+        let point = self.clamp_to_selection(point, selection);
+        self.selected_ids = vec![id];
+        self.text_editing = None;
+        self.interaction = AnnotationInteractionState::Reshaping {
             id,
+            handle,
             start: point,
             current: point,
-            origin: anchor,
+            origin,
         };
         self.sync_style_from_selected();
         self.bump_transient();
         true
     }
 
+    /// Starts dragging out a box to add items to the selection from
+    /// `point`, which has to lie inside the capture.
+    pub(crate) fn start_rubber_band(&mut self, point: (f64, f64), selection: Option<RectF>, idle_mode: bool) -> bool {
+        if !self.mode_enabled(selection, idle_mode) || !self.point_in_selection(point, selection) {
+            return false;
+        }
+        self.text_editing = None;
+        self.interaction = AnnotationInteractionState::RubberBand {
+            start: point,
+            current: point,
+        };
+        self.bump_transient();
+        true
+    }
+
     pub(crate) fn update_interaction(&mut self, point: (f64, f64), selection: Option<RectF>) -> bool {
         let interaction = std::mem::take(&mut self.interaction);
         match interaction {
@@ -101,13 +163,23 @@ impl AnnotationEngine {
                 self.interaction = AnnotationInteractionState::Idle;
                 false
             }
-            AnnotationInteractionState::Drawing { tool, start, current, style } => {
+            AnnotationInteractionState::Drawing {
+                tool,
+                start,
+                current,
+                mut path,
+                style,
+            } => {
                 let next = self.clamp_to_selection(point, selection);
                 let changed = current != next;
+                if changed && tool.is_freehand() {
+                    path.push(next);
+                }
                 self.interaction = AnnotationInteractionState::Drawing {
                     tool,
                     start,
                     current: next,
+                    path,
                     style,
                 };
                 if changed {
@@ -115,11 +187,27 @@ impl AnnotationEngine {
                 }
                 changed
             }
-            AnnotationInteractionState::Moving { id, start, current, origin } => {
+            AnnotationInteractionState::Moving { ids, start, current } => {
                 let next = self.clamp_to_selection(point, selection);
                 let changed = current != next;
-                self.interaction = AnnotationInteractionState::Moving {
+                self.interaction = AnnotationInteractionState::Moving { ids, start, current: next };
+                if changed {
+                    self.bump_transient();
+                }
+                changed
+            }
+            AnnotationInteractionState::Reshaping {
+                id,
+                handle,
+                start,
+                current,
+                origin,
+            } => {
+                let next = self.clamp_to_selection(point, selection);
+                let changed = current != next;
+                self.interaction = AnnotationInteractionState::Reshaping {
                     id,
+                    handle,
                     start,
                     current: next,
                     origin,
@@ -129,15 +217,30 @@ impl AnnotationEngine {
                 }
                 changed
             }
+            AnnotationInteractionState::RubberBand { start, current } => {
+                let next = self.clamp_to_selection(point, selection);
+                let changed = current != next;
+                self.interaction = AnnotationInteractionState::RubberBand { start, current: next };
+                if changed {
+                    self.bump_transient();
+                }
+                changed
+            }
         }
     }
 
     pub(crate) fn finish_interaction(&mut self, min_selection_size: f64) -> bool {
         match std::mem::take(&mut self.interaction) {
             AnnotationInteractionState::Idle => false,
-            AnnotationInteractionState::Drawing { tool, start, current, style } => {
+            AnnotationInteractionState::Drawing {
+                tool,
+                start,
+                current,
+                path,
+                style,
+            } => {
                 let id = self.consume_id();
-                if let Some(item) = build_drawing_item(tool, start, current, style, id)
+                if let Some(item) = build_drawing_item(tool, start, current, &path, style, id)
                     && annotation_item_large_enough(&item, min_selection_size)
                 {
                     self.commit_item(item);
@@ -146,20 +249,51 @@ impl AnnotationEngine {
                 self.bump_transient();
                 false
             }
-            AnnotationInteractionState::Moving { id, start, current, .. } => {
+            AnnotationInteractionState::Moving { ids, start, current } => {
                 let dx = current.0 - start.0;
                 let dy = current.1 - start.1;
                 if dx.abs() <= f64::EPSILON && dy.abs() <= f64::EPSILON {
                     self.bump_transient();
                     return false;
                 }
-                if !self.store.move_visible_item_by(id, dx, dy) {
+                if !self.store.move_visible_items_by(&ids, dx, dy) {
+                    self.bump_transient();
+                    return false;
+                }
+                self.bump_committed();
+                true
+            }
+            AnnotationInteractionState::Reshaping {
+                id,
+                handle,
+                start,
+                current,
+                origin,
+            } => {
+                let Some(mut item) = self.store.visible_item(id).cloned() else {
+                    self.bump_transient();
+                    return false;
+                };
+                item.drag_handle(&origin, handle, start, current);
+                if !annotation_item_large_enough(&item, min_selection_size) || !self.store.replace_visible_kind(id, item.kind) {
                     self.bump_transient();
                     return false;
                 }
                 self.bump_committed();
                 true
             }
+            AnnotationInteractionState::RubberBand { start, current } => {
+                let band = normalize_draw_rect(start, current);
+                let mut ids = self.selected_ids.clone();
+                for item in self.store.visible_items() {
+                    if !ids.contains(&item.id) && rects_overlap(band, item.bounds()) {
+                        ids.extend(self.with_group(item.id));
+                    }
+                }
+                self.set_selection(ids);
+                self.bump_transient();
+                true
+            }
         }
     }
 }
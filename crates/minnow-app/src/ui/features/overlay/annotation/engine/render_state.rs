@@ -11,7 +11,7 @@ use super::super::model::{
     AnnotationInteractionState, AnnotationItem, AnnotationKind, AnnotationKindTag, AnnotationLayerState, AnnotationOutline, AnnotationSelectionInfo,
     AnnotationUiState,
 };
-use super::super::ops::{build_drawing_item, contains_point_with_bounds};
+use super::super::ops::{build_drawing_item, contains_point_with_bounds, normalize_draw_rect};
 use super::super::raster::{compose_background_with_annotations, compose_selection_background, compose_selection_base, draw_items_on_selection};
 #[cfg(any(feature = "overlay-diagnostics", test))]
 use super::super::raster_cache::AnnotationRasterDiagnostics;
@@ -82,14 +82,16 @@ impl AnnotationEngine {
         };
 
         let mut outlines = self.committed_outlines();
-        let transient_item = self.transient_item();
+        let transient_items = self.transient_items();
 
-        if let Some(item) = transient_item.as_ref() {
+        for item in &transient_items {
             outlines.retain(|outline| outline.id != item.id);
+            let (bounds, rotation) = item.outline_frame();
             outlines.push(AnnotationOutline {
                 id: item.id,
-                bounds: item.bounds(),
-                selected: Some(item.id) == self.selected_id,
+                bounds,
+                rotation,
+                selected: self.selected_ids.contains(&item.id),
                 transient: true,
             });
         }
@@ -107,7 +109,8 @@ impl AnnotationEngine {
             outlines.push(AnnotationOutline {
                 id: editing.id,
                 bounds: draft_item.bounds(),
-                selected: Some(editing.id) == self.selected_id,
+                rotation: 0.0,
+                selected: self.selected_ids.contains(&editing.id),
                 transient: true,
             });
         }
@@ -121,9 +124,36 @@ impl AnnotationEngine {
             }
         }
 
-        let image = self.layer_image(selection, background, scale, transient_item.as_ref(), preview_translate);
+        let sole = self.sole_selected_item();
+        let selected = sole.and_then(|sole| transient_items.iter().find(|item| item.id == sole.id)).or(sole);
+        let mut handles = match selected {
+            Some(item) if self.text_editing.is_none() => item.handles(Some(selection)),
+            _ => Vec::new(),
+        };
+        if let Some((dx, dy)) = preview_translate {
+            for (_, point) in &mut handles {
+                point.0 += dx;
+                point.1 += dy;
+            }
+        }
+
+        let mut rubber_band = match self.interaction {
+            AnnotationInteractionState::RubberBand { start, current } => Some(normalize_draw_rect(start, current)),
+            _ => None,
+        };
+        if let (Some(band), Some((dx, dy))) = (rubber_band.as_mut(), preview_translate) {
+            band.x += dx;
+            band.y += dy;
+        }
+
+        let image = self.layer_image(selection, background, scale, &transient_items, preview_translate);
 
-        AnnotationLayerState { image, outlines }
+        AnnotationLayerState {
+            image,
+            outlines,
+            handles,
+            rubber_band,
+        }
     }
 
     fn layer_image(
@@ -131,10 +161,10 @@ impl AnnotationEngine {
         selection: RectF,
         background: Option<&Arc<RgbaImage>>,
         scale: f64,
-        transient_item: Option<&AnnotationItem>,
+        transient_items: &[AnnotationItem],
         preview_translate: Option<(f64, f64)>,
     ) -> Option<Arc<RenderImage>> {
-        if self.store.visible_len() == 0 && transient_item.is_none() {
+        if self.store.visible_len() == 0 && transient_items.is_empty() {
             return None;
         }
         let background = background?;
@@ -142,12 +172,13 @@ impl AnnotationEngine {
         let preview_translate = preview_translate.filter(|(dx, dy)| dx.abs() > f64::EPSILON || dy.abs() > f64::EPSILON);
         let committed = self.ensure_committed_layer(selection, background, scale)?;
         let editing = self.text_editing.clone();
-        let moving_id = match &self.interaction {
-            AnnotationInteractionState::Moving { id, .. } => Some(*id),
+        let moving_ids = match &self.interaction {
+            AnnotationInteractionState::Moving { ids, .. } => Some(ids.clone()),
+            AnnotationInteractionState::Reshaping { id, .. } => Some(vec![*id]),
             _ => None,
         };
 
-        if transient_item.is_none() && editing.is_none() && preview_translate.is_none() {
+        if transient_items.is_empty() && editing.is_none() && preview_translate.is_none() {
             return Some(committed.image.clone());
         }
 
@@ -162,7 +193,7 @@ impl AnnotationEngine {
         }
 
         if let Some((dx, dy)) = preview_translate
-            && transient_item.is_none()
+            && transient_items.is_empty()
         {
             let mut items = self.store.clone_visible_items();
             if let Some(editing) = &editing
@@ -184,19 +215,17 @@ impl AnnotationEngine {
             return Some(image);
         }
 
-        if editing.is_none()
-            && let Some(transient) = transient_item
-        {
+        if editing.is_none() && !transient_items.is_empty() {
             if matches!(self.interaction, AnnotationInteractionState::Drawing { .. }) {
                 self.raster_cache.drawing_fast_path_hits = self.raster_cache.drawing_fast_path_hits.saturating_add(1);
-                let image = self.render_transient_on_base(committed.rgba.as_ref(), selection, scale, transient);
+                let image = self.render_items_on_base(committed.rgba.as_ref(), selection, scale, transient_items);
                 self.cache_composed(selection, scale, None, image.clone());
                 return Some(image);
             }
-            if let Some(moving_id) = moving_id {
-                let base = self.ensure_interaction_base_layer(selection, background, scale, moving_id)?;
+            if let Some(moving_ids) = moving_ids {
+                let base = self.ensure_interaction_base_layer(selection, background, scale, moving_ids)?;
                 self.raster_cache.moving_fast_path_hits = self.raster_cache.moving_fast_path_hits.saturating_add(1);
-                let image = self.render_transient_on_base(base.as_ref(), selection, scale, transient);
+                let image = self.render_items_on_base(base.as_ref(), selection, scale, transient_items);
                 self.cache_composed(selection, scale, None, image.clone());
                 return Some(image);
             }
@@ -210,7 +239,7 @@ impl AnnotationEngine {
             item.kind = AnnotationKind::Text { origin, text: editing.draft };
         }
 
-        if let Some(transient) = transient_item {
+        for transient in transient_items {
             if let Some(item) = items.iter_mut().find(|item| item.id == transient.id) {
                 *item = transient.clone();
             } else {
@@ -248,25 +277,37 @@ impl AnnotationEngine {
         self.raster_cache.committed.clone()
     }
 
-    fn ensure_interaction_base_layer(&mut self, selection: RectF, background: &Arc<RgbaImage>, scale: f64, moving_id: u64) -> Option<Arc<RgbaImage>> {
+    fn ensure_interaction_base_layer(
+        &mut self,
+        selection: RectF,
+        background: &Arc<RgbaImage>,
+        scale: f64,
+        moving_ids: Vec<u64>,
+    ) -> Option<Arc<RgbaImage>> {
         let cache_valid = self.raster_cache.interaction_base.as_ref().is_some_and(|cache| {
             cache.selection == selection
                 && (cache.scale - scale).abs() <= f64::EPSILON
                 && cache.committed_revision == self.committed_revision
-                && cache.moving_id == moving_id
+                && cache.moving_ids == moving_ids
         });
         if cache_valid {
             return self.raster_cache.interaction_base.as_ref().map(|cache| cache.rgba.clone());
         }
 
-        let items: Vec<AnnotationItem> = self.store.visible_items().iter().filter(|item| item.id != moving_id).cloned().collect();
+        let items: Vec<AnnotationItem> = self
+            .store
+            .visible_items()
+            .iter()
+            .filter(|item| !moving_ids.contains(&item.id))
+            .cloned()
+            .collect();
         let layer = compose_selection_base(background.as_ref(), selection, &items, scale)?;
         self.raster_cache.interaction_base_rebuilds = self.raster_cache.interaction_base_rebuilds.saturating_add(1);
         self.raster_cache.interaction_base = Some(InteractionBaseCache {
             selection,
             scale,
             committed_revision: self.committed_revision,
-            moving_id,
+            moving_ids,
             rgba: Arc::new(layer),
         });
         self.raster_cache.interaction_base.as_ref().map(|cache| cache.rgba.clone())
@@ -284,12 +325,6 @@ impl AnnotationEngine {
         scratch
     }
 
-    fn render_transient_on_base(&mut self, base: &RgbaImage, selection: RectF, scale: f64, transient: &AnnotationItem) -> Arc<RenderImage> {
-        let scratch = self.prepare_scratch_layer(base);
-        draw_items_on_selection(scratch, selection, std::slice::from_ref(transient), scale);
-        render_image::from_rgba_copy(scratch)
-    }
-
     fn render_items_on_base(&mut self, base: &RgbaImage, selection: RectF, scale: f64, items: &[AnnotationItem]) -> Arc<RenderImage> {
         let scratch = self.prepare_scratch_layer(base);
         draw_items_on_selection(scratch, selection, items, scale);
@@ -311,27 +346,55 @@ impl AnnotationEngine {
     fn committed_outlines(&self) -> Vec<AnnotationOutline> {
         let mut proxy = self.hit_proxy.borrow_mut();
         proxy.sync(self.committed_revision, self.store.visible_items());
-        proxy.outlines(self.selected_id)
+        proxy.outlines(&self.selected_ids)
     }
 
-    fn transient_item(&self) -> Option<AnnotationItem> {
+    /// The items as they look mid-interaction, in place of their committed
+    /// versions.
+    fn transient_items(&self) -> Vec<AnnotationItem> {
         match &self.interaction {
-            AnnotationInteractionState::Drawing { tool, start, current, style } => {
-                let mut preview = build_drawing_item(*tool, *start, *current, *style, self.next_id)?;
+            AnnotationInteractionState::Drawing {
+                tool,
+                start,
+                current,
+                path,
+                style,
+            } => {
+                let Some(mut preview) = build_drawing_item(*tool, *start, *current, path, *style, self.next_id) else {
+                    return Vec::new();
+                };
                 preview.style.stroke_color = preview.style.stroke_color & 0xffffff00 | 0xcc;
                 preview.style.fill_color = preview.style.fill_color & 0xffffff00 | 0x88;
-                Some(preview)
+                vec![preview]
             }
-            AnnotationInteractionState::Moving { id, start, current, origin } => {
-                let source = self.store.visible_item(*id)?;
-                let mut item = source.clone();
-                item.kind = origin.clone();
+            AnnotationInteractionState::Moving { ids, start, current } => {
                 let dx = current.0 - start.0;
                 let dy = current.1 - start.1;
-                item.move_by(dx, dy);
-                Some(item)
+                self.store
+                    .visible_items()
+                    .iter()
+                    .filter(|item| ids.contains(&item.id))
+                    .map(|item| {
+                        let mut item = item.clone();
+                        item.move_by(dx, dy);
+                        item
+                    })
+                    .collect()
+            }
+            AnnotationInteractionState::Reshaping {
+                id,
+                handle,
+                start,
+                current,
+                origin,
+            } => {
+                let Some(mut item) = self.store.visible_item(*id).cloned() else {
+                    return Vec::new();
+                };
+                item.drag_handle(origin, *handle, *start, *current);
+                vec![item]
             }
-            AnnotationInteractionState::Idle => None,
+            AnnotationInteractionState::Idle | AnnotationInteractionState::RubberBand { .. } => Vec::new(),
         }
     }
 
@@ -1,7 +1,8 @@
 use crate::services::geometry::RectF;
 
-use super::super::model::{AnnotationKind, COLOR_PRESETS, MosaicMode};
-use super::super::ops::ensure_mosaic_kind_style;
+use super::super::model::{AnnotationKind, AnnotationKindTag, AnnotationStyleState, ArrowHeads, COLOR_PRESETS, LineDash, MosaicMode};
+use super::super::ops::{ensure_line_kind_style, ensure_mosaic_kind_style};
+use super::super::store::ChangeKind;
 use super::AnnotationEngine;
 
 impl AnnotationEngine {
@@ -25,18 +26,10 @@ impl AnnotationEngine {
             changed_style = true;
         }
 
-        let mut changed_item = false;
-        if let Some(item) = self.selected_item_mut() {
-            if item.style.stroke_color != color {
-                item.style.stroke_color = color;
-                changed_item = true;
-            }
-            let item_fill = color_rgb | (item.style.fill_color & 0x000000ff);
-            if item.style.fill_color != item_fill {
-                item.style.fill_color = item_fill;
-                changed_item = true;
-            }
-        }
+        let changed_item = self.update_selected(ChangeKind::Restyle, |item| {
+            item.style.stroke_color = color;
+            item.style.fill_color = color_rgb | (item.style.fill_color & 0x000000ff);
+        });
 
         if !changed_style && !changed_item {
             return false;
@@ -53,13 +46,7 @@ impl AnnotationEngine {
         let next_fill_enabled = !self.style.fill_enabled;
         let style_changed = self.style.fill_enabled != next_fill_enabled;
         self.style.fill_enabled = next_fill_enabled;
-        let mut changed_item = false;
-        if let Some(item) = self.selected_item_mut()
-            && item.style.fill_enabled != next_fill_enabled
-        {
-            item.style.fill_enabled = next_fill_enabled;
-            changed_item = true;
-        }
+        let changed_item = self.update_selected(ChangeKind::Restyle, |item| item.style.fill_enabled = next_fill_enabled);
         if changed_item {
             self.bump_committed();
         } else {
@@ -79,11 +66,10 @@ impl AnnotationEngine {
         self.style.text_size = next_text;
         self.style.counter_radius = next_counter;
 
-        let had_selected = self.selected_id.is_some();
-        let mut changed_item = false;
-        if let Some(item) = self.selected_item_mut() {
-            changed_item = item.resize_by_wheel(delta);
-        }
+        let had_selected = !self.selected_ids.is_empty();
+        let changed_item = self.update_selected(ChangeKind::Resize, |item| {
+            item.resize_by_wheel(delta);
+        });
         if changed_item {
             self.sync_style_from_selected();
             self.bump_committed();
@@ -103,14 +89,9 @@ impl AnnotationEngine {
     pub(crate) fn set_mosaic_mode(&mut self, mode: MosaicMode) -> bool {
         let style_changed = self.style.mosaic_mode != mode;
         self.style.mosaic_mode = mode;
-        let had_selected = self.selected_id.is_some();
-        let mut changed = false;
+        let had_selected = !self.selected_ids.is_empty();
         let style = self.style;
-        if let Some(item) = self.selected_item_mut() {
-            let before = item.kind.clone();
-            ensure_mosaic_kind_style(&mut item.kind, &style);
-            changed = before != item.kind;
-        }
+        let changed = self.update_selected(ChangeKind::Restyle, |item| ensure_mosaic_kind_style(&mut item.kind, &style));
         if changed {
             self.sync_style_from_selected();
             self.bump_committed();
@@ -127,20 +108,55 @@ impl AnnotationEngine {
         false
     }
 
+    pub(crate) fn set_arrow_heads(&mut self, heads: ArrowHeads) -> bool {
+        self.update_line_style(|style| style.arrow_heads = heads)
+    }
+
+    pub(crate) fn toggle_line_dash(&mut self) -> bool {
+        let next = match self.style.line_dash {
+            LineDash::Solid => LineDash::Dashed,
+            LineDash::Dashed => LineDash::Solid,
+        };
+        self.update_line_style(|style| style.line_dash = next)
+    }
+
+    pub(crate) fn toggle_line_curve(&mut self) -> bool {
+        let next = !self.style.line_curved;
+        self.update_line_style(|style| style.line_curved = next)
+    }
+
+    /// Applies a line option to the style for new lines and to the selected
+    /// items that are lines.
+    fn update_line_style(&mut self, apply: impl Fn(&mut AnnotationStyleState)) -> bool {
+        let before = self.style;
+        apply(&mut self.style);
+        let style_changed = before != self.style;
+        let changed_item = self.update_selected(ChangeKind::Restyle, |item| {
+            if item.kind.tag() == AnnotationKindTag::Arrow {
+                apply(&mut item.style);
+                ensure_line_kind_style(&mut item.kind, &item.style);
+            }
+        });
+        if changed_item {
+            self.sync_style_from_selected();
+            self.bump_committed();
+        } else if style_changed {
+            self.bump_transient();
+        }
+        changed_item || style_changed
+    }
+
     pub(crate) fn adjust_mosaic_intensity(&mut self, delta: f64) -> bool {
         let next_intensity = (self.style.mosaic_intensity + delta).clamp(2.0, 64.0);
         let style_changed = (self.style.mosaic_intensity - next_intensity).abs() > f64::EPSILON;
         self.style.mosaic_intensity = next_intensity;
-        let had_selected = self.selected_id.is_some();
-        let mut changed = false;
-        if let Some(item) = self.selected_item_mut()
-            && let AnnotationKind::Mosaic { intensity, .. } = &mut item.kind
-            && (*intensity - next_intensity).abs() > f64::EPSILON
-        {
-            *intensity = next_intensity;
-            item.style.mosaic_intensity = *intensity;
-            changed = true;
-        }
+        let had_selected = !self.selected_ids.is_empty();
+        let changed = self.update_selected(ChangeKind::Restyle, |item| {
+            if let AnnotationKind::Mosaic { intensity, .. } = &mut item.kind {
+                *intensity = next_intensity;
+                item.style.mosaic_intensity = next_intensity;
+            }
+        });
         if changed {
             self.sync_style_from_selected();
             self.bump_committed();
@@ -161,17 +177,13 @@ impl AnnotationEngine {
         if !self.mode_enabled(selection, idle_mode) {
             return false;
         }
-        let Some(selected_id) = self.selected_id else {
-            return false;
-        };
         let hit = self.hit_test(point, selection, idle_mode);
-        if hit != Some(selected_id) {
+        if !hit.is_some_and(|id| self.selected_ids.contains(&id)) {
             return false;
         }
-        let Some(item) = self.selected_item_mut() else {
-            return false;
-        };
-        let changed = item.resize_by_wheel(delta_steps);
+        let changed = self.update_selected(ChangeKind::Resize, |item| {
+            item.resize_by_wheel(delta_steps);
+        });
         if changed {
             self.sync_style_from_selected();
             self.bump_committed();
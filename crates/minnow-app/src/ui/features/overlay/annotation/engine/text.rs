@@ -1,9 +1,10 @@
 use super::super::model::{AnnotationKind, TEXT_DEFAULT, TextEditState};
+use super::super::store::ChangeKind;
 use super::AnnotationEngine;
 
 impl AnnotationEngine {
     pub(crate) fn begin_text_edit_selected(&mut self) -> bool {
-        let Some(id) = self.selected_id else {
+        let Some(id) = self.primary_id() else {
             return false;
         };
         let Some(item) = self.store.visible_item(id) else {
@@ -45,18 +46,16 @@ impl AnnotationEngine {
         let Some(edit) = self.text_editing.take() else {
             return false;
         };
-        let Some(item) = self.store.visible_item_mut(edit.id) else {
+        if !matches!(self.kind_for(edit.id), Some(AnnotationKind::Text { .. })) {
             return false;
-        };
-        let AnnotationKind::Text { text: value, .. } = &mut item.kind else {
-            return false;
-        };
-        let next = edit.draft.trim_end_matches('\n').to_string();
-        if next.trim().is_empty() {
-            *value = TEXT_DEFAULT.to_string();
-        } else {
-            *value = next;
         }
+        let next = edit.draft.trim_end_matches('\n');
+        let next = if next.trim().is_empty() { TEXT_DEFAULT } else { next };
+        self.store.update_visible_items(&[edit.id], ChangeKind::EditText, |item| {
+            if let AnnotationKind::Text { text, .. } = &mut item.kind {
+                next.clone_into(text);
+            }
+        });
         self.bump_committed();
         true
     }
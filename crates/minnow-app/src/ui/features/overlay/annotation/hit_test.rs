@@ -7,6 +7,9 @@ struct HitEntry {
     id: u64,
     bounds: RectF,
     kind: AnnotationKindTag,
+    /// What the outline follows, which is tighter than `bounds` for a
+    /// turned item.
+    frame: (RectF, f64),
 }
 
 #[derive(Clone, Debug, Default)]
@@ -31,6 +34,7 @@ impl AnnotationHitProxy {
                 id: item.id,
                 bounds: item.bounds(),
                 kind: item.kind.tag(),
+                frame: item.outline_frame(),
             })
             .collect();
         self.revision = revision;
@@ -48,13 +52,14 @@ impl AnnotationHitProxy {
         })
     }
 
-    pub(crate) fn outlines(&self, selected_id: Option<u64>) -> Vec<AnnotationOutline> {
+    pub(crate) fn outlines(&self, selected_ids: &[u64]) -> Vec<AnnotationOutline> {
         self.entries
             .iter()
             .map(|entry| AnnotationOutline {
                 id: entry.id,
-                bounds: entry.bounds,
-                selected: Some(entry.id) == selected_id,
+                bounds: entry.frame.0,
+                rotation: entry.frame.1,
+                selected: selected_ids.contains(&entry.id),
                 transient: false,
             })
             .collect()
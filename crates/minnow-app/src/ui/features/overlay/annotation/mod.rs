@@ -5,13 +5,14 @@ mod model;
 mod ops;
 mod raster;
 mod raster_cache;
+pub(crate) mod saved;
 mod store;
 
 pub(crate) use engine::AnnotationEngine;
 #[cfg(test)]
 pub(crate) use model::AnnotationItem;
 pub(crate) use model::{
-    AnnotationKind, AnnotationKindTag, AnnotationLayerState, AnnotationSelectionInfo, AnnotationStyleState, AnnotationTool, AnnotationUiState,
-    COLOR_PRESETS, MosaicMode,
+    AnnotationHandle, AnnotationKind, AnnotationKindTag, AnnotationLayerState, AnnotationSelectionInfo, AnnotationStyleState, AnnotationTool,
+    AnnotationUiState, ArrowHeads, COLOR_PRESETS, LineDash, MosaicMode,
 };
 pub(crate) use raster_cache::AnnotationRasterDiagnostics;
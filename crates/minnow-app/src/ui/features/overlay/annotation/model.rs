@@ -1,4 +1,5 @@
 use gpui::RenderImage;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::services::geometry::RectF;
@@ -16,15 +17,40 @@ pub(crate) enum AnnotationTool {
     Counter,
     Text,
     Mosaic,
+    Pen,
+    Highlighter,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+impl AnnotationTool {
+    /// Tools that follow the pointer rather than span a start and end point.
+    pub(crate) const fn is_freehand(self) -> bool {
+        matches!(self, Self::Pen | Self::Highlighter)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub(crate) enum MosaicMode {
     #[default]
     Pixelate,
     Blur,
 }
 
+/// Which ends of a line carry an arrowhead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub(crate) enum ArrowHeads {
+    None,
+    #[default]
+    End,
+    Both,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub(crate) enum LineDash {
+    #[default]
+    Solid,
+    Dashed,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub(crate) enum AnnotationKindTag {
     Arrow,
@@ -33,9 +59,11 @@ pub(crate) enum AnnotationKindTag {
     Counter,
     Text,
     Mosaic,
+    Pen,
+    Highlighter,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) struct AnnotationStyleState {
     pub stroke_color: u32,
     pub fill_color: u32,
@@ -45,6 +73,13 @@ pub(crate) struct AnnotationStyleState {
     pub counter_radius: f64,
     pub mosaic_intensity: f64,
     pub mosaic_mode: MosaicMode,
+    #[serde(default)]
+    pub arrow_heads: ArrowHeads,
+    #[serde(default)]
+    pub line_dash: LineDash,
+    /// New arrows are drawn with a control point to bend them.
+    #[serde(default)]
+    pub line_curved: bool,
 }
 
 impl Default for AnnotationStyleState {
@@ -58,18 +93,58 @@ impl Default for AnnotationStyleState {
             counter_radius: 18.0,
             mosaic_intensity: 10.0,
             mosaic_mode: MosaicMode::Pixelate,
+            arrow_heads: ArrowHeads::End,
+            line_dash: LineDash::Solid,
+            line_curved: false,
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) enum AnnotationKind {
-    Arrow { start: (f64, f64), end: (f64, f64) },
-    Rectangle { rect: RectF },
-    Circle { rect: RectF },
-    Counter { center: (f64, f64), number: u32 },
-    Text { origin: (f64, f64), text: String },
-    Mosaic { rect: RectF, mode: MosaicMode, intensity: f64 },
+    /// A line from `start` to `end`, bent towards `control` as a quadratic
+    /// curve when there is one.
+    Arrow {
+        start: (f64, f64),
+        end: (f64, f64),
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        control: Option<(f64, f64)>,
+    },
+    /// A box turned by `rotation` radians around its center.
+    Rectangle {
+        rect: RectF,
+        #[serde(default)]
+        rotation: f64,
+    },
+    /// An ellipse filling `rect`, turned like a rectangle.
+    Circle {
+        rect: RectF,
+        #[serde(default)]
+        rotation: f64,
+    },
+    Counter {
+        center: (f64, f64),
+        number: u32,
+    },
+    Text {
+        origin: (f64, f64),
+        text: String,
+    },
+    Mosaic {
+        rect: RectF,
+        mode: MosaicMode,
+        intensity: f64,
+        #[serde(default)]
+        rotation: f64,
+    },
+    /// A smoothed freehand stroke.
+    Pen {
+        points: Vec<(f64, f64)>,
+    },
+    /// A wide translucent stroke multiplied into what is under it.
+    Highlighter {
+        points: Vec<(f64, f64)>,
+    },
 }
 
 impl AnnotationKind {
@@ -81,15 +156,53 @@ impl AnnotationKind {
             Self::Counter { .. } => AnnotationKindTag::Counter,
             Self::Text { .. } => AnnotationKindTag::Text,
             Self::Mosaic { .. } => AnnotationKindTag::Mosaic,
+            Self::Pen { .. } => AnnotationKindTag::Pen,
+            Self::Highlighter { .. } => AnnotationKindTag::Highlighter,
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// A side or corner of a box, as the direction it moves that box's edges:
+/// -1 for the left or top edge, 1 for the right or bottom edge and 0 for
+/// neither.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct BoxHandle {
+    pub x: i8,
+    pub y: i8,
+}
+
+impl BoxHandle {
+    pub(crate) const ALL: [Self; 8] = [
+        Self { x: -1, y: -1 },
+        Self { x: 0, y: -1 },
+        Self { x: 1, y: -1 },
+        Self { x: 1, y: 0 },
+        Self { x: 1, y: 1 },
+        Self { x: 0, y: 1 },
+        Self { x: -1, y: 1 },
+        Self { x: -1, y: 0 },
+    ];
+}
+
+/// A point of the selected item that reshapes it when dragged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum AnnotationHandle {
+    Resize(BoxHandle),
+    Start,
+    End,
+    /// The control point of a curved line.
+    Control,
+    Rotate,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) struct AnnotationItem {
     pub id: u64,
     pub style: AnnotationStyleState,
     pub kind: AnnotationKind,
+    /// Items sharing a group are selected and moved together.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<u64>,
 }
 
 #[derive(Clone, Debug, PartialEq, Default)]
@@ -100,14 +213,23 @@ pub(crate) enum AnnotationInteractionState {
         tool: AnnotationTool,
         start: (f64, f64),
         current: (f64, f64),
+        /// Every point the pointer passed, kept for freehand tools only.
+        path: Vec<(f64, f64)>,
         style: AnnotationStyleState,
     },
-    Moving {
+    /// Dragging every item in `ids` by the same offset.
+    Moving { ids: Vec<u64>, start: (f64, f64), current: (f64, f64) },
+    /// Dragging a handle of the item, which had the shape `origin` when the
+    /// drag started.
+    Reshaping {
         id: u64,
+        handle: AnnotationHandle,
         start: (f64, f64),
         current: (f64, f64),
         origin: AnnotationKind,
     },
+    /// Dragging out a box that adds the items it touches to the selection.
+    RubberBand { start: (f64, f64), current: (f64, f64) },
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -127,7 +249,10 @@ pub(crate) struct AnnotationSelectionInfo {
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub(crate) struct AnnotationOutline {
     pub id: u64,
+    /// The box around the item before it is turned by `rotation` radians
+    /// around its center.
     pub bounds: RectF,
+    pub rotation: f64,
     pub selected: bool,
     pub transient: bool,
 }
@@ -136,6 +261,10 @@ pub(crate) struct AnnotationOutline {
 pub(crate) struct AnnotationLayerState {
     pub image: Option<Arc<RenderImage>>,
     pub outlines: Vec<AnnotationOutline>,
+    /// Handles of the selected item and where they are, while it is the only
+    /// one selected.
+    pub handles: Vec<(AnnotationHandle, (f64, f64))>,
+    pub rubber_band: Option<RectF>,
 }
 
 #[derive(Clone, Debug)]
@@ -1,14 +1,169 @@
-use std::f64::consts::PI;
+use std::f64::consts::{FRAC_PI_2, PI};
 
 use crate::services::geometry::{RectF, normalize_rect};
 
-use super::model::{AnnotationItem, AnnotationKind, AnnotationStyleState, AnnotationTool, MIN_DRAW_LENGTH};
+use super::model::{
+    AnnotationHandle, AnnotationItem, AnnotationKind, AnnotationOutline, AnnotationStyleState, AnnotationTool, ArrowHeads, BoxHandle, LineDash,
+    MIN_DRAW_LENGTH,
+};
+
+/// Pointer samples closer than this to the last kept one add no detail to a
+/// freehand stroke.
+const MIN_PATH_STEP: f64 = 2.0;
+const PATH_SMOOTHING_PASSES: usize = 2;
+/// A highlighter stroke is this many times the stroke width, enough to
+/// cover a line of text at the default width.
+const HIGHLIGHTER_WIDTH_SCALE: f64 = 5.0;
+
+/// Curved lines are drawn through this many straight pieces.
+const CURVE_SEGMENTS: usize = 24;
+/// How far a line bows out when it is first bent, as a share of its length.
+const CURVE_BEND: f64 = 0.25;
+const ARROW_HEAD_ANGLE: f64 = PI / 7.0;
+const ARROW_HEAD_INDENT: f64 = 0.2;
+/// Pointer distance at which a handle of the selected item is grabbed
+/// instead of the item itself.
+pub(crate) const HANDLE_RADIUS: f64 = 8.0;
+/// How far past the edge of a box its rotate handle sits.
+const ROTATE_HANDLE_OFFSET: f64 = 24.0;
+/// Turning a box to within this many radians of a quarter turn snaps it to
+/// that quarter turn.
+const ROTATION_SNAP: f64 = PI / 60.0;
+/// Turned ellipses are drawn and outlined through this many points.
+const ELLIPSE_SEGMENTS: usize = 64;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub(crate) struct ArrowGeometry {
     pub polygon: [(f64, f64); 7],
 }
 
+/// The outline of an arrowhead: tip, one barb, the notch and the other barb.
+pub(crate) type ArrowHead = [(f64, f64); 4];
+
+/// A line that is not the plain tapered arrow: the pieces of its shaft to
+/// stroke, split into dashes when dashed, and an outline per arrowhead.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct LineGeometry {
+    pub strokes: Vec<Vec<(f64, f64)>>,
+    pub heads: Vec<ArrowHead>,
+}
+
+pub(crate) const fn is_turned(rotation: f64) -> bool {
+    rotation.abs() > f64::EPSILON
+}
+
+fn rect_center(rect: RectF) -> (f64, f64) {
+    (rect.x + rect.width / 2.0, rect.y + rect.height / 2.0)
+}
+
+/// `point` turned by `rotation` radians around `center`.
+pub(crate) fn rotate_point(point: (f64, f64), center: (f64, f64), rotation: f64) -> (f64, f64) {
+    if !is_turned(rotation) {
+        return point;
+    }
+    let (sin, cos) = rotation.sin_cos();
+    let dx = point.0 - center.0;
+    let dy = point.1 - center.1;
+    (center.0 + dx * cos - dy * sin, center.1 + dx * sin + dy * cos)
+}
+
+/// Corners of `rect` turned by `rotation` around its center, clockwise from
+/// the top left one.
+pub(crate) fn rotated_corners(rect: RectF, rotation: f64) -> [(f64, f64); 4] {
+    let center = rect_center(rect);
+    [
+        (rect.x, rect.y),
+        (rect.x + rect.width, rect.y),
+        (rect.x + rect.width, rect.y + rect.height),
+        (rect.x, rect.y + rect.height),
+    ]
+    .map(|corner| rotate_point(corner, center, rotation))
+}
+
+/// Points around the ellipse filling `rect`, turned by `rotation`.
+pub(crate) fn ellipse_outline(rect: RectF, rotation: f64) -> Vec<(f64, f64)> {
+    let center = rect_center(rect);
+    (0..ELLIPSE_SEGMENTS)
+        .map(|i| {
+            let angle = i as f64 * 2.0 * PI / ELLIPSE_SEGMENTS as f64;
+            let point = (center.0 + rect.width / 2.0 * angle.cos(), center.1 + rect.height / 2.0 * angle.sin());
+            rotate_point(point, center, rotation)
+        })
+        .collect()
+}
+
+fn turned_rect_bounds(rect: RectF, rotation: f64) -> RectF {
+    if !is_turned(rotation) {
+        return rect;
+    }
+    path_bounds(&rotated_corners(rect, rotation), 0.0)
+}
+
+fn turned_ellipse_bounds(rect: RectF, rotation: f64) -> RectF {
+    if !is_turned(rotation) {
+        return rect;
+    }
+    let (sin, cos) = rotation.sin_cos();
+    let (rx, ry) = (rect.width / 2.0, rect.height / 2.0);
+    let half_width = ((rx * cos).powi(2) + (ry * sin).powi(2)).sqrt();
+    let half_height = ((rx * sin).powi(2) + (ry * cos).powi(2)).sqrt();
+    let (cx, cy) = rect_center(rect);
+    RectF::new(cx - half_width, cy - half_height, half_width * 2.0, half_height * 2.0)
+}
+
+/// `rect` with the edges `handle` moves dragged to `point`, keeping the box
+/// turned by `rotation` and its other edges where they were on screen.
+fn resize_turned_rect(rect: RectF, rotation: f64, handle: BoxHandle, point: (f64, f64)) -> RectF {
+    let center = rect_center(rect);
+    let local = rotate_point(point, center, -rotation);
+    let (mut left, mut top, mut right, mut bottom) = (rect.x, rect.y, rect.x + rect.width, rect.y + rect.height);
+    match handle.x {
+        -1 => left = local.0,
+        1 => right = local.0,
+        _ => {}
+    }
+    match handle.y {
+        -1 => top = local.1,
+        1 => bottom = local.1,
+        _ => {}
+    }
+    let (left, right) = (left.min(right), left.max(right));
+    let (top, bottom) = (top.min(bottom), top.max(bottom));
+    let (width, height) = (right - left, bottom - top);
+    if !is_turned(rotation) {
+        return RectF::new(left, top, width, height);
+    }
+    let moved = rotate_point(((left + right) / 2.0, (top + bottom) / 2.0), center, rotation);
+    RectF::new(moved.0 - width / 2.0, moved.1 - height / 2.0, width, height)
+}
+
+/// Wraps `rotation` into a half turn either way and snaps it to a nearby
+/// quarter turn.
+fn snap_rotation(rotation: f64) -> f64 {
+    let rotation = (rotation + PI).rem_euclid(2.0 * PI) - PI;
+    let quarter = (rotation / FRAC_PI_2).round() * FRAC_PI_2;
+    if (rotation - quarter).abs() <= ROTATION_SNAP {
+        quarter
+    } else {
+        rotation
+    }
+}
+
+/// Where `point` lands when the line from `from.0` to `from.1` is moved,
+/// turned and stretched onto the one from `to.0` to `to.1`, so a curve keeps
+/// its shape when one of its ends is dragged.
+fn follow_line(point: (f64, f64), from: ((f64, f64), (f64, f64)), to: ((f64, f64), (f64, f64))) -> (f64, f64) {
+    let a = (from.1.0 - from.0.0, from.1.1 - from.0.1);
+    let b = (to.1.0 - to.0.0, to.1.1 - to.0.1);
+    let length_sq = a.0 * a.0 + a.1 * a.1;
+    if length_sq <= f64::EPSILON {
+        return (point.0 + to.0.0 - from.0.0, point.1 + to.0.1 - from.0.1);
+    }
+    let z = ((b.0 * a.0 + b.1 * a.1) / length_sq, (b.1 * a.0 - b.0 * a.1) / length_sq);
+    let p = (point.0 - from.0.0, point.1 - from.0.1);
+    (to.0.0 + z.0 * p.0 - z.1 * p.1, to.0.1 + z.0 * p.1 + z.1 * p.0)
+}
+
 fn point_in_ellipse(point: (f64, f64), rect: RectF) -> bool {
     if rect.width <= 0.0 || rect.height <= 0.0 {
         return false;
@@ -34,6 +189,75 @@ fn distance_to_segment(point: (f64, f64), start: (f64, f64), end: (f64, f64)) ->
     ((point.0 - proj_x).powi(2) + (point.1 - proj_y).powi(2)).sqrt()
 }
 
+fn distance_to_path(point: (f64, f64), points: &[(f64, f64)]) -> f64 {
+    match points {
+        [] => f64::INFINITY,
+        [only] => distance_to_segment(point, *only, *only),
+        _ => points
+            .windows(2)
+            .map(|pair| distance_to_segment(point, pair[0], pair[1]))
+            .fold(f64::INFINITY, f64::min),
+    }
+}
+
+fn path_length(points: &[(f64, f64)]) -> f64 {
+    points
+        .windows(2)
+        .map(|pair| ((pair[1].0 - pair[0].0).powi(2) + (pair[1].1 - pair[0].1).powi(2)).sqrt())
+        .sum()
+}
+
+fn path_bounds(points: &[(f64, f64)], padding: f64) -> RectF {
+    let Some(first) = points.first() else {
+        return RectF::new(0.0, 0.0, 0.0, 0.0);
+    };
+    let (min, max) = points.iter().fold((*first, *first), |(min, max), point| {
+        ((min.0.min(point.0), min.1.min(point.1)), (max.0.max(point.0), max.1.max(point.1)))
+    });
+    RectF::new(
+        min.0 - padding,
+        min.1 - padding,
+        max.0 - min.0 + padding * 2.0,
+        max.1 - min.1 + padding * 2.0,
+    )
+}
+
+/// Thins out pointer samples and rounds off the corners between them with
+/// Chaikin's corner cutting, keeping both ends where the pointer put them.
+pub(crate) fn smooth_path(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut path: Vec<(f64, f64)> = Vec::with_capacity(points.len());
+    for &point in points {
+        let far_enough = path
+            .last()
+            .is_none_or(|last| (point.0 - last.0).powi(2) + (point.1 - last.1).powi(2) >= MIN_PATH_STEP * MIN_PATH_STEP);
+        if far_enough {
+            path.push(point);
+        }
+    }
+    if let (Some(&last), Some(&kept)) = (points.last(), path.last())
+        && last != kept
+        && path.len() > 1
+    {
+        path.push(last);
+    }
+
+    for _ in 0..PATH_SMOOTHING_PASSES {
+        if path.len() < 3 {
+            break;
+        }
+        let mut smoothed = Vec::with_capacity(path.len() * 2);
+        smoothed.push(path[0]);
+        for pair in path.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            smoothed.push((a.0 * 0.75 + b.0 * 0.25, a.1 * 0.75 + b.1 * 0.25));
+            smoothed.push((a.0 * 0.25 + b.0 * 0.75, a.1 * 0.25 + b.1 * 0.75));
+        }
+        smoothed.push(path[path.len() - 1]);
+        path = smoothed;
+    }
+    path
+}
+
 fn line_intersection(a1: (f64, f64), a2: (f64, f64), b1: (f64, f64), b2: (f64, f64)) -> Option<(f64, f64)> {
     let d = (a1.0 - a2.0) * (b1.1 - b2.1) - (a1.1 - a2.1) * (b1.0 - b2.0);
     if d.abs() <= f64::EPSILON {
@@ -58,7 +282,7 @@ pub(crate) fn text_layout(text: &str) -> (usize, usize) {
     if lines == 0 { (1, 1) } else { (max_width.max(1), lines.max(1)) }
 }
 
-fn normalize_draw_rect(start: (f64, f64), current: (f64, f64)) -> RectF {
+pub(crate) fn normalize_draw_rect(start: (f64, f64), current: (f64, f64)) -> RectF {
     let rect = normalize_rect(
         start.0.min(current.0),
         start.1.min(current.1),
@@ -68,10 +292,162 @@ fn normalize_draw_rect(start: (f64, f64), current: (f64, f64)) -> RectF {
     RectF::new(rect.x as f64, rect.y as f64, rect.width as f64, rect.height as f64)
 }
 
+pub(crate) fn rects_overlap(a: RectF, b: RectF) -> bool {
+    a.x <= b.x + b.width && b.x <= a.x + a.width && a.y <= b.y + b.height && b.y <= a.y + a.height
+}
+
 fn clamp_next(value: f64, delta: f64, min: f64, max: f64) -> f64 {
     (value + delta).clamp(min, max)
 }
 
+fn arrow_head_length(stroke_width: f64) -> f64 {
+    (12.0 + stroke_width * 3.0).clamp(8.0, 42.0)
+}
+
+/// The points a line passes through: its two ends, or its quadratic curve
+/// sampled finely enough to look smooth.
+pub(crate) fn line_path(start: (f64, f64), end: (f64, f64), control: Option<(f64, f64)>) -> Vec<(f64, f64)> {
+    let Some(control) = control else {
+        return vec![start, end];
+    };
+    (0..=CURVE_SEGMENTS)
+        .map(|step| {
+            let t = step as f64 / CURVE_SEGMENTS as f64;
+            let u = 1.0 - t;
+            (
+                u * u * start.0 + 2.0 * u * t * control.0 + t * t * end.0,
+                u * u * start.1 + 2.0 * u * t * control.1 + t * t * end.1,
+            )
+        })
+        .collect()
+}
+
+/// Where the control point goes when a straight line is bent: out from its
+/// middle, to the left of the direction it was drawn in.
+pub(crate) fn default_curve_control(start: (f64, f64), end: (f64, f64)) -> (f64, f64) {
+    (
+        (start.0 + end.0) / 2.0 + (end.1 - start.1) * CURVE_BEND,
+        (start.1 + end.1) / 2.0 - (end.0 - start.0) * CURVE_BEND,
+    )
+}
+
+/// An arrowhead with its tip at `tip`, pointing away from `from`, and the
+/// point where the shaft should stop so it does not show through the tip.
+fn arrow_head(tip: (f64, f64), from: (f64, f64), stroke_width: f64) -> Option<(ArrowHead, (f64, f64))> {
+    let dx = tip.0 - from.0;
+    let dy = tip.1 - from.1;
+    let len = (dx * dx + dy * dy).sqrt();
+    if len <= f64::EPSILON {
+        return None;
+    }
+
+    let angle = dy.atan2(dx);
+    let head_length = arrow_head_length(stroke_width);
+    let indent_dist = head_length * (1.0 - ARROW_HEAD_INDENT);
+    let inner = (tip.0 - indent_dist * dx / len, tip.1 - indent_dist * dy / len);
+    let w1 = (
+        tip.0 - head_length * (angle - ARROW_HEAD_ANGLE).cos(),
+        tip.1 - head_length * (angle - ARROW_HEAD_ANGLE).sin(),
+    );
+    let w2 = (
+        tip.0 - head_length * (angle + ARROW_HEAD_ANGLE).cos(),
+        tip.1 - head_length * (angle + ARROW_HEAD_ANGLE).sin(),
+    );
+    Some(([tip, w1, inner, w2], inner))
+}
+
+/// Drops the end of `path` that an arrowhead at its last point covers and
+/// finishes it at `cut` instead.
+fn trim_path_end(path: &mut Vec<(f64, f64)>, cut: (f64, f64), covered: f64) {
+    let Some(&tip) = path.last() else {
+        return;
+    };
+    while path.len() > 1
+        && path
+            .last()
+            .is_some_and(|point| (point.0 - tip.0).powi(2) + (point.1 - tip.1).powi(2) < covered * covered)
+    {
+        path.pop();
+    }
+    path.push(cut);
+}
+
+/// Splits a polyline into dashes `dash` long with `gap` between them.
+fn dash_path(points: &[(f64, f64)], dash: f64, gap: f64) -> Vec<Vec<(f64, f64)>> {
+    let mut dashes = Vec::new();
+    let mut current: Vec<(f64, f64)> = Vec::new();
+    let mut drawing = true;
+    let mut remaining = dash;
+    for pair in points.windows(2) {
+        let (mut from, to) = (pair[0], pair[1]);
+        let mut length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+        while length > f64::EPSILON {
+            if drawing && current.is_empty() {
+                current.push(from);
+            }
+            let step = remaining.min(length);
+            let t = step / length;
+            let next = (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+            if drawing {
+                current.push(next);
+            }
+            remaining -= step;
+            length -= step;
+            from = next;
+            if remaining <= f64::EPSILON {
+                if drawing {
+                    dashes.push(std::mem::take(&mut current));
+                }
+                drawing = !drawing;
+                remaining = if drawing { dash } else { gap };
+            }
+        }
+    }
+    if current.len() > 1 {
+        dashes.push(current);
+    }
+    dashes
+}
+
+/// Lines drawn with the plain tapered arrow polygon rather than as a stroked
+/// path.
+pub(crate) const fn is_plain_arrow(style: &AnnotationStyleState, control: Option<(f64, f64)>) -> bool {
+    matches!(style.arrow_heads, ArrowHeads::End) && matches!(style.line_dash, LineDash::Solid) && control.is_none()
+}
+
+pub(crate) fn line_geometry(start: (f64, f64), end: (f64, f64), control: Option<(f64, f64)>, style: &AnnotationStyleState) -> Option<LineGeometry> {
+    let mut path = line_path(start, end, control);
+    if path_length(&path) <= 1.0 {
+        return None;
+    }
+
+    let mut heads = Vec::new();
+    let covered = arrow_head_length(style.stroke_width) * (1.0 - ARROW_HEAD_INDENT);
+    if matches!(style.arrow_heads, ArrowHeads::End | ArrowHeads::Both)
+        && let Some((head, cut)) = arrow_head(end, control.unwrap_or(start), style.stroke_width)
+    {
+        trim_path_end(&mut path, cut, covered);
+        heads.push(head);
+    }
+    if matches!(style.arrow_heads, ArrowHeads::Both)
+        && let Some((head, cut)) = arrow_head(start, control.unwrap_or(end), style.stroke_width)
+    {
+        path.reverse();
+        trim_path_end(&mut path, cut, covered);
+        path.reverse();
+        heads.push(head);
+    }
+
+    let strokes = match style.line_dash {
+        LineDash::Solid => vec![path],
+        LineDash::Dashed => {
+            let width = style.stroke_width.max(1.0);
+            dash_path(&path, width * 2.0 + 4.0, width * 2.0 + 6.0)
+        }
+    };
+    Some(LineGeometry { strokes, heads })
+}
+
 pub(crate) fn arrow_geometry(start: (f64, f64), end: (f64, f64), stroke_width: f64) -> Option<ArrowGeometry> {
     let dx = end.0 - start.0;
     let dy = end.1 - start.1;
@@ -83,10 +459,9 @@ pub(crate) fn arrow_geometry(start: (f64, f64), end: (f64, f64), stroke_width: f
     let ux = dx / len;
     let uy = dy / len;
     let angle = dy.atan2(dx);
-    let head_length = (12.0 + stroke_width * 3.0).clamp(8.0, 42.0);
-    let arrow_angle = PI / 7.0;
-    let indent_ratio = 0.2;
-    let indent_dist = head_length * (1.0 - indent_ratio);
+    let head_length = arrow_head_length(stroke_width);
+    let arrow_angle = ARROW_HEAD_ANGLE;
+    let indent_dist = head_length * (1.0 - ARROW_HEAD_INDENT);
     let inner = (end.0 - indent_dist * ux, end.1 - indent_dist * uy);
 
     let nx = -uy;
@@ -116,34 +491,45 @@ pub(crate) fn arrow_geometry(start: (f64, f64), end: (f64, f64), stroke_width: f
     })
 }
 
+impl AnnotationOutline {
+    /// Corners of the outline, clockwise from the top left one.
+    pub(crate) fn corners(&self) -> [(f64, f64); 4] {
+        rotated_corners(self.bounds, self.rotation)
+    }
+}
+
 impl AnnotationItem {
     pub(crate) fn bounds(&self) -> RectF {
         match &self.kind {
-            AnnotationKind::Arrow { start, end } => {
-                let x = start.0.min(end.0);
-                let y = start.1.min(end.1);
-                let width = (end.0 - start.0).abs();
-                let height = (end.1 - start.1).abs();
-                RectF::new(x, y, width, height)
-            }
-            AnnotationKind::Rectangle { rect } | AnnotationKind::Circle { rect } | AnnotationKind::Mosaic { rect, .. } => *rect,
+            AnnotationKind::Arrow { start, end, control } => path_bounds(&line_path(*start, *end, *control), 0.0),
+            AnnotationKind::Rectangle { rect, rotation } | AnnotationKind::Mosaic { rect, rotation, .. } => turned_rect_bounds(*rect, *rotation),
+            AnnotationKind::Circle { rect, rotation } => turned_ellipse_bounds(*rect, *rotation),
             AnnotationKind::Counter { center, .. } => {
                 let r = self.style.counter_radius.max(8.0);
                 RectF::new(center.0 - r, center.1 - r, r * 2.0, r * 2.0)
             }
             AnnotationKind::Text { origin, text } => text_bounds(*origin, self.style.text_size, text),
+            AnnotationKind::Pen { points } | AnnotationKind::Highlighter { points } => path_bounds(points, self.path_width() / 2.0),
+        }
+    }
+
+    /// Width a freehand stroke is drawn with.
+    pub(crate) fn path_width(&self) -> f64 {
+        match self.kind {
+            AnnotationKind::Highlighter { .. } => self.style.stroke_width * HIGHLIGHTER_WIDTH_SCALE,
+            _ => self.style.stroke_width,
         }
     }
 
     pub(crate) fn move_by(&mut self, dx: f64, dy: f64) {
         match &mut self.kind {
-            AnnotationKind::Arrow { start, end } => {
-                start.0 += dx;
-                start.1 += dy;
-                end.0 += dx;
-                end.1 += dy;
+            AnnotationKind::Arrow { start, end, control } => {
+                for point in [Some(start), Some(end), control.as_mut()].into_iter().flatten() {
+                    point.0 += dx;
+                    point.1 += dy;
+                }
             }
-            AnnotationKind::Rectangle { rect } | AnnotationKind::Circle { rect } | AnnotationKind::Mosaic { rect, .. } => {
+            AnnotationKind::Rectangle { rect, .. } | AnnotationKind::Circle { rect, .. } | AnnotationKind::Mosaic { rect, .. } => {
                 rect.x += dx;
                 rect.y += dy;
             }
@@ -155,6 +541,107 @@ impl AnnotationItem {
                 origin.0 += dx;
                 origin.1 += dy;
             }
+            AnnotationKind::Pen { points } | AnnotationKind::Highlighter { points } => {
+                for point in points {
+                    point.0 += dx;
+                    point.1 += dy;
+                }
+            }
+        }
+    }
+
+    /// The box the item is drawn in and how far it is turned, for items
+    /// that can be resized and turned by their handles.
+    pub(crate) fn frame(&self) -> Option<(RectF, f64)> {
+        match &self.kind {
+            AnnotationKind::Rectangle { rect, rotation }
+            | AnnotationKind::Circle { rect, rotation }
+            | AnnotationKind::Mosaic { rect, rotation, .. } => Some((*rect, *rotation)),
+            _ => None,
+        }
+    }
+
+    /// The box to outline the item with and how far it is turned.
+    pub(crate) fn outline_frame(&self) -> (RectF, f64) {
+        self.frame().unwrap_or_else(|| (self.bounds(), 0.0))
+    }
+
+    /// The handles of the item and where they are. The rotate handle of a
+    /// box sits past its top edge, or past its bottom edge when the top one
+    /// would fall outside `within`.
+    pub(crate) fn handles(&self, within: Option<RectF>) -> Vec<(AnnotationHandle, (f64, f64))> {
+        if let AnnotationKind::Arrow { start, end, control } = &self.kind {
+            let mut handles = vec![(AnnotationHandle::Start, *start), (AnnotationHandle::End, *end)];
+            handles.extend(control.map(|control| (AnnotationHandle::Control, control)));
+            return handles;
+        }
+        let Some((rect, rotation)) = self.frame() else {
+            return Vec::new();
+        };
+        let center = rect_center(rect);
+        let mut handles: Vec<_> = BoxHandle::ALL
+            .into_iter()
+            .map(|handle| {
+                let point = (
+                    center.0 + f64::from(handle.x) * rect.width / 2.0,
+                    center.1 + f64::from(handle.y) * rect.height / 2.0,
+                );
+                (AnnotationHandle::Resize(handle), rotate_point(point, center, rotation))
+            })
+            .collect();
+        let reach = rect.height / 2.0 + ROTATE_HANDLE_OFFSET;
+        let mut rotate = rotate_point((center.0, center.1 - reach), center, rotation);
+        if within.is_some_and(|within| !within.contains_point(rotate.0, rotate.1)) {
+            rotate = rotate_point((center.0, center.1 + reach), center, rotation);
+        }
+        handles.push((AnnotationHandle::Rotate, rotate));
+        handles
+    }
+
+    /// Reshapes the item into `origin` with `handle` dragged from `start` to
+    /// `current`.
+    pub(crate) fn drag_handle(&mut self, origin: &AnnotationKind, handle: AnnotationHandle, start: (f64, f64), current: (f64, f64)) {
+        self.kind = origin.clone();
+        if handle == AnnotationHandle::Rotate {
+            if let Some((rect, rotation)) = self.frame() {
+                let (cx, cy) = rect_center(rect);
+                let turn = (current.1 - cy).atan2(current.0 - cx) - (start.1 - cy).atan2(start.0 - cx);
+                self.set_rotation(snap_rotation(rotation + turn));
+            }
+            return;
+        }
+        let Some(anchor) = self.handles(None).into_iter().find_map(|(h, point)| (h == handle).then_some(point)) else {
+            return;
+        };
+        let point = (anchor.0 + current.0 - start.0, anchor.1 + current.1 - start.1);
+        match (&mut self.kind, handle) {
+            (AnnotationKind::Arrow { start, end, control }, AnnotationHandle::Start | AnnotationHandle::End) => {
+                let from = (*start, *end);
+                if handle == AnnotationHandle::Start {
+                    *start = point;
+                } else {
+                    *end = point;
+                }
+                if let Some(control) = control {
+                    *control = follow_line(*control, from, (*start, *end));
+                }
+            }
+            (AnnotationKind::Arrow { control: Some(control), .. }, AnnotationHandle::Control) => *control = point,
+            (
+                AnnotationKind::Rectangle { rect, rotation }
+                | AnnotationKind::Circle { rect, rotation }
+                | AnnotationKind::Mosaic { rect, rotation, .. },
+                AnnotationHandle::Resize(handle),
+            ) => *rect = resize_turned_rect(*rect, *rotation, handle, point),
+            _ => {}
+        }
+    }
+
+    fn set_rotation(&mut self, value: f64) {
+        if let AnnotationKind::Rectangle { rotation, .. } | AnnotationKind::Circle { rotation, .. } | AnnotationKind::Mosaic { rotation, .. } =
+            &mut self.kind
+        {
+            *rotation = value;
         }
     }
 
@@ -163,7 +650,11 @@ impl AnnotationItem {
         let prev_kind = self.kind.clone();
 
         match &mut self.kind {
-            AnnotationKind::Arrow { .. } | AnnotationKind::Rectangle { .. } | AnnotationKind::Circle { .. } => {
+            AnnotationKind::Arrow { .. }
+            | AnnotationKind::Rectangle { .. }
+            | AnnotationKind::Circle { .. }
+            | AnnotationKind::Pen { .. }
+            | AnnotationKind::Highlighter { .. } => {
                 self.style.stroke_width = clamp_next(self.style.stroke_width, delta_steps, 1.0, 18.0);
             }
             AnnotationKind::Counter { .. } => {
@@ -183,7 +674,11 @@ impl AnnotationItem {
 
     pub(crate) fn primary_metric(&self) -> f64 {
         match &self.kind {
-            AnnotationKind::Arrow { .. } | AnnotationKind::Rectangle { .. } | AnnotationKind::Circle { .. } => self.style.stroke_width,
+            AnnotationKind::Arrow { .. }
+            | AnnotationKind::Rectangle { .. }
+            | AnnotationKind::Circle { .. }
+            | AnnotationKind::Pen { .. }
+            | AnnotationKind::Highlighter { .. } => self.style.stroke_width,
             AnnotationKind::Counter { .. } => self.style.counter_radius,
             AnnotationKind::Text { .. } => self.style.text_size,
             AnnotationKind::Mosaic { intensity, .. } => *intensity,
@@ -200,9 +695,14 @@ pub(crate) fn text_bounds(origin: (f64, f64), text_size: f64, text: &str) -> Rec
 
 pub(crate) fn contains_point_with_bounds(item: &AnnotationItem, point: (f64, f64), bounds: RectF) -> bool {
     match &item.kind {
-        AnnotationKind::Arrow { start, end } => distance_to_segment(point, *start, *end) <= item.style.stroke_width.max(3.0) + 6.0,
-        AnnotationKind::Rectangle { rect } | AnnotationKind::Mosaic { rect, .. } => rect.contains_point(point.0, point.1),
-        AnnotationKind::Circle { rect } => point_in_ellipse(point, *rect),
+        AnnotationKind::Arrow { start, end, control } => {
+            distance_to_path(point, &line_path(*start, *end, *control)) <= item.style.stroke_width.max(3.0) + 6.0
+        }
+        AnnotationKind::Rectangle { rect, rotation } | AnnotationKind::Mosaic { rect, rotation, .. } => {
+            let (x, y) = rotate_point(point, rect_center(*rect), -rotation);
+            rect.contains_point(x, y)
+        }
+        AnnotationKind::Circle { rect, rotation } => point_in_ellipse(rotate_point(point, rect_center(*rect), -rotation), *rect),
         AnnotationKind::Counter { center, .. } => {
             let dx = point.0 - center.0;
             let dy = point.1 - center.1;
@@ -210,6 +710,7 @@ pub(crate) fn contains_point_with_bounds(item: &AnnotationItem, point: (f64, f64
             dx * dx + dy * dy <= radius * radius
         }
         AnnotationKind::Text { .. } => bounds.contains_point(point.0, point.1),
+        AnnotationKind::Pen { points } | AnnotationKind::Highlighter { points } => distance_to_path(point, points) <= item.path_width() / 2.0 + 6.0,
     }
 }
 
@@ -217,37 +718,53 @@ pub(crate) fn build_drawing_item(
     tool: AnnotationTool,
     start: (f64, f64),
     current: (f64, f64),
+    path: &[(f64, f64)],
     style: AnnotationStyleState,
     id: u64,
 ) -> Option<AnnotationItem> {
     let kind = match tool {
-        AnnotationTool::Arrow => AnnotationKind::Arrow { start, end: current },
+        AnnotationTool::Arrow => AnnotationKind::Arrow {
+            start,
+            end: current,
+            control: style.line_curved.then(|| default_curve_control(start, current)),
+        },
         AnnotationTool::Rectangle => AnnotationKind::Rectangle {
             rect: normalize_draw_rect(start, current),
+            rotation: 0.0,
         },
         AnnotationTool::Circle => AnnotationKind::Circle {
             rect: normalize_draw_rect(start, current),
+            rotation: 0.0,
         },
         AnnotationTool::Mosaic => AnnotationKind::Mosaic {
             rect: normalize_draw_rect(start, current),
             mode: style.mosaic_mode,
             intensity: style.mosaic_intensity,
+            rotation: 0.0,
         },
+        AnnotationTool::Pen => AnnotationKind::Pen { points: smooth_path(path) },
+        AnnotationTool::Highlighter => AnnotationKind::Highlighter { points: smooth_path(path) },
         AnnotationTool::Counter | AnnotationTool::Text => return None,
     };
 
-    Some(AnnotationItem { id, style, kind })
+    Some(AnnotationItem {
+        id,
+        style,
+        kind,
+        group: None,
+    })
 }
 
 pub(crate) fn annotation_item_large_enough(item: &AnnotationItem, min_selection_size: f64) -> bool {
     match &item.kind {
-        AnnotationKind::Arrow { start, end } => {
+        AnnotationKind::Arrow { start, end, .. } => {
             let length = ((end.0 - start.0).powi(2) + (end.1 - start.1).powi(2)).sqrt();
             length >= MIN_DRAW_LENGTH
         }
-        AnnotationKind::Rectangle { rect } | AnnotationKind::Circle { rect } | AnnotationKind::Mosaic { rect, .. } => {
+        AnnotationKind::Rectangle { rect, .. } | AnnotationKind::Circle { rect, .. } | AnnotationKind::Mosaic { rect, .. } => {
             rect.width >= min_selection_size && rect.height >= min_selection_size
         }
+        AnnotationKind::Pen { points } | AnnotationKind::Highlighter { points } => path_length(points) >= MIN_DRAW_LENGTH,
         AnnotationKind::Counter { .. } | AnnotationKind::Text { .. } => true,
     }
 }
@@ -259,6 +776,12 @@ pub(crate) fn sync_style_from_item(style: &mut AnnotationStyleState, item: &Anno
     style.stroke_width = item.style.stroke_width;
     style.text_size = item.style.text_size;
     style.counter_radius = item.style.counter_radius;
+    style.arrow_heads = item.style.arrow_heads;
+    style.line_dash = item.style.line_dash;
+    style.line_curved = match &item.kind {
+        AnnotationKind::Arrow { control, .. } => control.is_some(),
+        _ => item.style.line_curved,
+    };
     match &item.kind {
         AnnotationKind::Mosaic { mode, intensity, .. } => {
             style.mosaic_mode = *mode;
@@ -277,3 +800,15 @@ pub(crate) fn ensure_mosaic_kind_style(kind: &mut AnnotationKind, style: &Annota
         *intensity = style.mosaic_intensity;
     }
 }
+
+/// Bends or straightens a line to match `style.line_curved`, keeping the
+/// control point of a line that is already bent.
+pub(crate) fn ensure_line_kind_style(kind: &mut AnnotationKind, style: &AnnotationStyleState) {
+    if let AnnotationKind::Arrow { start, end, control } = kind {
+        match (style.line_curved, &control) {
+            (true, None) => *control = Some(default_curve_control(*start, *end)),
+            (false, Some(_)) => *control = None,
+            _ => {}
+        }
+    }
+}
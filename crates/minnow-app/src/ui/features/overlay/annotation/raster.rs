@@ -1,8 +1,9 @@
 use image::imageops;
+use image::{GrayImage, Luma};
 use image::{Rgba, RgbaImage};
 use imageproc::drawing::{
-    draw_filled_circle_mut, draw_filled_ellipse_mut, draw_filled_rect_mut, draw_hollow_circle_mut, draw_hollow_ellipse_mut, draw_hollow_rect_mut,
-    draw_polygon_mut, draw_text_mut,
+    Canvas, draw_filled_circle_mut, draw_filled_ellipse_mut, draw_filled_rect_mut, draw_hollow_circle_mut, draw_hollow_ellipse_mut,
+    draw_hollow_rect_mut, draw_polygon_mut, draw_text_mut,
 };
 use imageproc::point::Point;
 use imageproc::rect::Rect as ImageRect;
@@ -12,7 +13,10 @@ use std::sync::LazyLock;
 use crate::services::geometry::RectF;
 
 use super::model::{AnnotationItem, AnnotationKind, MosaicMode};
-use super::ops::arrow_geometry;
+use super::ops::{arrow_geometry, ellipse_outline, is_plain_arrow, is_turned, line_geometry, rotated_corners};
+
+/// How strongly a highlighter tints what is under it.
+const HIGHLIGHTER_OPACITY: f64 = 0.7;
 
 static OVERLAY_TEXT_FONT: LazyLock<Option<ab_glyph::FontArc>> = LazyLock::new(load_text_font);
 
@@ -102,7 +106,19 @@ fn ellipse_stroke(image: &mut RgbaImage, center: (i32, i32), rx: i32, ry: i32, c
     }
 }
 
-fn draw_arrow(image: &mut RgbaImage, item: &AnnotationItem, start: (f64, f64), end: (f64, f64), scale: f64, offset: (f64, f64)) {
+fn draw_arrow(
+    image: &mut RgbaImage,
+    item: &AnnotationItem,
+    start: (f64, f64),
+    end: (f64, f64),
+    control: Option<(f64, f64)>,
+    scale: f64,
+    offset: (f64, f64),
+) {
+    if !is_plain_arrow(&item.style, control) {
+        draw_line(image, item, start, end, control, scale, offset);
+        return;
+    }
     let Some(geometry) = arrow_geometry(start, end, item.style.stroke_width) else {
         return;
     };
@@ -117,7 +133,114 @@ fn draw_arrow(image: &mut RgbaImage, item: &AnnotationItem, start: (f64, f64), e
     draw_polygon_mut(image, polygon.as_slice(), rgba_from_u32(item.style.stroke_color));
 }
 
-fn draw_rectangle(image: &mut RgbaImage, item: &AnnotationItem, rect: RectF, scale: f64, offset: (f64, f64)) {
+fn draw_line(
+    image: &mut RgbaImage,
+    item: &AnnotationItem,
+    start: (f64, f64),
+    end: (f64, f64),
+    control: Option<(f64, f64)>,
+    scale: f64,
+    offset: (f64, f64),
+) {
+    let Some(geometry) = line_geometry(start, end, control, &item.style) else {
+        return;
+    };
+    let color = rgba_from_u32(item.style.stroke_color);
+    for stroke in &geometry.strokes {
+        let points: Vec<(f64, f64)> = stroke.iter().map(|point| to_image_xy(*point, scale, offset)).collect();
+        stroke_polyline(image, &points, item.style.stroke_width * scale, color);
+    }
+    for head in &geometry.heads {
+        let polygon = head.map(|point| {
+            let (x, y) = to_image_xy(point, scale, offset);
+            Point::new(x.round() as i32, y.round() as i32)
+        });
+        draw_polygon_mut(image, &polygon, color);
+    }
+}
+
+/// Paints a polyline `width` wide with round joins and caps.
+fn stroke_polyline<C: Canvas>(canvas: &mut C, points: &[(f64, f64)], width: f64, color: C::Pixel) {
+    let radius = (width / 2.0).max(0.5);
+    for pair in points.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        let length = ((end.0 - start.0).powi(2) + (end.1 - start.1).powi(2)).sqrt();
+        if length <= f64::EPSILON {
+            continue;
+        }
+        let nx = -(end.1 - start.1) / length * radius;
+        let ny = (end.0 - start.0) / length * radius;
+        let quad = [
+            (start.0 + nx, start.1 + ny),
+            (end.0 + nx, end.1 + ny),
+            (end.0 - nx, end.1 - ny),
+            (start.0 - nx, start.1 - ny),
+        ]
+        .map(|(x, y)| Point::new(x.round() as i32, y.round() as i32));
+        draw_polygon_mut(canvas, &quad, color);
+    }
+    let joint_radius = radius.round() as i32;
+    for point in points {
+        draw_filled_circle_mut(canvas, (point.0.round() as i32, point.1.round() as i32), joint_radius, color);
+    }
+}
+
+fn draw_pen(image: &mut RgbaImage, item: &AnnotationItem, points: &[(f64, f64)], scale: f64, offset: (f64, f64)) {
+    let points: Vec<(f64, f64)> = points.iter().map(|point| to_image_xy(*point, scale, offset)).collect();
+    stroke_polyline(image, &points, item.path_width() * scale, rgba_from_u32(item.style.stroke_color));
+}
+
+/// Multiplies the highlighter color into the pixels under the stroke. The
+/// stroke is traced into a mask first so overlapping segments tint a pixel
+/// only once.
+fn draw_highlighter(image: &mut RgbaImage, item: &AnnotationItem, points: &[(f64, f64)], scale: f64, offset: (f64, f64)) {
+    let Some(area) = clamp_image_rect(image, item.bounds(), scale, offset) else {
+        return;
+    };
+    let points: Vec<(f64, f64)> = points
+        .iter()
+        .map(|point| {
+            let (x, y) = to_image_xy(*point, scale, offset);
+            (x - f64::from(area.left()), y - f64::from(area.top()))
+        })
+        .collect();
+    let mut mask = GrayImage::new(area.width(), area.height());
+    stroke_polyline(&mut mask, &points, item.path_width() * scale, Luma([255]));
+
+    let tint = rgba_from_u32(item.style.stroke_color);
+    let opacity = HIGHLIGHTER_OPACITY * f64::from(tint[3]) / 255.0;
+    for (x, y, coverage) in mask.enumerate_pixels() {
+        if coverage[0] == 0 {
+            continue;
+        }
+        let pixel = image.get_pixel_mut(area.left() as u32 + x, area.top() as u32 + y);
+        for channel in 0..3 {
+            let base = f64::from(pixel[channel]);
+            let multiplied = base * f64::from(tint[channel]) / 255.0;
+            pixel[channel] = (base + (multiplied - base) * opacity).round() as u8;
+        }
+    }
+}
+
+/// Fills and strokes the closed outline through `points`, for shapes that are
+/// turned and so cannot use the axis-aligned drawing helpers.
+fn draw_closed_shape(image: &mut RgbaImage, item: &AnnotationItem, points: &[(f64, f64)], scale: f64, offset: (f64, f64)) {
+    let mut points: Vec<(f64, f64)> = points.iter().map(|point| to_image_xy(*point, scale, offset)).collect();
+    if item.style.fill_enabled {
+        let polygon: Vec<Point<i32>> = points.iter().map(|(x, y)| Point::new(x.round() as i32, y.round() as i32)).collect();
+        if polygon.first() != polygon.last() {
+            draw_polygon_mut(image, &polygon, rgba_from_u32(item.style.fill_color));
+        }
+    }
+    points.extend(points.first().copied());
+    stroke_polyline(image, &points, item.style.stroke_width * scale, rgba_from_u32(item.style.stroke_color));
+}
+
+fn draw_rectangle(image: &mut RgbaImage, item: &AnnotationItem, rect: RectF, rotation: f64, scale: f64, offset: (f64, f64)) {
+    if is_turned(rotation) {
+        draw_closed_shape(image, item, &rotated_corners(rect, rotation), scale, offset);
+        return;
+    }
     let Some(image_rect) = clamp_image_rect(image, rect, scale, offset) else {
         return;
     };
@@ -132,7 +255,11 @@ fn draw_rectangle(image: &mut RgbaImage, item: &AnnotationItem, rect: RectF, sca
     );
 }
 
-fn draw_circle(image: &mut RgbaImage, item: &AnnotationItem, rect: RectF, scale: f64, offset: (f64, f64)) {
+fn draw_circle(image: &mut RgbaImage, item: &AnnotationItem, rect: RectF, rotation: f64, scale: f64, offset: (f64, f64)) {
+    if is_turned(rotation) {
+        draw_closed_shape(image, item, &ellipse_outline(rect, rotation), scale, offset);
+        return;
+    }
     let (cx, cy) = to_image_xy((rect.x + rect.width / 2.0, rect.y + rect.height / 2.0), scale, offset);
     let rx = ((rect.width * scale) / 2.0).round().max(1.0) as i32;
     let ry = ((rect.height * scale) / 2.0).round().max(1.0) as i32;
@@ -239,24 +366,79 @@ fn draw_mosaic_blur(image: &mut RgbaImage, image_rect: ImageRect, sigma: f32) {
     imageops::replace(image, &blurred, i64::from(min_x), i64::from(min_y));
 }
 
-fn draw_mosaic(image: &mut RgbaImage, rect: RectF, mode: MosaicMode, intensity: f64, scale: f64, offset: (f64, f64)) {
-    let Some(image_rect) = clamp_image_rect(image, rect, scale, offset) else {
-        return;
-    };
+fn apply_mosaic(image: &mut RgbaImage, image_rect: ImageRect, mode: MosaicMode, intensity: f64, scale: f64) {
     match mode {
         MosaicMode::Pixelate => draw_mosaic_pixelate(image, image_rect, (intensity * scale).round() as u32),
         MosaicMode::Blur => draw_mosaic_blur(image, image_rect, (intensity * scale * 0.3) as f32),
     }
 }
 
+/// Applies the mosaic to the box around a turned region on a copy, then
+/// keeps only the pixels inside the region.
+fn draw_turned_mosaic(
+    image: &mut RgbaImage,
+    bounds: RectF,
+    corners: [(f64, f64); 4],
+    mode: MosaicMode,
+    intensity: f64,
+    scale: f64,
+    offset: (f64, f64),
+) {
+    let Some(area) = clamp_image_rect(image, bounds, scale, offset) else {
+        return;
+    };
+    let mut patch = imageops::crop_imm(image, area.left() as u32, area.top() as u32, area.width(), area.height()).to_image();
+    apply_mosaic(
+        &mut patch,
+        ImageRect::at(0, 0).of_size(area.width(), area.height()),
+        mode,
+        intensity,
+        scale,
+    );
+
+    let polygon = corners.map(|point| {
+        let (x, y) = to_image_xy(point, scale, offset);
+        Point::new(x.round() as i32 - area.left(), y.round() as i32 - area.top())
+    });
+    let mut mask = GrayImage::new(area.width(), area.height());
+    draw_polygon_mut(&mut mask, &polygon, Luma([255]));
+    for (x, y, coverage) in mask.enumerate_pixels() {
+        if coverage[0] != 0 {
+            image.put_pixel(area.left() as u32 + x, area.top() as u32 + y, *patch.get_pixel(x, y));
+        }
+    }
+}
+
+fn draw_mosaic(image: &mut RgbaImage, item: &AnnotationItem, scale: f64, offset: (f64, f64)) {
+    let AnnotationKind::Mosaic {
+        rect,
+        mode,
+        intensity,
+        rotation,
+    } = item.kind
+    else {
+        return;
+    };
+    if is_turned(rotation) {
+        draw_turned_mosaic(image, item.bounds(), rotated_corners(rect, rotation), mode, intensity, scale, offset);
+        return;
+    }
+    let Some(image_rect) = clamp_image_rect(image, rect, scale, offset) else {
+        return;
+    };
+    apply_mosaic(image, image_rect, mode, intensity, scale);
+}
+
 pub(crate) fn draw_annotation_item(image: &mut RgbaImage, item: &AnnotationItem, scale: f64, offset: (f64, f64)) {
     match &item.kind {
-        AnnotationKind::Arrow { start, end } => draw_arrow(image, item, *start, *end, scale, offset),
-        AnnotationKind::Rectangle { rect } => draw_rectangle(image, item, *rect, scale, offset),
-        AnnotationKind::Circle { rect } => draw_circle(image, item, *rect, scale, offset),
+        AnnotationKind::Arrow { start, end, control } => draw_arrow(image, item, *start, *end, *control, scale, offset),
+        AnnotationKind::Rectangle { rect, rotation } => draw_rectangle(image, item, *rect, *rotation, scale, offset),
+        AnnotationKind::Circle { rect, rotation } => draw_circle(image, item, *rect, *rotation, scale, offset),
         AnnotationKind::Counter { center, number } => draw_counter(image, item, *center, *number, scale, offset),
         AnnotationKind::Text { origin, text } => draw_text(image, item, *origin, text, scale, offset),
-        AnnotationKind::Mosaic { rect, mode, intensity } => draw_mosaic(image, *rect, *mode, *intensity, scale, offset),
+        AnnotationKind::Mosaic { .. } => draw_mosaic(image, item, scale, offset),
+        AnnotationKind::Pen { points } => draw_pen(image, item, points, scale, offset),
+        AnnotationKind::Highlighter { points } => draw_highlighter(image, item, points, scale, offset),
     }
 }
 
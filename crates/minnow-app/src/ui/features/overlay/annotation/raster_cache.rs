@@ -29,7 +29,7 @@ pub(crate) struct InteractionBaseCache {
     pub selection: RectF,
     pub scale: f64,
     pub committed_revision: u64,
-    pub moving_id: u64,
+    pub moving_ids: Vec<u64>,
     pub rgba: Arc<RgbaImage>,
 }
 
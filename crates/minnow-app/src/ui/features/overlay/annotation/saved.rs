@@ -0,0 +1,245 @@
+//! Annotation documents stored with capture history.
+//!
+//! Items are saved relative to the selection and tagged with the monitor
+//! scale, so a reopened capture lines up wherever its crop is placed.
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::geometry::RectF;
+
+use super::model::{AnnotationItem, AnnotationKind};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedAnnotations {
+    scale: f64,
+    items: Vec<AnnotationItem>,
+}
+
+pub(crate) fn encode(items: &[AnnotationItem], selection: RectF, scale: f64) -> Option<String> {
+    if items.is_empty() {
+        return None;
+    }
+    let saved = SavedAnnotations {
+        scale,
+        items: items
+            .iter()
+            .map(|item| transformed(item, (selection.x, selection.y), 1.0, (0.0, 0.0)))
+            .collect(),
+    };
+    match toml::to_string(&saved) {
+        Ok(document) => Some(document),
+        Err(err) => {
+            tracing::error!("Failed to encode annotations for history: {err}");
+            None
+        }
+    }
+}
+
+/// Places a saved document on `selection`, converting from the scale it was
+/// drawn at to `scale`.
+pub(crate) fn decode(document: &str, selection: RectF, scale: f64) -> Option<Vec<AnnotationItem>> {
+    let saved: SavedAnnotations = match toml::from_str(document) {
+        Ok(saved) => saved,
+        Err(err) => {
+            tracing::warn!("Ignoring unreadable history annotations: {err}");
+            return None;
+        }
+    };
+    let factor = if saved.scale > 0.0 && scale > 0.0 { saved.scale / scale } else { 1.0 };
+    Some(
+        saved
+            .items
+            .iter()
+            .map(|item| transformed(item, (0.0, 0.0), factor, (selection.x, selection.y)))
+            .collect(),
+    )
+}
+
+fn transformed(item: &AnnotationItem, from: (f64, f64), factor: f64, to: (f64, f64)) -> AnnotationItem {
+    let point = |(x, y): (f64, f64)| ((x - from.0) * factor + to.0, (y - from.1) * factor + to.1);
+    let rect = |rect: RectF| {
+        let (x, y) = point((rect.x, rect.y));
+        RectF::new(x, y, rect.width * factor, rect.height * factor)
+    };
+
+    let mut item = item.clone();
+    item.kind = match item.kind {
+        AnnotationKind::Arrow { start, end, control } => AnnotationKind::Arrow {
+            start: point(start),
+            end: point(end),
+            control: control.map(point),
+        },
+        AnnotationKind::Rectangle { rect: r, rotation } => AnnotationKind::Rectangle { rect: rect(r), rotation },
+        AnnotationKind::Circle { rect: r, rotation } => AnnotationKind::Circle { rect: rect(r), rotation },
+        AnnotationKind::Counter { center, number } => AnnotationKind::Counter {
+            center: point(center),
+            number,
+        },
+        AnnotationKind::Text { origin, text } => AnnotationKind::Text { origin: point(origin), text },
+        AnnotationKind::Mosaic {
+            rect: r,
+            mode,
+            intensity,
+            rotation,
+        } => AnnotationKind::Mosaic {
+            rect: rect(r),
+            mode,
+            intensity,
+            rotation,
+        },
+        AnnotationKind::Pen { points } => AnnotationKind::Pen {
+            points: points.into_iter().map(point).collect(),
+        },
+        AnnotationKind::Highlighter { points } => AnnotationKind::Highlighter {
+            points: points.into_iter().map(point).collect(),
+        },
+    };
+    item.style.stroke_width *= factor;
+    item.style.text_size *= factor;
+    item.style.counter_radius *= factor;
+    item
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::features::overlay::annotation::model::{AnnotationStyleState, ArrowHeads, LineDash, MosaicMode};
+
+    fn items() -> Vec<AnnotationItem> {
+        let style = AnnotationStyleState::default();
+        vec![
+            AnnotationItem {
+                id: 1,
+                style,
+                kind: AnnotationKind::Arrow {
+                    start: (110.0, 60.0),
+                    end: (150.0, 90.0),
+                    control: None,
+                },
+                group: None,
+            },
+            AnnotationItem {
+                id: 2,
+                style,
+                kind: AnnotationKind::Text {
+                    origin: (120.0, 70.0),
+                    text: "note\nsecond line".to_string(),
+                },
+                group: None,
+            },
+            AnnotationItem {
+                id: 3,
+                style,
+                kind: AnnotationKind::Mosaic {
+                    rect: RectF::new(100.0, 50.0, 20.0, 10.0),
+                    mode: MosaicMode::Blur,
+                    intensity: 12.0,
+                    rotation: 0.5,
+                },
+                group: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn round_trip_moves_items_with_the_selection() {
+        let document = encode(&items(), RectF::new(100.0, 50.0, 80.0, 60.0), 1.0).expect("encode annotations");
+
+        let restored = decode(&document, RectF::new(10.0, 20.0, 80.0, 60.0), 1.0).expect("decode annotations");
+
+        assert_eq!(restored.len(), 3);
+        assert_eq!(
+            restored[0].kind,
+            AnnotationKind::Arrow {
+                start: (20.0, 30.0),
+                end: (60.0, 60.0),
+                control: None,
+            }
+        );
+        assert_eq!(
+            restored[1].kind,
+            AnnotationKind::Text {
+                origin: (30.0, 40.0),
+                text: "note\nsecond line".to_string(),
+            }
+        );
+        assert_eq!(
+            restored[2].kind,
+            AnnotationKind::Mosaic {
+                rect: RectF::new(10.0, 20.0, 20.0, 10.0),
+                mode: MosaicMode::Blur,
+                intensity: 12.0,
+                rotation: 0.5,
+            }
+        );
+    }
+
+    #[test]
+    fn decode_rescales_for_a_different_monitor_scale() {
+        let document = encode(&items(), RectF::new(100.0, 50.0, 80.0, 60.0), 2.0).expect("encode annotations");
+
+        let restored = decode(&document, RectF::new(0.0, 0.0, 160.0, 120.0), 1.0).expect("decode annotations");
+
+        assert_eq!(
+            restored[0].kind,
+            AnnotationKind::Arrow {
+                start: (20.0, 20.0),
+                end: (100.0, 80.0),
+                control: None,
+            }
+        );
+        assert_eq!(restored[0].style.stroke_width, AnnotationStyleState::default().stroke_width * 2.0);
+    }
+
+    #[test]
+    fn curves_keep_their_control_point_and_older_documents_read_as_plain_arrows() {
+        let style = AnnotationStyleState {
+            arrow_heads: ArrowHeads::Both,
+            line_dash: LineDash::Dashed,
+            line_curved: true,
+            ..AnnotationStyleState::default()
+        };
+        let curve = AnnotationItem {
+            id: 1,
+            style,
+            kind: AnnotationKind::Arrow {
+                start: (100.0, 50.0),
+                end: (140.0, 50.0),
+                control: Some((120.0, 30.0)),
+            },
+            group: None,
+        };
+        let document = encode(&[curve], RectF::new(100.0, 50.0, 80.0, 60.0), 1.0).expect("encode annotations");
+
+        let restored = decode(&document, RectF::new(0.0, 0.0, 80.0, 60.0), 1.0).expect("decode annotations");
+        assert_eq!(restored[0].style, style);
+        assert_eq!(
+            restored[0].kind,
+            AnnotationKind::Arrow {
+                start: (0.0, 0.0),
+                end: (40.0, 0.0),
+                control: Some((20.0, -20.0)),
+            }
+        );
+
+        let older = document
+            .lines()
+            .filter(|line| {
+                !["arrow_heads", "line_dash", "line_curved", "control"]
+                    .iter()
+                    .any(|key| line.starts_with(key))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let restored = decode(&older, RectF::new(0.0, 0.0, 80.0, 60.0), 1.0).expect("decode older annotations");
+        assert_eq!(restored[0].style.arrow_heads, ArrowHeads::End);
+        assert_eq!(restored[0].style.line_dash, LineDash::Solid);
+        assert!(matches!(restored[0].kind, AnnotationKind::Arrow { control: None, .. }));
+    }
+
+    #[test]
+    fn empty_or_unreadable_documents_are_skipped() {
+        assert_eq!(encode(&[], RectF::new(0.0, 0.0, 10.0, 10.0), 1.0), None);
+        assert_eq!(decode("items = [", RectF::new(0.0, 0.0, 10.0, 10.0), 1.0), None);
+    }
+}
@@ -1,13 +1,60 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
-use super::model::AnnotationItem;
+use super::model::{AnnotationItem, AnnotationKind};
+
+/// How many steps undo can go back.
+const HISTORY_LIMIT: usize = 100;
+
+/// What an in-place change did to the items it touched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ChangeKind {
+    Move,
+    Reshape,
+    Restyle,
+    /// A size step from the wheel or the stroke keys.
+    Resize,
+    EditText,
+    Group,
+}
+
+impl ChangeKind {
+    /// Back-to-back changes of this kind to the same items undo as one step,
+    /// so a run of wheel notches or text edits is not replayed one by one.
+    const fn coalesces(self) -> bool {
+        matches!(self, Self::Resize | Self::EditText)
+    }
+}
+
+/// A reversible step in the undo history, holding only the items it touched.
+#[derive(Clone, Debug, PartialEq)]
+enum StoreCommand {
+    Insert {
+        index: usize,
+        item: AnnotationItem,
+    },
+    /// Items taken out, by ascending index before the removal.
+    Remove {
+        removed: Vec<(usize, AnnotationItem)>,
+    },
+    /// Items changed where they are, as they were before and after.
+    Change {
+        kind: ChangeKind,
+        before: Vec<AnnotationItem>,
+        after: Vec<AnnotationItem>,
+    },
+    /// The stacking order of every item, by id.
+    Restack {
+        before: Vec<u64>,
+        after: Vec<u64>,
+    },
+}
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct AnnotationStore {
     items: Vec<AnnotationItem>,
     id_to_index: HashMap<u64, usize>,
-    undo_stack: Vec<Vec<AnnotationItem>>,
-    redo_stack: Vec<Vec<AnnotationItem>>,
+    undo_stack: VecDeque<StoreCommand>,
+    redo_stack: Vec<StoreCommand>,
 }
 
 impl AnnotationStore {
@@ -51,74 +98,200 @@ impl AnnotationStore {
         self.items.get(index)
     }
 
-    pub(crate) fn visible_item_mut(&mut self, id: u64) -> Option<&mut AnnotationItem> {
-        let index = self.visible_index(id)?;
-        self.items.get_mut(index)
+    /// Replaces every item without recording an undo step.
+    pub(crate) fn reset_to(&mut self, items: Vec<AnnotationItem>) {
+        self.clear();
+        self.items = items;
+        self.rebuild_visible_index();
     }
 
     pub(crate) fn push(&mut self, item: AnnotationItem) {
-        self.push_undo_snapshot();
-        self.items.push(item);
-        self.rebuild_visible_index();
+        let index = self.items.len();
+        self.id_to_index.insert(item.id, index);
+        self.items.push(item.clone());
+        self.record(StoreCommand::Insert { index, item });
     }
 
-    pub(crate) fn remove_visible_by_id(&mut self, id: u64) -> bool {
-        let Some(index) = self.visible_index(id) else {
+    pub(crate) fn remove_visible_by_ids(&mut self, ids: &[u64]) -> bool {
+        let removed: Vec<(usize, AnnotationItem)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| ids.contains(&item.id))
+            .map(|(index, item)| (index, item.clone()))
+            .collect();
+        if removed.is_empty() {
             return false;
-        };
-        self.push_undo_snapshot();
-        self.items.remove(index);
+        }
+        self.items.retain(|item| !ids.contains(&item.id));
         self.rebuild_visible_index();
+        self.record(StoreCommand::Remove { removed });
         true
     }
 
-    pub(crate) fn move_visible_item_by(&mut self, id: u64, dx: f64, dy: f64) -> bool {
+    pub(crate) fn move_visible_items_by(&mut self, ids: &[u64], dx: f64, dy: f64) -> bool {
         if dx.abs() <= f64::EPSILON && dy.abs() <= f64::EPSILON {
             return false;
         }
-        let Some(index) = self.visible_index(id) else {
-            return false;
-        };
-        self.push_undo_snapshot();
-        self.items[index].move_by(dx, dy);
-        true
+        self.update_visible_items(ids, ChangeKind::Move, |item| item.move_by(dx, dy))
+    }
+
+    pub(crate) fn replace_visible_kind(&mut self, id: u64, kind: AnnotationKind) -> bool {
+        self.update_visible_items(&[id], ChangeKind::Reshape, |item| item.kind.clone_from(&kind))
     }
 
     pub(crate) fn translate_all_visible(&mut self, dx: f64, dy: f64) -> bool {
         if dx.abs() <= f64::EPSILON && dy.abs() <= f64::EPSILON {
             return false;
         }
-        if self.items.is_empty() {
-            return false;
-        }
+        let ids: Vec<u64> = self.items.iter().map(|item| item.id).collect();
+        self.update_visible_items(&ids, ChangeKind::Move, |item| item.move_by(dx, dy))
+    }
 
-        self.push_undo_snapshot();
-        for item in &mut self.items {
-            item.move_by(dx, dy);
+    /// Applies `update` to the items in `ids` as one undo step, recorded only
+    /// when one of them changed.
+    pub(crate) fn update_visible_items(&mut self, ids: &[u64], kind: ChangeKind, mut update: impl FnMut(&mut AnnotationItem)) -> bool {
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+        for item in self.items.iter_mut().filter(|item| ids.contains(&item.id)) {
+            let original = item.clone();
+            update(item);
+            if *item != original {
+                before.push(original);
+                after.push(item.clone());
+            }
+        }
+        if before.is_empty() {
+            return false;
         }
+        self.record(StoreCommand::Change { kind, before, after });
         true
     }
 
+    pub(crate) fn set_visible_group(&mut self, ids: &[u64], group: Option<u64>) -> bool {
+        self.update_visible_items(ids, ChangeKind::Group, |item| item.group = group)
+    }
+
+    /// Moves the items in `ids` above every other item, keeping their order.
+    pub(crate) fn bring_to_front(&mut self, ids: &[u64]) -> bool {
+        self.restack(ids, false)
+    }
+
+    /// Moves the items in `ids` below every other item, keeping their order.
+    pub(crate) fn send_to_back(&mut self, ids: &[u64]) -> bool {
+        self.restack(ids, true)
+    }
+
     pub(crate) fn undo(&mut self) -> bool {
-        let Some(snapshot) = self.undo_stack.pop() else {
+        let Some(command) = self.undo_stack.pop_back() else {
             return false;
         };
-        self.redo_stack.push(std::mem::take(&mut self.items));
-        self.items = snapshot;
-        self.rebuild_visible_index();
+        self.revert(&command);
+        self.redo_stack.push(command);
         true
     }
 
     pub(crate) fn redo(&mut self) -> bool {
-        let Some(snapshot) = self.redo_stack.pop() else {
+        let Some(command) = self.redo_stack.pop() else {
             return false;
         };
-        self.undo_stack.push(std::mem::take(&mut self.items));
-        self.items = snapshot;
-        self.rebuild_visible_index();
+        self.apply(&command);
+        self.undo_stack.push_back(command);
         true
     }
 
+    fn restack(&mut self, ids: &[u64], to_back: bool) -> bool {
+        let before: Vec<u64> = self.items.iter().map(|item| item.id).collect();
+        let (picked, rest): (Vec<u64>, Vec<u64>) = before.iter().partition(|id| ids.contains(id));
+        let after: Vec<u64> = if to_back {
+            picked.into_iter().chain(rest).collect()
+        } else {
+            rest.into_iter().chain(picked).collect()
+        };
+        if after == before {
+            return false;
+        }
+        self.reorder(&after);
+        self.record(StoreCommand::Restack { before, after });
+        true
+    }
+
+    /// Adds `command`, which has already been applied, to the history.
+    fn record(&mut self, command: StoreCommand) {
+        self.redo_stack.clear();
+        if let StoreCommand::Change { kind, after, .. } = &command
+            && kind.coalesces()
+            && let Some(StoreCommand::Change {
+                kind: last_kind,
+                before: last_before,
+                after: last_after,
+            }) = self.undo_stack.back_mut()
+            && last_kind == kind
+            && last_after.iter().map(|item| item.id).eq(after.iter().map(|item| item.id))
+        {
+            if last_before == after {
+                self.undo_stack.pop_back();
+            } else {
+                last_after.clone_from(after);
+            }
+            return;
+        }
+        self.undo_stack.push_back(command);
+        if self.undo_stack.len() > HISTORY_LIMIT {
+            self.undo_stack.pop_front();
+        }
+    }
+
+    fn apply(&mut self, command: &StoreCommand) {
+        match command {
+            StoreCommand::Insert { index, item } => {
+                self.items.insert(*index, item.clone());
+                self.rebuild_visible_index();
+            }
+            StoreCommand::Remove { removed } => {
+                for (index, _) in removed.iter().rev() {
+                    self.items.remove(*index);
+                }
+                self.rebuild_visible_index();
+            }
+            StoreCommand::Change { after, .. } => self.overwrite(after),
+            StoreCommand::Restack { after, .. } => self.reorder(after),
+        }
+    }
+
+    fn revert(&mut self, command: &StoreCommand) {
+        match command {
+            StoreCommand::Insert { index, .. } => {
+                self.items.remove(*index);
+                self.rebuild_visible_index();
+            }
+            StoreCommand::Remove { removed } => {
+                for (index, item) in removed {
+                    self.items.insert(*index, item.clone());
+                }
+                self.rebuild_visible_index();
+            }
+            StoreCommand::Change { before, .. } => self.overwrite(before),
+            StoreCommand::Restack { before, .. } => self.reorder(before),
+        }
+    }
+
+    /// Puts `items` in place of the items with the same ids.
+    fn overwrite(&mut self, items: &[AnnotationItem]) {
+        for item in items {
+            if let Some(index) = self.visible_index(item.id) {
+                self.items[index].clone_from(item);
+            }
+        }
+    }
+
+    /// Stacks the items in the order of `ids`, which names each of them.
+    fn reorder(&mut self, ids: &[u64]) {
+        let mut by_id: HashMap<u64, AnnotationItem> = self.items.drain(..).map(|item| (item.id, item)).collect();
+        self.items = ids.iter().filter_map(|id| by_id.remove(id)).collect();
+        self.rebuild_visible_index();
+    }
+
     fn rebuild_visible_index(&mut self) {
         self.id_to_index.clear();
         for index in 0..self.items.len() {
@@ -126,17 +299,12 @@ impl AnnotationStore {
             self.id_to_index.insert(id, index);
         }
     }
-
-    fn push_undo_snapshot(&mut self) {
-        self.undo_stack.push(self.items.clone());
-        self.redo_stack.clear();
-    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ui::features::overlay::annotation::model::{AnnotationItem, AnnotationKind, AnnotationStyleState};
+    use crate::ui::features::overlay::annotation::model::{AnnotationItem, AnnotationKind, AnnotationStyleState, COLOR_PRESETS};
 
     fn item(id: u64, x: f64) -> AnnotationItem {
         AnnotationItem {
@@ -144,7 +312,9 @@ mod tests {
             style: AnnotationStyleState::default(),
             kind: AnnotationKind::Rectangle {
                 rect: crate::services::geometry::RectF::new(x, x, 10.0, 10.0),
+                rotation: 0.0,
             },
+            group: None,
         }
     }
 
@@ -160,7 +330,7 @@ mod tests {
         assert_eq!(store.visible_index(3), None);
         assert!(store.redo());
         assert_eq!(store.visible_index(3), Some(2));
-        assert!(store.remove_visible_by_id(2));
+        assert!(store.remove_visible_by_ids(&[2]));
         assert_eq!(store.visible_index(1), Some(0));
         assert_eq!(store.visible_index(3), Some(1));
     }
@@ -185,4 +355,249 @@ mod tests {
         assert_eq!(store.visible_item(1).unwrap().bounds().x, 11.0);
         assert_eq!(store.visible_item(2).unwrap().bounds().x, 12.0);
     }
+
+    #[test]
+    fn restacking_and_grouping_are_single_undo_steps() {
+        let mut store = AnnotationStore::default();
+        for id in 1..=4 {
+            store.push(item(id, id as f64));
+        }
+        let order = |store: &AnnotationStore| store.visible_items().iter().map(|item| item.id).collect::<Vec<_>>();
+
+        assert!(store.bring_to_front(&[1, 3]));
+        assert_eq!(order(&store), [2, 4, 1, 3]);
+        assert!(!store.bring_to_front(&[1, 3]));
+        assert!(store.send_to_back(&[3]));
+        assert_eq!(order(&store), [3, 2, 4, 1]);
+        assert_eq!(store.visible_index(1), Some(3));
+
+        assert!(store.set_visible_group(&[2, 4], Some(9)));
+        assert!(!store.set_visible_group(&[2, 4], Some(9)));
+        assert!(store.move_visible_items_by(&[2, 4], 5.0, 0.0));
+        assert_eq!(store.visible_item(4).unwrap().bounds().x, 9.0);
+
+        assert!(store.undo());
+        assert!(store.undo());
+        assert_eq!(store.visible_item(2).unwrap().group, None);
+        assert!(store.undo());
+        assert_eq!(order(&store), [2, 4, 1, 3]);
+    }
+
+    /// The whole-document snapshot history the store used to keep, as the
+    /// reference its commands have to match.
+    #[derive(Default)]
+    struct SnapshotStore {
+        items: Vec<AnnotationItem>,
+        undo_stack: Vec<Vec<AnnotationItem>>,
+        redo_stack: Vec<Vec<AnnotationItem>>,
+    }
+
+    impl SnapshotStore {
+        fn edit(&mut self, change: impl FnOnce(&mut Vec<AnnotationItem>)) -> bool {
+            let mut next = self.items.clone();
+            change(&mut next);
+            if next == self.items {
+                return false;
+            }
+            self.undo_stack.push(std::mem::replace(&mut self.items, next));
+            if self.undo_stack.len() > HISTORY_LIMIT {
+                self.undo_stack.remove(0);
+            }
+            self.redo_stack.clear();
+            true
+        }
+
+        fn undo(&mut self) -> bool {
+            let Some(snapshot) = self.undo_stack.pop() else {
+                return false;
+            };
+            self.redo_stack.push(std::mem::replace(&mut self.items, snapshot));
+            true
+        }
+
+        fn redo(&mut self) -> bool {
+            let Some(snapshot) = self.redo_stack.pop() else {
+                return false;
+            };
+            self.undo_stack.push(std::mem::replace(&mut self.items, snapshot));
+            true
+        }
+    }
+
+    fn stored_items(command: &StoreCommand) -> usize {
+        match command {
+            StoreCommand::Insert { .. } => 1,
+            StoreCommand::Remove { removed } => removed.len(),
+            StoreCommand::Change { before, after, .. } => before.len() + after.len(),
+            StoreCommand::Restack { .. } => 0,
+        }
+    }
+
+    #[test]
+    fn commands_undo_and_redo_like_whole_document_snapshots() {
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move |bound: u64| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed % bound
+        };
+        let mut store = AnnotationStore::default();
+        let mut reference = SnapshotStore::default();
+        let mut next_id = 1;
+
+        for step in 0..2_000 {
+            let ids: Vec<u64> = store.visible_items().iter().map(|item| item.id).filter(|_| next(3) == 0).collect();
+            let (ours, expected) = match next(10) {
+                0 | 1 => {
+                    let added = item(next_id, next(100) as f64);
+                    next_id += 1;
+                    let expected = reference.edit(|items| items.push(added.clone()));
+                    store.push(added);
+                    (true, expected)
+                }
+                2 => {
+                    let expected = reference.edit(|items| items.retain(|item| !ids.contains(&item.id)));
+                    (store.remove_visible_by_ids(&ids), expected)
+                }
+                3 => {
+                    let dx = next(5) as f64 - 2.0;
+                    let expected = reference.edit(|items| {
+                        items
+                            .iter_mut()
+                            .filter(|item| ids.contains(&item.id))
+                            .for_each(|item| item.move_by(dx, 1.5))
+                    });
+                    (store.move_visible_items_by(&ids, dx, 1.5), expected)
+                }
+                4 => {
+                    let color = COLOR_PRESETS[next(2) as usize];
+                    let expected = reference.edit(|items| {
+                        items
+                            .iter_mut()
+                            .filter(|item| ids.contains(&item.id))
+                            .for_each(|item| item.style.stroke_color = color);
+                    });
+                    (
+                        store.update_visible_items(&ids, ChangeKind::Restyle, |item| item.style.stroke_color = color),
+                        expected,
+                    )
+                }
+                5 => {
+                    let Some(&id) = ids.first() else {
+                        continue;
+                    };
+                    let kind = item(id, next(100) as f64).kind;
+                    let expected = reference.edit(|items| items.iter_mut().filter(|item| item.id == id).for_each(|item| item.kind.clone_from(&kind)));
+                    (store.replace_visible_kind(id, kind), expected)
+                }
+                6 => {
+                    let to_back = next(2) == 0;
+                    let expected = reference.edit(|items| {
+                        let (picked, rest): (Vec<_>, Vec<_>) = items.drain(..).partition(|item| ids.contains(&item.id));
+                        *items = if to_back { [picked, rest].concat() } else { [rest, picked].concat() };
+                    });
+                    (if to_back { store.send_to_back(&ids) } else { store.bring_to_front(&ids) }, expected)
+                }
+                7 => {
+                    let group = (next(2) == 0).then_some(step);
+                    let expected = reference.edit(|items| items.iter_mut().filter(|item| ids.contains(&item.id)).for_each(|item| item.group = group));
+                    (store.set_visible_group(&ids, group), expected)
+                }
+                8 => (store.undo(), reference.undo()),
+                _ => (store.redo(), reference.redo()),
+            };
+
+            assert_eq!(ours, expected, "step {step}");
+            assert_eq!(store.visible_items(), reference.items, "step {step}");
+            assert_eq!(
+                (store.can_undo(), store.can_redo()),
+                (!reference.undo_stack.is_empty(), !reference.redo_stack.is_empty())
+            );
+            for (index, item) in store.visible_items().iter().enumerate() {
+                assert_eq!(store.visible_index(item.id), Some(index));
+            }
+        }
+
+        while reference.undo() {
+            assert!(store.undo());
+            assert_eq!(store.visible_items(), reference.items);
+        }
+        assert!(!store.undo());
+    }
+
+    #[test]
+    fn history_grows_with_the_items_touched_not_the_document() {
+        let mut store = AnnotationStore::default();
+        store.reset_to((1..=500).map(|id| item(id, 0.0)).collect());
+
+        for _ in 0..50 {
+            assert!(store.move_visible_items_by(&[7], 1.0, 0.0));
+        }
+
+        // A snapshot per move held 50 × 500 items; each command holds the
+        // moved item before and after.
+        assert_eq!(store.undo_stack.iter().map(stored_items).sum::<usize>(), 100);
+    }
+
+    #[test]
+    fn history_keeps_only_the_latest_steps() {
+        let mut store = AnnotationStore::default();
+        for id in 0..150 {
+            store.push(item(id, 0.0));
+        }
+
+        let mut undone = 0;
+        while store.undo() {
+            undone += 1;
+        }
+        assert_eq!(undone, HISTORY_LIMIT);
+        assert_eq!(store.visible_len(), 50);
+        assert!(store.redo());
+        assert_eq!(store.visible_index(50), Some(50));
+    }
+
+    #[test]
+    fn consecutive_resizes_and_text_edits_undo_as_one_step() {
+        let mut store = AnnotationStore::default();
+        store.push(item(1, 0.0));
+        store.push(item(2, 0.0));
+        let widen = |item: &mut AnnotationItem| item.style.stroke_width += 1.0;
+
+        for _ in 0..5 {
+            assert!(store.update_visible_items(&[1], ChangeKind::Resize, widen));
+        }
+        assert!(store.update_visible_items(&[2], ChangeKind::Resize, widen));
+        assert!(store.update_visible_items(&[2], ChangeKind::Restyle, |item| item.style.fill_enabled = true));
+        assert!(store.update_visible_items(&[2], ChangeKind::Resize, widen));
+        assert_eq!(store.undo_stack.len(), 6);
+
+        assert!(store.undo());
+        assert!(store.undo());
+        assert!(store.undo());
+        assert_eq!(store.visible_item(2).unwrap().style, AnnotationStyleState::default());
+        assert!(store.undo());
+        assert_eq!(
+            store.visible_item(1).unwrap().style.stroke_width,
+            AnnotationStyleState::default().stroke_width
+        );
+
+        assert!(store.redo());
+        assert!(store.update_visible_items(&[1], ChangeKind::Resize, |item| item.style.stroke_width -= 5.0));
+        assert_eq!(store.undo_stack.len(), 2, "a run that ends where it started leaves no step");
+
+        let text = |value: &str| {
+            let value = value.to_string();
+            move |item: &mut AnnotationItem| {
+                item.kind = AnnotationKind::Text {
+                    origin: (0.0, 0.0),
+                    text: value.clone(),
+                }
+            }
+        };
+        assert!(store.update_visible_items(&[2], ChangeKind::EditText, text("a")));
+        assert!(store.update_visible_items(&[2], ChangeKind::EditText, text("ab")));
+        assert!(store.undo());
+        assert!(matches!(store.visible_item(2).unwrap().kind, AnnotationKind::Rectangle { .. }));
+    }
 }
@@ -6,8 +6,9 @@ mod view;
 pub(crate) mod window_catalog;
 
 use crate::platform::shell::{self, PopupWindowSpec};
+use crate::services::capture::{active_capture_target, monitor::CaptureTarget};
 use crate::ui::support::appearance;
-use gpui::{App, AppContext, Bounds, WindowBounds, WindowKind, WindowOptions};
+use gpui::{App, AppContext, Bounds, DisplayId, WindowBounds, WindowKind, WindowOptions, point, px, size};
 use gpui_component::Root;
 
 pub use actions::bind_keys;
@@ -30,18 +31,53 @@ pub fn open_window(cx: &mut App) {
     }
 }
 
+/// Opens the overlay on a capture from history so it can be annotated again.
+pub fn open_history_capture(cx: &mut App, id: &str) -> bool {
+    let overlay_handle = cx.global::<OverlayHandle>().clone();
+    if !overlay_handle.prepare_from_history(id, cx) {
+        return false;
+    }
+    open_window(cx);
+    true
+}
+
 fn window_options(cx: &App) -> WindowOptions {
-    let fullscreen_bounds = Bounds::maximized(None, cx);
+    let (window_bounds, display_id) = surface_bounds(cx);
 
     shell::popup_window_options(PopupWindowSpec {
-        window_bounds: Some(WindowBounds::Fullscreen(fullscreen_bounds)),
+        window_bounds: Some(window_bounds),
         kind: WindowKind::PopUp,
         focus: false,
         show: true,
         is_movable: false,
         is_resizable: false,
         is_minimizable: false,
-        display_id: None,
+        display_id,
         window_min_size: None,
     })
 }
+
+/// Places the overlay over the pinned capture target so the frozen preview and
+/// the window share one coordinate space.
+fn surface_bounds(cx: &App) -> (WindowBounds, Option<DisplayId>) {
+    let Some(target) = active_capture_target() else {
+        return (WindowBounds::Fullscreen(Bounds::maximized(None, cx)), None);
+    };
+
+    let logical = target.logical_bounds();
+    if matches!(target, CaptureTarget::VirtualDesktop(_)) {
+        let bounds = Bounds::new(
+            point(px(logical.x as f32), px(logical.y as f32)),
+            size(px(logical.width as f32), px(logical.height as f32)),
+        );
+        return (WindowBounds::Windowed(bounds), None);
+    }
+
+    let center = point(px((logical.x + logical.width / 2) as f32), px((logical.y + logical.height / 2) as f32));
+    let display_id = cx
+        .displays()
+        .into_iter()
+        .find(|display| display.bounds().contains(&center))
+        .map(|display| display.id());
+    (WindowBounds::Fullscreen(Bounds::maximized(display_id, cx)), display_id)
+}
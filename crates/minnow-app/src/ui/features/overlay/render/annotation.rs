@@ -1,14 +1,25 @@
-use gpui::prelude::FluentBuilder as _;
-use gpui::{App, IntoElement, ParentElement, Styled, div, img, px, rgba};
+use gpui::{App, IntoElement, ParentElement, PathBuilder, Styled, canvas, div, img, point, px, rgba};
 use gpui_component::ActiveTheme as _;
 
 use crate::services::geometry::RectF;
-use crate::ui::features::overlay::state::AnnotationLayerState;
+use crate::ui::features::overlay::state::{AnnotationHandle, AnnotationLayerState};
+
+const HANDLE_SIZE: f64 = 10.0;
 
 fn color(value: u32) -> gpui::Hsla {
     rgba(value).into()
 }
 
+fn outline_color(selected: bool, transient: bool) -> Option<gpui::Hsla> {
+    if transient {
+        Some(color(0xffffffaa))
+    } else if selected {
+        Some(color(0xffffffff).alpha(0.9))
+    } else {
+        None
+    }
+}
+
 fn relative(selection: RectF, x: f64, y: f64) -> (f64, f64) {
     (x - selection.x, y - selection.y)
 }
@@ -26,7 +37,16 @@ pub(crate) fn overlay_annotations_layer(cx: &App, selection: RectF, state: &Anno
         layer = layer.child(img(image).size_full());
     }
 
+    let mut turned = Vec::new();
     for outline in &state.outlines {
+        let Some(border) = outline_color(outline.selected, outline.transient) else {
+            continue;
+        };
+        if outline.rotation != 0.0 {
+            let corners = outline.corners().map(|(x, y)| relative(selection, x, y));
+            turned.push((corners, border));
+            continue;
+        }
         let (x, y) = relative(selection, outline.bounds.x, outline.bounds.y);
         layer = layer.child(
             div()
@@ -35,14 +55,64 @@ pub(crate) fn overlay_annotations_layer(cx: &App, selection: RectF, state: &Anno
                 .top(px(y as f32))
                 .w(px(outline.bounds.width.max(2.0) as f32))
                 .h(px(outline.bounds.height.max(2.0) as f32))
-                .when(outline.selected, |this| {
-                    this.border_1()
-                        .border_color(color(0xffffffff).alpha(if outline.transient { 0.75 } else { 0.9 }))
-                })
-                .when(outline.transient, |this| this.border_1().border_color(color(0xffffffaa))),
+                .border_1()
+                .border_color(border),
+        );
+    }
+
+    if !turned.is_empty() {
+        layer = layer.child(
+            canvas(
+                |_, _, _| {},
+                move |bounds, (), window, _| {
+                    for (corners, border) in &turned {
+                        let [first, rest @ ..] = corners.map(|(x, y)| point(bounds.origin.x + px(x as f32), bounds.origin.y + px(y as f32)));
+                        let mut builder = PathBuilder::stroke(px(1.0));
+                        builder.move_to(first);
+                        for corner in rest {
+                            builder.line_to(corner);
+                        }
+                        builder.line_to(first);
+                        if let Ok(path) = builder.build() {
+                            window.paint_path(path, *border);
+                        }
+                    }
+                },
+            )
+            .absolute()
+            .size_full(),
         );
     }
 
     let theme = cx.theme();
+    if let Some(band) = state.rubber_band {
+        let (x, y) = relative(selection, band.x, band.y);
+        layer = layer.child(
+            div()
+                .absolute()
+                .left(px(x as f32))
+                .top(px(y as f32))
+                .w(px(band.width as f32))
+                .h(px(band.height as f32))
+                .border_1()
+                .border_color(theme.selection)
+                .bg(theme.selection.alpha(0.12)),
+        );
+    }
+
+    for (handle, (x, y)) in &state.handles {
+        let (x, y) = relative(selection, *x, *y);
+        let marker = div()
+            .absolute()
+            .left(px((x - HANDLE_SIZE / 2.0) as f32))
+            .top(px((y - HANDLE_SIZE / 2.0) as f32))
+            .size(px(HANDLE_SIZE as f32))
+            .border_1();
+        layer = layer.child(match handle {
+            AnnotationHandle::Resize(_) => marker.rounded_xs().border_color(theme.selection).bg(color(0xffffffff)),
+            _ => marker.rounded_full().border_color(color(0xffffffff)).bg(theme.selection),
+        });
+    }
+
     layer.border_1().border_color(theme.selection.alpha(0.25))
 }
@@ -120,6 +120,12 @@ mod tests {
             y: 0,
             width: 100,
             height: 100,
+            monitor_id: 1,
+            scale: 1.0,
+            focused: false,
+            id: 0,
+            content: None,
+            bounds: None,
         };
 
         let content = window_info_tooltip_content(&info).unwrap();
@@ -136,6 +142,12 @@ mod tests {
             y: 0,
             width: 100,
             height: 100,
+            monitor_id: 1,
+            scale: 1.0,
+            focused: false,
+            id: 0,
+            content: None,
+            bounds: None,
         };
 
         let content = window_info_tooltip_content(&info).unwrap();
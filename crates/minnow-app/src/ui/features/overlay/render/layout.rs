@@ -42,7 +42,7 @@ struct PanelLayoutRequest<'a> {
     occupied: &'a [OverlayPanelLayout],
 }
 
-pub(crate) fn property_panel_size(include_text_action: bool, include_mosaic_controls: bool) -> (f64, f64) {
+pub(crate) fn property_panel_size(include_text_action: bool, include_mosaic_controls: bool, include_line_controls: bool) -> (f64, f64) {
     let row_height = PROPERTY_BUTTON_SIZE.max(PROPERTY_SWATCH_SIZE) + PROPERTY_ROW_PADDING_Y * 2.0;
 
     let swatch_strip_width = PROPERTY_SWATCH_COUNT * PROPERTY_SWATCH_SIZE + (PROPERTY_SWATCH_COUNT - 1.0) * PROPERTY_SWATCH_GAP;
@@ -55,7 +55,9 @@ pub(crate) fn property_panel_size(include_text_action: bool, include_mosaic_cont
         + PROPERTY_SIZE_LABEL_WIDTH
         + (parameter_elements - 1.0) * PROPERTY_BUTTON_GAP;
 
-    let mode_button_count = (if include_mosaic_controls { 2.0 } else { 0.0 }) + (if include_text_action { 1.0 } else { 0.0 });
+    let mode_button_count = (if include_mosaic_controls { 2.0 } else { 0.0 })
+        + (if include_line_controls { 5.0 } else { 0.0 })
+        + (if include_text_action { 1.0 } else { 0.0 });
     let mode_row_width = if mode_button_count > 0.0 {
         PROPERTY_ROW_PADDING_X * 2.0 + mode_button_count * PROPERTY_BUTTON_SIZE + (mode_button_count - 1.0) * PROPERTY_BUTTON_GAP
     } else {
@@ -211,16 +213,18 @@ pub(crate) fn resolve_info_reserved_slot_layout(selection: RectF, viewport_w: f6
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn resolve_property_layout(
     toolbar_layout: OverlayPanelLayout,
     reserved_info_slot: OverlayPanelLayout,
     include_text_action: bool,
     include_mosaic_controls: bool,
+    include_line_controls: bool,
     viewport_w: f64,
     viewport_h: f64,
     occupied: &[OverlayPanelLayout],
 ) -> OverlayPanelLayout {
-    let (panel_w, panel_h) = property_panel_size(include_text_action, include_mosaic_controls);
+    let (panel_w, panel_h) = property_panel_size(include_text_action, include_mosaic_controls, include_line_controls);
 
     let candidates = [
         (toolbar_layout.x + toolbar_layout.width + SELECTION_PANEL_GAP, toolbar_layout.y),
@@ -392,7 +396,7 @@ mod tests {
             width: 340.0,
             height: 44.0,
         };
-        let layout = resolve_property_layout(toolbar, reserved, false, false, 1400.0, 900.0, &[toolbar]);
+        let layout = resolve_property_layout(toolbar, reserved, false, false, false, 1400.0, 900.0, &[toolbar]);
 
         assert_eq!(layout.x, toolbar.x + toolbar.width + SELECTION_PANEL_GAP);
         assert_eq!(layout.y, toolbar.y);
@@ -413,7 +417,7 @@ mod tests {
             width: 1.0,
             height: 1.0,
         };
-        let (panel_w, panel_h) = property_panel_size(false, false);
+        let (panel_w, panel_h) = property_panel_size(false, false, false);
         let right_rect = OverlayPanelLayout {
             x: toolbar.x + toolbar.width + SELECTION_PANEL_GAP,
             y: toolbar.y,
@@ -433,15 +437,15 @@ mod tests {
             height: toolbar.height,
         };
 
-        let right = resolve_property_layout(toolbar, reserved, false, false, 1400.0, 900.0, &[toolbar]);
+        let right = resolve_property_layout(toolbar, reserved, false, false, false, 1400.0, 900.0, &[toolbar]);
         assert_eq!(right.x, right_rect.x);
         assert_eq!(right.y, right_rect.y);
 
-        let below = resolve_property_layout(toolbar, reserved, false, false, 1400.0, 900.0, &[toolbar, right_rect]);
+        let below = resolve_property_layout(toolbar, reserved, false, false, false, 1400.0, 900.0, &[toolbar, right_rect]);
         assert_eq!(below.x, below_rect.x);
         assert_eq!(below.y, below_rect.y);
 
-        let above = resolve_property_layout(toolbar, reserved, false, false, 1400.0, 900.0, &[toolbar, right_rect, below_rect]);
+        let above = resolve_property_layout(toolbar, reserved, false, false, false, 1400.0, 900.0, &[toolbar, right_rect, below_rect]);
         assert_eq!(above.x, above_rect.x);
         assert_eq!(above.y, above_rect.y);
 
@@ -450,6 +454,7 @@ mod tests {
             reserved,
             false,
             false,
+            false,
             1400.0,
             900.0,
             &[toolbar, right_rect, below_rect, above_rect],
@@ -460,16 +465,19 @@ mod tests {
 
     #[test]
     fn property_panel_size_grows_when_mode_section_is_visible() {
-        let base = property_panel_size(false, false);
-        let text_mode = property_panel_size(true, false);
-        let mosaic_mode = property_panel_size(false, true);
-        let all_mode = property_panel_size(true, true);
+        let base = property_panel_size(false, false, false);
+        let text_mode = property_panel_size(true, false, false);
+        let mosaic_mode = property_panel_size(false, true, false);
+        let line_mode = property_panel_size(false, false, true);
+        let all_mode = property_panel_size(true, true, false);
 
         assert_eq!(base.0, text_mode.0);
         assert_eq!(base.0, mosaic_mode.0);
+        assert_eq!(base.0, line_mode.0);
         assert_eq!(base.0, all_mode.0);
         assert!(text_mode.1 > base.1);
         assert_eq!(text_mode.1, mosaic_mode.1);
+        assert_eq!(text_mode.1, line_mode.1);
         assert_eq!(mosaic_mode.1, all_mode.1);
     }
 
@@ -478,7 +486,7 @@ mod tests {
         let selection = RectF::new(1180.0, 760.0, 80.0, 60.0);
         let toolbar = resolve_toolbar_layout(selection, TEST_ACTION_COUNT, 1200.0, 800.0, &[]);
         let reserved = resolve_info_reserved_slot_layout(selection, 1200.0, 800.0);
-        let property = resolve_property_layout(toolbar, reserved, true, true, 1200.0, 800.0, &[toolbar]);
+        let property = resolve_property_layout(toolbar, reserved, true, true, false, 1200.0, 800.0, &[toolbar]);
         let resolution = resolve_resolution_tooltip_layout_with_occupied(selection, 1200.0, 800.0, &[]);
         let info = resolve_info_tooltip_layout(selection, 44.0, 1200.0, 800.0);
 
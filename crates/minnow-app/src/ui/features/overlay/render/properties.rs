@@ -15,7 +15,8 @@ use crate::ui::features::overlay::annotation::COLOR_PRESETS;
 use crate::ui::features::overlay::render::OverlayActionHandler;
 use crate::ui::features::overlay::render::layout::OverlayPanelLayout;
 use crate::ui::features::overlay::state::{
-    AnnotationCommand, AnnotationKindTag, AnnotationSelectionInfo, AnnotationStyleState, AnnotationTool, MosaicMode, OverlayCommand,
+    AnnotationCommand, AnnotationKindTag, AnnotationSelectionInfo, AnnotationStyleState, AnnotationTool, ArrowHeads, LineDash, MosaicMode,
+    OverlayCommand,
 };
 
 #[derive(Clone)]
@@ -36,6 +37,11 @@ enum PropertyIcon {
     EditText,
     MosaicPixelate,
     MosaicBlur,
+    LinePlain,
+    LineArrow,
+    LineDoubleArrow,
+    LineDashed,
+    LineCurved,
 }
 
 #[derive(Clone)]
@@ -58,11 +64,21 @@ impl IconNamed for PropertyIcon {
             Self::EditText => asset_paths::icons::TEXT_FIELDS,
             Self::MosaicPixelate => asset_paths::icons::GRID_ON,
             Self::MosaicBlur => asset_paths::icons::LENS_BLUR,
+            Self::LinePlain => asset_paths::icons::HORIZONTAL_RULE,
+            Self::LineArrow => asset_paths::icons::ARROW_INSERT,
+            Self::LineDoubleArrow => asset_paths::icons::ARROW_RANGE,
+            Self::LineDashed => asset_paths::icons::LINE_STYLE,
+            Self::LineCurved => asset_paths::icons::LINE_CURVE,
         }
         .into()
     }
 }
 
+/// Whether the properties panel shows the arrowhead, dash and curve options.
+pub(crate) fn shows_line_controls(active_tool: Option<AnnotationTool>, selected_annotation_kind: Option<AnnotationKindTag>) -> bool {
+    selected_annotation_kind == Some(AnnotationKindTag::Arrow) || active_tool == Some(AnnotationTool::Arrow)
+}
+
 fn icon(app_ctx: &App, icon_name: PropertyIcon) -> Icon {
     Icon::new(icon_name).small().text_color(app_ctx.theme().popover_foreground)
 }
@@ -193,6 +209,7 @@ pub(crate) fn overlay_properties_panel(
     let selected_mosaic_mode = state.selected_annotation.as_ref().and_then(|item| item.mosaic_mode);
     let is_mosaic = selected_mosaic_mode.is_some() || state.active_tool == Some(AnnotationTool::Mosaic);
     let mosaic_mode = selected_mosaic_mode.unwrap_or(state.style.mosaic_mode);
+    let is_line = shows_line_controls(state.active_tool, state.selected_annotation.as_ref().map(|item| item.kind));
     let custom_color_active = COLOR_PRESETS.iter().all(|color| (color & 0xffffff00) != selected_color_key);
 
     let mut color_row = h_flex().items_center().gap_1();
@@ -309,6 +326,54 @@ pub(crate) fn overlay_properties_panel(
         });
     }
 
+    if is_line {
+        for (id, icon_name, tooltip, heads) in [
+            (
+                "overlay-prop-line-plain",
+                PropertyIcon::LinePlain,
+                i18n::overlay::annotation_line_plain(),
+                ArrowHeads::None,
+            ),
+            (
+                "overlay-prop-line-arrow",
+                PropertyIcon::LineArrow,
+                i18n::overlay::annotation_line_arrow(),
+                ArrowHeads::End,
+            ),
+            (
+                "overlay-prop-line-double-arrow",
+                PropertyIcon::LineDoubleArrow,
+                i18n::overlay::annotation_line_double_arrow(),
+                ArrowHeads::Both,
+            ),
+        ] {
+            mode_specs.push(PropertyButtonSpec {
+                id,
+                icon_name,
+                tooltip,
+                command: OverlayCommand::Annotation(AnnotationCommand::SetArrowHeads(heads)),
+                active: state.style.arrow_heads == heads,
+                disabled: false,
+            });
+        }
+        mode_specs.push(PropertyButtonSpec {
+            id: "overlay-prop-line-dashed",
+            icon_name: PropertyIcon::LineDashed,
+            tooltip: i18n::overlay::annotation_line_dashed(),
+            command: OverlayCommand::Annotation(AnnotationCommand::ToggleLineDash),
+            active: state.style.line_dash == LineDash::Dashed,
+            disabled: false,
+        });
+        mode_specs.push(PropertyButtonSpec {
+            id: "overlay-prop-line-curved",
+            icon_name: PropertyIcon::LineCurved,
+            tooltip: i18n::overlay::annotation_line_curved(),
+            command: OverlayCommand::Annotation(AnnotationCommand::ToggleLineCurve),
+            active: state.style.line_curved,
+            disabled: false,
+        });
+    }
+
     if selected_is_text {
         mode_specs.push(PropertyButtonSpec {
             id: "overlay-prop-edit-text",
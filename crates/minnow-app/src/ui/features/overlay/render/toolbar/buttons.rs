@@ -15,11 +15,14 @@ pub(super) enum ToolbarIcon {
     Counter,
     Text,
     Mosaic,
+    Pen,
+    Highlighter,
     Undo,
     Redo,
     Ocr,
     QrCode,
     Scroll,
+    Record,
     Save,
     Pin,
     Copy,
@@ -35,11 +38,14 @@ impl IconNamed for ToolbarIcon {
             Self::Counter => asset_paths::icons::COUNTER_1,
             Self::Text => asset_paths::icons::TEXT_FIELDS,
             Self::Mosaic => asset_paths::icons::BLUR_ON,
+            Self::Pen => asset_paths::icons::DRAW,
+            Self::Highlighter => asset_paths::icons::INK_HIGHLIGHTER,
             Self::Undo => asset_paths::icons::UNDO,
             Self::Redo => asset_paths::icons::REDO,
             Self::Ocr => asset_paths::icons::TEXT_FIELDS,
             Self::QrCode => asset_paths::icons::CROP_FREE,
             Self::Scroll => asset_paths::icons::SCROLL,
+            Self::Record => asset_paths::icons::VIDEOCAM,
             Self::Save => asset_paths::icons::SAVE,
             Self::Pin => asset_paths::icons::KEEP,
             Self::Copy => asset_paths::icons::FILE_COPY,
@@ -100,6 +106,14 @@ fn is_mosaic_active(state: OverlayToolbarState) -> bool {
     state.tool == Some(AnnotationTool::Mosaic)
 }
 
+fn is_pen_active(state: OverlayToolbarState) -> bool {
+    state.tool == Some(AnnotationTool::Pen)
+}
+
+fn is_highlighter_active(state: OverlayToolbarState) -> bool {
+    state.tool == Some(AnnotationTool::Highlighter)
+}
+
 fn is_undo_disabled(state: OverlayToolbarState) -> bool {
     !state.can_undo
 }
@@ -132,6 +146,14 @@ fn cmd_mosaic() -> OverlayCommand {
     OverlayCommand::Annotation(AnnotationCommand::SetTool(AnnotationTool::Mosaic))
 }
 
+fn cmd_pen() -> OverlayCommand {
+    OverlayCommand::Annotation(AnnotationCommand::SetTool(AnnotationTool::Pen))
+}
+
+fn cmd_highlighter() -> OverlayCommand {
+    OverlayCommand::Annotation(AnnotationCommand::SetTool(AnnotationTool::Highlighter))
+}
+
 fn cmd_undo() -> OverlayCommand {
     OverlayCommand::Annotation(AnnotationCommand::Undo)
 }
@@ -156,6 +178,10 @@ fn cmd_scroll() -> OverlayCommand {
     OverlayCommand::Capture(CaptureCommand::Execute(CaptureAction::Scroll))
 }
 
+fn cmd_record() -> OverlayCommand {
+    OverlayCommand::Capture(CaptureCommand::Execute(CaptureAction::Record))
+}
+
 fn cmd_pin() -> OverlayCommand {
     OverlayCommand::Capture(CaptureCommand::Execute(CaptureAction::Pin))
 }
@@ -225,6 +251,24 @@ pub(super) const TOOL_BUTTONS: &[ToolbarButtonSpec] = &[
         disabled: never_disabled,
         visible: always_visible,
     },
+    ToolbarButtonSpec {
+        id: "overlay-tool-pen",
+        icon: ToolbarIcon::Pen,
+        tooltip: i18n::overlay::annotation_tool_pen,
+        command: cmd_pen,
+        active: is_pen_active,
+        disabled: never_disabled,
+        visible: always_visible,
+    },
+    ToolbarButtonSpec {
+        id: "overlay-tool-highlighter",
+        icon: ToolbarIcon::Highlighter,
+        tooltip: i18n::overlay::annotation_tool_highlighter,
+        command: cmd_highlighter,
+        active: is_highlighter_active,
+        disabled: never_disabled,
+        visible: always_visible,
+    },
 ];
 
 pub(super) const HISTORY_BUTTONS: &[ToolbarButtonSpec] = &[
@@ -276,6 +320,15 @@ pub(super) const CAPTURE_BUTTONS: &[ToolbarButtonSpec] = &[
         disabled: never_disabled,
         visible: always_visible,
     },
+    ToolbarButtonSpec {
+        id: "overlay-record",
+        icon: ToolbarIcon::Record,
+        tooltip: i18n::common::record,
+        command: cmd_record,
+        active: always_inactive,
+        disabled: never_disabled,
+        visible: always_visible,
+    },
     ToolbarButtonSpec {
         id: SAVE_BUTTON_ID,
         icon: ToolbarIcon::Save,
@@ -1,9 +1,11 @@
 use gpui::{Pixels, Point};
 
+use crate::services::capture::action::HistorySource;
 use crate::services::capture::active_monitor_scale;
+use crate::services::geometry::RectF;
 #[cfg(test)]
 use crate::ui::features::overlay::annotation::AnnotationItem;
-use crate::ui::features::overlay::annotation::{AnnotationKind, AnnotationTool, AnnotationUiState, MosaicMode};
+use crate::ui::features::overlay::annotation::{AnnotationHandle, AnnotationKind, AnnotationTool, AnnotationUiState, ArrowHeads, MosaicMode, saved};
 
 use super::{DragMode, OverlaySession};
 
@@ -37,6 +39,12 @@ impl OverlaySession {
             .hit_test((x, y), self.viewport.selection, self.viewport.mode == DragMode::Idle)
     }
 
+    pub(crate) fn annotation_handle_hit(&self, point: Point<Pixels>) -> Option<(u64, AnnotationHandle)> {
+        let (x, y) = self.clamp_point_to_viewport(point);
+        self.annotation
+            .handle_hit((x, y), self.viewport.selection, self.viewport.mode == DragMode::Idle)
+    }
+
     pub(crate) fn set_annotation_tool(&mut self, tool: AnnotationTool) -> bool {
         self.annotation.set_tool(tool)
     }
@@ -65,6 +73,18 @@ impl OverlaySession {
         self.annotation.set_mosaic_mode(mode)
     }
 
+    pub(crate) fn set_annotation_arrow_heads(&mut self, heads: ArrowHeads) -> bool {
+        self.annotation.set_arrow_heads(heads)
+    }
+
+    pub(crate) fn toggle_annotation_line_dash(&mut self) -> bool {
+        self.annotation.toggle_line_dash()
+    }
+
+    pub(crate) fn toggle_annotation_line_curve(&mut self) -> bool {
+        self.annotation.toggle_line_curve()
+    }
+
     pub(crate) fn adjust_annotation_mosaic_intensity(&mut self, delta: f64) -> bool {
         self.annotation.adjust_mosaic_intensity(delta)
     }
@@ -79,6 +99,30 @@ impl OverlaySession {
         self.annotation.select(id)
     }
 
+    pub(crate) fn toggle_annotation_selected(&mut self, id: u64) -> bool {
+        self.annotation.toggle_selected(id)
+    }
+
+    pub(crate) fn select_all_annotations(&mut self) -> bool {
+        self.annotation.select_all()
+    }
+
+    pub(crate) fn bring_annotations_to_front(&mut self) -> bool {
+        self.annotation.bring_selected_to_front()
+    }
+
+    pub(crate) fn send_annotations_to_back(&mut self) -> bool {
+        self.annotation.send_selected_to_back()
+    }
+
+    pub(crate) fn group_annotations(&mut self) -> bool {
+        self.annotation.group_selected()
+    }
+
+    pub(crate) fn ungroup_annotations(&mut self) -> bool {
+        self.annotation.ungroup_selected()
+    }
+
     pub(crate) fn begin_text_edit_selected(&mut self) -> bool {
         self.annotation.begin_text_edit_selected()
     }
@@ -115,6 +159,18 @@ impl OverlaySession {
             .start_move(id, point, self.viewport.selection, self.viewport.mode == DragMode::Idle)
     }
 
+    pub(crate) fn start_annotation_reshape(&mut self, id: u64, handle: AnnotationHandle, point: Point<Pixels>) -> bool {
+        let point = self.clamp_point_to_viewport(point);
+        self.annotation
+            .start_reshape(id, handle, point, self.viewport.selection, self.viewport.mode == DragMode::Idle)
+    }
+
+    pub(crate) fn start_annotation_rubber_band(&mut self, point: Point<Pixels>) -> bool {
+        let point = self.clamp_point_to_viewport(point);
+        self.annotation
+            .start_rubber_band(point, self.viewport.selection, self.viewport.mode == DragMode::Idle)
+    }
+
     pub(crate) fn has_active_annotation_interaction(&self) -> bool {
         self.annotation.has_active_interaction() || self.viewport.selection_move_origin.is_some()
     }
@@ -155,4 +211,33 @@ impl OverlaySession {
         let scale = f64::from(active_monitor_scale()).max(1.0);
         self.annotation.composed_background_source(self.background_pixels.as_ref(), scale)
     }
+
+    /// The un-annotated background and annotation document recorded with a
+    /// capture, so it can be annotated again from history.
+    pub(crate) fn history_source(&self) -> HistorySource {
+        let Some(selection) = self.selection_rect() else {
+            return HistorySource::default();
+        };
+        let selection = RectF::new(
+            f64::from(selection.x),
+            f64::from(selection.y),
+            f64::from(selection.width),
+            f64::from(selection.height),
+        );
+        let scale = f64::from(active_monitor_scale()).max(1.0);
+        let annotations = saved::encode(self.annotation.items(), selection, scale);
+        HistorySource {
+            base_image: annotations.as_ref().and(self.background_pixels.clone()),
+            annotations,
+        }
+    }
+
+    /// Restores a saved annotation document onto the current selection.
+    pub(crate) fn restore_annotations(&mut self, document: &str) -> bool {
+        let Some(selection) = self.viewport.selection else {
+            return false;
+        };
+        let scale = f64::from(active_monitor_scale()).max(1.0);
+        saved::decode(document, selection, scale).is_some_and(|items| self.annotation.restore_items(items))
+    }
 }
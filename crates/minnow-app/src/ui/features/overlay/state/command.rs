@@ -2,9 +2,12 @@ use super::{AnnotationKind, DragMode, OverlayEffect, OverlayOutcome, OverlaySess
 use crate::platform::shell::NotificationType;
 use crate::services::capture::action::{ActionContext, CaptureAction};
 use crate::services::capture::active_monitor_scale;
-use crate::services::geometry::RectF;
+use crate::services::capture::filename::CaptureOrigin;
+use crate::services::geometry::{Rect, RectF};
 use crate::services::i18n;
-use crate::ui::features::overlay::annotation::{AnnotationTool, MosaicMode};
+use crate::ui::features::long_capture::CapturedWindow;
+use crate::ui::features::overlay::annotation::{AnnotationHandle, AnnotationTool, ArrowHeads, MosaicMode};
+use crate::ui::features::overlay::window_catalog::WindowInfo;
 use gpui::{Pixels, Point};
 
 #[derive(Clone, Debug, PartialEq)]
@@ -24,21 +27,50 @@ pub(crate) enum PickerCommand {
 pub(crate) enum AnnotationCommand {
     SetTool(AnnotationTool),
     StartDraw(Point<Pixels>),
-    StartMove { id: u64, point: Point<Pixels> },
+    StartMove {
+        id: u64,
+        point: Point<Pixels>,
+    },
+    StartReshape {
+        id: u64,
+        handle: AnnotationHandle,
+        point: Point<Pixels>,
+    },
+    StartRubberBand(Point<Pixels>),
     Select(Option<u64>),
+    ToggleSelect(u64),
+    SelectAll,
+    BringToFront,
+    SendToBack,
+    Group,
+    Ungroup,
     DeleteIntent,
     Undo,
     Redo,
     CycleColor,
-    SetColor { color: u32 },
+    SetColor {
+        color: u32,
+    },
     ToggleFill,
-    AdjustStroke { delta: f64 },
+    AdjustStroke {
+        delta: f64,
+    },
     SetMosaicMode(MosaicMode),
-    AdjustMosaicIntensity { delta: f64 },
-    AdjustByWheel { point: Point<Pixels>, delta: f64 },
+    AdjustMosaicIntensity {
+        delta: f64,
+    },
+    SetArrowHeads(ArrowHeads),
+    ToggleLineDash,
+    ToggleLineCurve,
+    AdjustByWheel {
+        point: Point<Pixels>,
+        delta: f64,
+    },
     StartTextEdit,
     StartTextEditAtPoint(Point<Pixels>),
-    AppendText { text: String },
+    AppendText {
+        text: String,
+    },
     InsertTextNewline,
 }
 
@@ -141,7 +173,17 @@ impl OverlaySession {
             AnnotationCommand::SetTool(tool) => SessionTransition::from_changed(self.set_annotation_tool(tool)),
             AnnotationCommand::StartDraw(point) => SessionTransition::from_changed(self.start_annotation_draw(point)),
             AnnotationCommand::StartMove { id, point } => SessionTransition::from_changed(self.start_annotation_move(id, point)),
+            AnnotationCommand::StartReshape { id, handle, point } => {
+                SessionTransition::from_changed(self.start_annotation_reshape(id, handle, point))
+            }
+            AnnotationCommand::StartRubberBand(point) => SessionTransition::from_changed(self.start_annotation_rubber_band(point)),
             AnnotationCommand::Select(id) => SessionTransition::from_changed(self.select_annotation(id)),
+            AnnotationCommand::ToggleSelect(id) => SessionTransition::from_changed(self.toggle_annotation_selected(id)),
+            AnnotationCommand::SelectAll => SessionTransition::from_changed(self.select_all_annotations()),
+            AnnotationCommand::BringToFront => SessionTransition::from_changed(self.bring_annotations_to_front()),
+            AnnotationCommand::SendToBack => SessionTransition::from_changed(self.send_annotations_to_back()),
+            AnnotationCommand::Group => SessionTransition::from_changed(self.group_annotations()),
+            AnnotationCommand::Ungroup => SessionTransition::from_changed(self.ungroup_annotations()),
             AnnotationCommand::DeleteIntent => {
                 if self.text_editing_id().is_some() {
                     SessionTransition::from_changed(self.backspace_text_edit())
@@ -157,6 +199,9 @@ impl OverlaySession {
             AnnotationCommand::AdjustStroke { delta } => SessionTransition::from_changed(self.adjust_annotation_stroke(delta)),
             AnnotationCommand::SetMosaicMode(mode) => SessionTransition::from_changed(self.set_annotation_mosaic_mode(mode)),
             AnnotationCommand::AdjustMosaicIntensity { delta } => SessionTransition::from_changed(self.adjust_annotation_mosaic_intensity(delta)),
+            AnnotationCommand::SetArrowHeads(heads) => SessionTransition::from_changed(self.set_annotation_arrow_heads(heads)),
+            AnnotationCommand::ToggleLineDash => SessionTransition::from_changed(self.toggle_annotation_line_dash()),
+            AnnotationCommand::ToggleLineCurve => SessionTransition::from_changed(self.toggle_annotation_line_curve()),
             AnnotationCommand::AdjustByWheel { point, delta } => {
                 SessionTransition::from_changed(self.adjust_selected_annotation_by_wheel(point, delta))
             }
@@ -198,7 +243,12 @@ impl OverlaySession {
                     self.finish_annotation_interaction();
                 } else {
                     match self.mode() {
-                        DragMode::Selecting => self.finish_selection(),
+                        DragMode::Selecting => {
+                            self.finish_selection();
+                            if let Some(effect) = self.long_capture_effect() {
+                                return SessionTransition::Effect(effect);
+                            }
+                        }
                         DragMode::Resizing(_) => self.finish_resize(),
                         DragMode::Idle => {}
                     }
@@ -219,6 +269,17 @@ impl OverlaySession {
         }
     }
 
+    /// Starts the long capture the overlay was opened for once a region is selected.
+    fn long_capture_effect(&mut self) -> Option<OverlayEffect> {
+        if !self.long_capture_on_select || self.mode() != DragMode::Idle {
+            return None;
+        }
+
+        let effect = self.capture_effect(CaptureAction::Scroll)?;
+        self.long_capture_on_select = false;
+        Some(effect)
+    }
+
     fn capture_effect(&self, action: CaptureAction) -> Option<OverlayEffect> {
         if matches!(action, CaptureAction::Scroll) {
             let selection_rect = self.selection_rect()?;
@@ -226,12 +287,29 @@ impl OverlaySession {
                 return None;
             }
 
+            let snapped = self.snapped_window(selection_rect);
+            let window = snapped.map(|window| CapturedWindow {
+                id: window.id,
+                bounds: window.tracked_bounds(),
+            });
+            let selection_rect = snapped.map_or(selection_rect, |window| window.content_rect());
             let viewport_rect = RectF::new(0.0, 0.0, self.viewport.viewport_w, self.viewport.viewport_h);
             let viewport_scale = f64::from(active_monitor_scale()).max(1.0);
             return Some(OverlayEffect::StartLongCapture {
                 selection_rect,
                 viewport_rect,
                 viewport_scale,
+                origin: self.capture_origin(),
+                window,
+            });
+        }
+        if matches!(action, CaptureAction::Record) {
+            let selection_rect = self.selection_rect().filter(|rect| rect.has_area())?;
+            return Some(OverlayEffect::StartRecording {
+                selection_rect,
+                viewport_rect: RectF::new(0.0, 0.0, self.viewport.viewport_w, self.viewport.viewport_h),
+                viewport_scale: f64::from(active_monitor_scale()).max(1.0),
+                origin: self.capture_origin(),
             });
         }
 
@@ -239,7 +317,29 @@ impl OverlaySession {
         let background_source = self.composed_background_source()?;
         Some(OverlayEffect::Capture {
             action,
-            context: ActionContext::crop_selection(background_source, selection),
+            context: ActionContext::crop_selection(background_source, selection)
+                .with_origin(self.capture_origin())
+                .with_history(self.history_source()),
         })
     }
+
+    /// The window a long capture should follow: the one the selection snapped
+    /// to, as long as the selection was not adjusted since.
+    fn snapped_window(&self, selection_rect: Rect) -> Option<&WindowInfo> {
+        self.hovered_window
+            .as_ref()
+            .filter(|window| window.id != 0 && window.rect() == selection_rect)
+    }
+
+    /// The window the selection snapped to, if any, for filename templates.
+    pub(super) fn capture_origin(&self) -> CaptureOrigin {
+        let non_empty = |value: &str| (!value.trim().is_empty()).then(|| value.to_string());
+        self.hovered_window
+            .as_ref()
+            .map(|window| CaptureOrigin {
+                app_name: non_empty(&window.app_name),
+                window_title: non_empty(&window.title),
+            })
+            .unwrap_or_default()
+    }
 }
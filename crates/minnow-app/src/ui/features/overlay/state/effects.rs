@@ -2,9 +2,12 @@ use super::OverlayHandle;
 use crate::app::workflows;
 use crate::platform::shell::{self, NotificationType};
 use crate::services::capture::action::{ActionContext, CaptureAction, PinCaptureRequest};
+use crate::services::capture::filename::CaptureOrigin;
+use crate::services::capture::remember_selection;
 use crate::services::geometry::{Rect, RectF};
-use crate::ui::features::long_capture::{self, LongCaptureRequest};
+use crate::ui::features::long_capture::{self, CapturedWindow, LongCaptureRequest};
 use crate::ui::features::pin::{self, PinRequest};
+use crate::ui::features::recording::{self, RecordingRequest};
 use crate::ui::support::capture_actions::{self, CaptureActionHost, CaptureActionHostKind};
 use gpui::{App, Window};
 
@@ -15,6 +18,14 @@ pub(crate) enum OverlayEffect {
         selection_rect: Rect,
         viewport_rect: RectF,
         viewport_scale: f64,
+        origin: CaptureOrigin,
+        window: Option<CapturedWindow>,
+    },
+    StartRecording {
+        selection_rect: Rect,
+        viewport_rect: RectF,
+        viewport_scale: f64,
+        origin: CaptureOrigin,
     },
     Capture {
         action: CaptureAction,
@@ -52,6 +63,14 @@ impl OverlayOutcome {
     }
 }
 
+struct LongCaptureStart {
+    selection_rect: Rect,
+    viewport_rect: RectF,
+    viewport_scale: f64,
+    origin: CaptureOrigin,
+    window: Option<CapturedWindow>,
+}
+
 struct CopyTextPayload {
     text: String,
     title: String,
@@ -90,7 +109,35 @@ impl OverlayHandle {
                 selection_rect,
                 viewport_rect,
                 viewport_scale,
-            } => self.start_long_capture(selection_rect, viewport_rect, viewport_scale, window, cx),
+                origin,
+                window: captured_window,
+            } => self.start_long_capture(
+                LongCaptureStart {
+                    selection_rect,
+                    viewport_rect,
+                    viewport_scale,
+                    origin,
+                    window: captured_window,
+                },
+                window,
+                cx,
+            ),
+            OverlayEffect::StartRecording {
+                selection_rect,
+                viewport_rect,
+                viewport_scale,
+                origin,
+            } => {
+                let bounds = window.window_bounds().get_bounds();
+                let request = RecordingRequest {
+                    selection_rect,
+                    viewport_rect,
+                    viewport_scale,
+                    viewport_origin_screen: (bounds.origin.x.to_f64(), bounds.origin.y.to_f64()),
+                    origin,
+                };
+                self.start_recording(request, window, cx);
+            }
             OverlayEffect::CopyText {
                 text,
                 title,
@@ -124,21 +171,16 @@ impl OverlayHandle {
         });
     }
 
-    fn start_long_capture(
-        &self,
-        selection_rect: crate::services::geometry::Rect,
-        viewport_rect: crate::services::geometry::RectF,
-        viewport_scale: f64,
-        window: &mut Window,
-        cx: &mut App,
-    ) {
+    fn start_long_capture(&self, start: LongCaptureStart, window: &mut Window, cx: &mut App) {
         self.0.update(cx, |session, _| session.clear());
         let bounds = window.window_bounds().get_bounds();
         let request = LongCaptureRequest {
-            selection_rect,
-            viewport_rect,
-            viewport_scale,
+            selection_rect: start.selection_rect,
+            viewport_rect: start.viewport_rect,
+            viewport_scale: start.viewport_scale,
             viewport_origin_screen: (bounds.origin.x.to_f64(), bounds.origin.y.to_f64()),
+            origin: start.origin,
+            window: start.window,
         };
         cx.defer(move |cx| {
             long_capture::open_window(cx, request);
@@ -146,6 +188,14 @@ impl OverlayHandle {
         self.close(window, cx);
     }
 
+    fn start_recording(&self, request: RecordingRequest, window: &mut Window, cx: &mut App) {
+        self.0.update(cx, |session, _| session.clear());
+        cx.defer(move |cx| {
+            recording::open_window(cx, request);
+        });
+        self.close(window, cx);
+    }
+
     fn copy_text(&self, payload: CopyTextPayload, window: &mut Window, cx: &mut App) {
         if shell::copy_text_to_clipboard(payload.text) {
             shell::show_notification(&payload.title, &payload.message, payload.notification_type);
@@ -159,6 +209,7 @@ impl OverlayHandle {
     }
 
     fn capture(&self, action: CaptureAction, context: crate::services::capture::action::ActionContext, window: &mut Window, cx: &mut App) {
+        remember_selection(context.rect);
         let result = workflows::execute_capture_action(action, context);
         let effect = capture_actions::interpret(action, result, CaptureActionHostKind::Overlay);
         capture_actions::apply_host_effect(self, effect, window, cx);
@@ -8,7 +8,8 @@ mod selection;
 mod session;
 
 pub(crate) use crate::ui::features::overlay::annotation::{
-    AnnotationKind, AnnotationKindTag, AnnotationLayerState, AnnotationSelectionInfo, AnnotationStyleState, AnnotationTool, MosaicMode,
+    AnnotationHandle, AnnotationKind, AnnotationKindTag, AnnotationLayerState, AnnotationSelectionInfo, AnnotationStyleState, AnnotationTool,
+    ArrowHeads, LineDash, MosaicMode,
 };
 pub(crate) use command::{AnnotationCommand, CaptureCommand, LifecycleCommand, OverlayCommand, PickerCommand};
 #[cfg(feature = "overlay-diagnostics")]
@@ -35,6 +36,12 @@ mod tests {
             y,
             width,
             height,
+            monitor_id: 1,
+            scale: 1.0,
+            focused: false,
+            id: 0,
+            content: None,
+            bounds: None,
         }
     }
 
@@ -53,6 +60,27 @@ mod tests {
         assert_eq!(session.viewport.target, None);
     }
 
+    #[test]
+    fn snapped_window_fills_the_capture_origin() {
+        let mut session = OverlaySession::default();
+        session.set_viewport_size(400.0, 300.0);
+        session.windows = vec![window("Release notes", "Editor", 10, 10, 100, 100)];
+        session.update_hover(Point::new(px(20.0), px(20.0)));
+
+        session.apply(OverlayCommand::Lifecycle(LifecycleCommand::StartSelection(Point::new(
+            px(20.0),
+            px(20.0),
+        ))));
+        session.apply(OverlayCommand::Lifecycle(LifecycleCommand::PointerReleased));
+
+        let origin = session.capture_origin();
+        assert_eq!(origin.app_name.as_deref(), Some("Editor"));
+        assert_eq!(origin.window_title.as_deref(), Some("Release notes"));
+
+        session.apply(OverlayCommand::Lifecycle(LifecycleCommand::ClearSelection));
+        assert_eq!(session.capture_origin(), Default::default());
+    }
+
     #[test]
     fn scroll_capture_dispatches_long_capture_effect() {
         let mut session = OverlaySession::default();
@@ -63,7 +91,110 @@ mod tests {
             crate::services::capture::action::CaptureAction::Scroll,
         )));
         assert_eq!(outcome.effects.len(), 1);
+        assert!(matches!(outcome.effects[0], OverlayEffect::StartLongCapture { window: None, .. }));
+    }
+
+    #[test]
+    fn record_action_starts_a_recording_of_the_selection() {
+        let mut session = OverlaySession::default();
+        session.set_viewport_size(600.0, 400.0);
+        let record = OverlayCommand::Capture(CaptureCommand::Execute(crate::services::capture::action::CaptureAction::Record));
+        assert!(session.apply(record.clone()).effects.is_empty());
+
+        session.viewport.selection = Some(crate::services::geometry::RectF::new(40.0, 40.0, 320.0, 220.0));
+        let outcome = session.apply(record);
+
+        assert_eq!(outcome.effects.len(), 1);
+        let OverlayEffect::StartRecording {
+            selection_rect,
+            viewport_rect,
+            ..
+        } = &outcome.effects[0]
+        else {
+            panic!("expected StartRecording effect");
+        };
+        assert_eq!(*selection_rect, crate::services::geometry::Rect::new(40, 40, 320, 220));
+        assert_eq!(*viewport_rect, crate::services::geometry::RectF::new(0.0, 0.0, 600.0, 400.0));
+    }
+
+    #[test]
+    fn scroll_capture_on_a_snapped_window_follows_its_content() {
+        let mut session = OverlaySession::default();
+        session.set_viewport_size(400.0, 300.0);
+        let mut snapped = window("Release notes", "Editor", 10, 10, 300, 250);
+        snapped.id = 42;
+        snapped.content = Some(crate::services::geometry::Rect::new(12, 40, 290, 218));
+        session.windows = vec![snapped.clone()];
+        session.update_hover(Point::new(px(20.0), px(20.0)));
+        session.apply(OverlayCommand::Lifecycle(LifecycleCommand::StartSelection(Point::new(
+            px(20.0),
+            px(20.0),
+        ))));
+        session.apply(OverlayCommand::Lifecycle(LifecycleCommand::PointerReleased));
+
+        let outcome = session.apply(OverlayCommand::Capture(CaptureCommand::Execute(
+            crate::services::capture::action::CaptureAction::Scroll,
+        )));
+
+        let OverlayEffect::StartLongCapture { selection_rect, window, .. } = &outcome.effects[0] else {
+            panic!("expected StartLongCapture effect");
+        };
+        assert_eq!(*selection_rect, crate::services::geometry::Rect::new(12, 40, 290, 218));
+        let window = window.expect("snapped window is followed");
+        assert_eq!((window.id, window.bounds), (42, snapped.rect()));
+    }
+
+    #[test]
+    fn scroll_capture_follows_the_unclipped_bounds_of_a_window_off_the_left_edge() {
+        let mut session = OverlaySession::default();
+        session.set_viewport_size(400.0, 300.0);
+        let mut snapped = window("Release notes", "Editor", 0, 10, 300, 250);
+        snapped.id = 42;
+        snapped.bounds = Some(crate::services::geometry::Rect::new(-120, 10, 420, 250));
+        session.windows = vec![snapped];
+        session.update_hover(Point::new(px(20.0), px(20.0)));
+        session.apply(OverlayCommand::Lifecycle(LifecycleCommand::StartSelection(Point::new(
+            px(20.0),
+            px(20.0),
+        ))));
+        session.apply(OverlayCommand::Lifecycle(LifecycleCommand::PointerReleased));
+
+        let outcome = session.apply(OverlayCommand::Capture(CaptureCommand::Execute(
+            crate::services::capture::action::CaptureAction::Scroll,
+        )));
+
+        let OverlayEffect::StartLongCapture { window, .. } = &outcome.effects[0] else {
+            panic!("expected StartLongCapture effect");
+        };
+        let window = window.expect("snapped window is followed");
+        assert_eq!(window.bounds, crate::services::geometry::Rect::new(-120, 10, 420, 250));
+    }
+
+    #[test]
+    fn long_capture_intent_starts_once_a_region_is_selected() {
+        let select = |session: &mut OverlaySession| {
+            session.apply(OverlayCommand::Lifecycle(LifecycleCommand::StartSelection(Point::new(
+                px(40.0),
+                px(40.0),
+            ))));
+            session.apply(OverlayCommand::Lifecycle(LifecycleCommand::PointerMoved(Point::new(
+                px(300.0),
+                px(220.0),
+            ))));
+            session.apply(OverlayCommand::Lifecycle(LifecycleCommand::PointerReleased))
+        };
+
+        let mut session = OverlaySession::default();
+        session.set_viewport_size(600.0, 400.0);
+        assert!(select(&mut session).effects.iter().all(|effect| matches!(effect, OverlayEffect::Refresh)));
+
+        let mut session = OverlaySession::default();
+        session.set_viewport_size(600.0, 400.0);
+        session.long_capture_on_select = true;
+        let outcome = select(&mut session);
+        assert_eq!(outcome.effects.len(), 1);
         assert!(matches!(outcome.effects[0], OverlayEffect::StartLongCapture { .. }));
+        assert!(!session.long_capture_on_select);
     }
 
     #[test]
@@ -106,6 +237,7 @@ mod tests {
             selection_rect,
             viewport_rect,
             viewport_scale,
+            ..
         } = &outcome.effects[0]
         else {
             panic!("expected StartLongCapture effect");
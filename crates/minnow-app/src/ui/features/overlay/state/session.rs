@@ -5,8 +5,10 @@ use std::sync::Arc;
 #[cfg(feature = "overlay-diagnostics")]
 use super::diagnostics::{OverlayDiagnostics, OverlayDiagnosticsSnapshot};
 use super::{PickerFormat, PickerNeighborhood, PickerSample};
-use crate::services::capture::service::CaptureService;
-use crate::services::geometry::{RectF, clamp_point, normalize_rect};
+use crate::platform::shell;
+use crate::services::capture::{select_capture_target, service::CaptureService};
+use crate::services::geometry::{Rect, RectF, clamp_point, normalize_rect};
+use crate::services::history;
 use crate::ui::features::overlay::annotation::{AnnotationEngine, AnnotationUiState};
 use crate::ui::features::overlay::window_catalog::{WindowInfo, fetch_windows_data, find_window_at};
 use crate::ui::support::render_image;
@@ -98,6 +100,9 @@ pub(crate) struct OverlaySession {
     #[cfg(feature = "overlay-diagnostics")]
     pub(super) diagnostics: OverlayDiagnostics,
     pub(super) windows: Vec<WindowInfo>,
+    /// Set when the overlay was opened for a long capture: the first finished
+    /// selection starts it without going through the toolbar.
+    pub(super) long_capture_on_select: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -149,6 +154,32 @@ impl OverlayHandle {
         });
     }
 
+    /// Prepares a fresh capture that starts a long capture as soon as a region
+    /// is selected.
+    pub fn prepare_long_capture(&self, cx: &mut App) {
+        let surface = OverlaySurface::capture();
+        self.0.update(cx, |session, _| {
+            session.prepare_surface(surface);
+            session.long_capture_on_select = true;
+        });
+    }
+
+    /// Prepares the overlay on a capture from history: its crop on a blank
+    /// canvas, already selected, with any saved annotations restored.
+    pub fn prepare_from_history(&self, id: &str, cx: &mut App) -> bool {
+        let Some(restored) = HistorySurface::load(id) else {
+            return false;
+        };
+        self.0.update(cx, |session, _| {
+            session.prepare_surface(restored.surface);
+            session.viewport.selection = Some(restored.selection);
+            if let Some(document) = restored.annotations {
+                session.restore_annotations(&document);
+            }
+        });
+        true
+    }
+
     pub(crate) fn session(&self) -> Entity<OverlaySession> {
         self.0.clone()
     }
@@ -181,6 +212,7 @@ impl OverlaySession {
         self.background_image = surface.background_image;
         self.background_pixels = surface.background_pixels;
         self.windows = surface.windows;
+        self.long_capture_on_select = false;
         self.picker_cursor = None;
         self.picker_sample = None;
         self.picker_neighborhood = None;
@@ -389,6 +421,7 @@ pub(crate) struct OverlaySurface {
 
 impl OverlaySurface {
     pub fn capture() -> Self {
+        select_capture_target(shell::cursor_position());
         let windows = fetch_windows_data();
         match CaptureService::capture_preview() {
             Some(image) => {
@@ -404,3 +437,94 @@ impl OverlaySurface {
         }
     }
 }
+
+const HISTORY_CANVAS_FILL: image::Rgba<u8> = image::Rgba([24, 24, 24, 255]);
+
+struct HistorySurface {
+    surface: OverlaySurface,
+    selection: RectF,
+    annotations: Option<String>,
+}
+
+impl HistorySurface {
+    fn load(id: &str) -> Option<Self> {
+        let store = history::store();
+        let entry = store.entry(id)?;
+        let (image_path, annotations) = match store.annotation_source(id) {
+            Some((base_path, document)) => (base_path, Some(document)),
+            None => (store.capture_path(id)?, None),
+        };
+        let base = match image::open(&image_path) {
+            Ok(image) => image.into_rgba8(),
+            Err(err) => {
+                tracing::error!("Failed to open history capture {}: {err}", image_path.display());
+                return None;
+            }
+        };
+
+        let target = select_capture_target(shell::cursor_position())?;
+        let scale = target.scale().max(1.0);
+        let bounds = target.logical_bounds();
+        let placement = history_placement(base.dimensions(), entry.source_bounds, (bounds.width, bounds.height), scale);
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+        let canvas = {
+            let physical = |value: i32| (value.max(0) as f32 * scale).round() as u32;
+            let mut canvas = RgbaImage::from_pixel(physical(bounds.width), physical(bounds.height), HISTORY_CANVAS_FILL);
+            image::imageops::replace(&mut canvas, &base, i64::from(physical(placement.x)), i64::from(physical(placement.y)));
+            Arc::new(canvas)
+        };
+        CaptureService::replace_preview(canvas.clone());
+
+        Some(Self {
+            surface: OverlaySurface {
+                background_image: Some(render_image::from_rgba_copy(canvas.as_ref())),
+                background_pixels: Some(canvas),
+                windows: Vec::new(),
+            },
+            selection: RectF::new(
+                f64::from(placement.x),
+                f64::from(placement.y),
+                f64::from(placement.width),
+                f64::from(placement.height),
+            ),
+            annotations,
+        })
+    }
+}
+
+/// Where a history crop of `image_size` physical pixels lands in a viewport
+/// of `viewport` logical pixels: back at its original spot when it still
+/// fits, otherwise centred.
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn history_placement(image_size: (u32, u32), source_bounds: Option<Rect>, viewport: (i32, i32), scale: f32) -> Rect {
+    let width = ((image_size.0 as f32 / scale).round() as i32).max(1);
+    let height = ((image_size.1 as f32 / scale).round() as i32).max(1);
+    let fits = |x: i32, y: i32| x >= 0 && y >= 0 && x + width <= viewport.0 && y + height <= viewport.1;
+
+    match source_bounds {
+        Some(bounds) if fits(bounds.x, bounds.y) => Rect::new(bounds.x, bounds.y, width, height),
+        _ => Rect::new(((viewport.0 - width) / 2).max(0), ((viewport.1 - height) / 2).max(0), width, height),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_placement_restores_the_original_spot_when_it_fits() {
+        let placed = history_placement((400, 200), Some(Rect::new(30, 40, 200, 100)), (800, 600), 2.0);
+
+        assert_eq!(placed, Rect::new(30, 40, 200, 100));
+    }
+
+    #[test]
+    fn history_placement_centres_captures_that_no_longer_fit() {
+        assert_eq!(
+            history_placement((300, 100), Some(Rect::new(700, 40, 300, 100)), (800, 600), 1.0),
+            Rect::new(250, 250, 300, 100)
+        );
+        assert_eq!(history_placement((300, 100), None, (200, 50), 1.0), Rect::new(0, 0, 300, 100));
+    }
+}
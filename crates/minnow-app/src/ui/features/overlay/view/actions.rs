@@ -1,10 +1,11 @@
 use super::OverlayView;
 use crate::services::capture::action::CaptureAction;
 use crate::ui::features::overlay::actions::{
-    CloseOverlay, CopyPixelColor, CopySelection, CycleAnnotationColorAction, CyclePickerFormat, DecreaseAnnotationStroke, DeleteAnnotation,
-    IncreaseAnnotationStroke, MovePickerDown, MovePickerLeft, MovePickerRight, MovePickerUp, PickColorSelection, PinSelection, QrSelection,
-    RedoAnnotationAction, ResetSelection, SaveSelection, SelectArrowTool, SelectCircleTool, SelectCounterTool, SelectMosaicTool, SelectRectangleTool,
-    SelectTextTool, StartTextEditAction, ToggleAnnotationFillAction, UndoAnnotationAction,
+    BringAnnotationsToFront, CloseOverlay, CopyPixelColor, CopySelection, CycleAnnotationColorAction, CyclePickerFormat, DecreaseAnnotationStroke,
+    DeleteAnnotation, GroupAnnotations, IncreaseAnnotationStroke, MovePickerDown, MovePickerLeft, MovePickerRight, MovePickerUp, PickColorSelection,
+    PinSelection, QrSelection, RedoAnnotationAction, ResetSelection, SaveSelection, SelectAllAnnotations, SelectArrowTool, SelectCircleTool,
+    SelectCounterTool, SelectHighlighterTool, SelectMosaicTool, SelectPenTool, SelectRectangleTool, SelectTextTool, SendAnnotationsToBack,
+    StartTextEditAction, ToggleAnnotationFillAction, UndoAnnotationAction, UngroupAnnotations,
 };
 use crate::ui::features::overlay::state::{AnnotationCommand, CaptureCommand, LifecycleCommand, PickerCommand};
 use gpui::{Context, Window};
@@ -100,9 +101,28 @@ impl OverlayView {
         SelectMosaicTool,
         AnnotationCommand::SetTool(crate::ui::features::overlay::state::AnnotationTool::Mosaic)
     );
+    annotation_action_handler!(
+        on_action_select_pen_tool,
+        SelectPenTool,
+        AnnotationCommand::SetTool(crate::ui::features::overlay::state::AnnotationTool::Pen)
+    );
+    annotation_action_handler!(
+        on_action_select_highlighter_tool,
+        SelectHighlighterTool,
+        AnnotationCommand::SetTool(crate::ui::features::overlay::state::AnnotationTool::Highlighter)
+    );
     annotation_action_handler!(on_action_undo_annotation, UndoAnnotationAction, AnnotationCommand::Undo);
     annotation_action_handler!(on_action_redo_annotation, RedoAnnotationAction, AnnotationCommand::Redo);
     annotation_action_handler!(on_action_delete_annotation, DeleteAnnotation, AnnotationCommand::DeleteIntent);
+    annotation_action_handler!(on_action_select_all_annotations, SelectAllAnnotations, AnnotationCommand::SelectAll);
+    annotation_action_handler!(
+        on_action_bring_annotations_to_front,
+        BringAnnotationsToFront,
+        AnnotationCommand::BringToFront
+    );
+    annotation_action_handler!(on_action_send_annotations_to_back, SendAnnotationsToBack, AnnotationCommand::SendToBack);
+    annotation_action_handler!(on_action_group_annotations, GroupAnnotations, AnnotationCommand::Group);
+    annotation_action_handler!(on_action_ungroup_annotations, UngroupAnnotations, AnnotationCommand::Ungroup);
     annotation_action_handler!(
         on_action_cycle_annotation_color,
         CycleAnnotationColorAction,
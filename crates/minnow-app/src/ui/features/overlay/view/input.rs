@@ -8,7 +8,7 @@ impl OverlayView {
         let session = self.handle.session();
         let next_command = {
             let state = session.read(cx);
-            resolve_mouse_down_command(state, event.button, event.position, event.click_count)
+            resolve_mouse_down_command(state, event.button, event.position, event.click_count, event.modifiers.shift)
         };
         if let Some(command) = next_command {
             self.dispatch_command(command, window, cx);
@@ -78,10 +78,11 @@ pub(crate) fn resolve_mouse_down_command(
     button: MouseButton,
     point: Point<Pixels>,
     click_count: usize,
+    shift: bool,
 ) -> Option<OverlayCommand> {
     match button {
         MouseButton::Right => Some(resolve_right_click_command(session)),
-        MouseButton::Left => resolve_left_click_command(session, point, click_count),
+        MouseButton::Left => resolve_left_click_command(session, point, click_count, shift),
         _ => None,
     }
 }
@@ -94,7 +95,7 @@ fn resolve_right_click_command(session: &OverlaySession) -> OverlayCommand {
     }
 }
 
-fn resolve_left_click_command(session: &OverlaySession, point: Point<Pixels>, click_count: usize) -> Option<OverlayCommand> {
+fn resolve_left_click_command(session: &OverlaySession, point: Point<Pixels>, click_count: usize, shift: bool) -> Option<OverlayCommand> {
     if let Some(selection) = session.selection() {
         if let Some(corner) = hit_resize_corner(selection, point) {
             return Some(OverlayCommand::Lifecycle(LifecycleCommand::StartResize { corner, point }));
@@ -105,6 +106,19 @@ fn resolve_left_click_command(session: &OverlaySession, point: Point<Pixels>, cl
         }
 
         if matches!(session.mode(), DragMode::Idle) {
+            if shift {
+                if let Some(id) = session.annotation_hit_test(point) {
+                    return Some(OverlayCommand::Annotation(AnnotationCommand::ToggleSelect(id)));
+                }
+                if point_in_rect(point, selection) {
+                    return Some(OverlayCommand::Annotation(AnnotationCommand::StartRubberBand(point)));
+                }
+            }
+
+            if let Some((id, handle)) = session.annotation_handle_hit(point) {
+                return Some(OverlayCommand::Annotation(AnnotationCommand::StartReshape { id, handle, point }));
+            }
+
             if let Some(id) = session.annotation_hit_test(point) {
                 return Some(OverlayCommand::Annotation(AnnotationCommand::StartMove { id, point }));
             }
@@ -159,7 +173,7 @@ fn hit_resize_corner(selection: RectF, point: Point<Pixels>) -> Option<ResizeCor
 #[cfg(test)]
 mod tests {
     use super::resolve_mouse_down_command;
-    use crate::ui::features::overlay::state::{DragMode, LifecycleCommand, OverlayCommand, OverlaySession};
+    use crate::ui::features::overlay::state::{AnnotationCommand, DragMode, LifecycleCommand, OverlayCommand, OverlaySession};
     use gpui::{MouseButton, Point, px};
 
     #[test]
@@ -172,11 +186,30 @@ mod tests {
 
         assert!(matches!(session.mode(), DragMode::Idle));
 
-        let command = resolve_mouse_down_command(&session, MouseButton::Left, Point::new(px(40.0), px(40.0)), 1);
+        let command = resolve_mouse_down_command(&session, MouseButton::Left, Point::new(px(40.0), px(40.0)), 1, false);
 
         assert_eq!(
             command,
             Some(OverlayCommand::Lifecycle(LifecycleCommand::StartMove(Point::new(px(40.0), px(40.0),))))
         );
     }
+
+    #[test]
+    fn shift_drag_inside_selection_starts_a_rubber_band() {
+        let mut session = OverlaySession::default();
+        session.set_viewport_size(300.0, 200.0);
+        session.start_selection(Point::new(px(20.0), px(20.0)));
+        session.update_selection(Point::new(px(80.0), px(70.0)));
+        session.finish_selection();
+
+        let command = resolve_mouse_down_command(&session, MouseButton::Left, Point::new(px(40.0), px(40.0)), 1, true);
+
+        assert_eq!(
+            command,
+            Some(OverlayCommand::Annotation(AnnotationCommand::StartRubberBand(Point::new(
+                px(40.0),
+                px(40.0)
+            ))))
+        );
+    }
 }
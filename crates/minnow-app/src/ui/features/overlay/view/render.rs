@@ -11,7 +11,7 @@ use crate::ui::features::overlay::render::layout::{
     resolve_toolbar_layout,
 };
 use crate::ui::features::overlay::render::picker::overlay_picker;
-use crate::ui::features::overlay::render::properties::{OverlayPropertyState, overlay_properties_panel};
+use crate::ui::features::overlay::render::properties::{OverlayPropertyState, overlay_properties_panel, shows_line_controls};
 use crate::ui::features::overlay::render::selection::{overlay_mask, selection_frame, selection_handles};
 use crate::ui::features::overlay::render::toolbar::{OverlayToolbarState, overlay_toolbar, toolbar_button_count};
 use crate::ui::features::overlay::state::{AnnotationKindTag, OverlayCommand, OverlayFrame, PickerVm};
@@ -62,6 +62,7 @@ impl OverlayView {
             .selected
             .as_ref()
             .is_some_and(|item| item.kind == AnnotationKindTag::Mosaic);
+        let show_line_controls = shows_line_controls(frame.annotation.tool, frame.annotation.selected.map(|item| item.kind));
         let show_property_panel = should_show_property_panel(frame.annotation.tool, frame.annotation.selected.map(|item| item.kind));
 
         let toolbar_layout = hud_visibility
@@ -76,6 +77,7 @@ impl OverlayView {
                     reserved_info_slot,
                     selected_is_text,
                     selected_is_mosaic,
+                    show_line_controls,
                     viewport_w,
                     viewport_h,
                     &occupied,
@@ -184,9 +186,16 @@ impl OverlayView {
             .on_action(cx.listener(Self::on_action_select_counter_tool))
             .on_action(cx.listener(Self::on_action_select_text_tool))
             .on_action(cx.listener(Self::on_action_select_mosaic_tool))
+            .on_action(cx.listener(Self::on_action_select_pen_tool))
+            .on_action(cx.listener(Self::on_action_select_highlighter_tool))
             .on_action(cx.listener(Self::on_action_undo_annotation))
             .on_action(cx.listener(Self::on_action_redo_annotation))
             .on_action(cx.listener(Self::on_action_delete_annotation))
+            .on_action(cx.listener(Self::on_action_select_all_annotations))
+            .on_action(cx.listener(Self::on_action_bring_annotations_to_front))
+            .on_action(cx.listener(Self::on_action_send_annotations_to_back))
+            .on_action(cx.listener(Self::on_action_group_annotations))
+            .on_action(cx.listener(Self::on_action_ungroup_annotations))
             .on_action(cx.listener(Self::on_action_cycle_annotation_color))
             .on_action(cx.listener(Self::on_action_toggle_annotation_fill))
             .on_action(cx.listener(Self::on_action_increase_annotation_stroke))
@@ -1,10 +1,15 @@
+use crate::platform::shell;
+use crate::services::capture::active_capture_target;
+use crate::services::capture::monitor::MonitorLayout;
 use crate::services::geometry::Rect;
 use serde::{Deserialize, Serialize};
 use tracing::info;
-use xcap::{Monitor, Window};
+use xcap::Window;
 
 const MIN_VIRTUAL_WIDTH: i32 = 1920;
 const MIN_VIRTUAL_HEIGHT: i32 = 1080;
+/// Smallest content area left after removing a window's chrome.
+const MIN_CONTENT_SIZE: i32 = 64;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct WindowInfo {
@@ -14,56 +19,84 @@ pub struct WindowInfo {
     pub width: u32,
     pub height: u32,
     pub app_name: String,
+    pub monitor_id: u32,
+    pub scale: f32,
+    /// Whether the window had keyboard focus when the catalog was taken.
+    #[serde(default)]
+    pub focused: bool,
+    /// Native window id, used to follow the window while it is captured.
+    #[serde(default)]
+    pub id: u32,
+    /// The window's client area, where the platform reports one.
+    #[serde(default)]
+    pub content: Option<Rect>,
+    /// The whole window as [`locate_window`] reports it, including any part
+    /// off-screen, which the rect above is clipped to.
+    #[serde(default)]
+    pub bounds: Option<Rect>,
 }
 
+impl WindowInfo {
+    pub(crate) fn rect(&self) -> Rect {
+        Rect::new(
+            self.x,
+            self.y,
+            i32::try_from(self.width).unwrap_or(i32::MAX),
+            i32::try_from(self.height).unwrap_or(i32::MAX),
+        )
+    }
+
+    /// The window without its title bar and borders, which a long capture of
+    /// the window leaves out. Windows without a known client area, or with
+    /// one too small to capture, are returned whole.
+    pub(crate) fn content_rect(&self) -> Rect {
+        match self.content {
+            Some(content) if content.width >= MIN_CONTENT_SIZE && content.height >= MIN_CONTENT_SIZE => content,
+            _ => self.rect(),
+        }
+    }
+
+    /// Where a capture following this window starts from, comparable with
+    /// what [`locate_window`] reports while it runs.
+    pub(crate) fn tracked_bounds(&self) -> Rect {
+        self.bounds.unwrap_or_else(|| self.rect())
+    }
+}
+
+/// Visible windows positioned relative to the overlay, which covers the
+/// capture target rather than the whole desktop.
 #[must_use]
 pub fn fetch_windows_data() -> Vec<WindowInfo> {
+    let origin = active_capture_target().map_or_else(Rect::empty, |target| target.logical_bounds());
+    fetch_windows_relative_to(origin)
+}
+
+/// Visible windows in logical desktop coordinates, for captures that run
+/// without an overlay.
+#[must_use]
+pub fn fetch_desktop_windows() -> Vec<WindowInfo> {
+    fetch_windows_relative_to(Rect::empty())
+}
+
+/// Where the window with `id` is now, relative to the overlay like
+/// [`fetch_windows_data`], or `None` once it is closed or minimized.
+#[must_use]
+pub fn locate_window(id: u32) -> Option<Rect> {
+    let bounds = shell::window_geometry(id)?.bounds;
+    let origin = active_capture_target().map_or_else(Rect::empty, |target| target.logical_bounds());
+    Some(place_window(&MonitorLayout::current(), bounds, origin).rect)
+}
+
+fn fetch_windows_relative_to(origin: Rect) -> Vec<WindowInfo> {
     let windows = Window::all().unwrap_or_default();
-    let monitors = Monitor::all().unwrap_or_default();
-    let scale_factor = monitors
-        .first()
-        .and_then(|monitor| monitor.scale_factor().ok())
-        .filter(|scale| *scale > 0.0)
-        .unwrap_or(1.0);
+    let layout = MonitorLayout::current();
     info!(
-        "Fetching window data, total windows found: {}, scale_factor: {}",
+        "Fetching window data, total windows found: {}, monitors: {}",
         windows.len(),
-        scale_factor
+        layout.regions().len()
     );
 
-    let (screen_rect, offset_x, offset_y) = if monitors.is_empty() {
-        (
-            Rect {
-                x: 0,
-                y: 0,
-                width: 10000,
-                height: 10000,
-            },
-            0,
-            0,
-        )
-    } else {
-        let (min_x, min_y, max_x, max_y) = monitors
-            .iter()
-            .fold((i32::MAX, i32::MAX, i32::MIN, i32::MIN), |(min_x, min_y, max_x, max_y), m| {
-                let x = m.x().unwrap_or(0);
-                let y = m.y().unwrap_or(0);
-                let w = i32::try_from(m.width().unwrap_or(0)).unwrap_or(0);
-                let h = i32::try_from(m.height().unwrap_or(0)).unwrap_or(0);
-                (min_x.min(x), min_y.min(y), max_x.max(x + w), max_y.max(y + h))
-            });
-
-        (
-            Rect {
-                x: min_x,
-                y: min_y,
-                width: (max_x - min_x).max(MIN_VIRTUAL_WIDTH),
-                height: (max_y - min_y).max(MIN_VIRTUAL_HEIGHT),
-            },
-            0,
-            0,
-        )
-    };
+    let screen_rect = virtual_screen_rect(&layout);
 
     let mut visible_rects: Vec<Rect> = Vec::with_capacity(windows.len());
     const SYSTEM_OVERLAYS: &[&str] = &["程序坞", "Dock", "Window Server", "Control Center", "Notification Center", "Spotlight"];
@@ -96,18 +129,26 @@ pub fn fetch_windows_data() -> Vec<WindowInfo> {
                 visible_rects.push(valid_rect);
             }
 
-            let logical_x = ((valid_rect.x - offset_x) as f32 / scale_factor) as i32;
-            let logical_y = ((valid_rect.y - offset_y) as f32 / scale_factor) as i32;
-            let logical_w = (valid_rect.width as f32 / scale_factor).max(1.0) as u32;
-            let logical_h = (valid_rect.height as f32 / scale_factor).max(1.0) as u32;
-
+            let placement = place_window(&layout, valid_rect, origin);
+            let id = window.id().unwrap_or(0);
+            let geometry = shell::window_geometry(id);
+            let content = geometry
+                .and_then(|geometry| geometry.content.intersect(screen_rect))
+                .map(|content| place_window(&layout, content, origin).rect);
+            let bounds = geometry.map(|geometry| place_window(&layout, geometry.bounds, origin).rect);
             Some(WindowInfo {
                 title: window.title().unwrap_or_else(|_| "Unknown".to_string()),
-                x: logical_x,
-                y: logical_y,
-                width: logical_w,
-                height: logical_h,
+                x: placement.rect.x,
+                y: placement.rect.y,
+                width: u32::try_from(placement.rect.width).unwrap_or(1),
+                height: u32::try_from(placement.rect.height).unwrap_or(1),
                 app_name,
+                monitor_id: placement.monitor_id,
+                scale: placement.scale,
+                focused: window.is_focused().unwrap_or(false),
+                id,
+                content,
+                bounds,
             })
         })
         .collect();
@@ -116,6 +157,58 @@ pub fn fetch_windows_data() -> Vec<WindowInfo> {
     results
 }
 
+fn virtual_screen_rect(layout: &MonitorLayout) -> Rect {
+    if layout.regions().is_empty() {
+        return Rect {
+            x: 0,
+            y: 0,
+            width: 10000,
+            height: 10000,
+        };
+    }
+
+    let (min_x, min_y, max_x, max_y) = layout
+        .regions()
+        .iter()
+        .map(|region| region.desktop_rect())
+        .fold((i32::MAX, i32::MAX, i32::MIN, i32::MIN), |(min_x, min_y, max_x, max_y), r| {
+            (min_x.min(r.x), min_y.min(r.y), max_x.max(r.x + r.width), max_y.max(r.y + r.height))
+        });
+
+    Rect {
+        x: min_x,
+        y: min_y,
+        width: (max_x - min_x).max(MIN_VIRTUAL_WIDTH),
+        height: (max_y - min_y).max(MIN_VIRTUAL_HEIGHT),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct WindowPlacement {
+    rect: Rect,
+    monitor_id: u32,
+    scale: f32,
+}
+
+/// Converts a desktop window rect into overlay coordinates using the scale of
+/// the monitor that shows most of the window.
+fn place_window(layout: &MonitorLayout, rect: Rect, origin: Rect) -> WindowPlacement {
+    let Some(monitor) = layout.monitor_for_rect(rect) else {
+        return WindowPlacement {
+            rect: Rect::new(rect.x - origin.x, rect.y - origin.y, rect.width.max(1), rect.height.max(1)),
+            monitor_id: 0,
+            scale: 1.0,
+        };
+    };
+
+    let logical = monitor.to_logical(rect);
+    WindowPlacement {
+        rect: Rect::new(logical.x - origin.x, logical.y - origin.y, logical.width, logical.height),
+        monitor_id: monitor.id,
+        scale: monitor.scale(),
+    }
+}
+
 #[must_use]
 pub fn find_window_at(windows: &[WindowInfo], x: f64, y: f64) -> Option<usize> {
     windows
@@ -131,3 +224,97 @@ pub fn find_window_at(windows: &[WindowInfo], x: f64, y: f64) -> Option<usize> {
         .min_by_key(|(_, w)| u64::from(w.width) * u64::from(w.height))
         .map(|(i, _)| i)
 }
+
+#[must_use]
+pub fn find_focused_window(windows: &[WindowInfo]) -> Option<usize> {
+    windows.iter().position(|w| w.focused)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::capture::monitor::MonitorRegion;
+
+    fn region(id: u32, x: i32, width: u32, height: u32, scale: f32) -> MonitorRegion {
+        MonitorRegion::new(id, (x, 0), (width, height), scale, id == 1)
+    }
+
+    #[test]
+    fn place_window_uses_the_scale_of_the_monitor_it_is_on() {
+        let primary = region(1, 0, 1000, 800, 1.0);
+        let secondary_origin = i32::try_from(primary.width).unwrap();
+        let secondary = region(2, secondary_origin, 2000, 1600, 2.0);
+        let layout = MonitorLayout::from_regions(vec![primary, secondary]);
+        let secondary_logical = layout.regions()[1].logical_rect();
+
+        let on_primary = place_window(&layout, Rect::new(100, 100, 200, 100), Rect::empty());
+        assert_eq!(on_primary.monitor_id, 1);
+        assert_eq!(on_primary.rect, Rect::new(100, 100, 200, 100));
+
+        let desktop = secondary.desktop_rect();
+        let window = Rect::new(desktop.x + desktop.width / 4, 0, desktop.width / 2, desktop.height / 2);
+        let on_secondary = place_window(&layout, window, Rect::empty());
+        assert_eq!(on_secondary.monitor_id, 2);
+        assert!((on_secondary.scale - 2.0).abs() < f32::EPSILON);
+        assert_eq!(on_secondary.rect.x, secondary_logical.x + secondary_logical.width / 4);
+        assert_eq!(on_secondary.rect.width, secondary_logical.width / 2);
+    }
+
+    #[test]
+    fn focused_and_hovered_windows_are_found_by_their_bounds() {
+        let window = |x, width, focused| WindowInfo {
+            title: String::new(),
+            x,
+            y: 0,
+            width,
+            height: 400,
+            app_name: String::new(),
+            monitor_id: 1,
+            scale: 1.0,
+            focused,
+            id: 0,
+            content: None,
+            bounds: None,
+        };
+        let windows = [window(0, 1000, false), window(100, 300, true)];
+
+        assert_eq!(find_focused_window(&windows), Some(1));
+        assert_eq!(find_window_at(&windows, 150.0, 10.0), Some(1));
+        assert_eq!(find_window_at(&windows, 900.0, 10.0), Some(0));
+        assert_eq!(find_focused_window(&windows[..1]), None);
+        assert_eq!(windows[1].rect(), Rect::new(100, 0, 300, 400));
+    }
+
+    #[test]
+    fn place_window_is_relative_to_the_capture_origin() {
+        let layout = MonitorLayout::from_regions(vec![region(1, 0, 1000, 800, 1.0), region(2, 1000, 1000, 800, 1.0)]);
+
+        let placed = place_window(&layout, Rect::new(1100, 50, 300, 200), Rect::new(1000, 0, 1000, 800));
+
+        assert_eq!(placed.monitor_id, 2);
+        assert_eq!(placed.rect, Rect::new(100, 50, 300, 200));
+    }
+
+    #[test]
+    fn content_rect_is_the_client_area_when_it_is_large_enough() {
+        let window = |content| WindowInfo {
+            title: String::new(),
+            x: 10,
+            y: 20,
+            width: 800,
+            height: 600,
+            app_name: String::new(),
+            monitor_id: 1,
+            scale: 1.0,
+            focused: false,
+            id: 7,
+            content,
+            bounds: None,
+        };
+
+        let client = Rect::new(18, 52, 784, 560);
+        assert_eq!(window(Some(client)).content_rect(), client);
+        assert_eq!(window(Some(Rect::new(18, 52, 784, 40))).content_rect(), Rect::new(10, 20, 800, 600));
+        assert_eq!(window(None).content_rect(), Rect::new(10, 20, 800, 600));
+    }
+}
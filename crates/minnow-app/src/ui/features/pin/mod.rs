@@ -33,9 +33,25 @@ pub fn bind_keys(cx: &mut App) {
     ]);
 }
 
+/// Closes every open pin window.
+pub fn close_all_windows(cx: &mut App) {
+    let manager = cx.global::<PinManager>().clone();
+    manager.close_all(cx);
+}
+
+/// Hides every open pin, or shows them again.
+pub fn toggle_visibility(cx: &mut App) {
+    let manager = cx.global::<PinManager>().clone();
+    let hidden = manager.is_hidden(cx);
+    manager.set_hidden(!hidden, cx);
+}
+
 pub fn open_window(cx: &mut App, request: PinRequest) {
     let options = window_options(cx, &request);
     let manager = cx.global::<PinManager>().clone();
+    if manager.is_hidden(cx) {
+        manager.set_hidden(false, cx);
+    }
 
     if let Err(err) = cx.open_window(
         options,
@@ -12,10 +12,10 @@ pub struct PinRequest {
     source_bounds: Option<Rect>,
     auto_ocr: bool,
     ocr_image: Option<Arc<RgbaImage>>,
+    history_id: Option<String>,
 }
 
 impl PinRequest {
-    #[cfg(test)]
     pub(crate) fn new(image_path: impl Into<PathBuf>, source_bounds: Option<Rect>, auto_ocr: bool) -> Self {
         let image_path = image_path.into();
         let image_size = image::image_dimensions(&image_path).ok();
@@ -25,6 +25,7 @@ impl PinRequest {
             source_bounds,
             auto_ocr,
             ocr_image: None,
+            history_id: None,
         }
     }
 
@@ -36,6 +37,7 @@ impl PinRequest {
             source_bounds: Some(request.source_bounds),
             auto_ocr: request.auto_ocr,
             ocr_image: Some(request.ocr_image),
+            history_id: request.history_id,
         }
     }
 
@@ -59,6 +61,10 @@ impl PinRequest {
         self.ocr_image.clone()
     }
 
+    pub(crate) fn history_id(&self) -> Option<&str> {
+        self.history_id.as_deref()
+    }
+
     pub(crate) fn base_size(&self) -> (f32, f32) {
         if let Some(source) = self.source_bounds() {
             return (source.width as f32, source.height as f32);
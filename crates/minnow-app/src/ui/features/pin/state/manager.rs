@@ -1,3 +1,4 @@
+use crate::platform::shell;
 use gpui::{AnyWindowHandle, App, AppContext, Entity, Global, WindowId};
 use tracing::{info, warn};
 
@@ -56,6 +57,28 @@ impl PinManager {
         }
     }
 
+    pub(in crate::ui::features::pin) fn is_hidden(&self, cx: &App) -> bool {
+        self.0.read(cx).hidden
+    }
+
+    /// Hides pins by rendering them empty and letting clicks fall through to
+    /// the windows below, so they keep their place and content.
+    pub(in crate::ui::features::pin) fn set_hidden(&self, hidden: bool, cx: &mut App) {
+        let handles = self.prune_closed(cx);
+        let hidden = hidden && !handles.is_empty();
+        self.0.update(cx, |state, _| state.hidden = hidden);
+        info!(target: "minnowsnap::pin", hidden, count = handles.len(), "updating pin visibility");
+
+        for handle in handles {
+            let _ = handle.update(cx, |_, window, _| {
+                if let Err(err) = shell::set_click_through(window, hidden) {
+                    warn!(target: "minnowsnap::pin", "failed to update pin click-through: {err}");
+                }
+                window.refresh();
+            });
+        }
+    }
+
     pub(in crate::ui::features::pin) fn prune_closed(&self, cx: &mut App) -> Vec<AnyWindowHandle> {
         let snapshot = self.0.read(cx).handles();
         let open_window_ids = cx.windows().into_iter().map(|handle| handle.window_id()).collect::<Vec<_>>();
@@ -72,6 +95,7 @@ impl PinManager {
 #[derive(Default)]
 struct PinManagerState {
     windows: Vec<TrackedPinWindow>,
+    hidden: bool,
 }
 
 impl PinManagerState {
@@ -97,6 +121,7 @@ impl PinManagerState {
 
     fn clear(&mut self) {
         self.windows.clear();
+        self.hidden = false;
     }
 }
 
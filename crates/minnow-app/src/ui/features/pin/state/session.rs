@@ -37,6 +37,7 @@ pub(in crate::ui::features::pin) struct PinSession {
     zoom: f32,
     opacity: f32,
     auto_ocr: bool,
+    history_id: Option<String>,
     ocr: PinOcrState,
 }
 
@@ -110,6 +111,7 @@ impl PinSession {
             zoom: Self::initial_zoom(base_size),
             opacity: Self::MAX_OPACITY,
             auto_ocr: request.auto_ocr(),
+            history_id: request.history_id().map(str::to_string),
             ocr: PinOcrState::default(),
         }
     }
@@ -196,6 +198,11 @@ impl PinSession {
         }
     }
 
+    /// History entry the pinned capture was recorded as, if any.
+    pub(in crate::ui::features::pin) fn history_id(&self) -> Option<&str> {
+        self.history_id.as_deref()
+    }
+
     pub(in crate::ui::features::pin) fn finish_ocr(&mut self, result: Result<Vec<OcrBlock>, String>) {
         self.ocr.processing = false;
         match result {
@@ -409,6 +416,7 @@ mod tests {
             zoom: 1.0,
             opacity: 1.0,
             auto_ocr: false,
+            history_id: None,
             ocr: PinOcrState::default(),
         };
 
@@ -432,6 +440,7 @@ mod tests {
             zoom: 1.0,
             opacity: 1.0,
             auto_ocr: false,
+            history_id: None,
             ocr: PinOcrState::default(),
         };
 
@@ -455,6 +464,7 @@ mod tests {
             zoom: 1.0,
             opacity: 1.0,
             auto_ocr: false,
+            history_id: None,
             ocr: PinOcrState::default(),
         };
 
@@ -484,6 +494,7 @@ mod tests {
             source_bounds: Rect::new(0, 0, 12, 6),
             auto_ocr: false,
             ocr_image: image.clone(),
+            history_id: None,
         });
         let mut session = PinSession::from_request(request);
 
@@ -502,6 +513,7 @@ mod tests {
             zoom: 1.0,
             opacity: 1.0,
             auto_ocr: false,
+            history_id: None,
             ocr: PinOcrState {
                 processing: false,
                 blocks: vec![
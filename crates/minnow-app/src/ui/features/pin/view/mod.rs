@@ -9,8 +9,8 @@ use super::{
 use crate::app::workflows;
 use crate::platform::shell::{self, NotificationType};
 use crate::services::capture::action::CaptureAction;
-use crate::services::i18n;
 use crate::services::ocr::service;
+use crate::services::{history, i18n};
 use crate::ui::support::capture_actions::{self, CaptureActionHostKind};
 use gpui::{App, Context, Entity, FocusHandle, Subscription, Window};
 use std::collections::BTreeSet;
@@ -87,6 +87,10 @@ impl PinView {
         cx.spawn(async move |cx| {
             let result = service::recognize_image_blocks(image_input).await;
             let _ = weak_session.update(cx, |session, _| {
+                if let (Some(id), Ok(blocks)) = (session.history_id(), &result) {
+                    let text = blocks.iter().map(|block| block.text.as_str()).collect::<Vec<_>>().join("\n");
+                    history::set_ocr_text_in_background(id.to_string(), text);
+                }
                 session.finish_ocr(result);
             });
         })
@@ -5,6 +5,7 @@ use crate::services::capture::action::CaptureAction;
 use crate::services::i18n;
 use crate::ui::features::pin::{PIN_CONTEXT, render, state::PinFrame};
 use gpui::InteractiveElement;
+use gpui::prelude::FluentBuilder as _;
 use gpui::{App, Context, IntoElement, MouseButton, ParentElement, Styled, Window, canvas, div, px, quad};
 use gpui_component::ActiveTheme as _;
 use gpui_component::menu::ContextMenuExt;
@@ -85,6 +86,7 @@ impl gpui::Render for PinView {
         let session = self.session.clone();
         let frame = self.session.read(cx).frame();
         let show_close_all = self.manager.prune_closed(BorrowMut::borrow_mut(cx)).len() > 1;
+        let hidden = self.manager.is_hidden(cx);
 
         div()
             .id("pin-view")
@@ -145,6 +147,7 @@ impl gpui::Render for PinView {
                 div()
                     .size_full()
                     .relative()
+                    .when(hidden, |this| this.invisible())
                     .popup_drag_region(PopupDragBehavior::HitTest)
                     .child(render::panel(frame.image_path.clone(), frame.opacity, cx).absolute().size_full())
                     .children((!frame.ocr.blocks.is_empty() || frame.ocr.processing).then(|| Self::render_ocr_overlay(frame.clone(), cx))),
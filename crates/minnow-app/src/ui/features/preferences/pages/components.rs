@@ -1,10 +1,10 @@
-use super::{SelectAction, ToggleAction};
+use super::{SelectAction, ShortcutsPageActions, ToggleAction};
 use crate::services::assets::asset_paths;
 use crate::services::i18n;
 use crate::ui::features::preferences::{
     state::{
         PreferencesNotice,
-        frame::{ActionRowProps, ButtonProps, SelectOption, SelectRowProps, SidebarItemProps, ToggleRowProps},
+        frame::{ActionRowProps, ButtonProps, SelectOption, SelectRowProps, ShortcutRowProps, SidebarItemProps, ToggleRowProps},
     },
     view::PreferencesView,
 };
@@ -221,6 +221,81 @@ pub(super) fn secondary_button(props: &ButtonProps, on_click: impl Fn(&ClickEven
         .into_any_element()
 }
 
+/// A shortcut table row: the action dropdown on the left, the recorded key
+/// combination and a remove button on the right.
+pub(super) fn shortcut_row(props: &ShortcutRowProps, actions: ShortcutsPageActions, cx: &mut Context<PreferencesView>) -> AnyElement {
+    let index = props.index;
+    let view = cx.entity();
+    let current_value = props.action.clone();
+    let options = props.action_options.clone();
+
+    let action_button = Button::new(("preferences-shortcut-action", index))
+        .label(props.action_label.clone())
+        .dropdown_caret(true)
+        .outline()
+        .with_size(Size::Small)
+        .dropdown_menu_with_anchor(gpui::Corner::TopLeft, move |menu: PopupMenu, _, _| {
+            let current_value = current_value.clone();
+            let view = view.clone();
+
+            options.iter().fold(menu, move |menu, option| {
+                let checked = current_value == option.value;
+                let value = option.value.clone();
+                let view = view.clone();
+
+                menu.item(PopupMenuItem::new(option.label.clone()).checked(checked).on_click(move |_, window, cx| {
+                    view.update(cx, |this, cx| (actions.select_action)(this, index, value.clone(), window, cx));
+                }))
+            })
+        });
+
+    let controls = div()
+        .flex()
+        .items_center()
+        .gap_2()
+        .child(
+            Button::new(("preferences-shortcut-record", index))
+                .label(props.shortcut_label.clone())
+                .outline()
+                .with_size(Size::Small)
+                .on_click(cx.listener(move |this, _: &ClickEvent, window, cx| (actions.record)(this, index, window, cx))),
+        )
+        .child(
+            Button::new(("preferences-shortcut-remove", index))
+                .label(props.remove_label.clone())
+                .ghost()
+                .with_size(Size::Small)
+                .on_click(cx.listener(move |this, _: &ClickEvent, window, cx| (actions.remove)(this, index, window, cx))),
+        );
+
+    div()
+        .w_full()
+        .flex()
+        .items_center()
+        .justify_between()
+        .gap_4()
+        .min_w(px(0.))
+        .child(
+            v_flex()
+                .flex_1()
+                .min_w(px(0.))
+                .gap_1()
+                .items_start()
+                .overflow_hidden()
+                .child(action_button)
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground)
+                        .line_clamp(2)
+                        .text_ellipsis()
+                        .child(props.description.clone()),
+                ),
+        )
+        .child(controls)
+        .into_any_element()
+}
+
 fn setting_row(title: SharedString, description: SharedString, disabled: bool, control: impl IntoElement, cx: &App) -> AnyElement {
     div()
         .w_full()
@@ -20,10 +20,41 @@ pub(super) fn render(props: &GeneralPageProps, actions: GeneralPageActions, cx:
             ],
             cx,
         ))
+        .child(components::setting_section(
+            [
+                components::setting_dropdown(&props.capture_scope, actions.capture_scope, cx),
+                components::setting_dropdown(&props.capture_delay, actions.capture_delay, cx),
+                components::setting_dropdown(&props.delayed_capture_mode, actions.delayed_capture_mode, cx),
+                components::setting_dropdown(&props.quick_capture_destination, actions.quick_capture_destination, cx),
+                components::setting_dropdown(&props.long_capture_max_length, actions.long_capture_max_length, cx),
+                components::setting_toggle(&props.long_capture_animation, actions.long_capture_animation, cx),
+            ],
+            cx,
+        ))
+        .child(components::setting_section(
+            [
+                components::setting_dropdown(&props.recording_format, actions.recording_format, cx),
+                components::setting_dropdown(&props.recording_frame_rate, actions.recording_frame_rate, cx),
+                components::setting_dropdown(&props.recording_max_seconds, actions.recording_max_seconds, cx),
+            ],
+            cx,
+        ))
         .child(components::setting_section(
             [
                 components::setting_action(&props.save_path, cx.listener(actions.browse_save_path), cx),
+                components::setting_dropdown(&props.filename_template, actions.filename_template, cx),
+                components::setting_dropdown(&props.output_format, actions.output_format, cx),
                 components::setting_toggle(&props.image_compression, actions.image_compression, cx),
+                components::setting_dropdown(&props.jpeg_quality, actions.jpeg_quality, cx),
+            ],
+            cx,
+        ))
+        .child(components::setting_section(
+            [
+                components::setting_toggle(&props.history_enabled, actions.history_enabled, cx),
+                components::setting_dropdown(&props.history_max_entries, actions.history_max_entries, cx),
+                components::setting_dropdown(&props.history_retention, actions.history_retention, cx),
+                components::setting_action(&props.open_history, actions.open_history, cx),
             ],
             cx,
         ))
@@ -16,6 +16,8 @@ pub(super) type ToggleAction = fn(&mut PreferencesView, bool, &mut Window, &mut
 pub(super) type ClickAction = fn(&mut PreferencesView, &ClickEvent, &mut Window, &mut Context<PreferencesView>);
 pub(super) type SelectAction = fn(SharedString, &mut Window, &mut App);
 pub(super) type PlainClickAction = fn(&ClickEvent, &mut Window, &mut App);
+pub(super) type ShortcutRowAction = fn(&mut PreferencesView, usize, &mut Window, &mut Context<PreferencesView>);
+pub(super) type ShortcutRowSelectAction = fn(&mut PreferencesView, usize, SharedString, &mut Window, &mut Context<PreferencesView>);
 
 #[derive(Clone, Copy)]
 pub(super) struct GeneralPageActions {
@@ -25,6 +27,22 @@ pub(super) struct GeneralPageActions {
     pub(super) font: SelectAction,
     pub(super) browse_save_path: ClickAction,
     pub(super) image_compression: ToggleAction,
+    pub(super) capture_scope: SelectAction,
+    pub(super) capture_delay: SelectAction,
+    pub(super) delayed_capture_mode: SelectAction,
+    pub(super) quick_capture_destination: SelectAction,
+    pub(super) long_capture_max_length: SelectAction,
+    pub(super) long_capture_animation: ToggleAction,
+    pub(super) recording_format: SelectAction,
+    pub(super) recording_frame_rate: SelectAction,
+    pub(super) recording_max_seconds: SelectAction,
+    pub(super) output_format: SelectAction,
+    pub(super) jpeg_quality: SelectAction,
+    pub(super) filename_template: SelectAction,
+    pub(super) history_enabled: ToggleAction,
+    pub(super) history_max_entries: SelectAction,
+    pub(super) history_retention: SelectAction,
+    pub(super) open_history: PlainClickAction,
 }
 
 #[derive(Clone, Copy)]
@@ -38,8 +56,10 @@ pub(super) struct NotificationsPageActions {
 
 #[derive(Clone, Copy)]
 pub(super) struct ShortcutsPageActions {
-    pub(super) record_capture: ClickAction,
-    pub(super) record_quick_capture: ClickAction,
+    pub(super) record: ShortcutRowAction,
+    pub(super) remove: ShortcutRowAction,
+    pub(super) select_action: ShortcutRowSelectAction,
+    pub(super) add: ClickAction,
     pub(super) restore_defaults: ClickAction,
 }
 
@@ -74,6 +94,22 @@ impl Default for PreferencesRenderActions {
                 font: PreferencesView::on_font_selected,
                 browse_save_path: PreferencesView::on_browse_save_path,
                 image_compression: PreferencesView::on_image_compression_changed,
+                capture_scope: PreferencesView::on_capture_scope_selected,
+                capture_delay: PreferencesView::on_capture_delay_selected,
+                delayed_capture_mode: PreferencesView::on_delayed_capture_mode_selected,
+                quick_capture_destination: PreferencesView::on_quick_capture_destination_selected,
+                long_capture_max_length: PreferencesView::on_long_capture_max_length_selected,
+                long_capture_animation: PreferencesView::on_long_capture_animation_changed,
+                recording_format: PreferencesView::on_recording_format_selected,
+                recording_frame_rate: PreferencesView::on_recording_frame_rate_selected,
+                recording_max_seconds: PreferencesView::on_recording_max_seconds_selected,
+                output_format: PreferencesView::on_output_format_selected,
+                jpeg_quality: PreferencesView::on_jpeg_quality_selected,
+                filename_template: PreferencesView::on_filename_template_selected,
+                history_enabled: PreferencesView::on_history_enabled_changed,
+                history_max_entries: PreferencesView::on_history_max_entries_selected,
+                history_retention: PreferencesView::on_history_retention_selected,
+                open_history: PreferencesView::on_open_history,
             },
             notifications: NotificationsPageActions {
                 enabled: PreferencesView::on_notifications_enabled_changed,
@@ -83,8 +119,10 @@ impl Default for PreferencesRenderActions {
                 shutter_sound: PreferencesView::on_shutter_sound_changed,
             },
             shortcuts: ShortcutsPageActions {
-                record_capture: PreferencesView::on_capture_shortcut_record,
-                record_quick_capture: PreferencesView::on_quick_shortcut_record,
+                record: PreferencesView::on_shortcut_record,
+                remove: PreferencesView::on_shortcut_remove,
+                select_action: PreferencesView::on_shortcut_action_selected,
+                add: PreferencesView::on_add_shortcut,
                 restore_defaults: PreferencesView::on_restore_default_shortcuts,
             },
             ocr: OcrPageActions {
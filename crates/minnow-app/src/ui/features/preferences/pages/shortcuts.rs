@@ -1,17 +1,21 @@
 use super::{ShortcutsPageActions, components};
 use crate::ui::features::preferences::{state::frame::ShortcutsPageProps, view::PreferencesView};
-use gpui::{AnyElement, Context, IntoElement, ParentElement, Styled, div, px};
+use gpui::{AnyElement, Context, IntoElement, ParentElement, Styled, div, prelude::FluentBuilder as _, px};
 use gpui_component::v_flex;
 
 pub(super) fn render(props: &ShortcutsPageProps, actions: ShortcutsPageActions, cx: &mut Context<PreferencesView>) -> AnyElement {
     let footer = components::surface_card(
-        div()
-            .px_4()
-            .py_3()
-            .child(div().flex().items_center().justify_end().child(components::secondary_button(
-                &props.restore_defaults,
-                cx.listener(actions.restore_defaults),
-            ))),
+        div().px_4().py_3().child(
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .child(components::secondary_button(&props.add_binding, cx.listener(actions.add)))
+                .child(components::secondary_button(
+                    &props.restore_defaults,
+                    cx.listener(actions.restore_defaults),
+                )),
+        ),
         cx,
     );
 
@@ -21,13 +25,14 @@ pub(super) fn render(props: &ShortcutsPageProps, actions: ShortcutsPageActions,
         .gap_4()
         .children(props.recording_notice.as_ref().map(|notice| components::notice_banner(notice, cx)))
         .children(props.conflict_notice.as_ref().map(|notice| components::notice_banner(notice, cx)))
-        .child(components::setting_section(
-            [
-                components::setting_action(&props.capture, cx.listener(actions.record_capture), cx),
-                components::setting_action(&props.quick_capture, cx.listener(actions.record_quick_capture), cx),
-            ],
-            cx,
-        ))
+        .when(!props.rows.is_empty(), |this| {
+            let rows = props
+                .rows
+                .iter()
+                .map(|row| components::shortcut_row(row, actions, cx))
+                .collect::<Vec<_>>();
+            this.child(components::setting_section(rows, cx))
+        })
         .child(footer)
         .into_any_element()
 }
@@ -4,7 +4,7 @@ use super::super::{
     ocr::{self, OcrSnapshot},
     shortcuts::ShortcutsSnapshot,
 };
-use super::rows::{ActionRowProps, ButtonProps, SelectRowProps, ToggleRowProps};
+use super::rows::{ActionRowProps, ButtonProps, SelectOption, SelectRowProps, ShortcutRowProps, ToggleRowProps};
 use crate::services::app_meta::APP_NAME;
 use crate::services::hotkeys::HotkeyAction;
 use crate::services::{i18n, ocr::service::OcrModelStatus, paths, settings};
@@ -19,6 +19,22 @@ pub(crate) struct GeneralPageProps {
     pub(crate) font: SelectRowProps,
     pub(crate) save_path: ActionRowProps,
     pub(crate) image_compression: ToggleRowProps,
+    pub(crate) capture_scope: SelectRowProps,
+    pub(crate) capture_delay: SelectRowProps,
+    pub(crate) delayed_capture_mode: SelectRowProps,
+    pub(crate) quick_capture_destination: SelectRowProps,
+    pub(crate) long_capture_max_length: SelectRowProps,
+    pub(crate) long_capture_animation: ToggleRowProps,
+    pub(crate) recording_format: SelectRowProps,
+    pub(crate) recording_frame_rate: SelectRowProps,
+    pub(crate) recording_max_seconds: SelectRowProps,
+    pub(crate) output_format: SelectRowProps,
+    pub(crate) jpeg_quality: SelectRowProps,
+    pub(crate) filename_template: SelectRowProps,
+    pub(crate) history_enabled: ToggleRowProps,
+    pub(crate) history_max_entries: SelectRowProps,
+    pub(crate) history_retention: SelectRowProps,
+    pub(crate) open_history: ActionRowProps,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -32,10 +48,10 @@ pub(crate) struct NotificationsPageProps {
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub(crate) struct ShortcutsPageProps {
-    pub(crate) capture: ActionRowProps,
-    pub(crate) quick_capture: ActionRowProps,
+    pub(crate) rows: Vec<ShortcutRowProps>,
     pub(crate) recording_notice: Option<PreferencesNotice>,
     pub(crate) conflict_notice: Option<PreferencesNotice>,
+    pub(crate) add_binding: ButtonProps,
     pub(crate) restore_defaults: ButtonProps,
 }
 
@@ -59,6 +75,9 @@ pub(crate) struct AboutPageProps {
 }
 
 pub(super) fn build_general_props(snapshot: GeneralSnapshot) -> GeneralPageProps {
+    let is_png = snapshot.output_format.as_ref() == settings::OutputFormat::Png.as_str();
+    let is_jpeg = snapshot.output_format.as_ref() == settings::OutputFormat::Jpeg.as_str();
+
     GeneralPageProps {
         auto_start: ToggleRowProps::new(
             "preferences-auto-start",
@@ -98,6 +117,119 @@ pub(super) fn build_general_props(snapshot: GeneralSnapshot) -> GeneralPageProps
             i18n::preferences::image_compression(),
             i18n::preferences::image_compression_description(),
             snapshot.oxipng_enabled,
+        )
+        .disabled(!is_png),
+        capture_scope: SelectRowProps::new(
+            "preferences-capture-scope",
+            i18n::preferences::capture_scope(),
+            i18n::preferences::capture_scope_description(),
+            snapshot.capture_scope,
+            snapshot.capture_scope_options,
+        ),
+        capture_delay: SelectRowProps::new(
+            "preferences-capture-delay",
+            i18n::preferences::capture_delay(),
+            i18n::preferences::capture_delay_description(),
+            snapshot.capture_delay,
+            snapshot.capture_delay_options,
+        ),
+        delayed_capture_mode: SelectRowProps::new(
+            "preferences-delayed-capture-mode",
+            i18n::preferences::delayed_capture_mode(),
+            i18n::preferences::delayed_capture_mode_description(),
+            snapshot.delayed_capture_mode,
+            snapshot.delayed_capture_mode_options,
+        ),
+        quick_capture_destination: SelectRowProps::new(
+            "preferences-quick-capture-destination",
+            i18n::preferences::quick_capture_destination(),
+            i18n::preferences::quick_capture_destination_description(),
+            snapshot.quick_capture_destination,
+            snapshot.quick_capture_destination_options,
+        ),
+        long_capture_max_length: SelectRowProps::new(
+            "preferences-long-capture-max-length",
+            i18n::preferences::long_capture_max_length(),
+            i18n::preferences::long_capture_max_length_description(),
+            snapshot.long_capture_max_length,
+            snapshot.long_capture_max_length_options,
+        ),
+        long_capture_animation: ToggleRowProps::new(
+            "preferences-long-capture-animation",
+            i18n::preferences::long_capture_animation(),
+            i18n::preferences::long_capture_animation_description(),
+            snapshot.long_capture_animation,
+        ),
+        recording_format: SelectRowProps::new(
+            "preferences-recording-format",
+            i18n::preferences::recording_format(),
+            i18n::preferences::recording_format_description(),
+            snapshot.recording_format,
+            snapshot.recording_format_options,
+        ),
+        recording_frame_rate: SelectRowProps::new(
+            "preferences-recording-frame-rate",
+            i18n::preferences::recording_frame_rate(),
+            i18n::preferences::recording_frame_rate_description(),
+            snapshot.recording_frame_rate,
+            snapshot.recording_frame_rate_options,
+        ),
+        recording_max_seconds: SelectRowProps::new(
+            "preferences-recording-max-seconds",
+            i18n::preferences::recording_max_seconds(),
+            i18n::preferences::recording_max_seconds_description(),
+            snapshot.recording_max_seconds,
+            snapshot.recording_max_seconds_options,
+        ),
+        output_format: SelectRowProps::new(
+            "preferences-output-format",
+            i18n::preferences::image_format(),
+            i18n::preferences::image_format_description(),
+            snapshot.output_format,
+            snapshot.output_format_options,
+        ),
+        jpeg_quality: SelectRowProps::new(
+            "preferences-jpeg-quality",
+            i18n::preferences::jpeg_quality(),
+            i18n::preferences::jpeg_quality_description(),
+            snapshot.jpeg_quality,
+            snapshot.jpeg_quality_options,
+        )
+        .disabled(!is_jpeg),
+        filename_template: SelectRowProps::new(
+            "preferences-filename-template",
+            i18n::preferences::filename_template(),
+            i18n::preferences::filename_template_description(),
+            snapshot.filename_template,
+            snapshot.filename_template_options,
+        ),
+        history_enabled: ToggleRowProps::new(
+            "preferences-history-enabled",
+            i18n::preferences::history_enabled(),
+            i18n::preferences::history_enabled_description(),
+            snapshot.history_enabled,
+        ),
+        history_max_entries: SelectRowProps::new(
+            "preferences-history-max-entries",
+            i18n::preferences::history_max_entries(),
+            i18n::preferences::history_max_entries_description(),
+            snapshot.history_max_entries,
+            snapshot.history_max_entries_options,
+        )
+        .disabled(!snapshot.history_enabled),
+        history_retention: SelectRowProps::new(
+            "preferences-history-retention",
+            i18n::preferences::history_retention(),
+            i18n::preferences::history_retention_description(),
+            snapshot.history_retention_days,
+            snapshot.history_retention_options,
+        )
+        .disabled(!snapshot.history_enabled),
+        open_history: ActionRowProps::new(
+            "preferences-open-history",
+            i18n::preferences::history_browse(),
+            i18n::preferences::history_browse_description(),
+            i18n::preferences::open(),
         ),
     }
 }
@@ -141,39 +273,83 @@ pub(super) fn build_notifications_props(snapshot: settings::NotificationSettings
 }
 
 pub(super) fn build_shortcuts_props(state: &PreferencesState, snapshot: ShortcutsSnapshot) -> ShortcutsPageProps {
-    let recording_capture = state.shortcut_recording == Some(HotkeyAction::Capture);
-    let recording_quick = state.shortcut_recording == Some(HotkeyAction::QuickCapture);
+    let action_options = HotkeyAction::ALL
+        .into_iter()
+        .map(|action| SelectOption::new(action.as_str(), shortcut_action_title(action)))
+        .collect::<Vec<_>>();
 
-    ShortcutsPageProps {
-        capture: ActionRowProps::new(
-            "preferences-shortcuts-capture",
-            i18n::preferences::capture_shortcut(),
-            i18n::preferences::capture_shortcut_description(),
-            if recording_capture {
-                SharedString::from(i18n::preferences::shortcuts_recording())
-            } else {
-                snapshot.bindings.capture.clone().into()
-            },
-        ),
-        quick_capture: ActionRowProps::new(
-            "preferences-shortcuts-quick",
-            i18n::preferences::quick_capture_shortcut(),
-            i18n::preferences::quick_capture_shortcut_description(),
-            if recording_quick {
-                SharedString::from(i18n::preferences::shortcuts_recording())
+    let rows = snapshot
+        .bindings
+        .entries()
+        .iter()
+        .enumerate()
+        .map(|(index, binding)| {
+            let shortcut_label = if state.shortcut_recording == Some(index) {
+                i18n::preferences::shortcuts_recording()
+            } else if binding.is_bound() {
+                binding.shortcut.clone()
             } else {
-                snapshot.bindings.quick_capture.clone().into()
-            },
-        ),
-        recording_notice: (recording_capture || recording_quick).then(|| PreferencesNotice::info(i18n::preferences::shortcuts_recording_hint())),
+                i18n::preferences::shortcuts_unbound()
+            };
+            ShortcutRowProps {
+                index,
+                action: binding.action.as_str().into(),
+                action_label: shortcut_action_title(binding.action).into(),
+                description: shortcut_action_description(binding.action).into(),
+                shortcut_label: shortcut_label.into(),
+                action_options: action_options.clone(),
+                remove_label: i18n::preferences::shortcuts_remove().into(),
+            }
+        })
+        .collect();
+
+    ShortcutsPageProps {
+        rows,
+        recording_notice: state
+            .shortcut_recording
+            .is_some()
+            .then(|| PreferencesNotice::info(i18n::preferences::shortcuts_recording_hint())),
         conflict_notice: snapshot
             .conflict_message
             .as_ref()
             .map(|message| PreferencesNotice::error(message.clone())),
+        add_binding: ButtonProps::new("preferences-shortcuts-add", i18n::preferences::shortcuts_add()),
         restore_defaults: ButtonProps::new("preferences-shortcuts-restore-defaults", i18n::preferences::shortcuts_restore_defaults()),
     }
 }
 
+fn shortcut_action_title(action: HotkeyAction) -> String {
+    match action {
+        HotkeyAction::Capture => i18n::preferences::capture_shortcut(),
+        HotkeyAction::QuickCapture => i18n::preferences::quick_capture_shortcut(),
+        HotkeyAction::DelayedCapture => i18n::preferences::delayed_capture_shortcut(),
+        HotkeyAction::WindowCapture => i18n::preferences::window_capture_shortcut(),
+        HotkeyAction::FocusedWindowCapture => i18n::preferences::focused_window_capture_shortcut(),
+        HotkeyAction::RepeatRegionCapture => i18n::preferences::repeat_region_capture_shortcut(),
+        HotkeyAction::PinClipboardImage => i18n::preferences::pin_clipboard_image_shortcut(),
+        HotkeyAction::CloseAllPins => i18n::preferences::close_all_pins_shortcut(),
+        HotkeyAction::TogglePinVisibility => i18n::preferences::toggle_pin_visibility_shortcut(),
+        HotkeyAction::LongCapture => i18n::preferences::long_capture_shortcut(),
+        HotkeyAction::OcrLastCapture => i18n::preferences::ocr_last_capture_shortcut(),
+    }
+}
+
+fn shortcut_action_description(action: HotkeyAction) -> String {
+    match action {
+        HotkeyAction::Capture => i18n::preferences::capture_shortcut_description(),
+        HotkeyAction::QuickCapture => i18n::preferences::quick_capture_shortcut_description(),
+        HotkeyAction::DelayedCapture => i18n::preferences::delayed_capture_shortcut_description(),
+        HotkeyAction::WindowCapture => i18n::preferences::window_capture_shortcut_description(),
+        HotkeyAction::FocusedWindowCapture => i18n::preferences::focused_window_capture_shortcut_description(),
+        HotkeyAction::RepeatRegionCapture => i18n::preferences::repeat_region_capture_shortcut_description(),
+        HotkeyAction::PinClipboardImage => i18n::preferences::pin_clipboard_image_shortcut_description(),
+        HotkeyAction::CloseAllPins => i18n::preferences::close_all_pins_shortcut_description(),
+        HotkeyAction::TogglePinVisibility => i18n::preferences::toggle_pin_visibility_shortcut_description(),
+        HotkeyAction::LongCapture => i18n::preferences::long_capture_shortcut_description(),
+        HotkeyAction::OcrLastCapture => i18n::preferences::ocr_last_capture_shortcut_description(),
+    }
+}
+
 pub(super) fn build_ocr_props(snapshot: OcrSnapshot) -> OcrPageProps {
     OcrPageProps {
         enabled: ToggleRowProps::new(
@@ -236,9 +412,8 @@ fn ocr_download_label(status: &OcrModelStatus) -> SharedString {
 #[cfg(test)]
 mod tests {
     use super::super::super::shortcuts;
-    use super::super::rows::SelectOption;
     use super::*;
-    use crate::services::hotkeys::ShortcutBindings;
+    use crate::services::hotkeys::{ShortcutBinding, ShortcutBindings};
 
     #[test]
     fn general_props_keep_dynamic_font_and_language_options() {
@@ -249,13 +424,45 @@ mod tests {
             auto_start: false,
             save_directory_description: "Default".into(),
             oxipng_enabled: true,
+            capture_scope: "cursor-monitor".into(),
+            capture_delay: "3".into(),
+            delayed_capture_mode: "overlay".into(),
+            quick_capture_destination: "clipboard".into(),
+            long_capture_max_length: "50000".into(),
+            long_capture_animation: false,
+            recording_format: "gif".into(),
+            recording_frame_rate: "10".into(),
+            recording_max_seconds: "30".into(),
+            output_format: "png".into(),
+            jpeg_quality: "90".into(),
+            filename_template: "snap_{timestamp}".into(),
+            history_enabled: false,
+            history_max_entries: "50".into(),
+            history_retention_days: "30".into(),
             language_options: vec![SelectOption::new("System", i18n::preferences::follow_system())],
             theme_options: vec![SelectOption::new("System", i18n::preferences::follow_system())],
             font_options: vec![SelectOption::new("", i18n::preferences::follow_system())],
+            capture_scope_options: vec![SelectOption::new("cursor-monitor", i18n::preferences::capture_scope_cursor_monitor())],
+            capture_delay_options: vec![SelectOption::new("3", i18n::preferences::capture_delay_seconds(3))],
+            delayed_capture_mode_options: vec![SelectOption::new("overlay", i18n::preferences::delayed_capture_mode_overlay())],
+            quick_capture_destination_options: vec![SelectOption::new("clipboard", i18n::preferences::quick_capture_destination_clipboard())],
+            long_capture_max_length_options: vec![SelectOption::new("50000", i18n::preferences::long_capture_max_length_pixels(50000))],
+            recording_format_options: vec![SelectOption::new("gif", i18n::preferences::recording_format_gif())],
+            recording_frame_rate_options: vec![SelectOption::new("10", i18n::preferences::recording_frame_rate_fps(10))],
+            recording_max_seconds_options: vec![SelectOption::new("30", i18n::preferences::recording_max_seconds_value(30))],
+            output_format_options: vec![SelectOption::new("png", i18n::preferences::format_png())],
+            jpeg_quality_options: vec![SelectOption::new("90", "90")],
+            filename_template_options: vec![SelectOption::new("snap_{timestamp}", "snap_{timestamp}")],
+            history_max_entries_options: vec![SelectOption::new("50", i18n::preferences::history_entries_count(50))],
+            history_retention_options: vec![SelectOption::new("30", i18n::preferences::history_retention_days(30))],
         });
 
         assert_eq!(props.language.options[0].label, SharedString::from(i18n::preferences::follow_system()));
         assert_eq!(props.font.options[0].label, SharedString::from(i18n::preferences::follow_system()));
+        assert!(!props.image_compression.disabled);
+        assert!(props.jpeg_quality.disabled);
+        assert!(props.history_max_entries.disabled);
+        assert!(props.history_retention.disabled);
     }
 
     #[test]
@@ -277,20 +484,27 @@ mod tests {
     #[test]
     fn shortcuts_props_show_recording_and_conflict_state() {
         let mut state = PreferencesState::new();
-        state.start_shortcut_recording(HotkeyAction::Capture);
+        state.start_shortcut_recording(0);
 
         let props = build_shortcuts_props(
             &state,
             shortcuts::ShortcutsSnapshot {
-                bindings: ShortcutBindings {
-                    capture: "Ctrl+Shift+1".to_string(),
-                    quick_capture: "Ctrl+Shift+1".to_string(),
-                },
+                bindings: ShortcutBindings::new(vec![
+                    ShortcutBinding::new("Ctrl+Shift+1", HotkeyAction::Capture),
+                    ShortcutBinding::new("Ctrl+Shift+1", HotkeyAction::QuickCapture),
+                    ShortcutBinding::new("Ctrl+Shift+3", HotkeyAction::CloseAllPins),
+                    ShortcutBinding::new("", HotkeyAction::LongCapture),
+                ]),
                 conflict_message: Some(i18n::preferences::shortcuts_conflict().into()),
             },
         );
 
-        assert_eq!(props.capture.button_label, SharedString::from(i18n::preferences::shortcuts_recording()));
+        assert_eq!(props.rows.len(), 4);
+        assert_eq!(props.rows[0].shortcut_label, SharedString::from(i18n::preferences::shortcuts_recording()));
+        assert_eq!(props.rows[2].shortcut_label, SharedString::from("Ctrl+Shift+3"));
+        assert_eq!(props.rows[2].action, SharedString::from("close-all-pins"));
+        assert_eq!(props.rows[3].shortcut_label, SharedString::from(i18n::preferences::shortcuts_unbound()));
+        assert_eq!(props.rows[3].action_options.len(), HotkeyAction::ALL.len());
         assert!(props.recording_notice.is_some());
         assert!(props.conflict_notice.as_ref().is_some_and(PreferencesNotice::is_error));
     }
@@ -84,6 +84,11 @@ impl SelectRowProps {
             options,
         }
     }
+
+    pub(super) fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -121,6 +126,19 @@ impl ActionRowProps {
     }
 }
 
+/// One row of the shortcut table: which action runs and the key combination
+/// that triggers it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct ShortcutRowProps {
+    pub(crate) index: usize,
+    pub(crate) action: SharedString,
+    pub(crate) action_label: SharedString,
+    pub(crate) description: SharedString,
+    pub(crate) shortcut_label: SharedString,
+    pub(crate) action_options: Vec<SelectOption>,
+    pub(crate) remove_label: SharedString,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub(crate) struct ButtonProps {
     pub(crate) id: &'static str,
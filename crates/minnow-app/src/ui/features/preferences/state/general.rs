@@ -5,7 +5,12 @@ use crate::services::{
     i18n,
     i18n::SYSTEM_LOCALE,
     settings,
-    settings::{AppSettings, SettingsAction, THEME_DARK, THEME_LIGHT, THEME_SYSTEM},
+    settings::{
+        AppSettings, CaptureScope, DEFAULT_CAPTURE_DELAY_SECONDS, DEFAULT_FILENAME_TEMPLATE, DEFAULT_HISTORY_MAX_ENTRIES,
+        DEFAULT_HISTORY_RETENTION_DAYS, DEFAULT_JPEG_QUALITY, DEFAULT_LONG_CAPTURE_MAX_LENGTH, DEFAULT_RECORDING_FRAME_RATE,
+        DEFAULT_RECORDING_MAX_SECONDS, DelayedCaptureMode, OutputFormat, QuickCaptureDestination, RecordingFormat, SettingsAction, THEME_DARK,
+        THEME_LIGHT, THEME_SYSTEM,
+    },
 };
 use crate::ui::features::preferences::view::PreferencesView;
 use crate::ui::support::appearance;
@@ -21,9 +26,37 @@ pub(crate) struct GeneralSnapshot {
     pub(crate) auto_start: bool,
     pub(crate) save_directory_description: SharedString,
     pub(crate) oxipng_enabled: bool,
+    pub(crate) capture_scope: SharedString,
+    pub(crate) capture_delay: SharedString,
+    pub(crate) delayed_capture_mode: SharedString,
+    pub(crate) quick_capture_destination: SharedString,
+    pub(crate) long_capture_max_length: SharedString,
+    pub(crate) long_capture_animation: bool,
+    pub(crate) recording_format: SharedString,
+    pub(crate) recording_frame_rate: SharedString,
+    pub(crate) recording_max_seconds: SharedString,
+    pub(crate) output_format: SharedString,
+    pub(crate) jpeg_quality: SharedString,
+    pub(crate) filename_template: SharedString,
+    pub(crate) history_enabled: bool,
+    pub(crate) history_max_entries: SharedString,
+    pub(crate) history_retention_days: SharedString,
     pub(crate) language_options: Vec<SelectOption>,
     pub(crate) theme_options: Vec<SelectOption>,
     pub(crate) font_options: Vec<SelectOption>,
+    pub(crate) capture_scope_options: Vec<SelectOption>,
+    pub(crate) capture_delay_options: Vec<SelectOption>,
+    pub(crate) delayed_capture_mode_options: Vec<SelectOption>,
+    pub(crate) quick_capture_destination_options: Vec<SelectOption>,
+    pub(crate) long_capture_max_length_options: Vec<SelectOption>,
+    pub(crate) recording_format_options: Vec<SelectOption>,
+    pub(crate) recording_frame_rate_options: Vec<SelectOption>,
+    pub(crate) recording_max_seconds_options: Vec<SelectOption>,
+    pub(crate) output_format_options: Vec<SelectOption>,
+    pub(crate) jpeg_quality_options: Vec<SelectOption>,
+    pub(crate) filename_template_options: Vec<SelectOption>,
+    pub(crate) history_max_entries_options: Vec<SelectOption>,
+    pub(crate) history_retention_options: Vec<SelectOption>,
 }
 
 pub(crate) fn snapshot() -> GeneralSnapshot {
@@ -36,9 +69,37 @@ pub(crate) fn snapshot() -> GeneralSnapshot {
         auto_start: settings.general.auto_start,
         save_directory_description: save_directory_description(&settings).into(),
         oxipng_enabled: settings.output.oxipng_enabled,
+        capture_scope: settings.general.capture_scope.as_str().into(),
+        capture_delay: settings.general.capture_delay_seconds.to_string().into(),
+        delayed_capture_mode: settings.general.delayed_capture_mode.as_str().into(),
+        quick_capture_destination: settings.general.quick_capture_destination.as_str().into(),
+        long_capture_max_length: settings.general.long_capture_max_length.to_string().into(),
+        long_capture_animation: settings.general.long_capture_animation,
+        recording_format: settings.general.recording_format.as_str().into(),
+        recording_frame_rate: settings.general.recording_frame_rate.to_string().into(),
+        recording_max_seconds: settings.general.recording_max_seconds.to_string().into(),
+        output_format: settings.output.format.as_str().into(),
+        jpeg_quality: settings.output.jpeg_quality.to_string().into(),
+        filename_template: settings.output.filename_template.clone().into(),
+        history_enabled: settings.history.enabled,
+        history_max_entries: settings.history.max_entries.to_string().into(),
+        history_retention_days: settings.history.retention_days.to_string().into(),
         language_options: language_options(),
         theme_options: theme_options(),
         font_options: font_options(),
+        capture_scope_options: capture_scope_options(),
+        capture_delay_options: capture_delay_options(settings.general.capture_delay_seconds),
+        delayed_capture_mode_options: delayed_capture_mode_options(),
+        quick_capture_destination_options: quick_capture_destination_options(),
+        long_capture_max_length_options: long_capture_max_length_options(settings.general.long_capture_max_length),
+        recording_format_options: recording_format_options(),
+        recording_frame_rate_options: recording_frame_rate_options(settings.general.recording_frame_rate),
+        recording_max_seconds_options: recording_max_seconds_options(settings.general.recording_max_seconds),
+        output_format_options: output_format_options(),
+        jpeg_quality_options: jpeg_quality_options(),
+        filename_template_options: filename_template_options(&settings.output.filename_template),
+        history_max_entries_options: history_max_entries_options(settings.history.max_entries),
+        history_retention_options: history_retention_options(settings.history.retention_days),
     }
 }
 
@@ -92,6 +153,108 @@ pub(crate) fn set_image_compression(enabled: bool) -> MutationResult {
     MutationResult::refresh_windows()
 }
 
+pub(crate) fn set_capture_scope(value: SharedString) -> MutationResult {
+    let Some(scope) = CaptureScope::parse(&value) else {
+        warn!("Ignoring unknown capture scope: {value}");
+        return MutationResult::NONE;
+    };
+    settings::apply(SettingsAction::CaptureScope(scope));
+    MutationResult::refresh_windows()
+}
+
+pub(crate) fn set_capture_delay(value: SharedString) -> MutationResult {
+    let seconds = value.parse().unwrap_or(DEFAULT_CAPTURE_DELAY_SECONDS);
+    settings::apply(SettingsAction::CaptureDelay(seconds));
+    MutationResult::refresh_windows()
+}
+
+pub(crate) fn set_long_capture_max_length(value: SharedString) -> MutationResult {
+    let length = value.parse().unwrap_or(DEFAULT_LONG_CAPTURE_MAX_LENGTH);
+    settings::apply(SettingsAction::LongCaptureMaxLength(length));
+    MutationResult::refresh_windows()
+}
+
+pub(crate) fn set_long_capture_animation(enabled: bool) -> MutationResult {
+    settings::apply(SettingsAction::LongCaptureAnimation(enabled));
+    MutationResult::refresh_windows()
+}
+
+pub(crate) fn set_recording_format(value: SharedString) -> MutationResult {
+    let Some(format) = RecordingFormat::parse(&value) else {
+        warn!("Ignoring unknown recording format: {value}");
+        return MutationResult::NONE;
+    };
+    settings::apply(SettingsAction::RecordingFormat(format));
+    MutationResult::refresh_windows()
+}
+
+pub(crate) fn set_recording_frame_rate(value: SharedString) -> MutationResult {
+    let frame_rate = value.parse().unwrap_or(DEFAULT_RECORDING_FRAME_RATE);
+    settings::apply(SettingsAction::RecordingFrameRate(frame_rate));
+    MutationResult::refresh_windows()
+}
+
+pub(crate) fn set_recording_max_seconds(value: SharedString) -> MutationResult {
+    let seconds = value.parse().unwrap_or(DEFAULT_RECORDING_MAX_SECONDS);
+    settings::apply(SettingsAction::RecordingMaxSeconds(seconds));
+    MutationResult::refresh_windows()
+}
+
+pub(crate) fn set_delayed_capture_mode(value: SharedString) -> MutationResult {
+    let Some(mode) = DelayedCaptureMode::parse(&value) else {
+        warn!("Ignoring unknown delayed capture mode: {value}");
+        return MutationResult::NONE;
+    };
+    settings::apply(SettingsAction::DelayedCaptureMode(mode));
+    MutationResult::refresh_windows()
+}
+
+pub(crate) fn set_quick_capture_destination(value: SharedString) -> MutationResult {
+    let Some(destination) = QuickCaptureDestination::parse(&value) else {
+        warn!("Ignoring unknown quick capture destination: {value}");
+        return MutationResult::NONE;
+    };
+    settings::apply(SettingsAction::QuickCaptureDestination(destination));
+    MutationResult::refresh_windows()
+}
+
+pub(crate) fn set_output_format(value: SharedString) -> MutationResult {
+    let Some(format) = OutputFormat::parse(&value) else {
+        warn!("Ignoring unknown output format: {value}");
+        return MutationResult::NONE;
+    };
+    settings::apply(SettingsAction::OutputFormat(format));
+    MutationResult::refresh_windows()
+}
+
+pub(crate) fn set_jpeg_quality(value: SharedString) -> MutationResult {
+    let quality = value.parse().unwrap_or(DEFAULT_JPEG_QUALITY);
+    settings::apply(SettingsAction::JpegQuality(quality));
+    MutationResult::refresh_windows()
+}
+
+pub(crate) fn set_filename_template(value: SharedString) -> MutationResult {
+    settings::apply(SettingsAction::FilenameTemplate(value.to_string()));
+    MutationResult::refresh_windows()
+}
+
+pub(crate) fn set_history_enabled(enabled: bool) -> MutationResult {
+    settings::apply(SettingsAction::HistoryEnabled(enabled));
+    MutationResult::refresh_windows()
+}
+
+pub(crate) fn set_history_max_entries(value: SharedString) -> MutationResult {
+    let max_entries = value.parse().unwrap_or(DEFAULT_HISTORY_MAX_ENTRIES);
+    settings::apply(SettingsAction::HistoryMaxEntries(max_entries));
+    MutationResult::refresh_windows()
+}
+
+pub(crate) fn set_history_retention_days(value: SharedString) -> MutationResult {
+    let days = value.parse().unwrap_or(DEFAULT_HISTORY_RETENTION_DAYS);
+    settings::apply(SettingsAction::HistoryRetentionDays(days));
+    MutationResult::refresh_windows()
+}
+
 fn available_font_values() -> Vec<SharedString> {
     std::iter::once(SharedString::from(""))
         .chain(get_system_fonts().into_iter().map(SharedString::from))
@@ -114,6 +277,153 @@ fn theme_options() -> Vec<SelectOption> {
     ]
 }
 
+fn capture_scope_options() -> Vec<SelectOption> {
+    vec![
+        SelectOption::new(CaptureScope::CursorMonitor.as_str(), i18n::preferences::capture_scope_cursor_monitor()),
+        SelectOption::new(CaptureScope::VirtualDesktop.as_str(), i18n::preferences::capture_scope_virtual_desktop()),
+    ]
+}
+
+const CAPTURE_DELAY_PRESETS: [u32; 3] = [3, 5, 10];
+
+fn capture_delay_options(current: u32) -> Vec<SelectOption> {
+    with_current(&CAPTURE_DELAY_PRESETS, current)
+        .into_iter()
+        .map(|seconds| SelectOption::new(seconds.to_string(), i18n::preferences::capture_delay_seconds(seconds)))
+        .collect()
+}
+
+fn delayed_capture_mode_options() -> Vec<SelectOption> {
+    vec![
+        SelectOption::new(DelayedCaptureMode::Overlay.as_str(), i18n::preferences::delayed_capture_mode_overlay()),
+        SelectOption::new(
+            DelayedCaptureMode::QuickCapture.as_str(),
+            i18n::preferences::delayed_capture_mode_quick_capture(),
+        ),
+    ]
+}
+
+fn quick_capture_destination_options() -> Vec<SelectOption> {
+    vec![
+        SelectOption::new(
+            QuickCaptureDestination::Clipboard.as_str(),
+            i18n::preferences::quick_capture_destination_clipboard(),
+        ),
+        SelectOption::new(
+            QuickCaptureDestination::File.as_str(),
+            i18n::preferences::quick_capture_destination_file(),
+        ),
+        SelectOption::new(QuickCaptureDestination::Pin.as_str(), i18n::preferences::quick_capture_destination_pin()),
+    ]
+}
+
+const LONG_CAPTURE_MAX_LENGTH_PRESETS: [u32; 5] = [10_000, 20_000, DEFAULT_LONG_CAPTURE_MAX_LENGTH, 100_000, 0];
+
+fn long_capture_max_length_options(current: u32) -> Vec<SelectOption> {
+    with_current(&LONG_CAPTURE_MAX_LENGTH_PRESETS, current)
+        .into_iter()
+        .map(|pixels| {
+            let label = if pixels == 0 {
+                i18n::preferences::long_capture_max_length_unlimited()
+            } else {
+                i18n::preferences::long_capture_max_length_pixels(pixels)
+            };
+            SelectOption::new(pixels.to_string(), label)
+        })
+        .collect()
+}
+
+fn recording_format_options() -> Vec<SelectOption> {
+    vec![
+        SelectOption::new(RecordingFormat::Gif.as_str(), i18n::preferences::recording_format_gif()),
+        SelectOption::new(RecordingFormat::Apng.as_str(), i18n::preferences::recording_format_apng()),
+    ]
+}
+
+const RECORDING_FRAME_RATE_PRESETS: [u32; 5] = [5, DEFAULT_RECORDING_FRAME_RATE, 15, 24, 30];
+const RECORDING_MAX_SECONDS_PRESETS: [u32; 5] = [10, DEFAULT_RECORDING_MAX_SECONDS, 60, 120, 300];
+
+fn recording_frame_rate_options(current: u32) -> Vec<SelectOption> {
+    with_current(&RECORDING_FRAME_RATE_PRESETS, current)
+        .into_iter()
+        .map(|fps| SelectOption::new(fps.to_string(), i18n::preferences::recording_frame_rate_fps(fps)))
+        .collect()
+}
+
+fn recording_max_seconds_options(current: u32) -> Vec<SelectOption> {
+    with_current(&RECORDING_MAX_SECONDS_PRESETS, current)
+        .into_iter()
+        .map(|seconds| SelectOption::new(seconds.to_string(), i18n::preferences::recording_max_seconds_value(seconds)))
+        .collect()
+}
+
+fn output_format_options() -> Vec<SelectOption> {
+    vec![
+        SelectOption::new(OutputFormat::Png.as_str(), i18n::preferences::format_png()),
+        SelectOption::new(OutputFormat::Jpeg.as_str(), i18n::preferences::format_jpeg()),
+        SelectOption::new(OutputFormat::Webp.as_str(), i18n::preferences::format_webp()),
+    ]
+}
+
+fn jpeg_quality_options() -> Vec<SelectOption> {
+    [60u8, 75, 85, 90, 95, 100]
+        .into_iter()
+        .map(|quality| SelectOption::new(quality.to_string(), quality.to_string()))
+        .collect()
+}
+
+const FILENAME_TEMPLATE_PRESETS: [&str; 5] = [
+    DEFAULT_FILENAME_TEMPLATE,
+    "{date}_{time}",
+    "{app}_{date}_{time}",
+    "{window_title}_{width}x{height}",
+    "Screenshot {counter}",
+];
+
+/// Presets plus the configured template, so a hand-written template from the
+/// config file still shows up as the current choice.
+fn filename_template_options(current: &str) -> Vec<SelectOption> {
+    let custom = (!FILENAME_TEMPLATE_PRESETS.contains(&current)).then_some(current);
+    FILENAME_TEMPLATE_PRESETS
+        .into_iter()
+        .chain(custom)
+        .map(|template| SelectOption::new(template.to_string(), template.to_string()))
+        .collect()
+}
+
+const HISTORY_MAX_ENTRIES_PRESETS: [u32; 5] = [10, 25, 50, 100, 200];
+const HISTORY_RETENTION_PRESETS: [u32; 4] = [7, 30, 90, 0];
+
+fn history_max_entries_options(current: u32) -> Vec<SelectOption> {
+    with_current(&HISTORY_MAX_ENTRIES_PRESETS, current)
+        .into_iter()
+        .map(|count| SelectOption::new(count.to_string(), i18n::preferences::history_entries_count(count)))
+        .collect()
+}
+
+fn history_retention_options(current: u32) -> Vec<SelectOption> {
+    with_current(&HISTORY_RETENTION_PRESETS, current)
+        .into_iter()
+        .map(|days| {
+            let label = if days == 0 {
+                i18n::preferences::history_retention_forever()
+            } else {
+                i18n::preferences::history_retention_days(days)
+            };
+            SelectOption::new(days.to_string(), label)
+        })
+        .collect()
+}
+
+/// Presets plus a value edited into the config file by hand.
+fn with_current(presets: &[u32], current: u32) -> Vec<u32> {
+    let mut values = presets.to_vec();
+    if !values.contains(&current) {
+        values.push(current);
+    }
+    values
+}
+
 fn font_options() -> Vec<SelectOption> {
     available_font_values()
         .into_iter()
@@ -149,6 +459,48 @@ mod tests {
         assert_eq!(snapshot.font_options[0].label, SharedString::from(i18n::preferences::follow_system()));
     }
 
+    #[test]
+    fn filename_template_options_keep_a_custom_template_selectable() {
+        assert_eq!(
+            filename_template_options(DEFAULT_FILENAME_TEMPLATE).len(),
+            FILENAME_TEMPLATE_PRESETS.len()
+        );
+
+        let options = filename_template_options("{app} - {counter}");
+        assert_eq!(options.len(), FILENAME_TEMPLATE_PRESETS.len() + 1);
+        assert_eq!(options.last().map(|option| option.value.as_ref()), Some("{app} - {counter}"));
+    }
+
+    #[test]
+    fn history_options_keep_a_hand_edited_value_selectable() {
+        assert_eq!(
+            history_max_entries_options(DEFAULT_HISTORY_MAX_ENTRIES).len(),
+            HISTORY_MAX_ENTRIES_PRESETS.len()
+        );
+
+        let options = history_retention_options(14);
+        assert_eq!(options.len(), HISTORY_RETENTION_PRESETS.len() + 1);
+        assert_eq!(options.last().map(|option| option.value.as_ref()), Some("14"));
+
+        assert_eq!(capture_delay_options(DEFAULT_CAPTURE_DELAY_SECONDS).len(), CAPTURE_DELAY_PRESETS.len());
+        assert_eq!(capture_delay_options(7).last().map(|option| option.value.as_ref()), Some("7"));
+
+        assert_eq!(
+            long_capture_max_length_options(DEFAULT_LONG_CAPTURE_MAX_LENGTH).len(),
+            LONG_CAPTURE_MAX_LENGTH_PRESETS.len()
+        );
+        assert_eq!(
+            long_capture_max_length_options(0).last().map(|option| option.label.clone()),
+            Some(SharedString::from(i18n::preferences::long_capture_max_length_unlimited()))
+        );
+
+        assert_eq!(
+            recording_frame_rate_options(DEFAULT_RECORDING_FRAME_RATE).len(),
+            RECORDING_FRAME_RATE_PRESETS.len()
+        );
+        assert_eq!(recording_max_seconds_options(45).last().map(|option| option.value.as_ref()), Some("45"));
+    }
+
     #[test]
     fn save_directory_description_uses_custom_path_when_present() {
         let settings = AppSettings {
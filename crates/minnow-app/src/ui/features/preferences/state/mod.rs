@@ -1,4 +1,3 @@
-use crate::services::hotkeys::HotkeyAction;
 use crate::services::i18n;
 use crate::services::ocr::service::OcrDownloadState;
 use gpui::SharedString;
@@ -78,7 +77,8 @@ impl PreferencesNotice {
 pub(crate) struct PreferencesState {
     pub(crate) active_page: PreferencesPage,
     pub(crate) notice: Option<PreferencesNotice>,
-    pub(crate) shortcut_recording: Option<HotkeyAction>,
+    /// Row of the shortcut table waiting for a key combination.
+    pub(crate) shortcut_recording: Option<usize>,
     pub(crate) ocr_download: OcrDownloadState,
 }
 
@@ -109,8 +109,8 @@ impl PreferencesState {
         self.notice.take().is_some()
     }
 
-    pub(crate) fn start_shortcut_recording(&mut self, action: HotkeyAction) {
-        self.shortcut_recording = Some(action);
+    pub(crate) fn start_shortcut_recording(&mut self, index: usize) {
+        self.shortcut_recording = Some(index);
         self.clear_notice();
     }
 
@@ -188,7 +188,7 @@ mod tests {
     fn selecting_a_new_page_clears_notice_and_shortcut_recording() {
         let mut state = PreferencesState::new();
         state.show_notice(PreferencesNotice::info("Heads up"));
-        state.start_shortcut_recording(HotkeyAction::Capture);
+        state.start_shortcut_recording(0);
 
         assert!(state.select_page(PreferencesPage::Ocr));
         assert_eq!(state.active_page, PreferencesPage::Ocr);
@@ -1,6 +1,6 @@
 use super::MutationResult;
 use crate::platform::shell::HotkeyService;
-use crate::services::hotkeys::{HotkeyAction, ShortcutBindings};
+use crate::services::hotkeys::ShortcutBindings;
 use crate::services::i18n;
 use crate::services::settings::{self, SettingsAction};
 use crate::ui::features::preferences::view::PreferencesView;
@@ -21,11 +21,8 @@ pub(crate) fn snapshot(cx: &App) -> ShortcutsSnapshot {
     }
 }
 
-pub(crate) fn next_shortcut_bindings(current: &ShortcutBindings, target_action: HotkeyAction, formatted: &str) -> ShortcutBindings {
-    match target_action {
-        HotkeyAction::Capture => current.with_capture(formatted),
-        HotkeyAction::QuickCapture => current.with_quick_capture(formatted),
-    }
+pub(crate) fn next_shortcut_bindings(current: &ShortcutBindings, target_row: usize, formatted: &str) -> ShortcutBindings {
+    current.with_shortcut(target_row, formatted)
 }
 
 pub(crate) fn persist_shortcut_bindings(bindings: ShortcutBindings, cx: &mut Context<PreferencesView>) -> Result<MutationResult, SharedString> {
@@ -39,10 +36,7 @@ pub(crate) fn persist_shortcut_bindings(bindings: ShortcutBindings, cx: &mut Con
             return Err(SharedString::from(i18n::preferences::shortcuts_conflict()));
         }
     } else {
-        settings::apply(SettingsAction::Shortcuts {
-            capture: bindings.capture.clone(),
-            quick_capture: bindings.quick_capture.clone(),
-        });
+        settings::apply(SettingsAction::Shortcuts(bindings.into_entries()));
     }
 
     Ok(MutationResult::refresh_windows().clear_notice())
@@ -66,13 +60,15 @@ fn shortcut_conflict_message(bindings: &ShortcutBindings) -> Option<SharedString
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::hotkeys::{HotkeyAction, ShortcutBinding};
 
     #[test]
     fn shortcut_conflict_message_only_shows_for_conflicts() {
-        let conflicting = ShortcutBindings {
-            capture: "Ctrl+Shift+A".to_string(),
-            quick_capture: "ctrl+shift+a".to_string(),
-        };
+        let conflicting = ShortcutBindings::new(vec![
+            ShortcutBinding::new("Ctrl+Shift+A", HotkeyAction::Capture),
+            ShortcutBinding::new("ctrl+shift+a", HotkeyAction::QuickCapture),
+            ShortcutBinding::new("Ctrl+Shift+D", HotkeyAction::DelayedCapture),
+        ]);
 
         let snapshot = ShortcutsSnapshot {
             conflict_message: shortcut_conflict_message(&conflicting),
@@ -87,15 +83,19 @@ mod tests {
     }
 
     #[test]
-    fn next_shortcut_bindings_updates_only_target_action() {
+    fn next_shortcut_bindings_updates_only_target_row() {
         let current = ShortcutBindings::default();
 
-        let capture = next_shortcut_bindings(&current, HotkeyAction::Capture, "Ctrl+Shift+1");
-        assert_eq!(capture.capture, "Ctrl+Shift+1");
-        assert_eq!(capture.quick_capture, current.quick_capture);
+        let first = next_shortcut_bindings(&current, 0, "Ctrl+Shift+1");
+        assert_eq!(first.entries()[0].shortcut, "Ctrl+Shift+1");
+        assert_eq!(first.entries()[0].action, current.entries()[0].action);
+        assert_eq!(first.entries()[1], current.entries()[1]);
+
+        let last = current.entries().len() - 1;
+        let updated = next_shortcut_bindings(&current, last, "Ctrl+Shift+4");
+        assert_eq!(updated.entries()[last].shortcut, "Ctrl+Shift+4");
+        assert_eq!(updated.entries()[0], current.entries()[0]);
 
-        let quick = next_shortcut_bindings(&current, HotkeyAction::QuickCapture, "Ctrl+Shift+2");
-        assert_eq!(quick.quick_capture, "Ctrl+Shift+2");
-        assert_eq!(quick.capture, current.capture);
+        assert_eq!(next_shortcut_bindings(&current, last + 1, "Ctrl+Shift+5"), current);
     }
 }
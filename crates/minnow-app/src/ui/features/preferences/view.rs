@@ -4,6 +4,7 @@ use super::{
 };
 use crate::services::hotkeys::{HotkeyAction, ShortcutBindings, format_keystroke};
 use crate::services::{i18n, ocr::service, settings, settings::SettingsAction};
+use crate::ui::features::history;
 use gpui::{
     AnyElement, App, AsyncWindowContext, ClickEvent, Context, FocusHandle, InteractiveElement, IntoElement, KeyDownEvent, ParentElement,
     PathPromptOptions, SharedString, StatefulInteractiveElement, Styled, WeakEntity, Window, div, px,
@@ -43,6 +44,62 @@ impl PreferencesView {
         Self::apply_app_mutation(state::general::set_font(value, cx), cx);
     }
 
+    pub(super) fn on_capture_scope_selected(value: SharedString, _: &mut Window, cx: &mut App) {
+        Self::apply_app_mutation(state::general::set_capture_scope(value), cx);
+    }
+
+    pub(super) fn on_capture_delay_selected(value: SharedString, _: &mut Window, cx: &mut App) {
+        Self::apply_app_mutation(state::general::set_capture_delay(value), cx);
+    }
+
+    pub(super) fn on_delayed_capture_mode_selected(value: SharedString, _: &mut Window, cx: &mut App) {
+        Self::apply_app_mutation(state::general::set_delayed_capture_mode(value), cx);
+    }
+
+    pub(super) fn on_quick_capture_destination_selected(value: SharedString, _: &mut Window, cx: &mut App) {
+        Self::apply_app_mutation(state::general::set_quick_capture_destination(value), cx);
+    }
+
+    pub(super) fn on_long_capture_max_length_selected(value: SharedString, _: &mut Window, cx: &mut App) {
+        Self::apply_app_mutation(state::general::set_long_capture_max_length(value), cx);
+    }
+
+    pub(super) fn on_recording_format_selected(value: SharedString, _: &mut Window, cx: &mut App) {
+        Self::apply_app_mutation(state::general::set_recording_format(value), cx);
+    }
+
+    pub(super) fn on_recording_frame_rate_selected(value: SharedString, _: &mut Window, cx: &mut App) {
+        Self::apply_app_mutation(state::general::set_recording_frame_rate(value), cx);
+    }
+
+    pub(super) fn on_recording_max_seconds_selected(value: SharedString, _: &mut Window, cx: &mut App) {
+        Self::apply_app_mutation(state::general::set_recording_max_seconds(value), cx);
+    }
+
+    pub(super) fn on_output_format_selected(value: SharedString, _: &mut Window, cx: &mut App) {
+        Self::apply_app_mutation(state::general::set_output_format(value), cx);
+    }
+
+    pub(super) fn on_jpeg_quality_selected(value: SharedString, _: &mut Window, cx: &mut App) {
+        Self::apply_app_mutation(state::general::set_jpeg_quality(value), cx);
+    }
+
+    pub(super) fn on_filename_template_selected(value: SharedString, _: &mut Window, cx: &mut App) {
+        Self::apply_app_mutation(state::general::set_filename_template(value), cx);
+    }
+
+    pub(super) fn on_history_max_entries_selected(value: SharedString, _: &mut Window, cx: &mut App) {
+        Self::apply_app_mutation(state::general::set_history_max_entries(value), cx);
+    }
+
+    pub(super) fn on_history_retention_selected(value: SharedString, _: &mut Window, cx: &mut App) {
+        Self::apply_app_mutation(state::general::set_history_retention_days(value), cx);
+    }
+
+    pub(super) fn on_open_history(_: &ClickEvent, _: &mut Window, cx: &mut App) {
+        history::open_window(cx);
+    }
+
     pub(super) fn on_open_repository(_: &ClickEvent, _: &mut Window, cx: &mut App) {
         cx.open_url("https://github.com/Lortunate/MinnowSnap");
     }
@@ -81,6 +138,14 @@ impl PreferencesView {
         self.apply_mutation(state::general::set_image_compression(checked), cx);
     }
 
+    pub(super) fn on_long_capture_animation_changed(&mut self, checked: bool, _: &mut Window, cx: &mut Context<Self>) {
+        self.apply_mutation(state::general::set_long_capture_animation(checked), cx);
+    }
+
+    pub(super) fn on_history_enabled_changed(&mut self, checked: bool, _: &mut Window, cx: &mut Context<Self>) {
+        self.apply_mutation(state::general::set_history_enabled(checked), cx);
+    }
+
     pub(super) fn on_notifications_enabled_changed(&mut self, checked: bool, _: &mut Window, cx: &mut Context<Self>) {
         settings::apply(SettingsAction::NotificationEnabled(checked));
         self.apply_mutation(MutationResult::refresh_windows(), cx);
@@ -110,12 +175,30 @@ impl PreferencesView {
         self.apply_mutation(state::ocr::set_enabled(checked), cx);
     }
 
-    pub(super) fn on_capture_shortcut_record(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
-        self.begin_shortcut_recording(HotkeyAction::Capture, window, cx);
+    pub(super) fn on_shortcut_record(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.begin_shortcut_recording(index, window, cx);
     }
 
-    pub(super) fn on_quick_shortcut_record(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
-        self.begin_shortcut_recording(HotkeyAction::QuickCapture, window, cx);
+    pub(super) fn on_shortcut_remove(&mut self, index: usize, _: &mut Window, cx: &mut Context<Self>) {
+        self.state.stop_shortcut_recording();
+        let current = state::shortcuts::snapshot(cx).bindings;
+        self.apply_shortcuts(current.without(index), cx);
+    }
+
+    pub(super) fn on_shortcut_action_selected(&mut self, index: usize, value: SharedString, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(action) = HotkeyAction::parse(value.as_ref()) else {
+            return;
+        };
+
+        let current = state::shortcuts::snapshot(cx).bindings;
+        self.apply_shortcuts(current.with_action(index, action), cx);
+    }
+
+    pub(super) fn on_add_shortcut(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let current = state::shortcuts::snapshot(cx).bindings;
+        let index = current.entries().len();
+        self.apply_shortcuts(current.with_added(HotkeyAction::Capture), cx);
+        self.begin_shortcut_recording(index, window, cx);
     }
 
     pub(super) fn on_restore_default_shortcuts(&mut self, _: &ClickEvent, _: &mut Window, cx: &mut Context<Self>) {
@@ -124,7 +207,7 @@ impl PreferencesView {
     }
 
     pub(super) fn on_shortcut_key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
-        let Some(target_action) = self.state.shortcut_recording else {
+        let Some(target_row) = self.state.shortcut_recording else {
             return;
         };
 
@@ -138,7 +221,7 @@ impl PreferencesView {
 
         self.state.stop_shortcut_recording();
         let current = state::shortcuts::snapshot(cx).bindings;
-        let updated = state::shortcuts::next_shortcut_bindings(&current, target_action, &formatted);
+        let updated = state::shortcuts::next_shortcut_bindings(&current, target_row, &formatted);
         self.apply_shortcuts(updated, cx);
     }
 
@@ -166,8 +249,8 @@ impl PreferencesView {
         cx.notify();
     }
 
-    fn begin_shortcut_recording(&mut self, action: HotkeyAction, window: &mut Window, cx: &mut Context<Self>) {
-        self.state.start_shortcut_recording(action);
+    fn begin_shortcut_recording(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.state.start_shortcut_recording(index);
         self.focus_handle.focus(window);
         cx.notify();
     }
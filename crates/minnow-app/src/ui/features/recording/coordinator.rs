@@ -0,0 +1,278 @@
+use super::RecordingRequest;
+use crate::services::capture::filename::CaptureOrigin;
+use crate::services::capture::screen_recording::{
+    Animation, MAX_RECORDING_BYTES, RecordingEvent, RecordingLimit, RecordingOptions, ScreenRecordingRuntime,
+};
+use crate::services::settings::{self, RecordingFormat};
+use crate::ui::support::session_windows::{self, SessionWindowKind, SessionWindows, WindowSession, frame_visibility_after_click_through};
+use gpui::{AnyWindowHandle, AppContext, Context, Window, WindowId};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+#[derive(Clone)]
+pub(crate) struct RecordingSnapshot {
+    pub(crate) recording: bool,
+    pub(crate) elapsed: Duration,
+    pub(crate) frames: usize,
+    /// Why the recording stopped on its own, if it did.
+    pub(crate) limit: Option<RecordingLimit>,
+    pub(crate) warning_text: String,
+    pub(crate) busy: bool,
+    pub(crate) frame_visible: bool,
+}
+
+impl Default for RecordingSnapshot {
+    fn default() -> Self {
+        Self {
+            recording: true,
+            elapsed: Duration::ZERO,
+            frames: 0,
+            limit: None,
+            warning_text: String::new(),
+            busy: false,
+            frame_visible: true,
+        }
+    }
+}
+
+#[derive(Default)]
+struct RecordingCoordinatorState {
+    snapshot: RecordingSnapshot,
+    animation: Option<Animation>,
+    windows: SessionWindows,
+    revision: u64,
+}
+
+impl RecordingCoordinatorState {
+    fn bump_revision(&mut self) {
+        self.revision = self.revision.saturating_add(1);
+    }
+
+    fn apply_runtime_events(&mut self, events: Vec<RecordingEvent>, final_animation: Option<Animation>) {
+        let mut changed = false;
+        let mut final_animation = final_animation;
+
+        for event in events {
+            match event {
+                RecordingEvent::Started => {
+                    self.snapshot.recording = true;
+                    changed = true;
+                }
+                RecordingEvent::Progress { elapsed, frames } => {
+                    self.snapshot.elapsed = elapsed;
+                    self.snapshot.frames = frames;
+                    changed = true;
+                }
+                RecordingEvent::Warning { text } => {
+                    self.snapshot.warning_text = text;
+                    changed = true;
+                }
+                RecordingEvent::LimitReached { limit } => {
+                    self.snapshot.limit = Some(limit);
+                    changed = true;
+                }
+                RecordingEvent::Finished => {
+                    if let Some(animation) = final_animation.take() {
+                        self.snapshot.elapsed = animation.duration();
+                        self.snapshot.frames = animation.len();
+                        self.animation = Some(animation);
+                    }
+                    self.snapshot.recording = false;
+                    changed = true;
+                }
+            }
+        }
+
+        if changed {
+            self.bump_revision();
+        }
+    }
+
+    fn register_window(&mut self, kind: SessionWindowKind, handle: AnyWindowHandle) {
+        self.windows.register(kind, handle);
+        self.bump_revision();
+    }
+
+    fn set_frame_visibility(&mut self, success: bool) {
+        self.snapshot.frame_visible = frame_visibility_after_click_through(success);
+        self.bump_revision();
+    }
+
+    fn start_save(&mut self) {
+        self.snapshot.busy = true;
+        self.snapshot.warning_text.clear();
+        self.bump_revision();
+    }
+
+    fn finish_save_with_warning(&mut self, warning_text: String) {
+        self.snapshot.busy = false;
+        self.snapshot.warning_text = warning_text;
+        self.bump_revision();
+    }
+}
+
+pub(crate) struct RecordingCoordinator {
+    runtime: ScreenRecordingRuntime,
+    request: RecordingRequest,
+    /// Format from the settings when recording started, so changing it midway
+    /// does not affect this recording.
+    format: RecordingFormat,
+    state: Mutex<RecordingCoordinatorState>,
+}
+
+impl RecordingCoordinator {
+    pub(crate) fn new(request: RecordingRequest) -> Self {
+        let general = settings::general_settings();
+        let coordinator = Self {
+            runtime: ScreenRecordingRuntime::new(),
+            request,
+            format: general.recording_format,
+            state: Mutex::new(RecordingCoordinatorState {
+                revision: 1,
+                ..RecordingCoordinatorState::default()
+            }),
+        };
+        coordinator.runtime.start(
+            coordinator.request.selection_rect,
+            coordinator.request.viewport_rect,
+            coordinator.request.viewport_scale as f32,
+            RecordingOptions {
+                frame_rate: general.recording_frame_rate,
+                max_duration: Duration::from_secs(u64::from(general.recording_max_seconds)),
+                max_bytes: MAX_RECORDING_BYTES,
+                format: general.recording_format,
+            },
+        );
+        coordinator
+    }
+
+    fn state_guard(&self) -> MutexGuard<'_, RecordingCoordinatorState> {
+        session_windows::lock_state(&self.state, "Recording")
+    }
+
+    pub(crate) fn snapshot(&self) -> RecordingSnapshot {
+        self.state_guard().snapshot.clone()
+    }
+
+    pub(crate) fn ensure_runtime_poller<V>(self: &Arc<Self>, window: &mut Window, cx: &mut Context<V>)
+    where
+        V: 'static,
+    {
+        session_windows::ensure_poller(self, window, cx);
+    }
+
+    pub(crate) fn register_window(&self, kind: SessionWindowKind, handle: AnyWindowHandle) {
+        self.state_guard().register_window(kind, handle);
+    }
+
+    pub(crate) fn on_frame_click_through_result(&self, success: bool) {
+        self.state_guard().set_frame_visibility(success);
+    }
+
+    pub(crate) fn start_save(&self) {
+        self.state_guard().start_save();
+    }
+
+    pub(crate) fn finish_save_with_warning(&self, warning_text: String) {
+        self.state_guard().finish_save_with_warning(warning_text);
+    }
+
+    /// Stops sampling frames; the recording stays open to be saved.
+    pub(crate) fn stop_recording(&self) {
+        self.runtime.stop();
+    }
+
+    pub(crate) fn origin(&self) -> &CaptureOrigin {
+        &self.request.origin
+    }
+
+    pub(crate) fn format(&self) -> RecordingFormat {
+        self.format
+    }
+
+    /// The finished recording, stopping it first if it is still running.
+    pub(crate) fn take_animation(&self, timeout: Duration) -> Option<Animation> {
+        let animation = self.state_guard().animation.take();
+        animation.or_else(|| self.runtime.stop_and_take_result(timeout))
+    }
+
+    pub(crate) fn close_windows_except<C: AppContext>(&self, except: Option<WindowId>, cx: &mut C) {
+        session_windows::close_windows_except(self, except, cx);
+    }
+}
+
+impl WindowSession for RecordingCoordinator {
+    fn revision(&self) -> u64 {
+        self.state_guard().revision
+    }
+
+    fn poll_runtime_events(&self) -> u64 {
+        let events = self.runtime.drain_events();
+        if events.is_empty() {
+            return self.revision();
+        }
+
+        let final_animation = events
+            .iter()
+            .any(|event| matches!(event, RecordingEvent::Finished))
+            .then(|| self.runtime.take_result())
+            .flatten();
+        let mut state = self.state_guard();
+        state.apply_runtime_events(events, final_animation);
+        state.revision
+    }
+
+    fn update_windows<R>(&self, update: impl FnOnce(&mut SessionWindows) -> R) -> R {
+        update(&mut self.state_guard().windows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    #[test]
+    fn finishing_keeps_the_recording_and_its_final_length() {
+        let mut state = RecordingCoordinatorState::default();
+        let mut animation = Animation::default();
+        animation.push(RgbaImage::from_pixel(4, 3, Rgba([1, 2, 3, 255])), Duration::ZERO);
+        animation.finish(Duration::from_secs(2));
+
+        state.apply_runtime_events(
+            vec![
+                RecordingEvent::Started,
+                RecordingEvent::Progress {
+                    elapsed: Duration::from_millis(1900),
+                    frames: 1,
+                },
+                RecordingEvent::LimitReached {
+                    limit: RecordingLimit::Duration,
+                },
+                RecordingEvent::Finished,
+            ],
+            Some(animation),
+        );
+
+        assert_eq!(state.revision, 1);
+        assert!(!state.snapshot.recording);
+        assert_eq!(state.snapshot.limit, Some(RecordingLimit::Duration));
+        assert_eq!((state.snapshot.elapsed, state.snapshot.frames), (Duration::from_secs(2), 1));
+        assert_eq!(state.animation.as_ref().map(Animation::dimensions), Some((4, 3)));
+    }
+
+    #[test]
+    fn save_transitions_own_busy_and_warning() {
+        let mut state = RecordingCoordinatorState::default();
+        state.snapshot.warning_text = "previous warning".to_string();
+
+        state.start_save();
+        assert!(state.snapshot.busy);
+        assert!(state.snapshot.warning_text.is_empty());
+
+        state.finish_save_with_warning("disk full".to_string());
+        assert!(!state.snapshot.busy);
+        assert_eq!(state.snapshot.warning_text, "disk full");
+        assert_eq!(state.revision, 2);
+    }
+}
@@ -0,0 +1,60 @@
+use super::RecordingRequest;
+use crate::services::geometry::RectF;
+use crate::ui::support::panel_layout;
+
+/// Toolbar room, in buttons, given to the elapsed time and frame count.
+pub(crate) const STATUS_SLOTS: usize = 4;
+/// The frame is drawn this far outside the selection so that it does not end
+/// up in the recording.
+pub(crate) const FRAME_OUTSET: f64 = 3.0;
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RecordingWindowLayout {
+    pub(crate) frame_rect: RectF,
+    pub(crate) toolbar_rect: RectF,
+}
+
+pub(crate) fn compute_window_layout(request: &RecordingRequest, toolbar_action_count: usize) -> RecordingWindowLayout {
+    let frame_local = RectF::new(0.0, 0.0, request.viewport_rect.width, request.viewport_rect.height);
+    let toolbar_local = compute_toolbar_window_local_rect(request, toolbar_action_count);
+
+    RecordingWindowLayout {
+        frame_rect: request.map_local_rect_to_screen(frame_local),
+        toolbar_rect: request.map_local_rect_to_screen(toolbar_local),
+    }
+}
+
+fn compute_toolbar_window_local_rect(request: &RecordingRequest, action_count: usize) -> RectF {
+    let toolbar_layout = panel_layout::resolve_toolbar_layout(
+        request.selection_rectf(),
+        action_count + STATUS_SLOTS,
+        request.viewport_rect.width,
+        request.viewport_rect.height,
+        &[],
+    );
+    toolbar_layout.as_rect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::geometry::Rect;
+
+    #[test]
+    fn toolbar_stays_in_the_viewport_and_off_the_recorded_region() {
+        let request = RecordingRequest {
+            selection_rect: Rect::new(700, 200, 400, 300),
+            viewport_rect: RectF::new(0.0, 0.0, 1200.0, 800.0),
+            viewport_scale: 1.0,
+            viewport_origin_screen: (0.0, 0.0),
+            origin: crate::services::capture::filename::CaptureOrigin::default(),
+        };
+
+        let toolbar = compute_toolbar_window_local_rect(&request, 3);
+
+        assert!(toolbar.x >= 0.0);
+        assert!(toolbar.x + toolbar.width <= request.viewport_rect.width + 0.0001);
+        assert!(toolbar.y + toolbar.height <= request.viewport_rect.height + 0.0001);
+        assert!(!panel_layout::rects_overlap(toolbar, request.selection_rectf()));
+    }
+}
@@ -0,0 +1,95 @@
+mod coordinator;
+mod layout;
+mod view;
+
+use crate::platform::shell;
+use crate::services::capture::filename::CaptureOrigin;
+use crate::services::geometry::{Rect, RectF};
+use crate::ui::support::appearance;
+use crate::ui::support::session_windows::SessionWindowKind;
+use gpui::{App, AppContext, WindowBackgroundAppearance};
+use std::sync::Arc;
+use view::{FrameWindowView, RecordingToolbarAction, ToolbarWindowView};
+use {coordinator::RecordingCoordinator, layout::compute_window_layout};
+
+#[derive(Clone)]
+pub struct RecordingRequest {
+    pub selection_rect: Rect,
+    pub viewport_rect: RectF,
+    pub viewport_scale: f64,
+    pub viewport_origin_screen: (f64, f64),
+    /// The window the selection snapped to, used to name the saved file.
+    pub origin: CaptureOrigin,
+}
+
+impl RecordingRequest {
+    #[must_use]
+    pub fn selection_rectf(&self) -> RectF {
+        RectF::new(
+            f64::from(self.selection_rect.x),
+            f64::from(self.selection_rect.y),
+            f64::from(self.selection_rect.width.max(0)),
+            f64::from(self.selection_rect.height.max(0)),
+        )
+    }
+
+    #[must_use]
+    pub fn map_local_rect_to_screen(&self, rect: RectF) -> RectF {
+        RectF::new(
+            self.viewport_origin_screen.0 + rect.x,
+            self.viewport_origin_screen.1 + rect.y,
+            rect.width,
+            rect.height,
+        )
+    }
+}
+
+pub fn open_window(cx: &mut App, request: RecordingRequest) {
+    let layout = compute_window_layout(&request, RecordingToolbarAction::ORDERED.len());
+    let coordinator = Arc::new(RecordingCoordinator::new(request.clone()));
+
+    if let Err(err) = cx.open_window(shell::fixed_popup_window_options(layout.frame_rect, false), {
+        let coordinator = coordinator.clone();
+        move |window, cx| {
+            appearance::apply_saved_preferences(Some(window), cx);
+            shell::configure_window(window, cx, false);
+            window.set_background_appearance(WindowBackgroundAppearance::Transparent);
+            if let Err(err) = shell::set_always_on_top(window) {
+                tracing::warn!("Failed to set recording frame window level: {err}");
+            }
+
+            if shell::set_click_through(window, true).is_ok() {
+                coordinator.register_window(SessionWindowKind::Frame, window.window_handle());
+            } else {
+                coordinator.on_frame_click_through_result(false);
+                window.defer(cx, |window, _| {
+                    window.remove_window();
+                });
+            }
+
+            cx.new(|cx| FrameWindowView::new(request, coordinator, window, cx))
+        }
+    }) {
+        tracing::error!("Failed to open recording frame window: {err}");
+        coordinator.on_frame_click_through_result(false);
+    }
+
+    if let Err(err) = cx.open_window(shell::fixed_popup_window_options(layout.toolbar_rect, true), {
+        let coordinator = coordinator.clone();
+        move |window, cx| {
+            appearance::apply_saved_preferences(Some(window), cx);
+            shell::configure_window(window, cx, true);
+            window.set_background_appearance(WindowBackgroundAppearance::Transparent);
+            if let Err(err) = shell::set_always_on_top(window) {
+                tracing::warn!("Failed to set recording toolbar window level: {err}");
+            }
+            let focus_handle = cx.focus_handle();
+            coordinator.register_window(SessionWindowKind::Toolbar, window.window_handle());
+            cx.new(|cx| ToolbarWindowView::new(coordinator, focus_handle, window, cx))
+        }
+    }) {
+        tracing::error!("Failed to open recording toolbar window: {err}");
+        coordinator.stop_recording();
+        coordinator.close_windows_except(None, cx);
+    }
+}
@@ -0,0 +1,44 @@
+use super::super::RecordingRequest;
+use crate::ui::features::recording::coordinator::RecordingCoordinator;
+use crate::ui::features::recording::layout::FRAME_OUTSET;
+use gpui::{Context, InteractiveElement, IntoElement, ParentElement, Render, Styled, Window, div, px};
+use gpui_component::ActiveTheme as _;
+use std::sync::Arc;
+
+pub(crate) struct FrameWindowView {
+    request: RecordingRequest,
+    coordinator: Arc<RecordingCoordinator>,
+}
+
+impl FrameWindowView {
+    pub(crate) fn new(request: RecordingRequest, coordinator: Arc<RecordingCoordinator>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        coordinator.ensure_runtime_poller(window, cx);
+        Self { request, coordinator }
+    }
+}
+
+impl Render for FrameWindowView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let snapshot = self.coordinator.snapshot();
+        let selection = self.request.selection_rectf();
+        let theme = cx.theme();
+        let border_color = if snapshot.recording { theme.danger } else { theme.selection };
+
+        let mut root = div().id("recording-frame").size_full().bg(gpui::transparent_black());
+
+        if snapshot.frame_visible {
+            root = root.child(
+                div()
+                    .absolute()
+                    .left(px((selection.x - FRAME_OUTSET) as f32))
+                    .top(px((selection.y - FRAME_OUTSET) as f32))
+                    .w(px((selection.width + FRAME_OUTSET * 2.0) as f32))
+                    .h(px((selection.height + FRAME_OUTSET * 2.0) as f32))
+                    .border_2()
+                    .border_color(border_color),
+            );
+        }
+
+        root
+    }
+}
@@ -0,0 +1,5 @@
+mod frame;
+mod toolbar;
+
+pub(crate) use frame::FrameWindowView;
+pub(crate) use toolbar::{RecordingToolbarAction, ToolbarWindowView};
@@ -0,0 +1,260 @@
+use crate::app::workflows;
+use crate::services::assets::asset_paths;
+use crate::services::capture::action::{CaptureAction, PinCaptureRequest};
+use crate::services::capture::screen_recording::RecordingLimit;
+use crate::services::i18n;
+use crate::ui::features::recording::coordinator::{RecordingCoordinator, RecordingSnapshot};
+use crate::ui::features::recording::layout::STATUS_SLOTS;
+use crate::ui::support::capture_actions::{self, CaptureActionHost, CaptureActionHostKind};
+use crate::ui::support::panel_layout;
+use gpui::InteractiveElement;
+use gpui::{
+    App, AsyncWindowContext, ClickEvent, Context, FocusHandle, IntoElement, KeyDownEvent, ParentElement, Render, Styled, WeakEntity, Window, div, px,
+};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme as _, Disableable, Icon, IconNamed, Sizable, h_flex};
+use std::borrow::BorrowMut;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long saving waits for a running recording to hand over its frames.
+const STOP_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum RecordingToolbarAction {
+    Stop,
+    Save,
+    Cancel,
+}
+
+impl RecordingToolbarAction {
+    pub(crate) const ORDERED: [Self; 3] = [Self::Stop, Self::Save, Self::Cancel];
+
+    fn id(self) -> &'static str {
+        match self {
+            Self::Stop => "recording-stop",
+            Self::Save => "recording-save",
+            Self::Cancel => "recording-cancel",
+        }
+    }
+
+    fn tooltip(self) -> String {
+        match self {
+            Self::Stop => i18n::overlay::recording_stop(),
+            Self::Save => i18n::common::save(),
+            Self::Cancel => i18n::common::cancel(),
+        }
+    }
+}
+
+impl IconNamed for RecordingToolbarAction {
+    fn path(self) -> gpui::SharedString {
+        match self {
+            Self::Stop => asset_paths::icons::STOP.into(),
+            Self::Save => asset_paths::icons::SAVE.into(),
+            Self::Cancel => asset_paths::icons::CLOSE.into(),
+        }
+    }
+}
+
+pub(crate) struct ToolbarWindowView {
+    coordinator: Arc<RecordingCoordinator>,
+    focus_handle: FocusHandle,
+}
+
+impl ToolbarWindowView {
+    pub(crate) fn new(coordinator: Arc<RecordingCoordinator>, focus_handle: FocusHandle, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        coordinator.ensure_runtime_poller(window, cx);
+        Self { coordinator, focus_handle }
+    }
+
+    fn toolbar_button(&self, action: RecordingToolbarAction, snapshot: &RecordingSnapshot, cx: &mut Context<Self>) -> Button {
+        let theme = cx.theme();
+        let button = Button::new(action.id())
+            .compact()
+            .icon(Icon::new(action).small().text_color(theme.popover_foreground))
+            .tooltip(action.tooltip())
+            .ghost()
+            .disabled(match action {
+                RecordingToolbarAction::Stop => !snapshot.recording,
+                RecordingToolbarAction::Save => snapshot.busy,
+                RecordingToolbarAction::Cancel => false,
+            });
+
+        match action {
+            RecordingToolbarAction::Stop => button.on_click(cx.listener(Self::on_stop)),
+            RecordingToolbarAction::Save => button.on_click(cx.listener(Self::on_save)),
+            RecordingToolbarAction::Cancel => button.on_click(cx.listener(Self::on_cancel)),
+        }
+    }
+
+    fn on_key_down(&mut self, event: &KeyDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if event.is_held {
+            return;
+        }
+        if event.keystroke.key == "escape" {
+            self.cancel(window, cx);
+        }
+    }
+
+    fn cancel(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.coordinator.stop_recording();
+        self.coordinator.close_windows_except(Some(window.window_handle().window_id()), cx);
+        window.defer(cx, |window, _| {
+            window.remove_window();
+        });
+    }
+
+    fn on_stop(&mut self, _: &ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        self.coordinator.stop_recording();
+        cx.notify();
+    }
+
+    fn on_cancel(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        self.cancel(window, cx);
+    }
+
+    fn on_save(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if self.coordinator.snapshot().busy {
+            return;
+        }
+
+        self.coordinator.start_save();
+        let Some(animation) = self.coordinator.take_animation(STOP_TIMEOUT) else {
+            self.coordinator.finish_save_with_warning(i18n::overlay::recording_empty());
+            cx.notify();
+            return;
+        };
+        // Encoding takes a while for long recordings; the toolbar stays busy
+        // until it is done.
+        let (format, origin) = (self.coordinator.format(), self.coordinator.origin().clone());
+        let encode = cx
+            .background_executor()
+            .spawn(async move { workflows::save_recording(&animation, format, &origin) });
+        cx.spawn_in(window, move |this: WeakEntity<Self>, cx: &mut AsyncWindowContext| {
+            let mut cx = cx.clone();
+            async move {
+                let result = encode.await;
+                let _ = this.update_in(&mut cx, |view: &mut Self, window, cx| {
+                    let effect = capture_actions::interpret(CaptureAction::Save, result, CaptureActionHostKind::Recording);
+                    capture_actions::apply_host_effect(view, effect, window, BorrowMut::borrow_mut(cx));
+                });
+            }
+        })
+        .detach();
+    }
+}
+
+impl CaptureActionHost for ToolbarWindowView {
+    fn close_capture(&self, window: &mut Window, cx: &mut App) {
+        self.coordinator.close_windows_except(Some(window.window_handle().window_id()), cx);
+        window.defer(cx, |window, _| {
+            window.remove_window();
+        });
+    }
+
+    fn refresh_capture(&self, window: &mut Window, _cx: &mut App) {
+        window.refresh();
+    }
+
+    fn open_pin(&self, _request: PinCaptureRequest, _cx: &mut App) {}
+
+    fn show_warning(&self, message: String, window: &mut Window, _cx: &mut App) {
+        self.coordinator.finish_save_with_warning(message);
+        window.refresh();
+    }
+}
+
+impl Render for ToolbarWindowView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let snapshot = self.coordinator.snapshot();
+        let (width, height) = panel_layout::toolbar_size(RecordingToolbarAction::ORDERED.len() + STATUS_SLOTS);
+
+        let mut action_row = h_flex().items_center().gap_0p5();
+        for action in RecordingToolbarAction::ORDERED {
+            action_row = action_row.child(self.toolbar_button(action, &snapshot, cx));
+        }
+
+        let theme = cx.theme();
+        let (status, status_color) = status_text(&snapshot).map_or_else(
+            || {
+                let elapsed = format_elapsed(snapshot.elapsed);
+                (i18n::overlay::recording_status(elapsed, snapshot.frames), theme.popover_foreground)
+            },
+            |text| (text, theme.danger),
+        );
+        let indicator = div()
+            .size(px(8.0))
+            .rounded_full()
+            .bg(if snapshot.recording { theme.danger } else { theme.muted_foreground });
+
+        let mut panel = h_flex()
+            .absolute()
+            .left(px(0.0))
+            .top(px(0.0))
+            .w(px(width as f32))
+            .h(px(height as f32))
+            .items_center()
+            .gap_2()
+            .rounded(theme.radius_lg)
+            .border_1()
+            .border_color(theme.border.alpha(0.82))
+            .bg(theme.popover.alpha(0.98))
+            .overflow_hidden()
+            .px_2()
+            .py_1()
+            .child(indicator)
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .text_ellipsis()
+                    .text_size(px(12.0))
+                    .text_color(status_color)
+                    .child(status),
+            )
+            .child(action_row);
+        if theme.shadow {
+            panel = panel.shadow_lg();
+        }
+
+        div()
+            .id("recording-toolbar")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(gpui::transparent_black())
+            .on_key_down(cx.listener(Self::on_key_down))
+            .child(panel)
+    }
+}
+
+/// A notice that takes the place of the elapsed time and frame count.
+fn status_text(snapshot: &RecordingSnapshot) -> Option<String> {
+    if snapshot.busy {
+        Some(i18n::overlay::recording_encoding())
+    } else if !snapshot.warning_text.is_empty() {
+        Some(snapshot.warning_text.clone())
+    } else {
+        snapshot.limit.map(|limit| match limit {
+            RecordingLimit::Duration => i18n::overlay::recording_duration_limit(),
+            RecordingLimit::Size => i18n::overlay::recording_size_limit(),
+        })
+    }
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elapsed_time_reads_as_minutes_and_seconds() {
+        assert_eq!(format_elapsed(Duration::from_millis(9_800)), "0:09");
+        assert_eq!(format_elapsed(Duration::from_secs(125)), "2:05");
+    }
+}
@@ -39,7 +39,12 @@ pub(crate) enum CaptureActionEffect {
 pub(crate) enum CaptureActionHostKind {
     Overlay,
     LongCapture,
+    Recording,
+    /// The animation saved alongside a long capture's still, once the
+    /// capture itself has closed.
+    SessionAnimation,
     Pin,
+    History,
 }
 
 pub(crate) trait CaptureActionHost {
@@ -53,7 +58,10 @@ pub(crate) fn interpret(action: CaptureAction, result: ActionResult, host: Captu
     match host {
         CaptureActionHostKind::Overlay => interpret_overlay(action, result),
         CaptureActionHostKind::LongCapture => interpret_long_capture(action, result),
+        CaptureActionHostKind::Recording => interpret_recording(result),
+        CaptureActionHostKind::SessionAnimation => interpret_session_animation(result),
         CaptureActionHostKind::Pin => interpret_pin(result),
+        CaptureActionHostKind::History => interpret_overlay(action, result),
     }
 }
 
@@ -97,6 +105,16 @@ pub(crate) fn apply_host_effect<H: CaptureActionHost>(host: &H, effect: CaptureA
     }
 }
 
+/// Applies the effect of an action that finishes after its capture closed,
+/// such as the animation saved with a long capture.
+pub(crate) fn apply_detached_effect(effect: CaptureActionEffect) {
+    match effect {
+        CaptureActionEffect::NotifyOnly(notification) => show_notification(notification),
+        CaptureActionEffect::LogError(error) => tracing::error!("Capture action error: {error}"),
+        _ => {}
+    }
+}
+
 pub(crate) fn apply_pin_effect(effect: CaptureActionEffect) {
     match effect {
         CaptureActionEffect::NotifyOnly(notification) => show_notification(notification),
@@ -166,6 +184,32 @@ fn interpret_long_capture(_action: CaptureAction, result: ActionResult) -> Captu
     }
 }
 
+fn interpret_recording(result: ActionResult) -> CaptureActionEffect {
+    match result {
+        ActionResult::Saved(path) => CaptureActionEffect::NotifyAndClose(NotificationSpec::new(
+            i18n::app::capture_name(),
+            i18n::notify::saved_recording(path),
+            NotificationType::Save,
+        )),
+        ActionResult::Error(error) => CaptureActionEffect::Warning(error),
+        _ => CaptureActionEffect::Warning(i18n::overlay::action_unavailable()),
+    }
+}
+
+fn interpret_session_animation(result: ActionResult) -> CaptureActionEffect {
+    match result {
+        ActionResult::Saved(path) => CaptureActionEffect::NotifyOnly(NotificationSpec::new(
+            i18n::app::capture_name(),
+            i18n::notify::saved_recording(path),
+            NotificationType::Save,
+        )),
+        ActionResult::Error(error) => {
+            CaptureActionEffect::NotifyOnly(NotificationSpec::new(i18n::app::capture_name(), error, NotificationType::Info))
+        }
+        _ => CaptureActionEffect::NoOp,
+    }
+}
+
 fn interpret_pin(result: ActionResult) -> CaptureActionEffect {
     match result {
         ActionResult::Copied => CaptureActionEffect::NotifyOnly(NotificationSpec::new(
@@ -216,6 +260,33 @@ mod tests {
         assert!(matches!(effect, CaptureActionEffect::Warning(_)));
     }
 
+    #[test]
+    fn recording_save_failure_stays_open_with_warning() {
+        let effect = interpret(
+            CaptureAction::Save,
+            ActionResult::Error("disk full".to_string()),
+            CaptureActionHostKind::Recording,
+        );
+        assert!(matches!(effect, CaptureActionEffect::Warning(message) if message == "disk full"));
+
+        let effect = interpret(
+            CaptureAction::Save,
+            ActionResult::Saved("a.gif".to_string()),
+            CaptureActionHostKind::Recording,
+        );
+        assert!(matches!(effect, CaptureActionEffect::NotifyAndClose(_)));
+    }
+
+    #[test]
+    fn session_animation_failure_is_reported_without_a_host_warning() {
+        let effect = interpret(
+            CaptureAction::Save,
+            ActionResult::Error("disk full".to_string()),
+            CaptureActionHostKind::SessionAnimation,
+        );
+        assert!(matches!(effect, CaptureActionEffect::NotifyOnly(notification) if notification.kind == NotificationType::Info));
+    }
+
     #[test]
     fn pin_copy_result_only_notifies() {
         let effect = interpret(CaptureAction::Copy, ActionResult::Copied, CaptureActionHostKind::Pin);
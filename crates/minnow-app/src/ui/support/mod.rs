@@ -3,3 +3,4 @@ pub(crate) mod capture_actions;
 pub mod locale;
 pub(crate) mod panel_layout;
 pub mod render_image;
+pub(crate) mod session_windows;
@@ -0,0 +1,189 @@
+//! The frame, toolbar and preview windows that long capture and recording
+//! float over the screen, and the poller that keeps them in step with the
+//! running session.
+
+use gpui::{AnyWindowHandle, AppContext, AsyncWindowContext, Context, WeakEntity, Window, WindowId};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum SessionWindowKind {
+    Frame,
+    Toolbar,
+    Preview,
+}
+
+/// Handles of the windows a session has open, and whether its poller runs.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct SessionWindows {
+    frame: Option<AnyWindowHandle>,
+    toolbar: Option<AnyWindowHandle>,
+    preview: Option<AnyWindowHandle>,
+    poller_running: bool,
+}
+
+impl SessionWindows {
+    pub(crate) fn register(&mut self, kind: SessionWindowKind, handle: AnyWindowHandle) {
+        match kind {
+            SessionWindowKind::Frame => self.frame = Some(handle),
+            SessionWindowKind::Toolbar => self.toolbar = Some(handle),
+            SessionWindowKind::Preview => self.preview = Some(handle),
+        }
+    }
+
+    pub(crate) fn frame(&self) -> Option<AnyWindowHandle> {
+        self.frame
+    }
+
+    pub(crate) fn handles(&self) -> impl Iterator<Item = AnyWindowHandle> {
+        [self.frame, self.toolbar, self.preview].into_iter().flatten()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.handles().next().is_none()
+    }
+
+    fn forget(&mut self, closed: &[WindowId]) {
+        self.retain(|id| !closed.contains(&id));
+    }
+
+    fn retain_except(&mut self, except: Option<WindowId>) {
+        self.retain(|id| except == Some(id));
+    }
+
+    fn retain(&mut self, keep: impl Fn(WindowId) -> bool) {
+        for slot in [&mut self.frame, &mut self.toolbar, &mut self.preview] {
+            *slot = slot.filter(|handle| keep(handle.window_id()));
+        }
+    }
+
+    fn start_poller(&mut self) -> bool {
+        if self.poller_running {
+            return false;
+        }
+        self.poller_running = true;
+        true
+    }
+
+    fn stop_poller(&mut self) {
+        self.poller_running = false;
+    }
+}
+
+/// A session coordinator whose windows the shared poller refreshes.
+pub(crate) trait WindowSession: 'static {
+    fn revision(&self) -> u64;
+
+    /// Applies the runtime events that arrived since the last poll and
+    /// returns the state revision after them.
+    fn poll_runtime_events(&self) -> u64;
+
+    /// Runs `update` on the session's windows under its state lock.
+    fn update_windows<R>(&self, update: impl FnOnce(&mut SessionWindows) -> R) -> R;
+
+    /// Runs after a state change, before the windows are refreshed.
+    fn before_refresh(&self, _cx: &mut AsyncWindowContext) {}
+}
+
+/// Starts polling `session` unless a poller already runs. It refreshes the
+/// windows whenever the state changes and stops once they are all closed.
+pub(crate) fn ensure_poller<S, V>(session: &Arc<S>, window: &mut Window, cx: &mut Context<V>)
+where
+    S: WindowSession,
+    V: 'static,
+{
+    if !session.update_windows(SessionWindows::start_poller) {
+        return;
+    }
+
+    let session = session.clone();
+    cx.spawn_in(window, move |_this: WeakEntity<V>, cx: &mut AsyncWindowContext| {
+        let mut cx = cx.clone();
+        async move {
+            let mut revision = session.revision();
+            loop {
+                cx.background_executor().timer(POLL_INTERVAL).await;
+                let next_revision = session.poll_runtime_events();
+                if next_revision != revision {
+                    revision = next_revision;
+                    session.before_refresh(&mut cx);
+                    if !refresh_windows(session.as_ref(), &mut cx) {
+                        break;
+                    }
+                } else if session.update_windows(|windows| windows.is_empty()) {
+                    break;
+                }
+            }
+            session.update_windows(SessionWindows::stop_poller);
+        }
+    })
+    .detach();
+}
+
+/// Redraws every window of `session` and forgets the closed ones. Returns
+/// whether any is still open.
+fn refresh_windows<S: WindowSession, C: AppContext>(session: &S, cx: &mut C) -> bool {
+    let windows = session.update_windows(|windows| *windows);
+    let (open, closed): (Vec<_>, Vec<_>) = windows
+        .handles()
+        .partition(|handle| handle.update(cx, |_, window, _| window.refresh()).is_ok());
+    let closed: Vec<WindowId> = closed.iter().map(AnyWindowHandle::window_id).collect();
+    session.update_windows(|windows| windows.forget(&closed));
+    !open.is_empty()
+}
+
+pub(crate) fn close_windows_except<S: WindowSession, C: AppContext>(session: &S, except: Option<WindowId>, cx: &mut C) {
+    let windows = session.update_windows(|windows| *windows);
+
+    for handle in windows.handles() {
+        if except.is_some_and(|id| id == handle.window_id()) {
+            continue;
+        }
+        let _ = handle.update(cx, |_, window, _| {
+            window.remove_window();
+        });
+    }
+
+    session.update_windows(|windows| windows.retain_except(except));
+}
+
+/// Locks a coordinator's state, recovering it when a panic poisoned the lock.
+pub(crate) fn lock_state<'a, T>(state: &'a Mutex<T>, owner: &str) -> MutexGuard<'a, T> {
+    match state.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => {
+            tracing::error!("{owner} coordinator state lock was poisoned; recovering state");
+            let guard = poisoned.into_inner();
+            state.clear_poison();
+            guard
+        }
+    }
+}
+
+pub(crate) fn frame_visibility_after_click_through(success: bool) -> bool {
+    success
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_hides_when_click_through_setup_fails() {
+        assert!(frame_visibility_after_click_through(true));
+        assert!(!frame_visibility_after_click_through(false));
+    }
+
+    #[test]
+    fn only_one_poller_runs_at_a_time() {
+        let mut windows = SessionWindows::default();
+        assert!(windows.is_empty());
+
+        assert!(windows.start_poller());
+        assert!(!windows.start_poller());
+        windows.stop_poller();
+        assert!(windows.start_poller());
+    }
+}
@@ -545,7 +545,7 @@ fn features_do_not_import_other_features_private_render_or_state_modules() {
 
         let source = fs::read_to_string(&file).unwrap_or_else(|err| panic!("read {rel}: {err}"));
         let imports = use_statements(&source);
-        for target_feature in ["long_capture", "overlay", "pin", "preferences"] {
+        for target_feature in ["history", "long_capture", "overlay", "pin", "preferences", "recording"] {
             if target_feature == source_feature {
                 continue;
             }